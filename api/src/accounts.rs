@@ -5,6 +5,7 @@ use crate::{
     context::Context,
     failpoint::fail_point,
     metrics::metrics,
+    page::{encode_cursor, StatePage},
     param::{AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam},
     version::Version,
 };
@@ -14,20 +15,24 @@ use aptos_api_types::{
     TransactionId,
 };
 use aptos_types::{
+    account_address::AccountAddress,
     account_config::AccountResource,
     account_state::AccountState,
     event::{EventHandle, EventKey},
 };
 
-use anyhow::Result;
-use aptos_types::{access_path::AccessPath, state_store::state_key::StateKey};
+use anyhow::{format_err, Result};
+use aptos_types::{
+    access_path::{AccessPath, Path},
+    state_store::{state_key::StateKey, state_key_prefix::StateKeyPrefix},
+};
 use move_deps::move_core_types::{
     identifier::Identifier,
     language_storage::{ResourceKey, StructTag},
     move_resource::MoveStructType,
     value::MoveValue,
 };
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
 
 // GET /accounts/<address>
@@ -40,26 +45,28 @@ pub fn get_account(context: Context) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
-// GET /accounts/<address>/resources
+// GET /accounts/<address>/resources?cursor={cursor}&limit={u16}
 pub fn get_account_resources(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "resources")
         .and(warp::get())
+        .and(warp::query::<StatePage>())
         .and(context.filter())
         .and(warp::query::<Version>())
-        .map(|address, ctx, version: Version| (version.version, address, ctx))
+        .map(|address, page, ctx, version: Version| (version.version, address, page, ctx))
         .untuple_one()
         .and_then(handle_get_account_resources)
         .with(metrics("get_account_resources"))
         .boxed()
 }
 
-// GET /accounts/<address>/modules
+// GET /accounts/<address>/modules?cursor={cursor}&limit={u16}
 pub fn get_account_modules(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "modules")
         .and(warp::get())
+        .and(warp::query::<StatePage>())
         .and(context.filter())
         .and(warp::query::<Version>())
-        .map(|address, ctx, version: Version| (version.version, address, ctx))
+        .map(|address, page, ctx, version: Version| (version.version, address, page, ctx))
         .untuple_one()
         .and_then(handle_get_account_modules)
         .with(metrics("get_account_modules"))
@@ -77,19 +84,21 @@ async fn handle_get_account(
 async fn handle_get_account_resources(
     ledger_version: Option<LedgerVersionParam>,
     address: AddressParam,
+    page: StatePage,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_resources")?;
-    Ok(Account::new(ledger_version, address, context)?.resources()?)
+    Ok(Account::new(ledger_version, address, context)?.resources(page)?)
 }
 
 async fn handle_get_account_modules(
     ledger_version: Option<LedgerVersionParam>,
     address: AddressParam,
+    page: StatePage,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_modules")?;
-    Ok(Account::new(ledger_version, address, context)?.modules()?)
+    Ok(Account::new(ledger_version, address, context)?.modules(page)?)
 }
 
 pub(crate) struct Account {
@@ -147,23 +156,34 @@ impl Account {
         Response::new(self.latest_ledger_info, &account)
     }
 
-    pub fn resources(self) -> Result<impl Reply, Error> {
+    pub fn resources(self, page: StatePage) -> Result<impl Reply, Error> {
+        let (resources, cursor) = self.account_state_page(&page, |path, bytes| match path {
+            Path::Resource(struct_tag) => Some((struct_tag, bytes)),
+            Path::Code(_) => None,
+        })?;
         let resources = self
             .context
             .move_resolver()?
             .as_converter()
-            .try_into_resources(self.account_state()?.get_resources())?;
-        Response::new(self.latest_ledger_info, &resources)
+            .try_into_resources(
+                resources
+                    .iter()
+                    .map(|(struct_tag, bytes)| (struct_tag.clone(), bytes.as_slice())),
+            )?;
+        Ok(Response::new(self.latest_ledger_info, &resources)?.with_cursor(cursor))
     }
 
-    pub fn modules(self) -> Result<impl Reply, Error> {
-        let modules = self
-            .account_state()?
-            .into_modules()
+    pub fn modules(self, page: StatePage) -> Result<impl Reply, Error> {
+        let (modules, cursor) = self.account_state_page(&page, |path, bytes| match path {
+            Path::Code(_) => Some(bytes),
+            Path::Resource(_) => None,
+        })?;
+        let modules = modules
+            .into_iter()
             .map(MoveModuleBytecode::new)
             .map(|m| m.try_parse_abi())
             .collect::<Result<Vec<MoveModuleBytecode>>>()?;
-        Response::new(self.latest_ledger_info, &modules)
+        Ok(Response::new(self.latest_ledger_info, &modules)?.with_cursor(cursor))
     }
 
     pub fn find_event_key(
@@ -217,6 +237,79 @@ impl Account {
         Ok(state)
     }
 
+    /// Returns the page of up to `limit` of the account state items picked by `select`, in the
+    /// order of their state keys, with the cursor of the next page if there are more items.
+    /// Without `cursor` and `limit` all the items are returned, in the same order, so that the
+    /// pages add up to the unpaginated list, unless the account has more state values than an
+    /// unpaginated request may scan, which then fails.
+    fn account_state_page<T>(
+        &self,
+        page: &StatePage,
+        select: fn(Path, Vec<u8>) -> Option<T>,
+    ) -> Result<(Vec<T>, Option<String>), Error> {
+        let key_prefix = StateKeyPrefix::from(AccountAddress::from(self.address));
+        let (chunk_size, limit) = if page.is_paginated() {
+            let limit = page.limit()? as usize;
+            (limit, limit)
+        } else {
+            (
+                self.context.max_unpaginated_account_state_values(),
+                usize::MAX,
+            )
+        };
+        let mut next_key = page.cursor()?;
+        if let Some(key) = &next_key {
+            if !key_prefix.is_prefix(key)? {
+                return Err(Error::invalid_param(
+                    "cursor",
+                    "not a cursor of the account",
+                ));
+            }
+        }
+        let first_page = next_key.is_none();
+
+        let mut items = vec![];
+        let mut account_exists = false;
+        loop {
+            let (values, chunk_next_key) = self.context.get_state_value_chunk(
+                &key_prefix,
+                next_key.as_ref(),
+                chunk_size,
+                self.ledger_version,
+            )?;
+            next_key = chunk_next_key;
+            // We don't allow scanning an arbitrarily large number of values without pagination
+            if !page.is_paginated() && next_key.is_some() {
+                return Err(format_err!(
+                    "Too many values requested for key_prefix {:?} - maximum allowed {:?}",
+                    key_prefix,
+                    chunk_size
+                )
+                .into());
+            }
+            for (key, value) in values {
+                if items.len() == limit {
+                    // the next page starts at the first value not consumed
+                    next_key = Some(key);
+                    break;
+                }
+                // deleted values are kept in the DB, they don't make the account exist
+                if let (StateKey::AccessPath(access_path), Some(bytes)) = (key, value.maybe_bytes) {
+                    account_exists = true;
+                    let path = Path::try_from(&access_path.path).map_err(anyhow::Error::from)?;
+                    items.extend(select(path, bytes));
+                }
+            }
+            if next_key.is_none() || items.len() == limit {
+                break;
+            }
+        }
+        if first_page && !account_exists {
+            return Err(self.account_not_found());
+        }
+        Ok((items, encode_cursor(next_key)?))
+    }
+
     fn account_not_found(&self) -> Error {
         Error::not_found(
             "account",
@@ -0,0 +1,171 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    context::Context,
+    failpoint::fail_point,
+    metrics::metrics,
+    param::{BlockHeightParam, Param, TransactionVersionParam},
+};
+
+use aptos_api_types::{AsConverter, Block, Error, LedgerInfo, Response, Transaction};
+use storage_interface::CommittedBlock;
+
+use anyhow::Result;
+use serde::Deserialize;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+/// Maximum number of transactions read from the database at once when rendering a block.
+const TRANSACTIONS_BATCH_SIZE: u16 = 1000;
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockQuery {
+    with_transactions: Option<Param<bool>>,
+}
+
+// GET /blocks/by_height/{height}?with_transactions={bool}
+pub fn get_block_by_height(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("blocks" / "by_height" / BlockHeightParam)
+        .and(warp::get())
+        .and(warp::query::<BlockQuery>())
+        .and(context.filter())
+        .and_then(handle_get_block_by_height)
+        .with(metrics("get_block_by_height"))
+        .boxed()
+}
+
+// GET /blocks/by_version/{version}?with_transactions={bool}
+pub fn get_block_by_version(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("blocks" / "by_version" / TransactionVersionParam)
+        .and(warp::get())
+        .and(warp::query::<BlockQuery>())
+        .and(context.filter())
+        .and_then(handle_get_block_by_version)
+        .with(metrics("get_block_by_version"))
+        .boxed()
+}
+
+async fn handle_get_block_by_height(
+    height: BlockHeightParam,
+    query: BlockQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_block_by_height")?;
+    let with_transactions = with_transactions(query)?;
+    Ok(Blocks::new(context)?.get_by_height(height.parse("block height")?, with_transactions)?)
+}
+
+async fn handle_get_block_by_version(
+    version: TransactionVersionParam,
+    query: BlockQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_block_by_version")?;
+    let with_transactions = with_transactions(query)?;
+    Ok(Blocks::new(context)?.get_by_version(version.parse("version")?, with_transactions)?)
+}
+
+fn with_transactions(query: BlockQuery) -> Result<bool, Error> {
+    query
+        .with_transactions
+        .map(|v| v.parse("with_transactions"))
+        .unwrap_or(Ok(false))
+}
+
+struct Blocks {
+    ledger_info: LedgerInfo,
+    context: Context,
+}
+
+impl Blocks {
+    fn new(context: Context) -> Result<Self, Error> {
+        let ledger_info = context.get_latest_ledger_info()?;
+        Ok(Self {
+            ledger_info,
+            context,
+        })
+    }
+
+    pub fn get_by_height(self, height: u64, with_transactions: bool) -> Result<impl Reply, Error> {
+        let block = self
+            .context
+            .get_block_by_height(height, self.ledger_info.version())?
+            .ok_or_else(|| {
+                Error::not_found(
+                    "block",
+                    format!("height({})", height),
+                    self.ledger_info.version(),
+                )
+            })?;
+        self.render_block(block, with_transactions)
+    }
+
+    pub fn get_by_version(
+        self,
+        version: u64,
+        with_transactions: bool,
+    ) -> Result<impl Reply, Error> {
+        let block = self
+            .context
+            .get_block_by_version(version, self.ledger_info.version())?
+            .ok_or_else(|| {
+                Error::not_found(
+                    "block",
+                    format!("version({})", version),
+                    self.ledger_info.version(),
+                )
+            })?;
+        self.render_block(block, with_transactions)
+    }
+
+    fn render_block(
+        self,
+        block: CommittedBlock,
+        with_transactions: bool,
+    ) -> Result<impl Reply, Error> {
+        let timestamp = block
+            .metadata
+            .as_ref()
+            .map_or(0, |metadata| metadata.timestamp_usecs());
+        let transactions = if with_transactions {
+            Some(self.block_transactions(&block, timestamp)?)
+        } else {
+            None
+        };
+        let block = Block::new(
+            block.height,
+            block.first_version,
+            block.last_version,
+            block.metadata.as_ref(),
+            transactions,
+        );
+        Response::new(self.ledger_info, &block)
+    }
+
+    fn block_transactions(
+        &self,
+        block: &CommittedBlock,
+        timestamp: u64,
+    ) -> Result<Vec<Transaction>, Error> {
+        let resolver = self.context.move_resolver()?;
+        let converter = resolver.as_converter();
+        let mut txns = vec![];
+        let mut start_version = block.first_version;
+        while start_version <= block.last_version {
+            let limit = std::cmp::min(
+                TRANSACTIONS_BATCH_SIZE as u64,
+                block.last_version - start_version + 1,
+            );
+            let data = self.context.get_transactions(
+                start_version,
+                limit as u16,
+                self.ledger_info.version(),
+            )?;
+            for txn in data {
+                txns.push(converter.try_into_onchain_transaction(timestamp, txn)?);
+            }
+            start_version += limit;
+        }
+        Ok(txns)
+    }
+}
@@ -9,21 +9,22 @@ use aptos_types::{
     account_address::AccountAddress,
     account_state::AccountState,
     chain_id::ChainId,
-    contract_event::ContractEvent,
+    contract_event::{ContractEvent, EventWithVersion},
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
     transaction::{SignedTransaction, TransactionWithProof},
 };
-use storage_interface::{DbReader, Order};
+use storage_interface::{CommittedBlock, DbReader, Order};
 
 use anyhow::{ensure, format_err, Result};
 use aptos_state_view::StateView;
 use aptos_types::{
-    state_store::{state_key::StateKey, state_key_prefix::StateKeyPrefix},
+    state_store::{state_key::StateKey, state_key_prefix::StateKeyPrefix, state_value::StateValue},
     transaction::Version,
 };
 use aptos_vm::data_cache::{IntoMoveResolver, RemoteStorageOwned};
 use futures::{channel::oneshot, SinkExt};
+use move_deps::move_core_types::language_storage::TypeTag;
 use std::{convert::Infallible, sync::Arc};
 use storage_interface::state_view::{
     DbStateView, DbStateViewAtVersion, LatestDbStateCheckpointView,
@@ -72,6 +73,14 @@ impl Context {
         self.api_config.content_length_limit()
     }
 
+    pub fn max_view_function_gas(&self) -> u64 {
+        self.api_config.max_view_function_gas()
+    }
+
+    pub fn max_unpaginated_account_state_values(&self) -> usize {
+        self.api_config.max_unpaginated_account_state_values()
+    }
+
     pub fn filter(self) -> impl Filter<Extract = (Context,), Error = Infallible> + Clone {
         warp::any().map(move || self.clone())
     }
@@ -115,10 +124,53 @@ impl Context {
         )
     }
 
+    pub fn get_state_value_chunk(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        first_key: Option<&StateKey>,
+        limit: usize,
+        version: u64,
+    ) -> Result<(Vec<(StateKey, StateValue)>, Option<StateKey>)> {
+        self.db
+            .get_state_value_chunk_by_key_prefix(key_prefix, first_key, limit, version)
+    }
+
     pub fn get_block_timestamp(&self, version: u64) -> Result<u64> {
         self.db.get_block_timestamp(version)
     }
 
+    pub fn get_block_by_height(
+        &self,
+        height: u64,
+        ledger_version: u64,
+    ) -> Result<Option<CommittedBlock>> {
+        let latest_block = self
+            .db
+            .get_block_info_by_version(ledger_version, ledger_version)?;
+        if height > latest_block.height {
+            return Ok(None);
+        }
+        if height == latest_block.height {
+            return Ok(Some(latest_block));
+        }
+        self.db
+            .get_block_info_by_height(height, ledger_version)
+            .map(Some)
+    }
+
+    pub fn get_block_by_version(
+        &self,
+        version: u64,
+        ledger_version: u64,
+    ) -> Result<Option<CommittedBlock>> {
+        if version > ledger_version {
+            return Ok(None);
+        }
+        self.db
+            .get_block_info_by_version(version, ledger_version)
+            .map(Some)
+    }
+
     pub fn get_transactions(
         &self,
         start_version: u64,
@@ -208,6 +260,32 @@ impl Context {
         callback.await.map_err(anyhow::Error::from)
     }
 
+    pub async fn get_pending_gas_unit_prices(&self, count: usize) -> Result<Vec<u64>> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetGasUnitPrices(count, req_sender))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
+    pub async fn get_pending_transactions(&self, count: usize) -> Result<Vec<SignedTransaction>> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetPendingTransactions(
+                count, req_sender,
+            ))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
     pub fn get_transaction_by_version(
         &self,
         version: u64,
@@ -254,6 +332,21 @@ impl Context {
             .collect::<Vec<_>>())
     }
 
+    pub fn get_event_by_type_index_start_version(&self) -> Result<Option<u64>> {
+        self.db.get_event_by_type_index_start_version()
+    }
+
+    pub fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<EventWithVersion>> {
+        self.db
+            .get_events_by_type(type_tag, start_version, limit as u64, ledger_version)
+    }
+
     pub fn health_check_route(&self) -> BoxedFilter<(impl Reply,)> {
         super::health_check::health_check_route(self.db.clone())
     }
@@ -7,14 +7,16 @@ use crate::{
     failpoint::fail_point,
     metrics::metrics,
     page::Page,
-    param::{AddressParam, EventKeyParam, MoveIdentifierParam, MoveStructTagParam},
+    param::{AddressParam, EventKeyParam, MoveIdentifierParam, MoveStructTagParam, MoveTypeParam},
 };
 
-use aptos_api_types::{AsConverter, Error, LedgerInfo, Response};
+use aptos_api_types::{AsConverter, Error, LedgerInfo, Response, VersionedEvent};
 
 use anyhow::Result;
 use aptos_types::event::EventKey;
-use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+use move_deps::move_core_types::language_storage::TypeTag;
+use std::convert::TryInto;
+use warp::{filters::BoxedFilter, http::StatusCode, Filter, Rejection, Reply};
 
 // GET /events/<event_key>
 pub fn get_events_by_event_key(context: Context) -> BoxedFilter<(impl Reply,)> {
@@ -38,6 +40,17 @@ pub fn get_events_by_event_handle(context: Context) -> BoxedFilter<(impl Reply,)
         .boxed()
 }
 
+// GET /events/by_type/<move_type>?start={version}&limit={u16}
+pub fn get_events_by_type(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("events" / "by_type" / MoveTypeParam)
+        .and(warp::get())
+        .and(warp::query::<Page>())
+        .and(context.filter())
+        .and_then(handle_get_events_by_type)
+        .with(metrics("get_events_by_type"))
+        .boxed()
+}
+
 async fn handle_get_events_by_event_key(
     event_key: EventKeyParam,
     page: Page,
@@ -60,6 +73,64 @@ async fn handle_get_events_by_event_handle(
     Ok(Events::new(key, context)?.list(page)?)
 }
 
+async fn handle_get_events_by_type(
+    event_type: MoveTypeParam,
+    page: Page,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_events_by_type")?;
+    let type_tag: TypeTag = event_type
+        .parse("event type")?
+        .try_into()
+        .map_err(|_| Error::invalid_param("event type", "not a valid Move type"))?;
+    Ok(list_events_by_type(type_tag, page, context)?)
+}
+
+fn list_events_by_type(
+    type_tag: TypeTag,
+    page: Page,
+    context: Context,
+) -> Result<impl Reply, Error> {
+    let ledger_info = context.get_latest_ledger_info()?;
+    let ledger_version = ledger_info.version();
+    let index_start_version = context
+        .get_event_by_type_index_start_version()?
+        .ok_or_else(|| {
+            Error::new(
+                StatusCode::NOT_FOUND,
+                "the events are not indexed by type on this node".to_owned(),
+            )
+            .aptos_ledger_version(ledger_version)
+        })?;
+    // the index starts after the latest version if it was enabled since, then nothing is indexed
+    let start = page.start(
+        index_start_version,
+        std::cmp::max(index_start_version, ledger_version),
+    )?;
+    if start < index_start_version {
+        return Err(Error::bad_request(format!(
+            "events of version {} are not indexed by type, the index starts at version {}",
+            start, index_start_version
+        )));
+    }
+    let (versions, contract_events): (Vec<_>, Vec<_>) = context
+        .get_events_by_type(&type_tag, start, page.limit()?, ledger_version)?
+        .into_iter()
+        .map(|e| (e.transaction_version, e.event))
+        .unzip();
+
+    let resolver = context.move_resolver()?;
+    let events: Vec<_> = versions
+        .into_iter()
+        .zip(resolver.as_converter().try_into_events(&contract_events)?)
+        .map(|(version, event)| VersionedEvent {
+            version: version.into(),
+            event,
+        })
+        .collect();
+    Response::new(ledger_info, &events)
+}
+
 struct Events {
     key: EventKey,
     ledger_info: LedgerInfo,
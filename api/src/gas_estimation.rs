@@ -0,0 +1,281 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{context::Context, failpoint::fail_point, metrics::metrics};
+
+use aptos_api_types::{
+    AsConverter, Error, GasEstimation, GasUsageEstimation, GasUsageEstimationRequest, LedgerInfo,
+    Response, TransactionPayload,
+};
+use aptos_crypto::ed25519::{Ed25519PublicKey, Ed25519Signature, ED25519_SIGNATURE_LENGTH};
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    account_config::{AccountResource, CoinStoreResource},
+    on_chain_config::{OnChainConfig, VMConfig},
+    state_store::state_key::StateKey,
+    transaction::{
+        authenticator::TransactionAuthenticator, ExecutionStatus, RawTransaction,
+        SignedTransaction, TransactionStatus,
+    },
+};
+use aptos_vm::AptosVM;
+use move_deps::move_core_types::{
+    gas_schedule::GasConstants, language_storage::ResourceKey, move_resource::MoveStructType,
+};
+
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use std::convert::TryFrom;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+/// Number of most recent blocks the committed gas unit prices are sampled from.
+const SAMPLED_BLOCKS: usize = 10;
+/// Upper bound of committed transactions read when sampling gas unit prices.
+const MAX_SAMPLED_TRANSACTIONS: u64 = 10_000;
+/// Maximum number of transactions read from the database at once.
+const TRANSACTIONS_BATCH_SIZE: u16 = 1000;
+/// Number of the highest ranked mempool transactions considered, roughly one block worth of
+/// transactions. When mempool holds that many ready transactions the next block is full.
+const PENDING_SAMPLE_SIZE: usize = 1000;
+/// Margin added to the gas used by the payload when recommending the `max_gas_amount`.
+const MAX_GAS_MARGIN_PERCENT: u64 = 50;
+
+// GET /estimate_gas_price
+pub fn estimate_gas_price(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("estimate_gas_price")
+        .and(warp::get())
+        .and(context.filter())
+        .and_then(handle_estimate_gas_price)
+        .with(metrics("estimate_gas_price"))
+        .boxed()
+}
+
+// POST /estimate_gas
+pub fn estimate_gas_usage(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("estimate_gas")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::body::json::<GasUsageEstimationRequest>())
+        .and(context.filter())
+        .and_then(handle_estimate_gas_usage)
+        .with(metrics("estimate_gas_usage"))
+        .boxed()
+}
+
+async fn handle_estimate_gas_price(context: Context) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_estimate_gas_price")?;
+    let estimator = GasEstimator::new(context)?;
+    let estimation = estimator.estimate_gas_price().await?;
+    Ok(Response::new(estimator.ledger_info, &estimation)?)
+}
+
+async fn handle_estimate_gas_usage(
+    body: GasUsageEstimationRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_estimate_gas_usage")?;
+    let estimator = GasEstimator::new(context)?;
+    let estimation = estimator.estimate_gas_usage(body).await?;
+    Ok(Response::new(estimator.ledger_info, &estimation)?)
+}
+
+struct GasEstimator {
+    ledger_info: LedgerInfo,
+    context: Context,
+}
+
+impl GasEstimator {
+    fn new(context: Context) -> Result<Self, Error> {
+        let ledger_info = context.get_latest_ledger_info()?;
+        Ok(Self {
+            ledger_info,
+            context,
+        })
+    }
+
+    async fn estimate_gas_price(&self) -> Result<GasEstimation, Error> {
+        let committed = self.committed_gas_unit_prices()?;
+        let pending = self
+            .context
+            .get_pending_gas_unit_prices(PENDING_SAMPLE_SIZE)
+            .await?;
+        let min_price = self.gas_constants()?.min_price_per_gas_unit.get();
+        Ok(estimate_gas_unit_prices(committed, &pending, min_price))
+    }
+
+    async fn estimate_gas_usage(
+        &self,
+        req: GasUsageEstimationRequest,
+    ) -> Result<GasUsageEstimation, Error> {
+        if !matches!(req.payload, TransactionPayload::ScriptFunctionPayload(_)) {
+            return Err(Error::invalid_request_body(
+                "only entry function payloads are supported",
+            ));
+        }
+        let public_key = Ed25519PublicKey::try_from(Vec::<u8>::from(req.public_key).as_slice())
+            .map_err(|e| Error::invalid_request_body(format!("invalid public key: {}", e)))?;
+        let address = req.sender;
+        let sender: AccountAddress = address.into();
+
+        let account: AccountResource = self
+            .get_resource(sender)?
+            .ok_or_else(|| Error::not_found("account", address, self.ledger_info.version()))?;
+        let balance = self
+            .get_resource::<CoinStoreResource>(sender)?
+            .map_or(0, |coin_store| coin_store.coin());
+
+        // Execute with the cheapest accepted price and the largest gas amount the sender can
+        // pay for, so that the estimation only fails when the payload itself fails.
+        let gas_constants = self.gas_constants()?;
+        let max_gas_units = gas_constants.maximum_number_of_gas_units.get();
+        let min_price = gas_constants.min_price_per_gas_unit.get();
+        let max_gas_amount = match min_price {
+            0 => max_gas_units,
+            price => std::cmp::min(max_gas_units, balance / price),
+        };
+
+        let resolver = self.context.move_resolver()?;
+        let payload = resolver
+            .as_converter()
+            .try_into_aptos_core_transaction_payload(req.payload)
+            .map_err(|e| Error::invalid_request_body(format!("invalid payload: {}", e)))?;
+        let raw_txn = RawTransaction::new(
+            sender,
+            account.sequence_number(),
+            payload,
+            max_gas_amount,
+            min_price,
+            u64::MAX,
+            self.context.chain_id(),
+        );
+        // simulation skips the signature check, any well formed signature does
+        let signature = Ed25519Signature::try_from(&[0u8; ED25519_SIGNATURE_LENGTH][..])
+            .map_err(anyhow::Error::from)?;
+        let txn = SignedTransaction::new_with_authenticator(
+            raw_txn,
+            TransactionAuthenticator::ed25519(public_key, signature),
+        );
+
+        let (status, output) = AptosVM::simulate_signed_transaction(&txn, &*resolver);
+        match status.into() {
+            TransactionStatus::Keep(ExecutionStatus::Success) => {}
+            status => {
+                return Err(Error::bad_request(format!(
+                    "execution failed: {:?}",
+                    status
+                )))
+            }
+        }
+
+        let gas_used = output.gas_used();
+        let max_gas_amount = std::cmp::min(
+            max_gas_units,
+            gas_used + gas_used * MAX_GAS_MARGIN_PERCENT / 100,
+        );
+        let gas_unit_price = self.estimate_gas_price().await?.gas_unit_price;
+        Ok(GasUsageEstimation {
+            gas_used: gas_used.into(),
+            max_gas_amount: max_gas_amount.into(),
+            gas_unit_price,
+        })
+    }
+
+    /// Returns the gas unit prices of the user transactions committed in the last
+    /// `SAMPLED_BLOCKS` blocks.
+    fn committed_gas_unit_prices(&self) -> Result<Vec<u64>, Error> {
+        let ledger_version = self.ledger_info.version();
+        let mut start_version = ledger_version + 1;
+        for _ in 0..SAMPLED_BLOCKS {
+            if start_version == 0 {
+                break;
+            }
+            match self
+                .context
+                .get_block_by_version(start_version - 1, ledger_version)?
+            {
+                Some(block) => start_version = block.first_version,
+                None => break,
+            }
+        }
+        let mut version = std::cmp::max(
+            start_version,
+            (ledger_version + 1).saturating_sub(MAX_SAMPLED_TRANSACTIONS),
+        );
+
+        let mut prices = vec![];
+        while version <= ledger_version {
+            let limit = std::cmp::min(TRANSACTIONS_BATCH_SIZE as u64, ledger_version - version + 1);
+            let txns = self
+                .context
+                .get_transactions(version, limit as u16, ledger_version)?;
+            prices.extend(txns.iter().filter_map(|txn| match &txn.transaction {
+                aptos_types::transaction::Transaction::UserTransaction(t) => {
+                    Some(t.gas_unit_price())
+                }
+                _ => None,
+            }));
+            version += limit;
+        }
+        Ok(prices)
+    }
+
+    fn gas_constants(&self) -> Result<GasConstants, Error> {
+        let resolver = self.context.move_resolver()?;
+        Ok(VMConfig::fetch_config(&resolver)
+            .map(|config| config.gas_schedule.gas_constants)
+            .unwrap_or_default())
+    }
+
+    fn get_resource<T: MoveStructType + DeserializeOwned>(
+        &self,
+        address: AccountAddress,
+    ) -> Result<Option<T>, Error> {
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            address,
+            T::struct_tag(),
+        )));
+        let resource = self
+            .context
+            .get_state_value(&state_key, self.ledger_info.version())?
+            .map(|bytes| bcs::from_bytes(&bytes))
+            .transpose()
+            .map_err(anyhow::Error::from)?;
+        Ok(resource)
+    }
+}
+
+/// Derives the recommended prices from the sorted gas unit prices of the recently committed
+/// transactions and the prices of the highest ranked pending transactions, from highest to
+/// lowest. None of the prices goes below the on-chain `min_price`.
+pub(crate) fn estimate_gas_unit_prices(
+    mut committed: Vec<u64>,
+    pending: &[u64],
+    min_price: u64,
+) -> GasEstimation {
+    committed.sort_unstable();
+    let percentile = |p: usize| {
+        committed
+            .get(committed.len().saturating_sub(1) * p / 100)
+            .copied()
+            .unwrap_or(min_price)
+            .max(min_price)
+    };
+    // When the ready transactions fill a whole block, getting into the next block requires
+    // outbidding the cheapest of them.
+    let next_block_price = match pending.last() {
+        Some(price) if pending.len() >= PENDING_SAMPLE_SIZE => price.saturating_add(1),
+        _ => min_price,
+    };
+
+    let deprioritized = percentile(25);
+    let regular = percentile(50);
+    let prioritized = percentile(90).max(next_block_price);
+    GasEstimation {
+        deprioritized_gas_unit_price: deprioritized.into(),
+        gas_unit_price: regular.into(),
+        prioritized_gas_unit_price: prioritized.into(),
+    }
+}
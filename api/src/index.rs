@@ -2,13 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    accounts,
+    accounts, blocks,
     context::Context,
     events,
     failpoint::fail_point,
-    log,
+    gas_estimation, log,
     metrics::{metrics, status_metrics},
-    state, transactions,
+    state, stream, transactions,
 };
 use aptos_api_types::{Error, Response};
 
@@ -31,6 +31,8 @@ pub fn routes(context: Context) -> impl Filter<Extract = impl Reply, Error = Inf
         .or(accounts::get_account(context.clone()))
         .or(accounts::get_account_resources(context.clone()))
         .or(accounts::get_account_modules(context.clone()))
+        // before `get_transaction`, which matches any path segment as a hash or version
+        .or(transactions::get_pending_transactions(context.clone()))
         .or(transactions::get_transaction(context.clone()))
         .or(transactions::get_transactions(context.clone()))
         .or(transactions::get_account_transactions(context.clone()))
@@ -39,11 +41,20 @@ pub fn routes(context: Context) -> impl Filter<Extract = impl Reply, Error = Inf
         .or(transactions::submit_bcs_transactions(context.clone()))
         .or(transactions::submit_json_transactions(context.clone()))
         .or(transactions::create_signing_message(context.clone()))
+        .or(blocks::get_block_by_height(context.clone()))
+        .or(blocks::get_block_by_version(context.clone()))
+        .or(gas_estimation::estimate_gas_price(context.clone()))
+        .or(gas_estimation::estimate_gas_usage(context.clone()))
         .or(events::get_events_by_event_key(context.clone()))
         .or(events::get_events_by_event_handle(context.clone()))
+        .or(events::get_events_by_type(context.clone()))
         .or(state::get_account_resource(context.clone()))
         .or(state::get_account_module(context.clone()))
         .or(state::get_table_item(context.clone()))
+        .or(state::get_table_items(context.clone()))
+        .or(state::view_function(context.clone()))
+        .or(stream::stream_transactions(context.clone()))
+        .or(stream::stream_events(context.clone()))
         .or(context.health_check_route().with(metrics("health_check")))
         .with(
             warp::cors()
@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod accounts;
+mod blocks;
 pub mod context;
 mod events;
+mod gas_estimation;
 mod health_check;
 mod index;
 pub(crate) mod log;
@@ -12,6 +14,7 @@ mod page;
 pub mod param;
 pub mod runtime;
 mod state;
+mod stream;
 mod transactions;
 pub(crate) mod version;
 
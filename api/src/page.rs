@@ -3,7 +3,8 @@
 
 use crate::param::{Param, TransactionVersionParam};
 
-use aptos_api_types::{Error, TransactionId};
+use aptos_api_types::{Error, HexEncodedBytes, TransactionId};
+use aptos_types::state_store::state_key::StateKey;
 
 use anyhow::Result;
 use serde::Deserialize;
@@ -36,18 +37,63 @@ impl Page {
     }
 
     pub fn limit(&self) -> Result<u16, Error> {
-        let limit = self
-            .limit
+        let limit = parse_limit(&self.limit)?.unwrap_or(DEFAULT_PAGE_SIZE);
+        Ok(limit)
+    }
+}
+
+/// Page of state values, e.g. the resources of an account. Pages are ordered by state key, the
+/// `cursor` is the opaque position of the first item of the page, as returned in the
+/// `X-Aptos-Cursor` header of the previous page.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct StatePage {
+    cursor: Option<Param<HexEncodedBytes>>,
+    limit: Option<Param<NonZeroU16>>,
+}
+
+impl StatePage {
+    /// Whether a specific page is requested, without `cursor` and `limit` all the items are
+    /// returned at once.
+    pub fn is_paginated(&self) -> bool {
+        self.cursor.is_some() || self.limit.is_some()
+    }
+
+    pub fn cursor(&self) -> Result<Option<StateKey>, Error> {
+        self.cursor
             .clone()
-            .map(|v| v.parse("limit"))
-            .unwrap_or_else(|| Ok(NonZeroU16::new(DEFAULT_PAGE_SIZE).unwrap()))?
-            .get();
+            .map(|cursor| {
+                let bytes: Vec<u8> = cursor.parse("cursor")?.into();
+                StateKey::decode(&bytes).map_err(|e| Error::invalid_param("cursor", e))
+            })
+            .transpose()
+    }
+
+    pub fn limit(&self) -> Result<u16, Error> {
+        let limit = parse_limit(&self.limit)?.unwrap_or(DEFAULT_PAGE_SIZE);
+        Ok(limit)
+    }
+}
+
+/// Encodes the key of the first item of the next page as returned in the `X-Aptos-Cursor` header.
+pub(crate) fn encode_cursor(next_key: Option<StateKey>) -> Result<Option<String>> {
+    next_key
+        .map(|key| Ok(HexEncodedBytes::from(key.encode()?).to_string()))
+        .transpose()
+}
+
+fn parse_limit(limit: &Option<Param<NonZeroU16>>) -> Result<Option<u16>, Error> {
+    let limit = limit
+        .clone()
+        .map(|v| v.parse("limit"))
+        .transpose()?
+        .map(NonZeroU16::get);
+    if let Some(limit) = limit {
         if limit > MAX_PAGE_SIZE {
             return Err(Error::invalid_param(
                 "limit",
                 format!("{}, exceed limit {}", limit, MAX_PAGE_SIZE),
             ));
         }
-        Ok(limit)
     }
+    Ok(limit)
 }
@@ -5,23 +5,30 @@ use crate::{
     context::Context,
     failpoint::fail_point,
     metrics::metrics,
+    page::{encode_cursor, StatePage},
     param::{
-        AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam, TableHandleParam,
+        AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam, MoveTypeParam,
+        TableHandleParam,
     },
     version::Version,
 };
 use anyhow::anyhow;
 use aptos_api_types::{
-    AsConverter, Error, LedgerInfo, MoveModuleBytecode, Response, TableItemRequest, TransactionId,
+    AsConverter, Error, LedgerInfo, MoveModuleBytecode, MoveValue, Response, TableItem,
+    TableItemRequest, TransactionId, ViewRequest,
 };
 use aptos_state_view::StateView;
-use aptos_types::{access_path::AccessPath, state_store::state_key::StateKey};
-use aptos_vm::data_cache::AsMoveResolver;
+use aptos_types::{
+    access_path::AccessPath,
+    state_store::{state_key::StateKey, state_key_prefix::StateKeyPrefix},
+};
+use aptos_vm::{data_cache::AsMoveResolver, AptosVM};
 use move_deps::move_core_types::{
     account_address::AccountAddress,
     identifier::Identifier,
-    language_storage::{ModuleId, ResourceKey, StructTag},
+    language_storage::{ModuleId, ResourceKey, StructTag, TypeTag},
 };
+use serde::Deserialize;
 use std::convert::TryInto;
 use storage_interface::state_view::DbStateView;
 use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
@@ -71,6 +78,40 @@ pub fn get_table_item(context: Context) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
+// GET /tables/<table_handle>/items?key_type={move_type}&value_type={move_type}&cursor={cursor}&limit={u16}
+pub fn get_table_items(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("tables" / TableHandleParam / "items")
+        .and(warp::get())
+        .and(warp::query::<TableItemsQuery>())
+        .and(warp::query::<StatePage>())
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|handle, query, page, ctx, version: Version| {
+            (version.version, handle, query, page, ctx)
+        })
+        .untuple_one()
+        .and_then(handle_get_table_items)
+        .with(metrics("get_table_items"))
+        .boxed()
+}
+
+// POST /view
+pub fn view_function(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("view")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::body::json::<ViewRequest>())
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|body, ctx, version: Version| (version.version, body, ctx))
+        .untuple_one()
+        .and_then(handle_view_function)
+        .with(metrics("view_function"))
+        .boxed()
+}
+
 async fn handle_get_account_resource(
     ledger_version: Option<LedgerVersionParam>,
     address: AddressParam,
@@ -111,10 +152,52 @@ async fn handle_get_table_item(
     Ok(State::new(ledger_version, context)?.table_item(handle.parse("table handle")?, body)?)
 }
 
+async fn handle_get_table_items(
+    ledger_version: Option<LedgerVersionParam>,
+    handle: TableHandleParam,
+    query: TableItemsQuery,
+    page: StatePage,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_table_items")?;
+    let key_type = parse_type_tag(query.key_type, "key_type")?;
+    let value_type = parse_type_tag(query.value_type, "value_type")?;
+    Ok(State::new(ledger_version, context)?.table_items(
+        handle.parse("table handle")?,
+        key_type,
+        value_type,
+        page,
+    )?)
+}
+
+async fn handle_view_function(
+    ledger_version: Option<LedgerVersionParam>,
+    body: ViewRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_view_function")?;
+    let max_gas = context.max_view_function_gas();
+    Ok(State::new(ledger_version, context)?.view_function(body, max_gas)?)
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct TableItemsQuery {
+    key_type: MoveTypeParam,
+    value_type: MoveTypeParam,
+}
+
+fn parse_type_tag(param: MoveTypeParam, name: &str) -> Result<TypeTag, Error> {
+    param
+        .parse(name)?
+        .try_into()
+        .map_err(|_| Error::invalid_param(name, "not a valid Move type"))
+}
+
 pub(crate) struct State {
     state_view: DbStateView,
     ledger_version: aptos_types::transaction::Version,
     latest_ledger_info: LedgerInfo,
+    context: Context,
 }
 
 impl State {
@@ -141,6 +224,7 @@ impl State {
             state_view,
             ledger_version,
             latest_ledger_info,
+            context,
         })
     }
 
@@ -209,4 +293,67 @@ impl State {
         let move_value = converter.try_into_move_value(&value_type, &bytes)?;
         Response::new(self.latest_ledger_info, &move_value)
     }
+
+    pub fn table_items(
+        self,
+        handle: u128,
+        key_type: TypeTag,
+        value_type: TypeTag,
+        page: StatePage,
+    ) -> Result<impl Reply, Error> {
+        let key_prefix = StateKeyPrefix::table(handle);
+        let first_key = page.cursor()?;
+        if let Some(key) = &first_key {
+            if !key_prefix.is_prefix(key)? {
+                return Err(Error::invalid_param("cursor", "not a cursor of the table"));
+            }
+        }
+        let (values, next_key) = self.context.get_state_value_chunk(
+            &key_prefix,
+            first_key.as_ref(),
+            page.limit()? as usize,
+            self.ledger_version,
+        )?;
+
+        let resolver = self.state_view.as_move_resolver();
+        let converter = resolver.as_converter();
+        let mut items = vec![];
+        for (state_key, state_value) in values {
+            if let (StateKey::TableItem { key, .. }, Some(bytes)) =
+                (state_key, state_value.maybe_bytes)
+            {
+                items.push(TableItem {
+                    key: converter.try_into_move_value(&key_type, &key)?,
+                    value: converter.try_into_move_value(&value_type, &bytes)?,
+                });
+            }
+        }
+        Ok(Response::new(self.latest_ledger_info, &items)?.with_cursor(encode_cursor(next_key)?))
+    }
+
+    pub fn view_function(self, body: ViewRequest, max_gas: u64) -> Result<impl Reply, Error> {
+        let resolver = self.state_view.as_move_resolver();
+        let converter = resolver.as_converter();
+        let function = converter
+            .try_into_view_function(body)
+            .map_err(|e| Error::invalid_request_body(format!("invalid ViewRequest: {}", e)))?;
+
+        let return_values = AptosVM::execute_view_function(
+            &self.state_view,
+            function.module,
+            function.function,
+            function.ty_args,
+            function.args,
+            max_gas,
+        )
+        .map_err(|status| Error::bad_request(format!("execution failed: {:?}", status)))?;
+
+        let values = function
+            .return_types
+            .iter()
+            .zip(return_values.iter())
+            .map(|(typ, bytes)| converter.try_into_move_value(typ, bytes))
+            .collect::<anyhow::Result<Vec<MoveValue>>>()?;
+        Response::new(self.latest_ledger_info, &values)
+    }
 }
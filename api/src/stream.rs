@@ -0,0 +1,325 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    context::Context,
+    failpoint::fail_point,
+    metrics::metrics,
+    param::{AddressParam, EventKeyParam, MoveTypeParam, TransactionVersionParam},
+};
+
+use aptos_api_types::{AsConverter, Error, Transaction, TransactionOnChainData, VersionedEvent};
+use aptos_types::{
+    account_address::AccountAddress, contract_event::ContractEvent, event::EventKey,
+};
+use move_deps::move_core_types::language_storage::TypeTag;
+
+use anyhow::Result;
+use serde::Deserialize;
+use std::{collections::VecDeque, convert::TryInto, time::Duration};
+use warp::{filters::BoxedFilter, sse, Filter, Rejection, Reply};
+
+/// How long the stream waits before checking the ledger again once it caught up.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// Maximum number of transactions read from the database per poll.
+const BATCH_SIZE: u16 = 100;
+/// Header set by SSE clients when they reconnect, holds the `id` of the last received message.
+const LAST_EVENT_ID: &str = "last-event-id";
+
+// GET /stream/transactions?start={u64}&address={address}&event_key={event_key}&event_type={move_type}
+pub fn stream_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("stream" / "transactions")
+        .and(warp::get())
+        .and(warp::query::<StreamFilterParams>())
+        .and(warp::header::optional::<TransactionVersionParam>(
+            LAST_EVENT_ID,
+        ))
+        .and(context.filter())
+        .and_then(handle_stream_transactions)
+        .with(metrics("stream_transactions"))
+        .boxed()
+}
+
+// GET /stream/events?start={u64}&address={address}&event_key={event_key}&event_type={move_type}
+pub fn stream_events(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("stream" / "events")
+        .and(warp::get())
+        .and(warp::query::<StreamFilterParams>())
+        .and(warp::header::optional::<TransactionVersionParam>(
+            LAST_EVENT_ID,
+        ))
+        .and(context.filter())
+        .and_then(handle_stream_events)
+        .with(metrics("stream_events"))
+        .boxed()
+}
+
+async fn handle_stream_transactions(
+    params: StreamFilterParams,
+    last_event_id: Option<TransactionVersionParam>,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_stream_transactions")?;
+    let stream = LedgerStream::new(StreamKind::Transactions, params, last_event_id, context)?;
+    Ok(stream.into_reply())
+}
+
+async fn handle_stream_events(
+    params: StreamFilterParams,
+    last_event_id: Option<TransactionVersionParam>,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_stream_events")?;
+    let stream = LedgerStream::new(StreamKind::Events, params, last_event_id, context)?;
+    Ok(stream.into_reply())
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct StreamFilterParams {
+    start: Option<TransactionVersionParam>,
+    address: Option<AddressParam>,
+    event_key: Option<EventKeyParam>,
+    event_type: Option<MoveTypeParam>,
+}
+
+/// Filter applied to committed transactions, all given conditions must hold.
+/// A transaction matches the `address` condition when it is sent by the address or
+/// emits an event created by the address.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct StreamFilter {
+    address: Option<AccountAddress>,
+    event_key: Option<EventKey>,
+    event_type: Option<TypeTag>,
+}
+
+impl StreamFilter {
+    pub fn new(
+        address: Option<AccountAddress>,
+        event_key: Option<EventKey>,
+        event_type: Option<TypeTag>,
+    ) -> Self {
+        Self {
+            address,
+            event_key,
+            event_type,
+        }
+    }
+
+    fn from_params(params: &StreamFilterParams) -> Result<Self, Error> {
+        let address = params
+            .address
+            .clone()
+            .map(|p| p.parse("address"))
+            .transpose()?
+            .map(Into::into);
+        let event_key = params
+            .event_key
+            .clone()
+            .map(|p| p.parse("event_key"))
+            .transpose()?
+            .map(Into::into);
+        let event_type = params
+            .event_type
+            .clone()
+            .map(|p| p.parse("event_type"))
+            .transpose()?
+            .map(|typ| {
+                typ.try_into()
+                    .map_err(|_| Error::invalid_param("event_type", "not a valid Move type"))
+            })
+            .transpose()?;
+        Ok(Self::new(address, event_key, event_type))
+    }
+
+    fn has_event_condition(&self) -> bool {
+        self.event_key.is_some() || self.event_type.is_some()
+    }
+
+    pub fn matches_event(&self, event: &ContractEvent) -> bool {
+        self.address
+            .map_or(true, |a| event.key().get_creator_address() == a)
+            && self.event_key.map_or(true, |k| event.key() == &k)
+            && self
+                .event_type
+                .as_ref()
+                .map_or(true, |t| event.type_tag() == t)
+    }
+
+    pub fn matches_transaction(&self, txn: &TransactionOnChainData) -> bool {
+        let sent_by_address = match (&self.address, &txn.transaction) {
+            (Some(address), aptos_types::transaction::Transaction::UserTransaction(t)) => {
+                t.sender() == *address
+            }
+            _ => false,
+        };
+        // the sender already satisfies the address condition, only the event conditions remain
+        let event_filter = if sent_by_address {
+            Self::new(None, self.event_key, self.event_type.clone())
+        } else {
+            self.clone()
+        };
+        if event_filter.address.is_none() && !event_filter.has_event_condition() {
+            return true;
+        }
+        txn.events.iter().any(|e| event_filter.matches_event(e))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum StreamKind {
+    Transactions,
+    Events,
+}
+
+/// One message of the stream. `version` is only set on the last message produced for a
+/// transaction and is sent as the SSE message `id`, so that a client resuming with the
+/// `Last-Event-ID` header never skips a partially delivered transaction.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct StreamItem {
+    pub name: &'static str,
+    pub version: Option<u64>,
+    pub data: serde_json::Value,
+}
+
+/// Tails the committed ledger starting at `next_version`, yielding the transactions or events
+/// that match the filter.
+pub(crate) struct LedgerStream {
+    kind: StreamKind,
+    filter: StreamFilter,
+    next_version: u64,
+    context: Context,
+    pending: VecDeque<StreamItem>,
+    done: bool,
+}
+
+impl LedgerStream {
+    fn new(
+        kind: StreamKind,
+        params: StreamFilterParams,
+        last_event_id: Option<TransactionVersionParam>,
+        context: Context,
+    ) -> Result<Self, Error> {
+        let filter = StreamFilter::from_params(&params)?;
+        // Resuming from `Last-Event-ID` takes precedence over the `start` parameter, without
+        // either the stream starts right after the latest committed version.
+        let next_version = match (last_event_id, params.start) {
+            (Some(id), _) => id.parse(LAST_EVENT_ID)?.saturating_add(1),
+            (None, Some(start)) => start.parse("start")?,
+            (None, None) => context.get_latest_ledger_info()?.version() + 1,
+        };
+        Ok(Self::with_filter(kind, filter, next_version, context))
+    }
+
+    pub fn with_filter(
+        kind: StreamKind,
+        filter: StreamFilter,
+        next_version: u64,
+        context: Context,
+    ) -> Self {
+        Self {
+            kind,
+            filter,
+            next_version,
+            context,
+            pending: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    pub fn next_version(&self) -> u64 {
+        self.next_version
+    }
+
+    /// Reads the next batch of committed transactions, returns the matching items.
+    /// The result is empty when there is nothing new in the ledger, or when none of the
+    /// transactions read matches the filter; `next_version` tells both cases apart.
+    pub fn poll(&mut self) -> Result<Vec<StreamItem>, Error> {
+        let ledger_version = self.context.get_latest_ledger_info()?.version();
+        if self.next_version > ledger_version {
+            return Ok(vec![]);
+        }
+        let limit = std::cmp::min(BATCH_SIZE as u64, ledger_version - self.next_version + 1);
+        let txns =
+            self.context
+                .get_transactions(self.next_version, limit as u16, ledger_version)?;
+
+        let mut timestamp = self.context.get_block_timestamp(self.next_version)?;
+        let resolver = self.context.move_resolver()?;
+        let converter = resolver.as_converter();
+        let mut items = vec![];
+        for txn in txns {
+            let version = txn.version;
+            // keep track of the block timestamp for the transactions filtered out as well
+            if let aptos_types::transaction::Transaction::BlockMetadata(metadata) = &txn.transaction
+            {
+                timestamp = metadata.timestamp_usecs();
+            }
+            match self.kind {
+                StreamKind::Transactions => {
+                    if self.filter.matches_transaction(&txn) {
+                        let txn: Transaction =
+                            converter.try_into_onchain_transaction(timestamp, txn)?;
+                        items.push(StreamItem {
+                            name: "transaction",
+                            version: Some(version),
+                            data: serde_json::to_value(&txn)?,
+                        });
+                    }
+                }
+                StreamKind::Events => {
+                    let events: Vec<ContractEvent> = txn
+                        .events
+                        .into_iter()
+                        .filter(|e| self.filter.matches_event(e))
+                        .collect();
+                    let count = events.len();
+                    for (i, event) in converter.try_into_events(&events)?.into_iter().enumerate() {
+                        items.push(StreamItem {
+                            name: "event",
+                            version: if i + 1 == count { Some(version) } else { None },
+                            data: serde_json::to_value(&VersionedEvent {
+                                version: version.into(),
+                                event,
+                            })?,
+                        });
+                    }
+                }
+            }
+            self.next_version = version + 1;
+        }
+        Ok(items)
+    }
+
+    fn into_reply(self) -> impl Reply {
+        let stream = futures::stream::unfold(self, |mut stream| async move {
+            loop {
+                if stream.done {
+                    return None;
+                }
+                if let Some(item) = stream.pending.pop_front() {
+                    let mut event = sse::Event::default().event(item.name);
+                    if let Some(version) = item.version {
+                        event = event.id(version.to_string());
+                    }
+                    return Some((event.json_data(&item.data), stream));
+                }
+                let from_version = stream.next_version();
+                match stream.poll() {
+                    Ok(items) => {
+                        stream.pending.extend(items);
+                        // only wait when we have caught up with the ledger
+                        if stream.next_version() == from_version {
+                            tokio::time::sleep(POLL_INTERVAL).await;
+                        }
+                    }
+                    Err(err) => {
+                        stream.done = true;
+                        let event = sse::Event::default().event("error").json_data(&err);
+                        return Some((event, stream));
+                    }
+                }
+            }
+        });
+        sse::reply(sse::keep_alive().stream(stream))
+    }
+}
@@ -3,9 +3,11 @@
 
 use crate::{
     current_function_name,
-    tests::{find_value, new_test_context},
+    tests::{find_value, new_test_context, new_test_context_with_config, TestContext},
 };
-use serde_json::json;
+use aptos_api_types::X_APTOS_CURSOR;
+use aptos_config::config::ApiConfig;
+use serde_json::{json, Value};
 
 #[tokio::test]
 async fn test_get_account_resources_returns_empty_array_for_account_has_no_resources() {
@@ -204,6 +206,106 @@ async fn test_get_core_account_data_not_found() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_account_resources_by_page() {
+    let context = new_test_context(current_function_name!());
+    let address = "0xA550C18";
+
+    let all = context.get(&account_resources(address)).await;
+    let paged = get_all_pages(&context, &account_resources(address), 2).await;
+    assert!(all.as_array().unwrap().len() > 2);
+    assert_eq!(json!(paged), all);
+}
+
+#[tokio::test]
+async fn test_get_account_modules_by_page() {
+    let context = new_test_context(current_function_name!());
+    let address = "0x1";
+
+    let all = context.get(&account_modules(address)).await;
+    let paged = get_all_pages(&context, &account_modules(address), 10).await;
+    assert!(all.as_array().unwrap().len() > 10);
+    assert_eq!(json!(paged), all);
+}
+
+#[tokio::test]
+async fn test_get_account_resources_by_invalid_cursor() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(400)
+        .get(&format!("{}?cursor=0xzz", account_resources("0xA550C18")))
+        .await;
+
+    // cursor of another account
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path(&format!("{}?limit=1", account_resources("0xA550C18"))),
+        )
+        .await;
+    let cursor = resp.headers()[X_APTOS_CURSOR].to_str().unwrap().to_owned();
+    context
+        .expect_status_code(400)
+        .get(&format!("{}?cursor={}", account_resources("0x1"), cursor))
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_account_resources_over_unpaginated_limit() {
+    let context = new_test_context_with_config(
+        current_function_name!(),
+        ApiConfig {
+            max_unpaginated_account_state_values: Some(2),
+            ..ApiConfig::default()
+        },
+    );
+    let address = "0xA550C18";
+
+    context
+        .expect_status_code(500)
+        .get(&account_resources(address))
+        .await;
+    // the account can still be listed by pages
+    let paged = get_all_pages(&context, &account_resources(address), 2).await;
+    assert!(paged.len() > 2);
+}
+
+#[tokio::test]
+async fn test_get_account_resources_by_page_address_not_found() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(404)
+        .get(&format!("{}?limit=10", account_resources("0xf")))
+        .await;
+}
+
+/// Follows the `X-Aptos-Cursor` header until the last page, returns the items of all the pages.
+async fn get_all_pages(context: &TestContext, path: &str, limit: u16) -> Vec<Value> {
+    let mut items = vec![];
+    let mut cursor: Option<String> = None;
+    loop {
+        let page_path = match &cursor {
+            Some(cursor) => format!("{}?limit={}&cursor={}", path, limit, cursor),
+            None => format!("{}?limit={}", path, limit),
+        };
+        let resp = context
+            .reply(warp::test::request().method("GET").path(&page_path))
+            .await;
+        assert_eq!(resp.status(), 200);
+        let page: Vec<Value> = serde_json::from_slice(resp.body()).unwrap();
+        assert!(page.len() <= limit as usize);
+        items.extend(page);
+        cursor = resp
+            .headers()
+            .get(X_APTOS_CURSOR)
+            .map(|cursor| cursor.to_str().unwrap().to_owned());
+        if cursor.is_none() {
+            return items;
+        }
+    }
+}
+
 fn account_resources(address: &str) -> String {
     format!("/accounts/{}/resources", address)
 }
@@ -0,0 +1,93 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{current_function_name, tests::new_test_context};
+
+#[tokio::test]
+async fn test_get_genesis_block_by_height() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context.get("/blocks/by_height/0").await;
+    assert_eq!(resp["block_height"], "0");
+    assert_eq!(resp["first_version"], "0");
+    assert_eq!(resp["block_timestamp"], "0");
+    assert_eq!(
+        resp["last_version"],
+        context.get_latest_ledger_info().version().to_string()
+    );
+    assert!(resp.get("transactions").is_none());
+}
+
+#[tokio::test]
+async fn test_get_block_by_version_with_transactions() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let first_version = context.get_latest_ledger_info().version() + 1;
+    context.commit_block(&vec![txn.clone()]).await;
+    let last_version = context.get_latest_ledger_info().version();
+
+    let resp = context
+        .get(&format!(
+            "/blocks/by_version/{}?with_transactions=true",
+            first_version + 1
+        ))
+        .await;
+    assert_eq!(resp["block_height"], "1");
+    assert_eq!(resp["first_version"], first_version.to_string());
+    assert_eq!(resp["last_version"], last_version.to_string());
+    assert_eq!(resp["proposer"], context.validator_owner.to_hex_literal());
+
+    let txns = resp["transactions"].as_array().unwrap();
+    assert_eq!(txns.len() as u64, last_version - first_version + 1);
+    assert_eq!(txns[0]["type"], "block_metadata_transaction");
+    assert_eq!(txns[1]["hash"], txn.committed_hash().to_hex_literal());
+    for txn in txns {
+        assert_eq!(txn["timestamp"], resp["block_timestamp"]);
+    }
+
+    let mut by_height = context
+        .get("/blocks/by_height/1?with_transactions=true")
+        .await;
+    assert_eq!(by_height, resp);
+
+    // the transactions are only rendered when requested
+    by_height = context.get("/blocks/by_height/1").await;
+    assert!(by_height.get("transactions").is_none());
+}
+
+#[tokio::test]
+async fn test_get_block_by_height_not_found() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .expect_status_code(404)
+        .get("/blocks/by_height/100")
+        .await;
+    assert_eq!(resp["message"], "block not found by height(100)");
+}
+
+#[tokio::test]
+async fn test_get_block_by_version_not_found() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .expect_status_code(404)
+        .get(&format!(
+            "/blocks/by_version/{}",
+            context.get_latest_ledger_info().version() + 1
+        ))
+        .await;
+    assert_eq!(resp["code"], 404);
+}
+
+#[tokio::test]
+async fn test_get_block_by_invalid_height() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .expect_status_code(400)
+        .get("/blocks/by_height/invalid")
+        .await;
+    assert_eq!(resp["message"], "invalid parameter block height: invalid");
+}
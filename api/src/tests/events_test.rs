@@ -1,8 +1,12 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{current_function_name, tests::new_test_context};
+use crate::{
+    current_function_name,
+    tests::{new_test_context, new_test_context_with_event_by_type_index},
+};
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde_json::json;
 
 static EVENT_KEY: &str =
     "0x0500000000000000000000000000000000000000000000000000000000000000000000000a550c18";
@@ -130,3 +134,65 @@ async fn test_get_events_by_struct_type_has_generic_type_parameter() {
     let resp = context.expect_status_code(404).get(path.as_str()).await;
     context.check_golden_output(resp);
 }
+
+#[tokio::test]
+async fn test_get_events_by_type() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .get("/events/by_type/0x1::Reconfiguration::NewEpochEvent")
+        .await;
+    assert_eq!(
+        resp,
+        json!([{
+            "version": "0",
+            "key": EVENT_KEY,
+            "sequence_number": "0",
+            "type": "0x1::Reconfiguration::NewEpochEvent",
+            "data": {
+                "epoch": "1"
+            }
+        }])
+    );
+
+    // genesis is the only version emitting the event
+    let resp = context
+        .get("/events/by_type/0x1::Reconfiguration::NewEpochEvent?start=1")
+        .await;
+    assert_eq!(resp, json!([]));
+}
+
+#[tokio::test]
+async fn test_get_events_by_invalid_type() {
+    let context = new_test_context(current_function_name!());
+
+    context
+        .expect_status_code(400)
+        .get("/events/by_type/0x1::Reconfiguration")
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_events_by_type_index_enabled_after_genesis() {
+    let context = new_test_context_with_event_by_type_index(current_function_name!(), false, true);
+
+    // the index starts after genesis
+    let resp = context
+        .get("/events/by_type/0x1::Reconfiguration::NewEpochEvent")
+        .await;
+    assert_eq!(resp, json!([]));
+    context
+        .expect_status_code(400)
+        .get("/events/by_type/0x1::Reconfiguration::NewEpochEvent?start=0")
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_events_by_type_index_disabled() {
+    let context = new_test_context_with_event_by_type_index(current_function_name!(), false, false);
+
+    context
+        .expect_status_code(404)
+        .get("/events/by_type/0x1::Reconfiguration::NewEpochEvent")
+        .await;
+}
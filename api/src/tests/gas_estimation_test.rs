@@ -0,0 +1,140 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    current_function_name, gas_estimation::estimate_gas_unit_prices, tests::new_test_context,
+};
+use aptos_api_types::HexEncodedBytes;
+use serde_json::{json, Value};
+
+fn as_u64(value: &Value) -> u64 {
+    value.as_str().unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn test_estimate_gas_price() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let resp = context.get("/estimate_gas_price").await;
+    let deprioritized = as_u64(&resp["deprioritized_gas_unit_price"]);
+    let regular = as_u64(&resp["gas_unit_price"]);
+    let prioritized = as_u64(&resp["prioritized_gas_unit_price"]);
+    assert!(deprioritized <= regular);
+    assert!(regular <= prioritized);
+}
+
+#[tokio::test]
+async fn test_estimate_gas_usage() {
+    let context = new_test_context(current_function_name!());
+    let root_account = context.root_account();
+    let new_account = context.gen_account();
+
+    let resp = context
+        .post(
+            "/estimate_gas",
+            json!({
+                "sender": root_account.address().to_hex_literal(),
+                "public_key": HexEncodedBytes::from(root_account.public_key().to_bytes().to_vec()),
+                "payload": {
+                    "type": "script_function_payload",
+                    "function": "0x1::Account::create_account",
+                    "type_arguments": [],
+                    "arguments": [new_account.address().to_hex_literal()]
+                }
+            }),
+        )
+        .await;
+    let gas_used = as_u64(&resp["gas_used"]);
+    assert!(gas_used > 0);
+    assert!(as_u64(&resp["max_gas_amount"]) >= gas_used);
+    as_u64(&resp["gas_unit_price"]);
+}
+
+#[tokio::test]
+async fn test_estimate_gas_usage_with_failed_payload() {
+    let context = new_test_context(current_function_name!());
+    let root_account = context.root_account();
+
+    // the account already exists, creating it again aborts
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/estimate_gas",
+            json!({
+                "sender": root_account.address().to_hex_literal(),
+                "public_key": HexEncodedBytes::from(root_account.public_key().to_bytes().to_vec()),
+                "payload": {
+                    "type": "script_function_payload",
+                    "function": "0x1::Account::create_account",
+                    "type_arguments": [],
+                    "arguments": [root_account.address().to_hex_literal()]
+                }
+            }),
+        )
+        .await;
+    assert!(resp["message"]
+        .as_str()
+        .unwrap()
+        .contains("execution failed"));
+}
+
+#[tokio::test]
+async fn test_estimate_gas_usage_rejects_non_entry_function_payloads() {
+    let context = new_test_context(current_function_name!());
+    let root_account = context.root_account();
+
+    context
+        .expect_status_code(400)
+        .post(
+            "/estimate_gas",
+            json!({
+                "sender": root_account.address().to_hex_literal(),
+                "public_key": HexEncodedBytes::from(root_account.public_key().to_bytes().to_vec()),
+                "payload": {
+                    "type": "module_bundle_payload",
+                    "modules": []
+                }
+            }),
+        )
+        .await;
+}
+
+#[test]
+fn test_estimate_gas_unit_prices() {
+    // nothing committed or pending, falls back to the minimum price
+    let estimation = estimate_gas_unit_prices(vec![], &[], 1);
+    assert_eq!(
+        serde_json::to_value(&estimation).unwrap(),
+        json!({
+            "deprioritized_gas_unit_price": "1",
+            "gas_unit_price": "1",
+            "prioritized_gas_unit_price": "1",
+        })
+    );
+
+    let committed = (1..=100).rev().collect();
+    let estimation = estimate_gas_unit_prices(committed, &[], 0);
+    assert_eq!(
+        serde_json::to_value(&estimation).unwrap(),
+        json!({
+            "deprioritized_gas_unit_price": "25",
+            "gas_unit_price": "50",
+            "prioritized_gas_unit_price": "90",
+        })
+    );
+
+    // a full block of pending transactions has to be outbid
+    let pending = vec![200; 1000];
+    let estimation = estimate_gas_unit_prices(vec![1, 2, 3], &pending, 0);
+    assert_eq!(
+        serde_json::to_value(&estimation).unwrap(),
+        json!({
+            "deprioritized_gas_unit_price": "1",
+            "gas_unit_price": "2",
+            "prioritized_gas_unit_price": "201",
+        })
+    );
+}
@@ -2,19 +2,26 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod accounts_test;
+mod blocks_test;
 mod converter_test;
 mod events_test;
+mod gas_estimation_test;
 mod golden_output;
 mod index_test;
 mod invalid_post_request_test;
 mod state_test;
+mod stream_test;
 mod string_resource_test;
 mod test_context;
 mod transaction_vector_test;
 mod transactions_test;
+mod view_test;
 
 use serde_json::Value;
-pub use test_context::{new_test_context, TestContext};
+pub use test_context::{
+    new_test_context, new_test_context_with_config, new_test_context_with_event_by_type_index,
+    TestContext,
+};
 
 pub fn find_value(val: &Value, filter: for<'r> fn(&'r &Value) -> bool) -> Value {
     let resources = val
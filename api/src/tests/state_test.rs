@@ -174,6 +174,15 @@ async fn test_get_table_item() {
     )
     .await;
     assert_table_item(ctx, &nested_table, "u8", "u8", 2, 3).await;
+
+    let handle: u128 = tt["u64_table"]["handle"].as_str().unwrap().parse().unwrap();
+    let items = ctx
+        .get(&format!(
+            "{}?key_type=u64&value_type=u64&limit=10",
+            get_table_items(handle)
+        ))
+        .await;
+    assert_eq!(items, json!([{"key": "1", "value": "1"}]));
 }
 
 fn get_account_resource(address: &str, struct_tag: &str) -> String {
@@ -188,6 +197,10 @@ fn get_table_item(handle: u128) -> String {
     format!("/tables/{}/item", handle)
 }
 
+fn get_table_items(handle: u128) -> String {
+    format!("/tables/{}/items", handle)
+}
+
 async fn make_test_tables(ctx: &mut TestContext, account: &mut LocalAccount) {
     let module = build_test_module(account.address()).await;
 
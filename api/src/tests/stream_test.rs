@@ -0,0 +1,111 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    current_function_name,
+    stream::{LedgerStream, StreamFilter, StreamKind},
+    tests::new_test_context,
+};
+use aptos_api_types::{EventKey, MoveType};
+use move_deps::move_core_types::language_storage::TypeTag;
+use std::{convert::TryInto, str::FromStr};
+
+static EVENT_KEY: &str =
+    "0x0500000000000000000000000000000000000000000000000000000000000000000000000a550c18";
+
+#[tokio::test]
+async fn test_stream_transactions_filtered_by_sender() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let start = context.get_latest_ledger_info().version() + 1;
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let filter = StreamFilter::new(Some(context.root_account().address()), None, None);
+    let mut stream = LedgerStream::with_filter(
+        StreamKind::Transactions,
+        filter,
+        start,
+        context.context.clone(),
+    );
+    let items = stream.poll().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "transaction");
+    assert_eq!(items[0].data["type"], "user_transaction");
+    assert_eq!(items[0].data["hash"], txn.committed_hash().to_hex_literal());
+
+    // the block metadata and state checkpoint transactions are consumed but filtered out
+    let ledger_version = context.get_latest_ledger_info().version();
+    assert_eq!(stream.next_version(), ledger_version + 1);
+    assert!(stream.poll().unwrap().is_empty());
+    assert_eq!(stream.next_version(), ledger_version + 1);
+}
+
+#[tokio::test]
+async fn test_stream_transactions_without_filter_resumes_from_start() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    let mut stream = LedgerStream::with_filter(
+        StreamKind::Transactions,
+        StreamFilter::default(),
+        ledger_version,
+        context.context.clone(),
+    );
+    let items = stream.poll().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].version, Some(ledger_version));
+    assert_eq!(items[0].data["version"], ledger_version.to_string());
+}
+
+#[tokio::test]
+async fn test_stream_events_filtered_by_event_key() {
+    let context = new_test_context(current_function_name!());
+
+    let filter = StreamFilter::new(
+        None,
+        Some(EventKey::from_str(EVENT_KEY).unwrap().into()),
+        None,
+    );
+    let mut stream =
+        LedgerStream::with_filter(StreamKind::Events, filter, 0, context.context.clone());
+    let items = stream.poll().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "event");
+    assert_eq!(items[0].version, Some(0));
+    assert_eq!(items[0].data["version"], "0");
+    assert_eq!(items[0].data["key"], EVENT_KEY);
+    assert_eq!(items[0].data["type"], "0x1::Reconfiguration::NewEpochEvent");
+}
+
+#[tokio::test]
+async fn test_stream_events_filtered_by_event_type() {
+    let context = new_test_context(current_function_name!());
+
+    let typ: TypeTag = MoveType::from_str("0x1::Reconfiguration::NewEpochEvent")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let filter = StreamFilter::new(None, None, Some(typ));
+    let mut stream =
+        LedgerStream::with_filter(StreamKind::Events, filter, 0, context.context.clone());
+    let items = stream.poll().unwrap();
+    assert!(!items.is_empty());
+    for item in items {
+        assert_eq!(item.data["type"], "0x1::Reconfiguration::NewEpochEvent");
+    }
+}
+
+#[tokio::test]
+async fn test_stream_transactions_by_invalid_event_type() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .expect_status_code(400)
+        .get("/stream/transactions?event_type=invalid%3A%3Atype")
+        .await;
+    assert_eq!(resp["code"], 400);
+}
@@ -6,7 +6,7 @@ use aptos_api_types::{
     mime_types, HexEncodedBytes, TransactionOnChainData, X_APTOS_CHAIN_ID,
     X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION,
 };
-use aptos_config::config::ApiConfig;
+use aptos_config::config::{ApiConfig, RocksdbConfig, NO_OP_STORAGE_PRUNER_CONFIG};
 use aptos_crypto::{hash::HashValue, SigningKey};
 use aptos_mempool::mocks::MockSharedMempool;
 use aptos_sdk::{
@@ -46,6 +46,45 @@ use vm_validator::vm_validator::VMValidator;
 use warp::http::header::CONTENT_TYPE;
 
 pub fn new_test_context(test_name: &'static str) -> TestContext {
+    new_test_context_with_config(test_name, ApiConfig::default())
+}
+
+pub fn new_test_context_with_config(test_name: &'static str, api_config: ApiConfig) -> TestContext {
+    new_test_context_impl(test_name, api_config, true, true)
+}
+
+/// Like `new_test_context`, the events are indexed by type from genesis if `index_genesis`, and
+/// after genesis if `index_after_genesis`.
+pub fn new_test_context_with_event_by_type_index(
+    test_name: &'static str,
+    index_genesis: bool,
+    index_after_genesis: bool,
+) -> TestContext {
+    new_test_context_impl(
+        test_name,
+        ApiConfig::default(),
+        index_genesis,
+        index_after_genesis,
+    )
+}
+
+fn open_test_db(tmp_dir: &TempPath, enable_event_by_type_index: bool) -> AptosDB {
+    AptosDB::open(
+        tmp_dir,
+        false, /* readonly */
+        NO_OP_STORAGE_PRUNER_CONFIG,
+        RocksdbConfig::default(),
+        enable_event_by_type_index,
+    )
+    .expect("Unable to open AptosDB")
+}
+
+fn new_test_context_impl(
+    test_name: &'static str,
+    api_config: ApiConfig,
+    index_genesis: bool,
+    index_after_genesis: bool,
+) -> TestContext {
     let tmp_dir = TempPath::new();
     tmp_dir.create_as_dir().unwrap();
 
@@ -62,10 +101,18 @@ pub fn new_test_context(test_name: &'static str) -> TestContext {
     let (validator_identity, _, _) = validators[0].get_key_objects(None).unwrap();
     let validator_owner = validator_identity.account_address.unwrap();
 
-    let (db, db_rw) = DbReaderWriter::wrap(AptosDB::new_for_test(&tmp_dir));
+    let (db, db_rw) = DbReaderWriter::wrap(open_test_db(&tmp_dir, index_genesis));
     let ret =
         db_bootstrapper::maybe_bootstrap::<AptosVM>(&db_rw, &genesis, genesis_waypoint).unwrap();
     assert!(ret);
+    let (db, db_rw) = if index_genesis == index_after_genesis {
+        (db, db_rw)
+    } else {
+        // reopen the DB with the other config
+        drop(db_rw);
+        drop(db);
+        DbReaderWriter::wrap(open_test_db(&tmp_dir, index_after_genesis))
+    };
 
     let mempool = MockSharedMempool::new_in_runtime(&db_rw, VMValidator::new(db.clone()));
 
@@ -74,7 +121,7 @@ pub fn new_test_context(test_name: &'static str) -> TestContext {
             ChainId::test(),
             db.clone(),
             mempool.ac_client.clone(),
-            ApiConfig::default(),
+            api_config,
         ),
         rng,
         root_key,
@@ -410,6 +410,28 @@ async fn test_multi_ed25519_signed_transaction() {
         .await;
 }
 
+#[tokio::test]
+async fn test_get_pending_transactions() {
+    let mut context = new_test_context(current_function_name!());
+    let resp = context.get("/transactions/pending").await;
+    assert_json(resp, json!([]));
+
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let body = bcs::to_bytes(&txn).unwrap();
+    let pending_txn = context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", body)
+        .await;
+
+    let resp = context.get("/transactions/pending?limit=10").await;
+    assert_json(resp, json!([pending_txn]));
+
+    context.commit_mempool_txns(1).await;
+    let resp = context.get("/transactions/pending").await;
+    assert_json(resp, json!([]));
+}
+
 #[tokio::test]
 async fn test_get_transaction_by_hash() {
     let mut context = new_test_context(current_function_name!());
@@ -0,0 +1,92 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{current_function_name, tests::new_test_context};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_view_function() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let resp = context
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::Coin::balance",
+                "type_arguments": ["0x1::TestCoin::TestCoin"],
+                "arguments": [account.address().to_hex_literal()],
+            }),
+        )
+        .await;
+    assert_eq!(resp, json!(["0"]));
+
+    let resp = context
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::Timestamp::now_microseconds",
+                "type_arguments": [],
+                "arguments": [],
+            }),
+        )
+        .await;
+    assert!(resp[0].as_str().unwrap().parse::<u64>().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn test_view_function_not_found() {
+    let mut context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::Coin::does_not_exist",
+                "type_arguments": [],
+                "arguments": [],
+            }),
+        )
+        .await;
+    assert_eq!(resp["code"], 400);
+}
+
+#[tokio::test]
+async fn test_view_function_with_wrong_type_arguments() {
+    let mut context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::Coin::balance",
+                "type_arguments": [],
+                "arguments": ["0x1"],
+            }),
+        )
+        .await;
+    assert_eq!(resp["code"], 400);
+}
+
+#[tokio::test]
+async fn test_view_function_abort() {
+    let mut context = new_test_context(current_function_name!());
+    // the account has no CoinStore, `balance` aborts
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::Coin::balance",
+                "type_arguments": ["0x1::TestCoin::TestCoin"],
+                "arguments": ["0x12345"],
+            }),
+        )
+        .await;
+    assert!(resp["message"]
+        .as_str()
+        .unwrap()
+        .contains("execution failed"));
+}
@@ -52,6 +52,17 @@ pub fn get_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
+// GET /transactions/pending?limit={u16}
+pub fn get_pending_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("transactions" / "pending")
+        .and(warp::get())
+        .and(warp::query::<Page>())
+        .and(context.filter())
+        .and_then(handle_get_pending_transactions)
+        .with(metrics("get_pending_transactions"))
+        .boxed()
+}
+
 // GET /accounts/{address}/transactions?start={u64}&limit={u16}
 pub fn get_account_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "transactions")
@@ -161,6 +172,14 @@ async fn handle_get_transactions(page: Page, context: Context) -> Result<impl Re
     Ok(Transactions::new(context)?.list(page)?)
 }
 
+async fn handle_get_pending_transactions(
+    page: Page,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_pending_transactions")?;
+    Ok(Transactions::new(context)?.list_pending(page).await?)
+}
+
 async fn handle_get_account_transactions(
     address: AddressParam,
     page: Page,
@@ -351,6 +370,20 @@ impl Transactions {
         self.render_transactions(data)
     }
 
+    pub async fn list_pending(self, page: Page) -> Result<impl Reply, Error> {
+        let data = self
+            .context
+            .get_pending_transactions(page.limit()? as usize)
+            .await?;
+        let resolver = self.context.move_resolver()?;
+        let converter = resolver.as_converter();
+        let txns: Vec<Transaction> = data
+            .into_iter()
+            .map(|t| converter.try_into_pending_transaction(t))
+            .collect::<Result<_>>()?;
+        Response::new(self.ledger_info, &txns)
+    }
+
     fn render_transactions(self, data: Vec<TransactionOnChainData>) -> Result<impl Reply, Error> {
         if data.is_empty() {
             let txns: Vec<Transaction> = vec![];
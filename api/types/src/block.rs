@@ -0,0 +1,64 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, HashValue, Transaction, U64};
+
+use aptos_types::{account_address::AccountAddress, block_metadata::BlockMetadata};
+
+use serde::{Deserialize, Serialize};
+
+/// A committed block: the block metadata transaction and the transactions following it, up to
+/// the next block metadata transaction.
+/// The genesis transaction is the block at height 0, it has a zero `block_hash`, is proposed by
+/// the `0x0` address and is timestamped at 0.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Block {
+    pub block_height: U64,
+    pub block_hash: HashValue,
+    pub block_timestamp: U64,
+    pub epoch: U64,
+    pub round: U64,
+    pub proposer: Address,
+    pub first_version: U64,
+    pub last_version: U64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transactions: Option<Vec<Transaction>>,
+}
+
+impl Block {
+    pub fn new(
+        height: u64,
+        first_version: u64,
+        last_version: u64,
+        metadata: Option<&BlockMetadata>,
+        transactions: Option<Vec<Transaction>>,
+    ) -> Self {
+        let (block_hash, block_timestamp, epoch, round, proposer) = match metadata {
+            Some(m) => (
+                m.id(),
+                m.timestamp_usecs(),
+                m.epoch(),
+                m.round(),
+                m.proposer(),
+            ),
+            None => (
+                aptos_crypto::HashValue::zero(),
+                0,
+                0,
+                0,
+                AccountAddress::ZERO,
+            ),
+        };
+        Self {
+            block_height: height.into(),
+            block_hash: block_hash.into(),
+            block_timestamp: block_timestamp.into(),
+            epoch: epoch.into(),
+            round: round.into(),
+            proposer: proposer.into(),
+            first_version: first_version.into(),
+            last_version: last_version.into(),
+            transactions,
+        }
+    }
+}
@@ -4,9 +4,10 @@
 use crate::{
     transaction::{ModuleBundlePayload, StateCheckpointTransaction},
     Bytecode, DirectWriteSet, Event, HexEncodedBytes, MoveFunction, MoveModuleBytecode,
-    MoveResource, MoveScriptBytecode, MoveValue, ScriptFunctionId, ScriptFunctionPayload,
+    MoveResource, MoveScriptBytecode, MoveType, MoveValue, ScriptFunctionId, ScriptFunctionPayload,
     ScriptPayload, ScriptWriteSet, Transaction, TransactionInfo, TransactionOnChainData,
-    TransactionPayload, UserTransactionRequest, WriteSet, WriteSetChange, WriteSetPayload,
+    TransactionPayload, UserTransactionRequest, ViewFunction, ViewRequest, WriteSet,
+    WriteSetChange, WriteSetPayload,
 };
 use anyhow::{bail, ensure, format_err, Result};
 use aptos_crypto::{hash::CryptoHash, HashValue};
@@ -421,6 +422,80 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
         Ok(ret)
     }
 
+    pub fn try_into_view_function(&self, req: ViewRequest) -> Result<ViewFunction> {
+        let ViewRequest {
+            function,
+            type_arguments,
+            arguments,
+        } = req;
+
+        let module = function.module.clone();
+        let code = self.inner.get_module(&module.clone().into())? as Rc<dyn Bytecode>;
+        let func = code
+            .find_public_function(function.name.as_ident_str())
+            .ok_or_else(|| format_err!("could not find public function by {}", function))?;
+        ensure!(
+            func.generic_type_params.len() == type_arguments.len(),
+            "expect {} type arguments for function {}, but got {}",
+            func.generic_type_params.len(),
+            function,
+            type_arguments.len()
+        );
+        ensure!(
+            !func.params.iter().any(|p| p.is_signer()),
+            "function {} takes a signer, which can not be provided without a transaction",
+            function
+        );
+        let ty_args = type_arguments
+            .into_iter()
+            .map(|v| v.try_into())
+            .collect::<Result<Vec<TypeTag>>>()?;
+        let return_types = func
+            .return_
+            .iter()
+            .map(|typ| Self::substitute_type_params(typ, &ty_args))
+            .collect::<Result<_>>()?;
+        let args = self
+            .try_into_vm_values(func, arguments)?
+            .iter()
+            .map(bcs::to_bytes)
+            .collect::<Result<_, bcs::Error>>()?;
+
+        Ok(ViewFunction {
+            module: module.into(),
+            function: function.name,
+            ty_args,
+            args,
+            return_types,
+        })
+    }
+
+    // Converts a type from a function signature into a `TypeTag`, replacing the generic type
+    // parameters by the type arguments of the call.
+    fn substitute_type_params(typ: &MoveType, ty_args: &[TypeTag]) -> Result<TypeTag> {
+        Ok(match typ {
+            MoveType::GenericTypeParam { index } => ty_args
+                .get(*index as usize)
+                .cloned()
+                .ok_or_else(|| format_err!("type parameter {} is out of bound", index))?,
+            MoveType::Vector { items } => {
+                TypeTag::Vector(Box::new(Self::substitute_type_params(items, ty_args)?))
+            }
+            MoveType::Struct(tag) => TypeTag::Struct(StructTag {
+                address: tag.address.into(),
+                module: tag.module.clone(),
+                name: tag.name.clone(),
+                type_params: tag
+                    .generic_type_params
+                    .iter()
+                    .map(|t| Self::substitute_type_params(t, ty_args))
+                    .collect::<Result<_>>()?,
+            }),
+            MoveType::Reference { .. } => bail!("unsupported reference type {}", typ),
+            _ => typ.clone().try_into()?,
+        })
+    }
+
     pub fn try_into_vm_values(
         &self,
         func: MoveFunction,
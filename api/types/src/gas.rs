@@ -0,0 +1,38 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, HexEncodedBytes, TransactionPayload, U64};
+use serde::{Deserialize, Serialize};
+
+/// Recommended gas unit prices, derived from the recently committed transactions and the
+/// transactions waiting in mempool.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GasEstimation {
+    /// Price for transactions that can wait for a less busy network.
+    pub deprioritized_gas_unit_price: U64,
+    /// Price expected to get the transaction into one of the next blocks.
+    pub gas_unit_price: U64,
+    /// Price expected to get the transaction into the next block.
+    pub prioritized_gas_unit_price: U64,
+}
+
+/// Entry function payload to estimate the gas usage of. The `public_key` is the Ed25519
+/// public key of the sender, it is used to pass the authentication key check when executing
+/// the payload, no signature is needed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GasUsageEstimationRequest {
+    pub sender: Address,
+    pub public_key: HexEncodedBytes,
+    pub payload: TransactionPayload,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GasUsageEstimation {
+    /// Gas units used when executing the payload against the latest ledger state.
+    pub gas_used: U64,
+    /// Recommended `max_gas_amount`, leaves a margin for state changes happening before the
+    /// transaction is executed.
+    pub max_gas_amount: U64,
+    /// Recommended `gas_unit_price`, same as `GasEstimation::gas_unit_price`.
+    pub gas_unit_price: U64,
+}
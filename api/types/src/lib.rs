@@ -3,10 +3,12 @@
 
 mod account;
 mod address;
+mod block;
 mod bytecode;
 mod convert;
 mod error;
 mod event_key;
+mod gas;
 mod hash;
 mod ledger_info;
 pub mod mime_types;
@@ -14,13 +16,16 @@ mod move_types;
 mod response;
 mod table;
 mod transaction;
+mod view;
 
 pub use account::AccountData;
 pub use address::Address;
+pub use block::Block;
 pub use bytecode::Bytecode;
 pub use convert::{new_vm_ascii_string, AsConverter, MoveConverter};
 pub use error::Error;
 pub use event_key::EventKey;
+pub use gas::{GasEstimation, GasUsageEstimation, GasUsageEstimationRequest};
 pub use hash::HashValue;
 pub use ledger_info::LedgerInfo;
 pub use move_types::{
@@ -29,13 +34,15 @@ pub use move_types::{
     U128, U64,
 };
 pub use response::{
-    Response, X_APTOS_CHAIN_ID, X_APTOS_EPOCH, X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION,
+    Response, X_APTOS_CHAIN_ID, X_APTOS_CURSOR, X_APTOS_EPOCH, X_APTOS_LEDGER_TIMESTAMP,
+    X_APTOS_LEDGER_VERSION,
 };
-pub use table::TableItemRequest;
+pub use table::{TableItem, TableItemRequest};
 pub use transaction::{
     BlockMetadataTransaction, DirectWriteSet, Event, GenesisTransaction, PendingTransaction,
     ScriptFunctionPayload, ScriptPayload, ScriptWriteSet, Transaction, TransactionData,
     TransactionId, TransactionInfo, TransactionOnChainData, TransactionPayload,
     TransactionSigningMessage, UserCreateSigningMessageRequest, UserTransaction,
-    UserTransactionRequest, WriteSet, WriteSetChange, WriteSetPayload,
+    UserTransactionRequest, VersionedEvent, WriteSet, WriteSetChange, WriteSetPayload,
 };
+pub use view::{ViewFunction, ViewRequest};
@@ -11,10 +11,13 @@ pub const X_APTOS_CHAIN_ID: &str = "X-Aptos-Chain-Id";
 pub const X_APTOS_EPOCH: &str = "X-Aptos-Epoch";
 pub const X_APTOS_LEDGER_VERSION: &str = "X-Aptos-Ledger-Version";
 pub const X_APTOS_LEDGER_TIMESTAMP: &str = "X-Aptos-Ledger-TimestampUsec";
+pub const X_APTOS_CURSOR: &str = "X-Aptos-Cursor";
 
 pub struct Response {
     pub ledger_info: LedgerInfo,
     pub body: Vec<u8>,
+    /// Cursor of the next page, for paginated responses that have more items.
+    pub cursor: Option<String>,
 }
 
 impl Response {
@@ -22,8 +25,14 @@ impl Response {
         Ok(Self {
             ledger_info,
             body: serde_json::to_vec(body)?,
+            cursor: None,
         })
     }
+
+    pub fn with_cursor(mut self, cursor: Option<String>) -> Self {
+        self.cursor = cursor;
+        self
+    }
 }
 
 impl warp::Reply for Response {
@@ -42,6 +51,12 @@ impl warp::Reply for Response {
             self.ledger_info.ledger_timestamp.into(),
         );
         headers.insert(X_APTOS_EPOCH, self.ledger_info.epoch.into());
+        if let Some(cursor) = self.cursor {
+            // the cursor is hex encoded, always a valid header value
+            if let Ok(value) = HeaderValue::from_str(&cursor) {
+                headers.insert(X_APTOS_CURSOR, value);
+            }
+        }
 
         res
     }
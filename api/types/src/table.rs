@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::MoveType;
+use crate::{MoveType, MoveValue};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -11,3 +11,9 @@ pub struct TableItemRequest {
     pub value_type: MoveType,
     pub key: Value,
 }
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TableItem {
+    pub key: MoveValue,
+    pub value: MoveValue,
+}
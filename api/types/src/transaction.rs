@@ -378,6 +378,14 @@ impl From<(&ContractEvent, serde_json::Value)> for Event {
     }
 }
 
+/// An event tagged with the version of the transaction that emitted it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VersionedEvent {
+    pub version: U64,
+    #[serde(flatten)]
+    pub event: Event,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum GenesisPayload {
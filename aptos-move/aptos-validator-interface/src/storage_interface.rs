@@ -25,6 +25,7 @@ impl DBDebuggerInterface {
             true,
             NO_OP_STORAGE_PRUNER_CONFIG,
             RocksdbConfig::default(),
+            false, /* enable_event_by_type_index */
         )?)))
     }
 }
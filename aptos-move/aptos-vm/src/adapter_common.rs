@@ -163,9 +163,11 @@ pub(crate) fn execute_block_impl<A: VMAdapter, S: StateView>(
     adapter: &A,
     transactions: Vec<Transaction>,
     data_cache: &mut StateViewCache<S>,
+    maybe_block_gas_limit: Option<u64>,
 ) -> Result<Vec<(VMStatus, TransactionOutput)>, VMStatus> {
     let mut result = vec![];
     let mut should_restart = false;
+    let mut accumulated_gas: u64 = 0;
 
     info!(
         AdapterLogSchema::new(data_cache.id(), 0),
@@ -196,7 +198,10 @@ pub(crate) fn execute_block_impl<A: VMAdapter, S: StateView>(
             let txn_output =
                 TransactionOutput::new(WriteSet::default(), vec![], 0, TransactionStatus::Retry);
             result.push((VMStatus::Error(StatusCode::UNKNOWN_STATUS), txn_output));
-            debug!(log_context, "Retry after reconfiguration");
+            debug!(
+                log_context,
+                "Retry after reconfiguration or reaching the block gas limit"
+            );
             continue;
         };
         let (vm_status, output, sender) = adapter.execute_single_transaction(
@@ -226,6 +231,19 @@ pub(crate) fn execute_block_impl<A: VMAdapter, S: StateView>(
             should_restart = true;
         }
 
+        // The gas limit is checked after the transaction, so that the block execution stops at
+        // the same transaction regardless of how the block is executed.
+        accumulated_gas = accumulated_gas.saturating_add(output.gas_used());
+        if let Some(block_gas_limit) = maybe_block_gas_limit {
+            if !should_restart && accumulated_gas >= block_gas_limit {
+                info!(
+                    AdapterLogSchema::new(data_cache.id(), 0),
+                    "Block gas limit {} reached at transaction {}", block_gas_limit, idx,
+                );
+                should_restart = true;
+            }
+        }
+
         // `result` is initially empty, a single element is pushed per loop iteration and
         // the number of iterations is bound to the max size of `signature_verified_block`
         assume!(result.len() < usize::max_value());
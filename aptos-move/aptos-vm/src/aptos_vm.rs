@@ -11,7 +11,7 @@ use crate::{
         charge_global_write_gas_usage, get_transaction_output, AptosVMImpl, AptosVMInternals,
     },
     counters::*,
-    data_cache::{AsMoveResolver, StateViewCache},
+    data_cache::{AsMoveResolver, RemoteStorage, StateViewCache},
     errors::expect_only_successful_execution,
     logging::AdapterLogSchema,
     move_vm_ext::{MoveResolverExt, SessionExt, SessionId},
@@ -27,7 +27,9 @@ use aptos_state_view::StateView;
 use aptos_types::{
     account_config,
     block_metadata::BlockMetadata,
-    on_chain_config::{VMConfig, VMPublishingOption, Version},
+    on_chain_config::{
+        OnChainConfig, OnChainConsensusConfig, VMConfig, VMPublishingOption, Version,
+    },
     transaction::{
         ChangeSet, ExecutionStatus, ModuleBundle, SignatureCheckedTransaction, SignedTransaction,
         Transaction, TransactionOutput, TransactionPayload, TransactionStatus, VMValidatorResult,
@@ -46,7 +48,8 @@ use move_deps::{
     move_core_types::{
         account_address::AccountAddress,
         gas_schedule::{GasAlgebra, GasUnits},
-        language_storage::ModuleId,
+        identifier::Identifier,
+        language_storage::{ModuleId, TypeTag},
         transaction_argument::convert_txn_args,
         value::{serialize_values, MoveValue},
     },
@@ -111,6 +114,13 @@ impl AptosVM {
         }
     }
 
+    /// Get the gas limit of a block from the on-chain consensus config, None (no limit) if the
+    /// config doesn't set it.
+    pub fn get_block_gas_limit(state_view: &impl StateView) -> Option<u64> {
+        OnChainConsensusConfig::fetch_config(&RemoteStorage::new(state_view))
+            .and_then(|config| config.block_gas_limit())
+    }
+
     pub fn internals(&self) -> AptosVMInternals {
         AptosVMInternals::new(&self.0)
     }
@@ -819,11 +829,30 @@ impl AptosVM {
     pub fn execute_block_and_keep_vm_status(
         transactions: Vec<Transaction>,
         state_view: &impl StateView,
+    ) -> Result<Vec<(VMStatus, TransactionOutput)>, VMStatus> {
+        Self::execute_block_with_gas_limit_and_keep_vm_status(
+            transactions,
+            state_view,
+            Self::get_block_gas_limit(state_view),
+        )
+    }
+
+    /// Executes the block sequentially, the transactions after the one with which the gas used
+    /// by the block reaches `maybe_block_gas_limit` are marked as `TransactionStatus::Retry`.
+    pub(crate) fn execute_block_with_gas_limit_and_keep_vm_status(
+        transactions: Vec<Transaction>,
+        state_view: &impl StateView,
+        maybe_block_gas_limit: Option<u64>,
     ) -> Result<Vec<(VMStatus, TransactionOutput)>, VMStatus> {
         let mut state_view_cache = StateViewCache::new(state_view);
         let count = transactions.len();
         let vm = AptosVM::new(&state_view_cache);
-        let res = adapter_common::execute_block_impl(&vm, transactions, &mut state_view_cache)?;
+        let res = adapter_common::execute_block_impl(
+            &vm,
+            transactions,
+            &mut state_view_cache,
+            maybe_block_gas_limit,
+        )?;
         // Record the histogram count for transactions per block.
         BLOCK_TRANSACTION_COUNT.observe(count as f64);
         Ok(res)
@@ -839,6 +868,41 @@ impl AptosVM {
         simulation_vm.simulate_signed_transaction(&state_view.as_move_resolver(), txn, &log_context)
     }
 
+    /// Runs the function in a session on top of `state_view` and returns its BCS serialized
+    /// return values. The changes made by the function are discarded, and gas is only metered
+    /// against `gas_budget` to bound the execution, no account is charged.
+    /// Callers are expected to check the visibility of the function.
+    pub fn execute_view_function(
+        state_view: &impl StateView,
+        module_id: ModuleId,
+        func_name: Identifier,
+        type_args: Vec<TypeTag>,
+        arguments: Vec<Vec<u8>>,
+        gas_budget: u64,
+    ) -> Result<Vec<Vec<u8>>, VMStatus> {
+        let vm = AptosVM::new(state_view);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        let gas_schedule = vm.0.get_gas_schedule(&log_context)?;
+        let mut gas_status = GasStatus::new(gas_schedule, GasUnits::new(gas_budget));
+
+        let resolver = state_view.as_move_resolver();
+        let mut session = vm.0.new_session(&resolver, SessionId::void());
+        let return_values = session
+            .execute_function_bypass_visibility(
+                &module_id,
+                &func_name,
+                type_args,
+                arguments,
+                &mut gas_status,
+            )
+            .map_err(|e| e.into_vm_status())?;
+        Ok(return_values
+            .return_values
+            .into_iter()
+            .map(|(bytes, _layout)| bytes)
+            .collect())
+    }
+
     fn run_prologue_with_payload<S: MoveResolverExt>(
         &self,
         session: &mut SessionExt<S>,
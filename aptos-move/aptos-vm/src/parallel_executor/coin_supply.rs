@@ -0,0 +1,60 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The supply of a coin is tracked in the `CoinInfo` resource of the coin, which is updated by
+//! every mint and burn, including the burn of the gas fees of every transaction. During parallel
+//! execution these updates are recorded as deltas, so that they don't conflict with each other.
+
+use aptos_types::{account_config::CORE_CODE_ADDRESS, state_store::state_key::StateKey};
+use mvhashmap::delta::{apply_deltas, DeltaOp};
+use serde::{Deserialize, Serialize};
+
+/// Rust representation of the `0x1::Coin::CoinInfo` resource.
+#[derive(Deserialize, Serialize)]
+struct CoinInfoResource {
+    name: Vec<u8>,
+    symbol: Vec<u8>,
+    decimals: u64,
+    supply: Option<u128>,
+}
+
+/// Returns whether the state key holds a `CoinInfo` resource.
+pub(crate) fn is_coin_info(state_key: &StateKey) -> bool {
+    match state_key {
+        StateKey::AccessPath(access_path) => {
+            access_path.get_struct_tag().map_or(false, |struct_tag| {
+                struct_tag.address == CORE_CODE_ADDRESS
+                    && struct_tag.module.as_str() == "Coin"
+                    && struct_tag.name.as_str() == "CoinInfo"
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Returns the delta turning the supply of the `CoinInfo` resource `before` into the supply of
+/// `after`, or None if the supply is not tracked or if other fields of the resource changed. The
+/// limit of the delta matches the overflow check of `Coin::mint`.
+pub(crate) fn supply_delta(before: &[u8], after: &[u8]) -> Option<DeltaOp> {
+    let before: CoinInfoResource = bcs::from_bytes(before).ok()?;
+    let after: CoinInfoResource = bcs::from_bytes(after).ok()?;
+    if before.name != after.name
+        || before.symbol != after.symbol
+        || before.decimals != after.decimals
+    {
+        return None;
+    }
+    Some(DeltaOp::from_change(
+        before.supply?,
+        after.supply?,
+        u128::MAX,
+    ))
+}
+
+/// Applies the deltas in order to the supply of the serialized `CoinInfo` resource, returns None
+/// if the supply is not tracked or would overflow or underflow.
+pub(crate) fn apply_supply_deltas(coin_info: &[u8], deltas: &[DeltaOp]) -> Option<Vec<u8>> {
+    let mut coin_info: CoinInfoResource = bcs::from_bytes(coin_info).ok()?;
+    coin_info.supply = Some(apply_deltas(coin_info.supply?, deltas).ok()?);
+    bcs::to_bytes(&coin_info).ok()
+}
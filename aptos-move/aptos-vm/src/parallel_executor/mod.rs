@@ -1,6 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod coin_supply;
 mod storage_wrapper;
 mod vm_wrapper;
 
@@ -18,22 +19,28 @@ use aptos_state_view::StateView;
 use aptos_types::{
     state_store::state_key::StateKey,
     transaction::{Transaction, TransactionOutput, TransactionStatus},
-    write_set::{WriteOp, WriteSet},
+    write_set::{WriteOp, WriteSet, WriteSetMut},
 };
 use move_deps::move_core_types::vm_status::{StatusCode, VMStatus};
+use mvhashmap::delta::DeltaOp;
 use rayon::prelude::*;
+use std::{collections::HashMap, mem};
 
 impl PTransaction for PreprocessedTransaction {
     type Key = StateKey;
     type Value = WriteOp;
 }
 
-// Wrapper to avoid orphan rule
-pub(crate) struct AptosTransactionOutput(TransactionOutput);
+// Wrapper to avoid orphan rule. The writes to the keys with deltas are placeholders, replaced by
+// the materialized values once the block commits.
+pub(crate) struct AptosTransactionOutput(TransactionOutput, Vec<(StateKey, DeltaOp)>);
 
 impl AptosTransactionOutput {
     pub fn new(output: TransactionOutput) -> Self {
-        Self(output)
+        Self(output, vec![])
+    }
+    pub fn with_deltas(output: TransactionOutput, deltas: Vec<(StateKey, DeltaOp)>) -> Self {
+        Self(output, deltas)
     }
     pub fn into(self) -> TransactionOutput {
         self.0
@@ -44,12 +51,42 @@ impl PTransactionOutput for AptosTransactionOutput {
     type T = PreprocessedTransaction;
 
     fn get_writes(&self) -> Vec<(StateKey, WriteOp)> {
-        self.0.write_set().iter().cloned().collect()
+        self.0
+            .write_set()
+            .iter()
+            .filter(|(key, _)| !self.1.iter().any(|(delta_key, _)| delta_key == key))
+            .cloned()
+            .collect()
+    }
+
+    fn get_deltas(&self) -> Vec<(StateKey, DeltaOp)> {
+        self.1.clone()
+    }
+
+    fn incorporate_delta_writes(&mut self, writes: Vec<(StateKey, WriteOp)>) {
+        let mut writes: HashMap<_, _> = writes.into_iter().collect();
+        let (write_set, events, gas_used, status) =
+            mem::replace(&mut self.0, Self::skip_output().0).unpack();
+        let write_set = write_set
+            .into_iter()
+            .map(|(key, op)| {
+                let op = writes.remove(&key).unwrap_or(op);
+                (key, op)
+            })
+            .collect::<WriteSetMut>()
+            .freeze()
+            .expect("Materializing deltas must produce a valid write set");
+        self.0 = TransactionOutput::new(write_set, events, gas_used, status);
+        self.1.clear();
+    }
+
+    fn gas_used(&self) -> u64 {
+        self.0.gas_used()
     }
 
     /// Execution output for transactions that comes after SkipRest signal.
     fn skip_output() -> Self {
-        Self(TransactionOutput::new(
+        Self::new(TransactionOutput::new(
             WriteSet::default(),
             vec![],
             0,
@@ -66,6 +103,9 @@ impl ParallelAptosVM {
         state_view: &S,
         concurrency_level: usize,
     ) -> Result<(Vec<TransactionOutput>, Option<Error<VMStatus>>), VMStatus> {
+        // The gas limit is part of the on-chain config, so that all the validators stop
+        // executing the block at the same transaction.
+        let maybe_block_gas_limit = AptosVM::get_block_gas_limit(state_view);
         // Verify the signatures of all the transactions in parallel.
         // This is time consuming so don't wait and do the checking
         // sequentially while executing the transactions.
@@ -76,6 +116,7 @@ impl ParallelAptosVM {
 
         match ParallelTransactionExecutor::<PreprocessedTransaction, AptosVMWrapper<S>>::new(
             concurrency_level,
+            maybe_block_gas_limit,
         )
         .execute_transactions_parallel(state_view, signature_verified_block)
         {
@@ -86,8 +127,14 @@ impl ParallelAptosVM {
                     .collect(),
                 None,
             )),
-            Err(err @ Error::InferencerError) | Err(err @ Error::UnestimatedWrite) => {
-                let output = AptosVM::execute_block_and_keep_vm_status(transactions, state_view)?;
+            Err(err @ Error::InferencerError)
+            | Err(err @ Error::UnestimatedWrite)
+            | Err(err @ Error::DeltaApplicationFailure) => {
+                let output = AptosVM::execute_block_with_gas_limit_and_keep_vm_status(
+                    transactions,
+                    state_view,
+                    maybe_block_gas_limit,
+                )?;
                 Ok((
                     output
                         .into_iter()
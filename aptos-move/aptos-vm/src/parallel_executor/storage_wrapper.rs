@@ -1,14 +1,22 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::data_cache::{IntoMoveResolver, RemoteStorageOwned};
-use aptos_parallel_executor::executor::MVHashMapView;
+use crate::{
+    data_cache::{IntoMoveResolver, RemoteStorageOwned},
+    parallel_executor::coin_supply::{apply_supply_deltas, is_coin_info},
+};
+use aptos_infallible::Mutex;
+use aptos_parallel_executor::executor::{MVHashMapView, ReadResult};
 use aptos_state_view::{StateView, StateViewId};
 use aptos_types::{state_store::state_key::StateKey, write_set::WriteOp};
+use std::collections::HashMap;
 
 pub(crate) struct VersionedView<'a, S: StateView> {
     base_view: &'a S,
     hashmap_view: &'a MVHashMapView<'a, StateKey, WriteOp>,
+    // The `CoinInfo` resources read by the transaction. Every read of a resource returns the
+    // first value read, which is the value the supply deltas of the transaction are computed from.
+    coin_info_reads: Mutex<HashMap<StateKey, Option<Vec<u8>>>>,
 }
 
 impl<'a, S: StateView> VersionedView<'a, S> {
@@ -19,9 +27,41 @@ impl<'a, S: StateView> VersionedView<'a, S> {
         VersionedView {
             base_view,
             hashmap_view,
+            coin_info_reads: Mutex::new(HashMap::new()),
         }
         .into_move_resolver()
     }
+
+    /// The value of the `CoinInfo` resource read by the transaction, if any.
+    pub fn coin_info_read(&self, state_key: &StateKey) -> Option<Vec<u8>> {
+        self.coin_info_reads
+            .lock()
+            .get(state_key)
+            .cloned()
+            .flatten()
+    }
+
+    fn read(&self, state_key: &StateKey) -> anyhow::Result<Option<Vec<u8>>> {
+        match self.hashmap_view.read(state_key) {
+            ReadResult::Value(v) => Ok(match v.as_ref() {
+                WriteOp::Value(w) => Some(w.clone()),
+                WriteOp::Deletion => None,
+            }),
+            ReadResult::Deltas(base, deltas) => {
+                let base = match base {
+                    Some(v) => match v.as_ref() {
+                        WriteOp::Value(w) => Some(w.clone()),
+                        WriteOp::Deletion => None,
+                    },
+                    None => self.base_view.get_state_value(state_key)?,
+                };
+                // If the deltas can't be applied, the block fails when the deltas are
+                // materialized, so the value read here doesn't matter.
+                Ok(base.map(|bytes| apply_supply_deltas(&bytes, &deltas).unwrap_or(bytes)))
+            }
+            ReadResult::None => self.base_view.get_state_value(state_key),
+        }
+    }
 }
 
 impl<'a, S: StateView> StateView for VersionedView<'a, S> {
@@ -31,13 +71,17 @@ impl<'a, S: StateView> StateView for VersionedView<'a, S> {
 
     // Get some data either through the cache or the `StateView` on a cache miss.
     fn get_state_value(&self, state_key: &StateKey) -> anyhow::Result<Option<Vec<u8>>> {
-        match self.hashmap_view.read(state_key) {
-            Some(v) => Ok(match v.as_ref() {
-                WriteOp::Value(w) => Some(w.clone()),
-                WriteOp::Deletion => None,
-            }),
-            None => self.base_view.get_state_value(state_key),
+        if !is_coin_info(state_key) {
+            return self.read(state_key);
+        }
+        if let Some(value) = self.coin_info_reads.lock().get(state_key) {
+            return Ok(value.clone());
         }
+        let value = self.read(state_key)?;
+        self.coin_info_reads
+            .lock()
+            .insert(state_key.clone(), value.clone());
+        Ok(value)
     }
 
     fn is_genesis(&self) -> bool {
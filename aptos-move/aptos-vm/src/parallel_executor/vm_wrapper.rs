@@ -6,7 +6,11 @@ use crate::{
     aptos_vm::AptosVM,
     data_cache::RemoteStorage,
     logging::AdapterLogSchema,
-    parallel_executor::{storage_wrapper::VersionedView, AptosTransactionOutput},
+    parallel_executor::{
+        coin_supply::{apply_supply_deltas, is_coin_info, supply_delta},
+        storage_wrapper::VersionedView,
+        AptosTransactionOutput,
+    },
 };
 use aptos_logger::prelude::*;
 use aptos_parallel_executor::{
@@ -20,6 +24,7 @@ use move_deps::move_core_types::{
     language_storage::{ModuleId, CORE_CODE_ADDRESS},
     vm_status::VMStatus,
 };
+use mvhashmap::delta::DeltaOp;
 
 pub(crate) struct AptosVMWrapper<'a, S> {
     vm: AptosVM,
@@ -80,13 +85,40 @@ impl<'a, S: 'a + StateView> ExecutorTask for AptosVMWrapper<'a, S> {
                         }
                     };
                 }
+                // Updates of the coin supplies are recorded as deltas, so that the fees burnt by
+                // every transaction don't make the transactions of the block conflict.
+                let deltas = output
+                    .write_set()
+                    .iter()
+                    .filter_map(|(key, op)| match op {
+                        WriteOp::Value(after) if is_coin_info(key) => versioned_view
+                            .coin_info_read(key)
+                            .and_then(|before| supply_delta(&before, after))
+                            .map(|delta| (key.clone(), delta)),
+                        _ => None,
+                    })
+                    .collect();
                 if AptosVM::should_restart_execution(&output) {
-                    ExecutionStatus::SkipRest(AptosTransactionOutput::new(output))
+                    ExecutionStatus::SkipRest(AptosTransactionOutput::with_deltas(output, deltas))
                 } else {
-                    ExecutionStatus::Success(AptosTransactionOutput::new(output))
+                    ExecutionStatus::Success(AptosTransactionOutput::with_deltas(output, deltas))
                 }
             }
             Err(err) => ExecutionStatus::Abort(err),
         }
     }
+
+    fn apply_delta(
+        &self,
+        key: &StateKey,
+        base: Option<&WriteOp>,
+        delta: &DeltaOp,
+    ) -> Option<WriteOp> {
+        let coin_info = match base {
+            Some(WriteOp::Value(bytes)) => bytes.clone(),
+            Some(WriteOp::Deletion) => return None,
+            None => self.base_view.get_state_value(key).ok()??,
+        };
+        apply_supply_deltas(&coin_info, &[*delta]).map(WriteOp::Value)
+    }
 }
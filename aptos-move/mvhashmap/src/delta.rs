@@ -0,0 +1,73 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Commutative updates of counters. Transactions that only add to or subtract from a counter
+//! record a delta instead of the new value, so that they don't conflict with each other during
+//! parallel execution. Deltas are applied in order of the transactions when the block commits.
+
+/// Update of a counter described by a delta.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeltaUpdate {
+    Plus(u128),
+    Minus(u128),
+}
+
+/// Bounded update of a counter: applying the delta must yield a value in [0, limit].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeltaOp {
+    update: DeltaUpdate,
+    limit: u128,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeltaApplicationError {
+    /// The counter would exceed the limit of the delta.
+    Overflow,
+    /// The counter would drop below zero.
+    Underflow,
+}
+
+impl DeltaOp {
+    pub fn new(update: DeltaUpdate, limit: u128) -> Self {
+        Self { update, limit }
+    }
+
+    /// Returns the delta that turns the counter value `before` into `after`.
+    pub fn from_change(before: u128, after: u128, limit: u128) -> Self {
+        let update = if after >= before {
+            DeltaUpdate::Plus(after - before)
+        } else {
+            DeltaUpdate::Minus(before - after)
+        };
+        Self::new(update, limit)
+    }
+
+    pub fn update(&self) -> DeltaUpdate {
+        self.update
+    }
+
+    pub fn limit(&self) -> u128 {
+        self.limit
+    }
+
+    /// Applies the delta to the given counter value.
+    pub fn apply_to(&self, base: u128) -> Result<u128, DeltaApplicationError> {
+        match self.update {
+            DeltaUpdate::Plus(value) => base
+                .checked_add(value)
+                .filter(|result| *result <= self.limit)
+                .ok_or(DeltaApplicationError::Overflow),
+            DeltaUpdate::Minus(value) => base
+                .checked_sub(value)
+                .ok_or(DeltaApplicationError::Underflow),
+        }
+    }
+}
+
+/// Applies the deltas to the given counter value in order, every intermediate value must be
+/// within the bounds of the corresponding delta.
+pub fn apply_deltas(base: u128, deltas: &[DeltaOp]) -> Result<u128, DeltaApplicationError> {
+    deltas
+        .iter()
+        .try_fold(base, |value, delta| delta.apply_to(value))
+}
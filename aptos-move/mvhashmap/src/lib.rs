@@ -1,6 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::delta::DeltaOp;
 use crossbeam::utils::CachePadded;
 use dashmap::DashMap;
 use std::{
@@ -12,6 +13,7 @@ use std::{
     },
 };
 
+pub mod delta;
 #[cfg(test)]
 mod unit_tests;
 
@@ -23,6 +25,15 @@ pub type Version = (TxnIndex, Incarnation);
 const FLAG_DONE: usize = 0;
 const FLAG_ESTIMATE: usize = 1;
 
+/// Contents of an entry: either a full value or a delta applied to the previous value.
+enum EntryCell<V> {
+    /// Actual data stored in a shared pointer (to ensure ownership and avoid clones).
+    Write(Arc<V>),
+    /// Delta to be applied to the latest value written by a lower transaction (or to the
+    /// value in storage).
+    Delta(DeltaOp),
+}
+
 /// Type of entry, recorded in the shared multi-version data-structure for each write.
 struct WriteCell<V> {
     /// Used to mark the entry as a "write estimate".
@@ -30,16 +41,15 @@ struct WriteCell<V> {
     /// Incarnation number of the transaction that wrote the entry. Note that
     /// TxnIndex is part of the key and not recorded here.
     incarnation: Incarnation,
-    /// Actual data stored in a shared pointer (to ensure ownership and avoid clones).
-    data: Arc<V>,
+    cell: EntryCell<V>,
 }
 
 impl<V> WriteCell<V> {
-    pub fn new_from(flag: usize, incarnation: Incarnation, data: V) -> WriteCell<V> {
+    fn new_from(flag: usize, incarnation: Incarnation, cell: EntryCell<V>) -> WriteCell<V> {
         WriteCell {
             flag: AtomicUsize::new(flag),
             incarnation,
-            data: Arc::new(data),
+            cell,
         }
     }
 
@@ -52,6 +62,16 @@ impl<V> WriteCell<V> {
     }
 }
 
+/// Successful read from the multi-version data-structure.
+#[derive(Debug, PartialEq)]
+pub enum MVHashMapOutput<V> {
+    /// Value written by the transaction with the given version.
+    Version(Version, Arc<V>),
+    /// Deltas written by lower transactions, to be applied in order on top of the value
+    /// written with the given version, or on top of the value in storage when None.
+    Deltas(Option<(Version, Arc<V>)>, Vec<DeltaOp>),
+}
+
 /// Main multi-version data-structure used by threads to read/write during parallel
 /// execution. Maps each access path to an interal BTreeMap that contains the indices
 /// of transactions that write at the given access path alongside the corresponding
@@ -74,12 +94,22 @@ impl<K: Hash + Clone + Eq, V> MVHashMap<K, V> {
     /// Write a versioned data at a specified key. If the WriteCell entry is overwritten,
     /// asserts that the new incarnation is strictly higher.
     pub fn write(&self, key: &K, version: Version, data: V) {
+        self.insert(key, version, EntryCell::Write(Arc::new(data)));
+    }
+
+    /// Record a versioned delta at a specified key. If the WriteCell entry is overwritten,
+    /// asserts that the new incarnation is strictly higher.
+    pub fn add_delta(&self, key: &K, version: Version, delta: DeltaOp) {
+        self.insert(key, version, EntryCell::Delta(delta));
+    }
+
+    fn insert(&self, key: &K, version: Version, cell: EntryCell<V>) {
         let (txn_idx, incarnation) = version;
 
         let mut map = self.data.entry(key.clone()).or_insert(BTreeMap::new());
         let prev_cell = map.insert(
             txn_idx,
-            CachePadded::new(WriteCell::new_from(FLAG_DONE, incarnation, data)),
+            CachePadded::new(WriteCell::new_from(FLAG_DONE, incarnation, cell)),
         );
 
         // Assert that the previous entry for txn_idx, if present, had lower incarnation.
@@ -105,30 +135,91 @@ impl<K: Hash + Clone + Eq, V> MVHashMap<K, V> {
         map.remove(&txn_idx);
     }
 
-    /// read may return Ok((Arc<V>, txn_idx, incarnation)), Err(dep_txn_idx) for
-    /// a dependency of transaction dep_txn_idx or Err(None) when no prior entry is found.
-    pub fn read(&self, key: &K, txn_idx: TxnIndex) -> Result<(Version, Arc<V>), Option<TxnIndex>> {
+    /// read may return Ok(MVHashMapOutput), Err(dep_txn_idx) for a dependency of transaction
+    /// dep_txn_idx or Err(None) when no prior entry is found. Deltas written after the latest
+    /// prior value are returned alongside it, in the order of the transactions.
+    pub fn read(&self, key: &K, txn_idx: TxnIndex) -> Result<MVHashMapOutput<V>, Option<TxnIndex>> {
         match self.data.get(key) {
             Some(tree) => {
-                // Find the dependency
-                let mut iter = tree.range(0..txn_idx);
-                if let Some((idx, write_cell)) = iter.next_back() {
+                let mut deltas = vec![];
+                // Walk back from the dependency until an entry with a full value is found.
+                for (idx, write_cell) in tree.range(0..txn_idx).rev() {
                     let flag = write_cell.flag();
 
                     if flag == FLAG_ESTIMATE {
                         // Found a dependency.
-                        Err(Some(*idx))
-                    } else {
-                        debug_assert!(flag == FLAG_DONE);
-                        // The entry is populated, return its contents.
-                        let write_version = (*idx, write_cell.incarnation);
-                        Ok((write_version, write_cell.data.clone()))
+                        return Err(Some(*idx));
                     }
-                } else {
+                    debug_assert!(flag == FLAG_DONE);
+
+                    match &write_cell.cell {
+                        EntryCell::Write(data) => {
+                            // The entry is populated, return its contents.
+                            let write_version = (*idx, write_cell.incarnation);
+                            return Ok(if deltas.is_empty() {
+                                MVHashMapOutput::Version(write_version, data.clone())
+                            } else {
+                                deltas.reverse();
+                                MVHashMapOutput::Deltas(Some((write_version, data.clone())), deltas)
+                            });
+                        }
+                        EntryCell::Delta(delta) => deltas.push(*delta),
+                    }
+                }
+                if deltas.is_empty() {
                     Err(None)
+                } else {
+                    // Deltas are applied on top of the value in storage.
+                    deltas.reverse();
+                    Ok(MVHashMapOutput::Deltas(None, deltas))
                 }
             }
             None => Err(None),
         }
     }
+
+    /// Materializes the deltas recorded at a key by the transactions with indices lower than
+    /// `num_txns`, in the order of the transactions. `apply` computes the value resulting from
+    /// applying a delta on top of the latest value of the key (None if the key was not written
+    /// in the block, i.e. the delta applies to the value in storage). Must be called after the
+    /// execution is done, returns None if a delta could not be applied.
+    pub fn materialize_deltas<F>(
+        &self,
+        key: &K,
+        num_txns: TxnIndex,
+        mut apply: F,
+    ) -> Option<Vec<(TxnIndex, V)>>
+    where
+        F: FnMut(Option<&V>, &DeltaOp) -> Option<V>,
+    {
+        let tree = match self.data.get(key) {
+            Some(tree) => tree,
+            None => return Some(vec![]),
+        };
+
+        let mut latest: Option<Arc<V>> = None;
+        let mut materialized = vec![];
+        for (idx, write_cell) in tree.range(0..num_txns) {
+            debug_assert!(write_cell.flag() == FLAG_DONE);
+            match &write_cell.cell {
+                EntryCell::Write(data) => latest = Some(data.clone()),
+                EntryCell::Delta(delta) => {
+                    let value = Arc::new(apply(latest.as_deref(), delta)?);
+                    latest = Some(value.clone());
+                    materialized.push((*idx, value));
+                }
+            }
+        }
+        drop(latest);
+
+        Some(
+            materialized
+                .into_iter()
+                .map(|(idx, value)| match Arc::try_unwrap(value) {
+                    Ok(value) => (idx, value),
+                    Err(_) => unreachable!("Materialized values are uniquely owned"),
+                })
+                .collect(),
+        )
+    }
 }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
+use crate::delta::{apply_deltas, DeltaApplicationError, DeltaUpdate};
 
 mod proptest_types;
 
@@ -40,7 +41,10 @@ fn create_write_read_placeholder_struct() {
 
     // Reads for a higher txn return the entry written by txn 10.
     let r_10 = mvtbl.read(&ap1, 15);
-    assert_eq!(Ok(((10, 1), arc_value_for(10, 1))), r_10);
+    assert_eq!(
+        Ok(MVHashMapOutput::Version((10, 1), arc_value_for(10, 1))),
+        r_10
+    );
 
     // More writes.
     mvtbl.write(&ap1, (12, 0), value_for(12, 0));
@@ -48,11 +52,20 @@ fn create_write_read_placeholder_struct() {
 
     // Verify reads.
     let r_12 = mvtbl.read(&ap1, 15);
-    assert_eq!(Ok(((12, 0), arc_value_for(12, 0))), r_12);
+    assert_eq!(
+        Ok(MVHashMapOutput::Version((12, 0), arc_value_for(12, 0))),
+        r_12
+    );
     let r_10 = mvtbl.read(&ap1, 11);
-    assert_eq!(Ok(((10, 1), arc_value_for(10, 1))), r_10);
+    assert_eq!(
+        Ok(MVHashMapOutput::Version((10, 1), arc_value_for(10, 1))),
+        r_10
+    );
     let r_8 = mvtbl.read(&ap1, 10);
-    assert_eq!(Ok(((8, 3), arc_value_for(8, 3))), r_8);
+    assert_eq!(
+        Ok(MVHashMapOutput::Version((8, 3), arc_value_for(8, 3))),
+        r_8
+    );
 
     // Mark the entry written by 10 as an estimate.
     mvtbl.mark_estimate(&ap1, 10);
@@ -67,15 +80,24 @@ fn create_write_read_placeholder_struct() {
 
     // Read by txn 11 no longer observes entry from txn 10.
     let r_8 = mvtbl.read(&ap1, 11);
-    assert_eq!(Ok(((8, 3), arc_value_for(8, 3))), r_8);
+    assert_eq!(
+        Ok(MVHashMapOutput::Version((8, 3), arc_value_for(8, 3))),
+        r_8
+    );
 
     // Reads, writes for ap2 and ap3.
     mvtbl.write(&ap2, (5, 0), value_for(5, 0));
     mvtbl.write(&ap3, (20, 4), value_for(20, 4));
     let r_5 = mvtbl.read(&ap2, 10);
-    assert_eq!(Ok(((5, 0), arc_value_for(5, 0))), r_5);
+    assert_eq!(
+        Ok(MVHashMapOutput::Version((5, 0), arc_value_for(5, 0))),
+        r_5
+    );
     let r_20 = mvtbl.read(&ap3, 21);
-    assert_eq!(Ok(((20, 4), arc_value_for(20, 4))), r_20);
+    assert_eq!(
+        Ok(MVHashMapOutput::Version((20, 4), arc_value_for(20, 4))),
+        r_20
+    );
 
     // Clear ap1 and ap3.
     mvtbl.delete(&ap1, 12);
@@ -93,5 +115,64 @@ fn create_write_read_placeholder_struct() {
 
     // Read entry by txn 10 at ap2.
     let r_10 = mvtbl.read(&ap2, 15);
-    assert_eq!(Ok(((10, 2), arc_value_for(10, 2))), r_10);
+    assert_eq!(
+        Ok(MVHashMapOutput::Version((10, 2), arc_value_for(10, 2))),
+        r_10
+    );
+}
+
+#[test]
+fn read_and_materialize_deltas() {
+    let ap = b"/foo/counter".to_vec();
+    let plus = |value| DeltaOp::new(DeltaUpdate::Plus(value), 100);
+    let minus = |value| DeltaOp::new(DeltaUpdate::Minus(value), 100);
+
+    let mvtbl = MVHashMap::new();
+
+    // Deltas without a prior write are applied on top of storage.
+    mvtbl.add_delta(&ap, (3, 0), plus(10));
+    mvtbl.add_delta(&ap, (5, 0), minus(4));
+    assert_eq!(
+        Ok(MVHashMapOutput::Deltas(None, vec![plus(10), minus(4)])),
+        mvtbl.read(&ap, 6)
+    );
+    assert_eq!(
+        Ok(MVHashMapOutput::Deltas(None, vec![plus(10)])),
+        mvtbl.read(&ap, 5)
+    );
+
+    // Deltas after a write are applied on top of the written value.
+    mvtbl.write(&ap, (4, 1), 20u128);
+    assert_eq!(
+        Ok(MVHashMapOutput::Version((4, 1), Arc::new(20))),
+        mvtbl.read(&ap, 5)
+    );
+    assert_eq!(
+        Ok(MVHashMapOutput::Deltas(
+            Some(((4, 1), Arc::new(20))),
+            vec![minus(4)]
+        )),
+        mvtbl.read(&ap, 8)
+    );
+
+    // An estimated delta is a dependency.
+    mvtbl.mark_estimate(&ap, 5);
+    assert_eq!(Err(Some(5)), mvtbl.read(&ap, 8));
+    mvtbl.add_delta(&ap, (5, 1), plus(80));
+
+    // Materialize on top of a storage value of 1, the delta of txn 7 is not committed.
+    mvtbl.add_delta(&ap, (7, 0), plus(1));
+    let apply = |base: Option<&u128>, delta: &DeltaOp| delta.apply_to(*base.unwrap_or(&1)).ok();
+    assert_eq!(
+        Some(vec![(3, 11), (5, 100)]),
+        mvtbl.materialize_deltas(&ap, 7, apply)
+    );
+    // Including txn 7 overflows the limit.
+    assert_eq!(None, mvtbl.materialize_deltas(&ap, 8, apply));
+
+    assert_eq!(Ok(9), apply_deltas(10, &[minus(4), plus(3)]));
+    assert_eq!(
+        Err(DeltaApplicationError::Underflow),
+        apply_deltas(3, &[minus(4), plus(3)])
+    );
 }
@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use super::MVHashMap;
+use super::{MVHashMap, MVHashMapOutput};
 use proptest::{collection::vec, prelude::*, sample::Index, strategy::Strategy};
 use std::{
     collections::{BTreeMap, HashMap},
@@ -121,7 +121,7 @@ where
                         let mut retry_attempts = 0;
                         loop {
                             match map.read(key, idx) {
-                                Ok((_, v)) => {
+                                Ok(MVHashMapOutput::Version(_, v)) => {
                                     match &*v {
                                         Some(w) => {
                                             assert_eq!(
@@ -146,6 +146,9 @@ where
                                     assert_eq!(baseline, ExpectedOutput::NotInMap, "{:?}", idx);
                                     break;
                                 }
+                                Ok(MVHashMapOutput::Deltas(..)) => {
+                                    unreachable!("No deltas are recorded")
+                                }
                                 Err(Some(_i)) => (),
                             }
                             retry_attempts += 1;
@@ -9,16 +9,16 @@ use crate::{
     txn_last_input_output::{ReadDescriptor, TxnLastInputOutput},
 };
 use aptos_infallible::Mutex;
-use mvhashmap::MVHashMap;
+use mvhashmap::{delta::DeltaOp, MVHashMap, MVHashMapOutput};
 use num_cpus;
 use once_cell::sync::Lazy;
 use rayon::prelude::*;
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     hash::Hash,
     marker::PhantomData,
     sync::{
-        atomic::{AtomicUsize, Ordering},
+        atomic::{AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
     thread::spawn,
@@ -31,6 +31,17 @@ static RAYON_EXEC_POOL: Lazy<rayon::ThreadPool> = Lazy::new(|| {
         .unwrap()
 });
 
+/// Result of a read from the multi-version view.
+pub enum ReadResult<V> {
+    /// Value written by a lower transaction in the block.
+    Value(Arc<V>),
+    /// Deltas written by lower transactions, to be applied in order on top of the given value,
+    /// or on top of the value in storage when None.
+    Deltas(Option<Arc<V>>, Vec<DeltaOp>),
+    /// No lower transaction wrote to the key, the value needs to be read from storage.
+    None,
+}
+
 /// A struct that is always used by a single thread performing an execution task. The struct is
 /// passed to the VM and acts as a proxy to resolve reads first in the shared multi-version
 /// data-structure. It also allows the caller to track the read-set and any dependencies.
@@ -53,23 +64,35 @@ impl<'a, K: PartialOrd + Send + Clone + Hash + Eq, V: Send + Sync> MVHashMapView
     }
 
     /// Captures a read from the VM execution.
-    pub fn read(&self, key: &K) -> Option<Arc<V>> {
+    pub fn read(&self, key: &K) -> ReadResult<V> {
         loop {
             match self.versioned_map.read(key, self.txn_idx) {
-                Ok((version, v)) => {
+                Ok(MVHashMapOutput::Version(version, v)) => {
                     let (txn_idx, incarnation) = version;
                     self.captured_reads.lock().push(ReadDescriptor::from(
                         key.clone(),
                         txn_idx,
                         incarnation,
                     ));
-                    return Some(v);
+                    return ReadResult::Value(v);
+                }
+                Ok(MVHashMapOutput::Deltas(base, deltas)) => {
+                    let (base_version, base_value) = match base {
+                        Some((version, value)) => (Some(version), Some(value)),
+                        None => (None, None),
+                    };
+                    self.captured_reads.lock().push(ReadDescriptor::from_deltas(
+                        key.clone(),
+                        base_version,
+                        deltas.clone(),
+                    ));
+                    return ReadResult::Deltas(base_value, deltas);
                 }
                 Err(None) => {
                     self.captured_reads
                         .lock()
                         .push(ReadDescriptor::from_storage(key.clone()));
-                    return None;
+                    return ReadResult::None;
                 }
                 Err(Some(dep_idx)) => {
                     // `self.txn_idx` estimated to depend on a write from `dep_idx`.
@@ -108,10 +131,57 @@ impl<'a, K: PartialOrd + Send + Clone + Hash + Eq, V: Send + Sync> MVHashMapView
     }
 }
 
+/// Speculatively accounts the gas used by the prefix of the block executed so far, to stop
+/// scheduling the transactions after the one with which the block is estimated to reach its gas
+/// limit. The estimate is checked against the final outputs once the block is executed.
+struct BlockGasTracker {
+    block_gas_limit: u64,
+    // Gas used by the last execution of each transaction plus one, 0 if not executed yet.
+    gas_used: Vec<AtomicU64>,
+    // The next transaction to account, and the gas used by the transactions before it.
+    prefix: Mutex<(TxnIndex, u64)>,
+}
+
+impl BlockGasTracker {
+    fn new(block_gas_limit: u64, num_txns: usize) -> Self {
+        Self {
+            block_gas_limit,
+            gas_used: (0..num_txns).map(|_| AtomicU64::new(0)).collect(),
+            prefix: Mutex::new((0, 0)),
+        }
+    }
+
+    fn record(&self, txn_idx: TxnIndex, gas_used: u64, scheduler: &Scheduler) {
+        self.gas_used[txn_idx].store(gas_used.saturating_add(1), Ordering::SeqCst);
+
+        let mut prefix = self.prefix.lock();
+        let (mut next_idx, mut accumulated_gas) = *prefix;
+        // As in sequential execution, the limit is checked after each transaction.
+        let reached =
+            |next_idx, accumulated_gas| next_idx > 0 && accumulated_gas >= self.block_gas_limit;
+        while next_idx < self.gas_used.len() && !reached(next_idx, accumulated_gas) {
+            match self.gas_used[next_idx].load(Ordering::SeqCst) {
+                0 => break,
+                gas_used_plus_one => {
+                    accumulated_gas = accumulated_gas.saturating_add(gas_used_plus_one - 1);
+                    next_idx += 1;
+                }
+            }
+        }
+        if reached(next_idx, accumulated_gas) {
+            scheduler.halt_at(next_idx);
+        }
+        *prefix = (next_idx, accumulated_gas);
+    }
+}
+
 pub struct ParallelTransactionExecutor<T: Transaction, E: ExecutorTask> {
     // number of active concurrent tasks, corresponding to the maximum number of rayon
     // threads that may be concurrently participating in parallel execution.
     concurrency_level: usize,
+    // gas budget of a block: once the gas used by the committed transactions reaches it, the
+    // rest of the transactions are skipped.
+    maybe_block_gas_limit: Option<u64>,
     phantom: PhantomData<(T, E)>,
 }
 
@@ -122,7 +192,7 @@ where
 {
     /// The caller needs to ensure that concurrency_level > 1 (0 is illegal and 1 should
     /// be handled by sequential execution) and that concurrency_level <= num_cpus.
-    pub fn new(concurrency_level: usize) -> Self {
+    pub fn new(concurrency_level: usize, maybe_block_gas_limit: Option<u64>) -> Self {
         assert!(
             concurrency_level > 1 && concurrency_level <= num_cpus::get(),
             "Parallel execution concurrency level {} should be between 2 and number of CPUs",
@@ -130,6 +200,7 @@ where
         );
         Self {
             concurrency_level,
+            maybe_block_gas_limit,
             phantom: PhantomData,
         }
     }
@@ -147,6 +218,7 @@ where
         versioned_data_cache: &MVHashMap<<T as Transaction>::Key, <T as Transaction>::Value>,
         scheduler: &'a Scheduler,
         executor: &E,
+        gas_tracker: Option<&BlockGasTracker>,
     ) -> SchedulerTask<'a> {
         let (idx_to_execute, incarnation) = version;
         let txn = &signature_verified_block[idx_to_execute];
@@ -161,6 +233,7 @@ where
         // VM execution.
         let execute_result = executor.execute_transaction(&state_view, txn);
         let mut prev_write_set: HashSet<T::Key> = last_input_output.write_set(idx_to_execute);
+        let mut captured_reads = state_view.take_reads();
 
         // For tracking whether the recent execution wrote outside of the previous write set.
         let mut writes_outside = false;
@@ -172,6 +245,17 @@ where
                 }
                 versioned_data_cache.write(&k, write_version, v);
             }
+            for (k, delta) in output.get_deltas().into_iter() {
+                if !prev_write_set.remove(&k) {
+                    writes_outside = true
+                }
+                // Reads of a path updated with a delta were only used to compute the delta,
+                // which commutes with the deltas of lower transactions.
+                for read in captured_reads.iter_mut().filter(|read| read.path() == &k) {
+                    read.validate_base_only();
+                }
+                versioned_data_cache.add_delta(&k, write_version, delta);
+            }
         };
 
         let result = match execute_result {
@@ -200,7 +284,17 @@ where
             versioned_data_cache.delete(k, idx_to_execute);
         }
 
-        last_input_output.record(idx_to_execute, state_view.take_reads(), result);
+        if let Some(gas_tracker) = gas_tracker {
+            let gas_used = match &result {
+                ExecutionStatus::Success(output) | ExecutionStatus::SkipRest(output) => {
+                    output.gas_used()
+                }
+                ExecutionStatus::Abort(_) => 0,
+            };
+            gas_tracker.record(idx_to_execute, gas_used, scheduler);
+        }
+
+        last_input_output.record(idx_to_execute, captured_reads, result);
         scheduler.finish_execution(idx_to_execute, incarnation, writes_outside, guard)
     }
 
@@ -223,7 +317,10 @@ where
 
         let valid = read_set.iter().all(|r| {
             match versioned_data_cache.read(r.path(), idx_to_validate) {
-                Ok((version, _)) => r.validate_version(version),
+                Ok(MVHashMapOutput::Version(version, _)) => r.validate_version(version),
+                Ok(MVHashMapOutput::Deltas(base, deltas)) => {
+                    r.validate_deltas(base.map(|(version, _)| version), deltas)
+                }
                 Err(Some(_)) => false, // Dependency implies a validation failure.
                 Err(None) => r.validate_storage(),
             }
@@ -254,6 +351,7 @@ where
         >,
         versioned_data_cache: &MVHashMap<<T as Transaction>::Key, <T as Transaction>::Value>,
         scheduler: &Scheduler,
+        gas_tracker: Option<&BlockGasTracker>,
     ) {
         // Make executor for each task. TODO: fast concurrent executor.
         let executor = E::init(*executor_arguments);
@@ -276,6 +374,7 @@ where
                     versioned_data_cache,
                     scheduler,
                     &executor,
+                    gas_tracker,
                 ),
                 SchedulerTask::ExecutionTask(_, Some(condvar), _guard) => {
                     let (lock, cvar) = &*condvar;
@@ -303,32 +402,71 @@ where
             return Ok(vec![]);
         }
 
+        let results = match self.execute_transactions_parallel_impl(
+            executor_initial_arguments,
+            &signature_verified_block,
+            self.maybe_block_gas_limit.is_some(),
+        ) {
+            Some(results) => results,
+            // The block reaches its gas limit later than estimated, execute it again without
+            // halting, the gas limit is then applied to the final outputs.
+            None => self
+                .execute_transactions_parallel_impl(
+                    executor_initial_arguments,
+                    &signature_verified_block,
+                    false,
+                )
+                .expect("Executing the block without halting is final"),
+        };
+
+        spawn(move || {
+            // Explicit async drop.
+            drop(signature_verified_block);
+        });
+        results
+    }
+
+    /// Executes the block, halting the scheduling at the transaction with which the block is
+    /// estimated to reach its gas limit if `halt_at_gas_limit`. Returns None if the execution
+    /// halted, but the final outputs of the executed transactions don't reach the limit.
+    fn execute_transactions_parallel_impl(
+        &self,
+        executor_initial_arguments: E::Argument,
+        signature_verified_block: &[T],
+        halt_at_gas_limit: bool,
+    ) -> Option<Result<Vec<E::Output>, E::Error>> {
         let num_txns = signature_verified_block.len();
         let versioned_data_cache = MVHashMap::new();
         let outcomes = OutcomeArray::new(num_txns);
         let last_input_output = TxnLastInputOutput::new(num_txns);
         let scheduler = Scheduler::new(num_txns);
+        let gas_tracker = self
+            .maybe_block_gas_limit
+            .filter(|_| halt_at_gas_limit)
+            .map(|block_gas_limit| BlockGasTracker::new(block_gas_limit, num_txns));
 
         RAYON_EXEC_POOL.scope(|s| {
             for _ in 0..self.concurrency_level {
                 s.spawn(|_| {
                     self.work_task_with_scope(
                         &executor_initial_arguments,
-                        &signature_verified_block,
+                        signature_verified_block,
                         &last_input_output,
                         &versioned_data_cache,
                         &scheduler,
+                        gas_tracker.as_ref(),
                     );
                 });
             }
         });
 
-        // Extract outputs in parallel.
-        let num_txns = scheduler.num_txn_to_execute();
-        let valid_results_size = AtomicUsize::new(num_txns);
-        let chunk_size = (num_txns + 4 * self.concurrency_level - 1) / (4 * self.concurrency_level);
+        // Extract outputs in parallel, only the transactions before the halt were executed.
+        let num_executed_txns = scheduler.num_txn_to_execute();
+        let valid_results_size = AtomicUsize::new(num_executed_txns);
+        let chunk_size =
+            (num_executed_txns + 4 * self.concurrency_level - 1) / (4 * self.concurrency_level);
         RAYON_EXEC_POOL.install(|| {
-            (0..num_txns)
+            (0..num_executed_txns)
                 .collect::<Vec<TxnIndex>>()
                 .par_chunks(chunk_size)
                 .map(|chunk| {
@@ -343,13 +481,69 @@ where
                 .collect::<()>();
         });
 
+        let mut valid_results_size = valid_results_size.load(Ordering::SeqCst);
+        if let Some(block_gas_limit) = self.maybe_block_gas_limit {
+            // The gas used by a transaction is final once the block is executed, so the
+            // transactions exceeding the limit are determined in the preset serialization order.
+            match outcomes.gas_limit_reached_at(valid_results_size, block_gas_limit) {
+                Some(gas_limit_stop_at) => valid_results_size = gas_limit_stop_at,
+                None if num_executed_txns < num_txns && valid_results_size == num_executed_txns => {
+                    return None;
+                }
+                None => (),
+            }
+        }
+        let results = outcomes
+            .get_all_results(valid_results_size)
+            .and_then(|outputs| {
+                Self::materialize_deltas(
+                    executor_initial_arguments,
+                    outputs,
+                    valid_results_size,
+                    &versioned_data_cache,
+                )
+            });
+
         spawn(move || {
             // Explicit async drops.
             drop(last_input_output);
-            drop(signature_verified_block);
             drop(versioned_data_cache);
             drop(scheduler);
         });
-        outcomes.get_all_results(valid_results_size.load(Ordering::SeqCst))
+        Some(results)
+    }
+
+    /// Applies the deltas of the committed transactions (the first `num_txns` of the block) in
+    /// order, and replaces them with the materialized values in the outputs.
+    fn materialize_deltas(
+        executor_arguments: E::Argument,
+        mut outputs: Vec<E::Output>,
+        num_txns: usize,
+        versioned_data_cache: &MVHashMap<<T as Transaction>::Key, <T as Transaction>::Value>,
+    ) -> Result<Vec<E::Output>, E::Error> {
+        let delta_keys: HashSet<T::Key> = outputs
+            .iter()
+            .flat_map(|output| output.get_deltas().into_iter().map(|(k, _)| k))
+            .collect();
+        if delta_keys.is_empty() {
+            return Ok(outputs);
+        }
+
+        let executor = E::init(executor_arguments);
+        let mut delta_writes: HashMap<TxnIndex, Vec<_>> = HashMap::new();
+        for k in delta_keys {
+            let materialized = versioned_data_cache
+                .materialize_deltas(&k, num_txns, |base, delta| {
+                    executor.apply_delta(&k, base, delta)
+                })
+                .ok_or(Error::DeltaApplicationFailure)?;
+            for (idx, v) in materialized {
+                delta_writes.entry(idx).or_default().push((k.clone(), v));
+            }
+        }
+        for (idx, writes) in delta_writes {
+            outputs[idx].incorporate_delta_writes(writes);
+        }
+        Ok(outputs)
     }
 }
@@ -134,6 +134,17 @@ optimistically creating validation tasks for higher transactions in 2(b),
 and threads that perform these tasks can already detect validation failures
 due to the ESTIMATE markers on memory locations, instead of waiting for a
 subsequent incarnation to finish.
+
+Transactions that only add to or subtract from a counter (e.g. a coin supply)
+may record a delta instead of a write. Deltas are stored in the multi-version
+data-structure alongside writes, and a read of a location returns the latest
+prior write together with the deltas recorded after it. Since deltas commute,
+the reads of a location that a transaction updates with a delta are only
+validated against the write the deltas apply to, i.e. such transactions do not
+conflict with each other. Deltas are materialized in the preset serialization
+order once the block is executed. Whether a counter stays within its bounds is
+only known at that point, so a failure to apply a delta fails the block, which
+then needs to be executed sequentially.
 **/
 pub mod errors;
 pub mod executor;
@@ -28,6 +28,26 @@ impl<T: TransactionOutput, E: Send> OutcomeArray<T, E> {
         assert!(entry.set(res).is_ok());
     }
 
+    /// Returns the number of transactions to commit among the first `stop_at`, such that the
+    /// execution stops after the transaction with which the gas used by the committed
+    /// transactions reaches `block_gas_limit`, or None if they don't reach it.
+    pub fn gas_limit_reached_at(&self, stop_at: usize, block_gas_limit: u64) -> Option<usize> {
+        let mut accumulated_gas: u64 = 0;
+        for (idx, status) in self.results.iter().take(stop_at).enumerate() {
+            match status.get() {
+                Some(ExecutionStatus::Success(t)) | Some(ExecutionStatus::SkipRest(t)) => {
+                    accumulated_gas = accumulated_gas.saturating_add(t.gas_used());
+                    if accumulated_gas >= block_gas_limit {
+                        return Some(idx + 1);
+                    }
+                }
+                // The block fails at this transaction.
+                Some(ExecutionStatus::Abort(_)) | None => return None,
+            }
+        }
+        None
+    }
+
     pub fn get_all_results(self, stop_at: usize) -> Result<Vec<T>, E> {
         let len = self.results.len();
         let mut final_results = Vec::with_capacity(stop_at);
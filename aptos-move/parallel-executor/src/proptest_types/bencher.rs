@@ -4,7 +4,7 @@
 use crate::{
     executor::ParallelTransactionExecutor,
     proptest_types::types::{
-        ExpectedOutput, Task, Transaction, TransactionGen, TransactionGenParams,
+        CounterValue, ExpectedOutput, Task, Transaction, TransactionGen, TransactionGenParams,
     },
 };
 use criterion::{BatchSize, Bencher as CBencher};
@@ -35,7 +35,7 @@ pub(crate) struct BencherState<K, V> {
 impl<K, V> Bencher<K, V>
 where
     K: Hash + Clone + Debug + Eq + Send + Sync + PartialOrd + Ord + Arbitrary + 'static,
-    V: Clone + Eq + Send + Sync + Arbitrary + 'static,
+    V: Clone + Eq + Send + Sync + Arbitrary + CounterValue + 'static,
 {
     pub fn new(transaction_size: usize, universe_size: usize) -> Self {
         Self {
@@ -66,7 +66,7 @@ where
 impl<K, V> BencherState<K, V>
 where
     K: Hash + Clone + Debug + Eq + Send + Sync + PartialOrd + Ord + 'static,
-    V: Clone + Eq + Send + Sync + Arbitrary + 'static,
+    V: Clone + Eq + Send + Sync + Arbitrary + CounterValue + 'static,
 {
     /// Creates a new benchmark state with the given account universe strategy and number of
     /// transactions.
@@ -94,7 +94,7 @@ where
             .map(|txn_gen| txn_gen.materialize(&key_universe))
             .collect();
 
-        let expected_output = ExpectedOutput::generate_baseline(&transactions);
+        let expected_output = ExpectedOutput::generate_baseline(&transactions, None);
 
         Self {
             transactions,
@@ -103,9 +103,11 @@ where
     }
 
     pub(crate) fn run(self) {
-        let output =
-            ParallelTransactionExecutor::<Transaction<K, V>, Task<K, V>>::new(num_cpus::get())
-                .execute_transactions_parallel((), self.transactions.clone());
+        let output = ParallelTransactionExecutor::<Transaction<K, V>, Task<K, V>>::new(
+            num_cpus::get(),
+            None,
+        )
+        .execute_transactions_parallel((), self.transactions.clone());
 
         assert!(self.expected_output.check_output(&output));
     }
@@ -4,7 +4,7 @@
 use crate::{
     executor::ParallelTransactionExecutor,
     proptest_types::types::{
-        ExpectedOutput, Task, Transaction, TransactionGen, TransactionGenParams,
+        CounterValue, ExpectedOutput, Task, Transaction, TransactionGen, TransactionGenParams,
     },
 };
 use num_cpus;
@@ -22,11 +22,12 @@ fn run_transactions<K, V>(
     transaction_gens: Vec<TransactionGen<V>>,
     abort_transactions: Vec<Index>,
     skip_rest_transactions: Vec<Index>,
+    maybe_block_gas_limit: Option<u64>,
     num_repeat: usize,
 ) -> bool
 where
     K: Hash + Clone + Debug + Eq + Send + Sync + PartialOrd + Ord + 'static,
-    V: Clone + Eq + Send + Sync + Arbitrary + 'static,
+    V: Clone + Eq + Send + Sync + Arbitrary + CounterValue + 'static,
 {
     let mut transactions: Vec<_> = transaction_gens
         .into_iter()
@@ -45,11 +46,13 @@ where
 
     let mut ret = true;
     for _ in 0..num_repeat {
-        let output =
-            ParallelTransactionExecutor::<Transaction<K, V>, Task<K, V>>::new(num_cpus::get())
-                .execute_transactions_parallel((), transactions.clone());
+        let output = ParallelTransactionExecutor::<Transaction<K, V>, Task<K, V>>::new(
+            num_cpus::get(),
+            maybe_block_gas_limit,
+        )
+        .execute_transactions_parallel((), transactions.clone());
 
-        let baseline = ExpectedOutput::generate_baseline(&transactions);
+        let baseline = ExpectedOutput::generate_baseline(&transactions, maybe_block_gas_limit);
 
         ret = ret && baseline.check_output(&output);
     }
@@ -65,7 +68,7 @@ proptest! {
         abort_transactions in vec(any::<Index>(), 0),
         skip_rest_transactions in vec(any::<Index>(), 0),
     ) {
-        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, 1));
+        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, None, 1));
     }
 
     #[test]
@@ -75,7 +78,7 @@ proptest! {
         abort_transactions in vec(any::<Index>(), 5),
         skip_rest_transactions in vec(any::<Index>(), 0),
     ) {
-        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, 1));
+        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, None, 1));
     }
 
     #[test]
@@ -85,7 +88,7 @@ proptest! {
         abort_transactions in vec(any::<Index>(), 0),
         skip_rest_transactions in vec(any::<Index>(), 5),
     ) {
-        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, 1));
+        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, None, 1));
     }
 
     #[test]
@@ -95,7 +98,7 @@ proptest! {
         abort_transactions in vec(any::<Index>(), 5),
         skip_rest_transactions in vec(any::<Index>(), 5),
     ) {
-        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, 1));
+        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, None, 1));
     }
 
     #[test]
@@ -105,7 +108,38 @@ proptest! {
         abort_transactions in vec(any::<Index>(), 3),
         skip_rest_transactions in vec(any::<Index>(), 3),
     ) {
-        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, 1));
+        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, None, 1));
+    }
+
+    #[test]
+    fn block_gas_limit_mixed(
+        universe in vec(any::<[u8; 32]>(), 100),
+        transaction_gen in vec(any_with::<TransactionGen<[u8;32]>>(TransactionGenParams::new_dynamic()), 3000).no_shrink(),
+        abort_transactions in vec(any::<Index>(), 3),
+        skip_rest_transactions in vec(any::<Index>(), 3),
+        block_gas_limit in 1..10000u64,
+    ) {
+        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, Some(block_gas_limit), 1));
+    }
+
+    #[test]
+    fn deltas_mixed(
+        universe in vec(any::<[u8; 32]>(), 100),
+        transaction_gen in vec(any_with::<TransactionGen<[u8;32]>>(TransactionGenParams::new_with_deltas(u128::MAX)), 3000).no_shrink(),
+        abort_transactions in vec(any::<Index>(), 3),
+        skip_rest_transactions in vec(any::<Index>(), 3),
+    ) {
+        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, None, 1));
+    }
+
+    #[test]
+    fn deltas_overflow_underflow(
+        universe in vec(any::<[u8; 32]>(), 10),
+        transaction_gen in vec(any_with::<TransactionGen<[u8;32]>>(TransactionGenParams::new_with_deltas(1 << 37)), 1000).no_shrink(),
+        abort_transactions in vec(any::<Index>(), 0),
+        skip_rest_transactions in vec(any::<Index>(), 0),
+    ) {
+        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, None, 1));
     }
 }
 
@@ -130,6 +164,7 @@ fn dynamic_read_writes() {
         transaction_gen,
         vec![],
         vec![],
+        None,
         100
     ));
 }
@@ -156,6 +191,7 @@ fn dynamic_read_writes_contended() {
         transaction_gen,
         vec![],
         vec![],
+        None,
         100
     ));
 }
@@ -3,13 +3,15 @@
 
 use crate::{
     errors::{Error, Result},
-    executor::MVHashMapView,
+    executor::{MVHashMapView, ReadResult},
     task::{ExecutionStatus, ExecutorTask, Transaction as TransactionType, TransactionOutput},
 };
+use mvhashmap::delta::{apply_deltas, DeltaOp, DeltaUpdate};
 use proptest::{arbitrary::Arbitrary, collection::vec, prelude::*, proptest, sample::Index};
 use proptest_derive::Arbitrary;
 use std::{
     collections::{BTreeSet, HashMap},
+    convert::TryFrom,
     fmt::Debug,
     hash::Hash,
     marker::PhantomData,
@@ -34,6 +36,11 @@ pub struct TransactionGenParams {
     /// corresponds to a static transaction, while read_write_alternatives > 1 may lead to dynamic
     /// behavior when executing different incarnations of the transaction.
     pub read_write_alternatives: usize,
+    /// Each transaction's delta-set consists of between 0 and delta_size-1 many deltas.
+    pub delta_size: usize,
+    /// Limit of the generated deltas. The deltas of keys that have no value apply to half of
+    /// the limit, so that both overflows and underflows may occur.
+    pub delta_limit: u128,
 }
 
 #[derive(Arbitrary, Debug, Clone)]
@@ -49,6 +56,50 @@ pub struct TransactionGen<V: Arbitrary + Debug + 'static + Clone> {
         strategy = "vec(vec(any::<Index>(), 1..params.read_size), 1..params.read_write_alternatives)"
     )]
     keys_read: Vec<Vec<Index>>,
+    /// Generate keys and updates (addition or subtraction of a value) for possible delta-sets
+    /// of the transaction based on the above parameters.
+    #[proptest(
+        strategy = "vec(vec((any::<Index>(), any::<bool>(), any::<u32>()), 0..params.delta_size), 1..params.read_write_alternatives)"
+    )]
+    keys_delta: Vec<Vec<(Index, bool, u32)>>,
+    #[proptest(strategy = "Just(params.delta_limit)")]
+    delta_limit: u128,
+}
+
+/// Values of the naive transactions, interpreted as counters when updated by deltas.
+pub trait CounterValue {
+    fn counter(&self) -> u128;
+
+    fn from_counter(counter: u128) -> Self;
+}
+
+impl CounterValue for [u8; 32] {
+    fn counter(&self) -> u128 {
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&self[..16]);
+        u128::from_le_bytes(bytes)
+    }
+
+    fn from_counter(counter: u128) -> Self {
+        let mut value = [0u8; 32];
+        value[..16].copy_from_slice(&counter.to_le_bytes());
+        value
+    }
+}
+
+impl CounterValue for u64 {
+    fn counter(&self) -> u128 {
+        *self as u128
+    }
+
+    fn from_counter(counter: u128) -> Self {
+        u64::try_from(counter).expect("Counter must fit in u64")
+    }
+}
+
+/// Base of the deltas of a key without a value: half of the limit.
+fn counter_base<V: CounterValue>(value: Option<&V>, delta: &DeltaOp) -> u128 {
+    value.map_or(delta.limit() / 2, V::counter)
 }
 
 /// A naive transaction that could be used to test the correctness and throughput of the system.
@@ -68,6 +119,10 @@ pub enum Transaction<K, V> {
         /// Vector of all possible read-sets of the transaction execution (chosen round-robin depending
         /// on the incarnation counter value). Each read set is a vector of keys that are read.
         reads: Vec<Vec<K>>,
+        /// Vector of all possible delta-sets of transaction execution (chosen round-robin
+        /// depending on the incarnation counter value). Each delta set is a vector of deltas,
+        /// each to a key that is not written by the transaction.
+        deltas: Vec<Vec<(K, DeltaOp)>>,
     },
     /// Skip the execution of trailing transactions.
     SkipRest,
@@ -81,6 +136,18 @@ impl TransactionGenParams {
             write_size: 5,
             read_size: 10,
             read_write_alternatives: 4,
+            delta_size: 1,
+            delta_limit: u128::MAX,
+        }
+    }
+
+    pub fn new_with_deltas(delta_limit: u128) -> Self {
+        TransactionGenParams {
+            write_size: 5,
+            read_size: 10,
+            read_write_alternatives: 4,
+            delta_size: 4,
+            delta_limit,
         }
     }
 }
@@ -91,6 +158,8 @@ impl Default for TransactionGenParams {
             write_size: 5,
             read_size: 10,
             read_write_alternatives: 2,
+            delta_size: 1,
+            delta_limit: u128::MAX,
         }
     }
 }
@@ -112,9 +181,28 @@ impl<V: Arbitrary + Debug + Clone> TransactionGen<V> {
             writes.push(incarnation_writes);
         }
 
+        let mut deltas = vec![];
+        for keys_delta in self.keys_delta.into_iter() {
+            let mut incarnation_deltas: Vec<(K, DeltaOp)> = vec![];
+            for (idx, plus, value) in keys_delta.into_iter() {
+                let key = universe[idx.index(universe.len())].clone();
+                if !keys_modified.contains(&key) {
+                    keys_modified.insert(key.clone());
+                    let update = if plus {
+                        DeltaUpdate::Plus(value as u128)
+                    } else {
+                        DeltaUpdate::Minus(value as u128)
+                    };
+                    incarnation_deltas.push((key, DeltaOp::new(update, self.delta_limit)));
+                }
+            }
+            deltas.push(incarnation_deltas);
+        }
+
         Transaction::Write {
             incarnation: Arc::new(AtomicUsize::new(0)),
             writes,
+            deltas,
             reads: self
                 .keys_read
                 .into_iter()
@@ -153,7 +241,7 @@ impl<K, V> Task<K, V> {
 impl<K, V> ExecutorTask for Task<K, V>
 where
     K: PartialOrd + Send + Sync + Clone + Hash + Eq + 'static,
-    V: Send + Sync + Debug + Clone + 'static,
+    V: Send + Sync + Debug + Clone + CounterValue + 'static,
 {
     type T = Transaction<K, V>;
     type Output = Output<K, V>;
@@ -174,6 +262,7 @@ where
                 incarnation,
                 reads,
                 writes,
+                deltas,
             } => {
                 // Use incarnation counter value as an index to determine the read-
                 // and write-sets of the execution. Increment incarnation counter to
@@ -182,21 +271,53 @@ where
                 let idx = incarnation.fetch_add(1, Ordering::SeqCst);
                 let read_idx = idx % reads.len();
                 let write_idx = idx % writes.len();
+                let delta_idx = idx % deltas.len();
 
                 // Reads
                 let mut reads_result = vec![];
                 for k in reads[read_idx].iter() {
-                    reads_result.push(view.read(k).map(|v| (*v).clone()));
+                    let value = match view.read(k) {
+                        ReadResult::Value(v) => Some((*v).clone()),
+                        ReadResult::Deltas(base, deltas) => {
+                            apply_deltas(counter_base(base.as_deref(), &deltas[0]), &deltas)
+                                .ok()
+                                .map(V::from_counter)
+                        }
+                        ReadResult::None => None,
+                    };
+                    // Reads of keys the transaction updates with deltas may only be used
+                    // to compute the deltas, so their values are not recorded.
+                    if deltas[delta_idx]
+                        .iter()
+                        .any(|(delta_key, _)| delta_key == k)
+                    {
+                        reads_result.push(None);
+                    } else {
+                        reads_result.push(value);
+                    }
                 }
-                ExecutionStatus::Success(Output(writes[write_idx].clone(), reads_result))
+                ExecutionStatus::Success(Output(
+                    writes[write_idx].clone(),
+                    deltas[delta_idx].clone(),
+                    reads_result,
+                ))
             }
-            Transaction::SkipRest => ExecutionStatus::SkipRest(Output(vec![], vec![])),
+            Transaction::SkipRest => ExecutionStatus::SkipRest(Output(vec![], vec![], vec![])),
             Transaction::Abort => ExecutionStatus::Abort(view.txn_idx()),
         }
     }
+
+    fn apply_delta(&self, _key: &K, base: Option<&V>, delta: &DeltaOp) -> Option<V> {
+        delta
+            .apply_to(counter_base(base, delta))
+            .ok()
+            .map(V::from_counter)
+    }
 }
 
-pub struct Output<K, V>(Vec<(K, V)>, Vec<Option<V>>);
+/// Output of a naive transaction: its writes, its deltas and the results of its reads, followed
+/// by the values materialized from its deltas (in order of the deltas) once it commits.
+pub struct Output<K, V>(Vec<(K, V)>, Vec<(K, DeltaOp)>, Vec<Option<V>>);
 
 impl<K, V> TransactionOutput for Output<K, V>
 where
@@ -209,8 +330,25 @@ where
         self.0.clone()
     }
 
+    fn get_deltas(&self) -> Vec<(K, DeltaOp)> {
+        self.1.clone()
+    }
+
+    fn incorporate_delta_writes(&mut self, writes: Vec<(K, V)>) {
+        for (k, _) in self.1.drain(..) {
+            let materialized = writes.iter().find(|(key, _)| *key == k);
+            self.2.push(materialized.map(|(_, v)| v.clone()));
+        }
+        self.0.extend(writes);
+    }
+
+    /// Every write and delta of a naive transaction costs one unit of gas.
+    fn gas_used(&self) -> u64 {
+        (self.0.len() + self.1.len()) as u64
+    }
+
     fn skip_output() -> Self {
-        Self(vec![], vec![])
+        Self(vec![], vec![], vec![])
     }
 }
 
@@ -223,13 +361,20 @@ pub enum ExpectedOutput<V> {
     Aborted(usize),
     SkipRest(usize, Vec<Vec<Option<V>>>),
     Success(Vec<Vec<Option<V>>>),
+    DeltaFailure,
 }
 
-impl<V: Clone + Eq> ExpectedOutput<V> {
+impl<V: Clone + Eq + CounterValue> ExpectedOutput<V> {
     /// Must be invoked after parallel execution to work with dynamic read/writes.
-    pub fn generate_baseline<K: Hash + Clone + Eq>(txns: &[Transaction<K, V>]) -> Self {
+    pub fn generate_baseline<K: Hash + Clone + Eq>(
+        txns: &[Transaction<K, V>],
+        maybe_block_gas_limit: Option<u64>,
+    ) -> Self {
         let mut current_world = HashMap::new();
         let mut result_vec = vec![];
+        let mut accumulated_gas: u64 = 0;
+        // Whether a delta failed to apply, which fails the block unless a transaction aborts.
+        let mut delta_failure = false;
         for (idx, txn) in txns.iter().enumerate() {
             match txn {
                 Transaction::Abort => return Self::Aborted(idx),
@@ -237,6 +382,7 @@ impl<V: Clone + Eq> ExpectedOutput<V> {
                     incarnation,
                     reads,
                     writes,
+                    deltas,
                 } => {
                     // Determine the read and write sets of the latest incarnation
                     // of the transaction. The index for choosing the read and
@@ -259,40 +405,78 @@ impl<V: Clone + Eq> ExpectedOutput<V> {
                         assert!(incarnation > 0, "must run after parallel execution");
                         &writes[(incarnation - 1) as usize % writes.len()]
                     };
+                    let delta_set = if deltas.len() == 1 {
+                        // Static delta-set.
+                        &deltas[0]
+                    } else {
+                        assert!(incarnation > 0, "must run after parallel execution");
+                        &deltas[(incarnation - 1) as usize % deltas.len()]
+                    };
 
                     let mut result = vec![];
                     for k in read_set.iter() {
-                        result.push(current_world.get(k).cloned());
+                        if delta_set.iter().any(|(delta_key, _)| delta_key == k) {
+                            result.push(None);
+                        } else {
+                            result.push(current_world.get(k).cloned());
+                        }
                     }
                     for (k, v) in write_set.iter() {
                         current_world.insert(k.clone(), v.clone());
                     }
-                    result_vec.push(result)
+                    for (k, delta) in delta_set.iter() {
+                        match delta.apply_to(counter_base(current_world.get(k), delta)) {
+                            Ok(counter) => {
+                                let v = V::from_counter(counter);
+                                current_world.insert(k.clone(), v.clone());
+                                result.push(Some(v));
+                            }
+                            Err(_) => delta_failure = true,
+                        }
+                    }
+                    result_vec.push(result);
+
+                    accumulated_gas += (write_set.len() + delta_set.len()) as u64;
+                    if maybe_block_gas_limit.map_or(false, |limit| accumulated_gas >= limit) {
+                        // The rest of the transactions are skipped as with SkipRest, except
+                        // that the transaction reaching the limit is committed.
+                        return if delta_failure {
+                            Self::DeltaFailure
+                        } else {
+                            Self::SkipRest(idx + 1, result_vec)
+                        };
+                    }
                 }
+                Transaction::SkipRest if delta_failure => return Self::DeltaFailure,
                 Transaction::SkipRest => return Self::SkipRest(idx, result_vec),
             }
         }
-        Self::Success(result_vec)
+        if delta_failure {
+            Self::DeltaFailure
+        } else {
+            Self::Success(result_vec)
+        }
     }
 
     pub fn check_output<K>(&self, results: &Result<Vec<Output<K, V>>, usize>) -> bool {
         match (self, results) {
             (Self::Aborted(i), Err(Error::UserError(idx))) => i == idx,
+            (Self::DeltaFailure, Err(Error::DeltaApplicationFailure)) => true,
             (Self::SkipRest(skip_at, expected_results), Ok(results)) => {
                 results
                     .iter()
                     .take(*skip_at)
                     .zip(expected_results.iter())
-                    .all(|(Output(_, result), expected_results)| expected_results == result)
+                    .all(|(Output(_, _, result), expected_results)| expected_results == result)
                     && results
                         .iter()
                         .skip(*skip_at)
-                        .all(|Output(_, result)| result.is_empty())
+                        .all(|Output(_, _, result)| result.is_empty())
             }
             (Self::Success(expected_results), Ok(results)) => expected_results
                 .iter()
                 .zip(results.iter())
-                .all(|(expected_result, Output(_, result))| expected_result == result),
+                .all(|(expected_result, Output(_, _, result))| expected_result == result),
             _ => false,
         }
     }
@@ -105,8 +105,9 @@ impl PartialEq for TransactionStatus {
 }
 
 pub struct Scheduler {
-    /// Number of txns to execute, immutable.
-    num_txns: usize,
+    /// Number of txns to execute, initially the size of the block. Transactions from this index
+    /// on are not scheduled, it's lowered when the block is estimated to reach its gas limit.
+    stop_idx: AtomicUsize,
 
     /// A shared index that tracks the minimum of all transaction indices that require execution.
     /// The threads increment the index and attempt to create an execution task for the corresponding
@@ -141,7 +142,7 @@ pub struct Scheduler {
 impl Scheduler {
     pub fn new(num_txns: usize) -> Self {
         Self {
-            num_txns,
+            stop_idx: AtomicUsize::new(num_txns),
             execution_idx: AtomicUsize::new(0),
             validation_idx: AtomicUsize::new(0),
             decrease_cnt: AtomicUsize::new(0),
@@ -158,7 +159,13 @@ impl Scheduler {
 
     /// Return the number of transactions to be executed from the block.
     pub fn num_txn_to_execute(&self) -> usize {
-        self.num_txns
+        self.stop_idx.load(Ordering::SeqCst)
+    }
+
+    /// Stop scheduling the transactions from txn_idx on. The transactions before are still
+    /// executed and validated as usual, as they never read the writes of higher transactions.
+    pub fn halt_at(&self, txn_idx: TxnIndex) {
+        self.stop_idx.fetch_min(txn_idx, Ordering::SeqCst);
     }
 
     /// Try to abort version = (txn_idx, incarnation), called upon validation failure.
@@ -393,7 +400,7 @@ impl Scheduler {
     fn try_validate_next_version(&self) -> Option<(Version, TaskGuard)> {
         let idx_to_validate = self.validation_idx.load(Ordering::SeqCst);
 
-        if idx_to_validate >= self.num_txns {
+        if idx_to_validate >= self.num_txn_to_execute() {
             if !self.check_done() {
                 // Avoid pointlessly spinning, and give priority to other threads that may
                 // be working to finish the remaining tasks.
@@ -423,7 +430,7 @@ impl Scheduler {
     fn try_execute_next_version(&self) -> Option<(Version, Option<DependencyCondvar>, TaskGuard)> {
         let idx_to_execute = self.execution_idx.load(Ordering::SeqCst);
 
-        if idx_to_execute >= self.num_txns {
+        if idx_to_execute >= self.num_txn_to_execute() {
             if !self.check_done() {
                 // Avoid pointlessly spinning, and give priority to other threads that may
                 // be working to finish the remaining tasks.
@@ -508,7 +515,7 @@ impl Scheduler {
         let val_idx = self.validation_idx.load(Ordering::SeqCst);
         let exec_idx = self.execution_idx.load(Ordering::SeqCst);
         let num_tasks = self.num_active_tasks.load(Ordering::SeqCst);
-        if min(exec_idx, val_idx) < self.num_txns || num_tasks > 0 {
+        if min(exec_idx, val_idx) < self.num_txn_to_execute() || num_tasks > 0 {
             // There is work remaining.
             return false;
         }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::executor::MVHashMapView;
+use mvhashmap::delta::DeltaOp;
 use std::{fmt::Debug, hash::Hash};
 
 /// The execution result of a transaction
@@ -55,6 +56,16 @@ pub trait ExecutorTask: Sync {
         view: &MVHashMapView<<Self::T as Transaction>::Key, <Self::T as Transaction>::Value>,
         txn: &Self::T,
     ) -> ExecutionStatus<Self::Output, Self::Error>;
+
+    /// Apply a delta to `base`, the latest value written to `key` in the block, or to the value
+    /// of `key` in storage when `base` is None. Returns None if the delta can't be applied, e.g.
+    /// when the counter it updates overflows.
+    fn apply_delta(
+        &self,
+        key: &<Self::T as Transaction>::Key,
+        base: Option<&<Self::T as Transaction>::Value>,
+        delta: &DeltaOp,
+    ) -> Option<<Self::T as Transaction>::Value>;
 }
 
 /// Trait for execution result of a transaction.
@@ -70,6 +81,23 @@ pub trait TransactionOutput: Send + Sync {
         <Self::T as Transaction>::Value,
     )>;
 
+    /// Get the delta operations of a transaction from its output. A transaction must use its
+    /// reads of a key it updates with a delta only to compute the delta, since these reads are
+    /// validated against the value the deltas apply to, ignoring the deltas of lower transactions.
+    fn get_deltas(&self) -> Vec<(<Self::T as Transaction>::Key, DeltaOp)>;
+
+    /// Replace the deltas of a transaction with the values materialized from them at commit.
+    fn incorporate_delta_writes(
+        &mut self,
+        writes: Vec<(
+            <Self::T as Transaction>::Key,
+            <Self::T as Transaction>::Value,
+        )>,
+    );
+
+    /// Get the gas used by a transaction, counted against the gas limit of the block.
+    fn gas_used(&self) -> u64;
+
     /// Execution output for transactions that comes after SkipRest signal.
     fn skip_output() -> Self;
 }
@@ -8,6 +8,7 @@ use crate::{
 };
 use arc_swap::ArcSwapOption;
 use crossbeam::utils::CachePadded;
+use mvhashmap::delta::DeltaOp;
 use std::{collections::HashSet, sync::Arc};
 
 type TxnInput<K> = Vec<ReadDescriptor<K>>;
@@ -15,19 +16,37 @@ type TxnOutput<T, E> = ExecutionStatus<T, Error<E>>;
 
 // If an entry was read from the multi-version data-structure, then kind is
 // MVHashMap(txn_idx, incarnation), with transaction index and incarnation number
-// of the execution associated with the write of the entry. Otherwise, if the read
-// occured from storage, and kind is set to Storage.
+// of the execution associated with the write of the entry. If the read resolved
+// deltas written by lower transactions, kind is Deltas, with the version of the
+// write the deltas apply to (None for storage) and the deltas themselves. Otherwise,
+// if the read occured from storage, and kind is set to Storage.
 #[derive(Clone, PartialEq)]
 enum ReadKind {
     MVHashMap(TxnIndex, Incarnation),
+    Deltas(Option<Version>, Vec<DeltaOp>),
     Storage,
 }
 
+impl ReadKind {
+    // Version of the write the read value is based on, None for storage.
+    fn base(&self) -> Option<Version> {
+        match self {
+            ReadKind::MVHashMap(txn_idx, incarnation) => Some((*txn_idx, *incarnation)),
+            ReadKind::Deltas(base, _) => *base,
+            ReadKind::Storage => None,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct ReadDescriptor<K> {
     access_path: K,
 
     kind: ReadKind,
+
+    // Set when the transaction updates the read path with a delta, in which case the
+    // read was only used to compute the delta and only its base needs to be validated.
+    base_only: bool,
 }
 
 impl<K> ReadDescriptor<K> {
@@ -35,6 +54,15 @@ impl<K> ReadDescriptor<K> {
         Self {
             access_path,
             kind: ReadKind::MVHashMap(txn_idx, incarnation),
+            base_only: false,
+        }
+    }
+
+    pub fn from_deltas(access_path: K, base: Option<Version>, deltas: Vec<DeltaOp>) -> Self {
+        Self {
+            access_path,
+            kind: ReadKind::Deltas(base, deltas),
+            base_only: false,
         }
     }
 
@@ -42,6 +70,7 @@ impl<K> ReadDescriptor<K> {
         Self {
             access_path,
             kind: ReadKind::Storage,
+            base_only: false,
         }
     }
 
@@ -49,15 +78,33 @@ impl<K> ReadDescriptor<K> {
         &self.access_path
     }
 
+    // Only validate the base of the read, used for paths the transaction updates with deltas.
+    pub fn validate_base_only(&mut self) {
+        self.base_only = true;
+    }
+
     // Does the read descriptor describe a read from MVHashMap w. a specified version.
     pub fn validate_version(&self, version: Version) -> bool {
         let (txn_idx, incarnation) = version;
-        self.kind == ReadKind::MVHashMap(txn_idx, incarnation)
+        self.validate(ReadKind::MVHashMap(txn_idx, incarnation))
+    }
+
+    // Does the read descriptor describe a read of the specified deltas on top of a base.
+    pub fn validate_deltas(&self, base: Option<Version>, deltas: Vec<DeltaOp>) -> bool {
+        self.validate(ReadKind::Deltas(base, deltas))
     }
 
     // Does the read descriptor describe a read from storage.
     pub fn validate_storage(&self) -> bool {
-        self.kind == ReadKind::Storage
+        self.validate(ReadKind::Storage)
+    }
+
+    fn validate(&self, kind: ReadKind) -> bool {
+        if self.base_only {
+            self.kind.base() == kind.base()
+        } else {
+            self.kind == kind
+        }
     }
 }
 
@@ -93,7 +140,8 @@ impl<K, T: TransactionOutput, E: Send + Clone> TxnLastInputOutput<K, T, E> {
         self.inputs[txn_idx].load_full()
     }
 
-    // Extracts a set of paths written during execution from transaction output.
+    // Extracts a set of paths written (or updated with deltas) during execution from
+    // transaction output.
     pub fn write_set(
         &self,
         txn_idx: TxnIndex,
@@ -101,9 +149,12 @@ impl<K, T: TransactionOutput, E: Send + Clone> TxnLastInputOutput<K, T, E> {
         match &self.outputs[txn_idx].load_full() {
             None => HashSet::new(),
             Some(txn_output) => match txn_output.as_ref() {
-                ExecutionStatus::Success(t) | ExecutionStatus::SkipRest(t) => {
-                    t.get_writes().into_iter().map(|(k, _)| k).collect()
-                }
+                ExecutionStatus::Success(t) | ExecutionStatus::SkipRest(t) => t
+                    .get_writes()
+                    .into_iter()
+                    .map(|(k, _)| k)
+                    .chain(t.get_deltas().into_iter().map(|(k, _)| k))
+                    .collect(),
                 ExecutionStatus::Abort(_) => HashSet::new(),
             },
         }
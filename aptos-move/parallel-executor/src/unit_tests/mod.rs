@@ -3,9 +3,10 @@
 
 use crate::{
     executor::ParallelTransactionExecutor,
-    proptest_types::types::{ExpectedOutput, Task, Transaction},
+    proptest_types::types::{CounterValue, ExpectedOutput, Task, Transaction},
     scheduler::{Scheduler, SchedulerTask, TaskGuard},
 };
+use mvhashmap::delta::{DeltaOp, DeltaUpdate};
 use rand::random;
 use std::{
     fmt::Debug,
@@ -13,15 +14,18 @@ use std::{
     sync::{atomic::AtomicUsize, Arc},
 };
 
-fn run_and_assert<K, V>(transactions: Vec<Transaction<K, V>>)
+fn run_and_assert<K, V>(transactions: Vec<Transaction<K, V>>, maybe_block_gas_limit: Option<u64>)
 where
     K: PartialOrd + Send + Sync + Clone + Hash + Eq + 'static,
-    V: Send + Sync + Debug + Clone + Eq + 'static,
+    V: Send + Sync + Debug + Clone + Eq + CounterValue + 'static,
 {
-    let output = ParallelTransactionExecutor::<Transaction<K, V>, Task<K, V>>::new(num_cpus::get())
-        .execute_transactions_parallel((), transactions.clone());
+    let output = ParallelTransactionExecutor::<Transaction<K, V>, Task<K, V>>::new(
+        num_cpus::get(),
+        maybe_block_gas_limit,
+    )
+    .execute_transactions_parallel((), transactions.clone());
 
-    let baseline = ExpectedOutput::generate_baseline(&transactions);
+    let baseline = ExpectedOutput::generate_baseline(&transactions, maybe_block_gas_limit);
 
     assert!(baseline.check_output(&output))
 }
@@ -41,10 +45,11 @@ fn cycle_transactions() {
                 incarnation: Arc::new(AtomicUsize::new(0)),
                 reads: vec![vec![key]],
                 writes: vec![vec![(key, random::<u64>())]],
+                deltas: vec![vec![]],
             })
         }
     }
-    run_and_assert(transactions)
+    run_and_assert(transactions, None)
 }
 
 const NUM_BLOCKS: u64 = 10;
@@ -60,6 +65,7 @@ fn one_reads_all_barrier() {
                 incarnation: Arc::new(AtomicUsize::new(0)),
                 reads: vec![vec![*key]],
                 writes: vec![vec![(*key, random::<u64>())]],
+                deltas: vec![vec![]],
             })
         }
         // One transaction reading the write results of every prior transactions in the block.
@@ -67,9 +73,10 @@ fn one_reads_all_barrier() {
             incarnation: Arc::new(AtomicUsize::new(0)),
             reads: vec![keys.clone()],
             writes: vec![vec![]],
+            deltas: vec![vec![]],
         })
     }
-    run_and_assert(transactions)
+    run_and_assert(transactions, None)
 }
 
 #[test]
@@ -82,6 +89,7 @@ fn one_writes_all_barrier() {
                 incarnation: Arc::new(AtomicUsize::new(0)),
                 reads: vec![vec![*key]],
                 writes: vec![vec![(*key, random::<u64>())]],
+                deltas: vec![vec![]],
             })
         }
         // One transaction writing to the write results of every prior transactions in the block.
@@ -92,9 +100,10 @@ fn one_writes_all_barrier() {
                 .iter()
                 .map(|key| (*key, random::<u64>()))
                 .collect::<Vec<_>>()],
+            deltas: vec![vec![]],
         })
     }
-    run_and_assert(transactions)
+    run_and_assert(transactions, None)
 }
 
 #[test]
@@ -108,12 +117,13 @@ fn early_aborts() {
                 incarnation: Arc::new(AtomicUsize::new(0)),
                 reads: vec![vec![*key]],
                 writes: vec![vec![(*key, random::<u64>())]],
+                deltas: vec![vec![]],
             })
         }
         // One transaction that triggers an abort
         transactions.push(Transaction::Abort)
     }
-    run_and_assert(transactions)
+    run_and_assert(transactions, None)
 }
 
 #[test]
@@ -127,12 +137,167 @@ fn early_skips() {
                 incarnation: Arc::new(AtomicUsize::new(0)),
                 reads: vec![vec![*key]],
                 writes: vec![vec![(*key, random::<u64>())]],
+                deltas: vec![vec![]],
             })
         }
         // One transaction that triggers an abort
         transactions.push(Transaction::SkipRest)
     }
-    run_and_assert(transactions)
+    run_and_assert(transactions, None)
+}
+
+// Generates a block where every transaction but the last updates a counter with the given
+// delta, while the last transaction reads the counter.
+fn counter_transactions(
+    counter: [u8; 32],
+    update: DeltaUpdate,
+    limit: u128,
+) -> Vec<Transaction<[u8; 32], u64>> {
+    let mut transactions: Vec<_> = (0..TXN_PER_BLOCK)
+        .map(|_| {
+            let key = random::<[u8; 32]>();
+            Transaction::Write {
+                incarnation: Arc::new(AtomicUsize::new(0)),
+                reads: vec![vec![counter, key]],
+                writes: vec![vec![(key, random::<u64>())]],
+                deltas: vec![vec![(counter, DeltaOp::new(update, limit))]],
+            }
+        })
+        .collect();
+    transactions.push(Transaction::Write {
+        incarnation: Arc::new(AtomicUsize::new(0)),
+        reads: vec![vec![counter]],
+        writes: vec![vec![]],
+        deltas: vec![vec![]],
+    });
+    transactions
+}
+
+#[test]
+fn delta_counters() {
+    let counter = random::<[u8; 32]>();
+    let mut transactions = vec![];
+    for _ in 0..NUM_BLOCKS {
+        transactions.extend(counter_transactions(
+            counter,
+            DeltaUpdate::Plus(1),
+            u64::MAX as u128,
+        ));
+        transactions.extend(counter_transactions(
+            counter,
+            DeltaUpdate::Minus(1),
+            u64::MAX as u128,
+        ));
+        // One transaction that overwrites the counter.
+        transactions.push(Transaction::Write {
+            incarnation: Arc::new(AtomicUsize::new(0)),
+            reads: vec![vec![]],
+            writes: vec![vec![(counter, random::<u32>() as u64)]],
+            deltas: vec![vec![]],
+        })
+    }
+    assert!(matches!(
+        ExpectedOutput::generate_baseline(&transactions, None),
+        ExpectedOutput::Success(_)
+    ));
+    run_and_assert(transactions, None)
+}
+
+#[test]
+fn delta_overflow() {
+    // The counter starts at half of the limit.
+    let limit = 2 * (TXN_PER_BLOCK as u128 - 1);
+    let transactions = counter_transactions(random(), DeltaUpdate::Plus(1), limit);
+    assert!(matches!(
+        ExpectedOutput::generate_baseline(&transactions, None),
+        ExpectedOutput::DeltaFailure
+    ));
+    run_and_assert(transactions, None)
+}
+
+#[test]
+fn delta_underflow() {
+    // The counter is overwritten before being updated.
+    let counter = random::<[u8; 32]>();
+    let mut transactions = vec![Transaction::Write {
+        incarnation: Arc::new(AtomicUsize::new(0)),
+        reads: vec![vec![]],
+        writes: vec![vec![(counter, TXN_PER_BLOCK - 1)]],
+        deltas: vec![vec![]],
+    }];
+    transactions.extend(counter_transactions(
+        counter,
+        DeltaUpdate::Minus(1),
+        u64::MAX as u128,
+    ));
+    assert!(matches!(
+        ExpectedOutput::generate_baseline(&transactions, None),
+        ExpectedOutput::DeltaFailure
+    ));
+    run_and_assert(transactions, None)
+}
+
+#[test]
+fn block_gas_limit() {
+    // Every transaction writes to a single key, so it uses one unit of gas.
+    let keys: Vec<_> = (0..TXN_PER_BLOCK).map(|_| random::<[u8; 32]>()).collect();
+    let transactions: Vec<_> = (0..NUM_BLOCKS * TXN_PER_BLOCK)
+        .map(|i| Transaction::Write {
+            incarnation: Arc::new(AtomicUsize::new(0)),
+            reads: vec![keys.clone()],
+            writes: vec![vec![(keys[(i % TXN_PER_BLOCK) as usize], random::<u64>())]],
+            deltas: vec![vec![]],
+        })
+        .collect();
+    assert!(matches!(
+        ExpectedOutput::generate_baseline(&transactions, Some(TXN_PER_BLOCK)),
+        ExpectedOutput::SkipRest(idx, _) if idx == TXN_PER_BLOCK as usize
+    ));
+    run_and_assert(transactions.clone(), Some(TXN_PER_BLOCK));
+
+    // The limit is not reached.
+    run_and_assert(transactions, Some(NUM_BLOCKS * TXN_PER_BLOCK + 1))
+}
+
+#[test]
+fn block_gas_limit_with_deltas() {
+    // Every transaction writes to a key and updates the counter, i.e. uses two units of gas.
+    // Deltas are only materialized for the committed transactions, so the overflow of the
+    // counter after the limit is reached doesn't fail the block.
+    let limit = 2 * (TXN_PER_BLOCK as u128 - 1);
+    let transactions = counter_transactions(random(), DeltaUpdate::Plus(1), limit);
+    assert!(matches!(
+        ExpectedOutput::generate_baseline(&transactions, Some(TXN_PER_BLOCK)),
+        ExpectedOutput::SkipRest(idx, _) if idx == TXN_PER_BLOCK as usize / 2
+    ));
+    run_and_assert(transactions, Some(TXN_PER_BLOCK))
+}
+
+#[test]
+fn block_gas_limit_cutoff() {
+    // Transaction i writes to (i % 3) + 1 keys, so the transactions use different amounts of gas
+    // and the limits around the gas used by a prefix check both sides of the cutoff.
+    let keys: Vec<_> = (0..TXN_PER_BLOCK).map(|_| random::<[u8; 32]>()).collect();
+    let transactions: Vec<_> = (0..TXN_PER_BLOCK as usize)
+        .map(|i| Transaction::Write {
+            incarnation: Arc::new(AtomicUsize::new(0)),
+            reads: vec![vec![keys[i], keys[(i + 1) % keys.len()]]],
+            writes: vec![(0..=i % 3)
+                .map(|j| (keys[(i + j) % keys.len()], random::<u64>()))
+                .collect()],
+            deltas: vec![vec![]],
+        })
+        .collect();
+    for cutoff in [1, 2, 3, 50, 99] {
+        let prefix_gas: u64 = (0..cutoff).map(|i| (i % 3 + 1) as u64).sum();
+        for limit in [prefix_gas - 1, prefix_gas, prefix_gas + 1] {
+            run_and_assert(transactions.clone(), Some(limit));
+        }
+        assert!(matches!(
+            ExpectedOutput::generate_baseline(&transactions, Some(prefix_gas)),
+            ExpectedOutput::SkipRest(idx, _) if idx == cutoff
+        ));
+    }
 }
 
 #[test]
@@ -421,6 +586,40 @@ fn scheduler_stop_idx() {
     assert!(matches!(s.next_task(), SchedulerTask::Done));
 }
 
+#[test]
+fn scheduler_halt() {
+    let s = Scheduler::new(4);
+    let fake_counter = AtomicUsize::new(0);
+
+    for i in 0..2 {
+        assert!(matches!(
+            s.next_task(),
+            SchedulerTask::ExecutionTask((j, 0), None, _) if j == i
+        ));
+    }
+    // The transactions from 2 on are no longer scheduled.
+    s.halt_at(2);
+    assert_eq!(s.num_txn_to_execute(), 2);
+
+    for i in 0..2 {
+        assert!(matches!(
+            s.finish_execution(i, 0, true, TaskGuard::new(&fake_counter)),
+            SchedulerTask::NoTask
+        ));
+    }
+    for i in 0..2 {
+        assert!(matches!(
+            s.next_task(),
+            SchedulerTask::ValidationTask((j, 0), _) if j == i
+        ));
+    }
+    assert!(matches!(s.next_task(), SchedulerTask::Done));
+
+    // Halting can only lower the number of transactions to execute.
+    s.halt_at(3);
+    assert_eq!(s.num_txn_to_execute(), 2);
+}
+
 #[test]
 fn scheduler_drain_idx() {
     let s = Scheduler::new(3);
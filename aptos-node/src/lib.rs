@@ -23,7 +23,7 @@ use aptos_types::{
 use aptos_vm::AptosVM;
 use aptosdb::AptosDB;
 use backup_service::start_backup_service;
-use consensus::consensus_provider::start_consensus;
+use consensus::consensus_provider::{start_consensus, start_consensus_observer};
 use consensus_notifications::ConsensusNotificationListener;
 use data_streaming_service::{
     streaming_client::{new_streaming_service_client_listener_pair, StreamingServiceClient},
@@ -446,12 +446,14 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
             false, /* readonly */
             node_config.storage.storage_pruner_config,
             node_config.storage.rocksdb_config,
+            node_config.storage.enable_event_by_type_index,
         )
         .expect("DB should open."),
     );
     let backup_service = start_backup_service(
         node_config.storage.backup_service_address,
         Arc::clone(&aptos_db),
+        node_config.storage.checkpoint_dir(),
     );
 
     let genesis_waypoint = node_config.base.waypoint.genesis_waypoint();
@@ -474,6 +476,7 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
     let mut state_sync_network_handles = vec![];
     let mut mempool_network_handles = vec![];
     let mut consensus_network_handles = None;
+    let mut consensus_observer_network_handles = vec![];
     let mut storage_service_server_network_handles = vec![];
     let mut storage_service_client_network_handles = HashMap::new();
 
@@ -573,6 +576,21 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
         );
         mempool_network_handles.push((network_id, mempool_sender, mempool_events));
 
+        // Create the endpoints to connect the fullnode networks to the consensus observer, either
+        // to publish the blocks of a validator or to follow them on a fullnode.
+        let consensus_observer_enabled = if node_config.base.role.is_validator() {
+            node_config.consensus_observer.publisher_enabled
+        } else {
+            node_config.consensus_observer.observer_enabled
+        };
+        if consensus_observer_enabled && !network_id.is_validator_network() {
+            let (observer_sender, observer_events) =
+                network_builder.add_p2p_service(&consensus::observer::network_endpoint_config(
+                    node_config.consensus_observer.max_network_channel_size,
+                ));
+            consensus_observer_network_handles.push((network_id, observer_sender, observer_events));
+        }
+
         // Perform steps relevant specifically to Validator networks.
         if network_id.is_validator_network() {
             // A valid config is allowed to have at most one ValidatorNetwork
@@ -640,10 +658,6 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
     );
     debug!("Mempool started in {} ms", instant.elapsed().as_millis());
 
-    assert!(
-        !node_config.consensus.use_quorum_store,
-        "QuorumStore is not yet implemented"
-    );
     assert_ne!(
         node_config.consensus.use_quorum_store,
         node_config.mempool.shared_mempool_validator_broadcast,
@@ -674,8 +688,28 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
             consensus_reconfig_subscription
                 .expect("Consensus requires a reconfiguration subscription!"),
             peer_metadata_storage,
+            consensus_observer_network_handles,
         ));
         debug!("Consensus started in {} ms", instant.elapsed().as_millis());
+    } else if node_config.consensus_observer.observer_enabled {
+        // The observer executes blocks on top of the synced state, like consensus.
+        debug!("Wait until state sync is initialized");
+        state_sync_runtimes.block_until_initialized();
+        debug!("State sync initialization complete.");
+
+        instant = Instant::now();
+        consensus_runtime = Some(start_consensus_observer(
+            node_config,
+            consensus_observer_network_handles,
+            Arc::new(consensus_notifier),
+            consensus_to_mempool_sender,
+            db_rw.clone(),
+            peer_metadata_storage,
+        ));
+        debug!(
+            "Consensus observer started in {} ms",
+            instant.elapsed().as_millis()
+        );
     }
 
     // Spawn a task which will periodically dump some interesting state
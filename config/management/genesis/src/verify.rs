@@ -214,6 +214,7 @@ fn compute_genesis(
         false,
         NO_OP_STORAGE_PRUNER_CONFIG,
         RocksdbConfig::default(),
+        false, /* enable_event_by_type_index */
     )
     .map_err(|e| Error::UnexpectedError(e.to_string()))?;
     let db_rw = DbReaderWriter::new(aptosdb);
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::error::Error;
-use aptos_config::config::{self, GitHubConfig, OnDiskStorageConfig, Token, VaultConfig};
+use aptos_config::config::{
+    self, EncryptedOnDiskStorageConfig, GitHubConfig, OnDiskStorageConfig, Token, VaultConfig,
+};
 use std::{
     collections::HashMap,
     convert::{TryFrom, TryInto},
@@ -13,6 +15,7 @@ use structopt::StructOpt;
 
 pub const BACKEND: &str = "backend";
 pub const DISK: &str = "disk";
+pub const ENCRYPTED_DISK: &str = "encrypted_disk";
 pub const GITHUB: &str = "github";
 pub const MEMORY: &str = "memory";
 pub const VAULT: &str = "vault";
@@ -80,6 +83,23 @@ impl TryInto<config::SecureBackend> for SecureBackend {
                 config.namespace = self.parameters.remove("namespace");
                 config::SecureBackend::OnDiskStorage(config)
             }
+            ENCRYPTED_DISK => {
+                let path = self
+                    .parameters
+                    .remove("path")
+                    .ok_or_else(|| Error::BackendParsingError("missing path".into()))?;
+                let passphrase = self
+                    .parameters
+                    .remove("passphrase")
+                    .ok_or_else(|| Error::BackendParsingError("missing passphrase".into()))?;
+                let mut config = EncryptedOnDiskStorageConfig::new(
+                    PathBuf::from(path),
+                    Token::FromDisk(PathBuf::from(passphrase)),
+                );
+                config.set_data_dir(PathBuf::from(""));
+                config.namespace = self.parameters.remove("namespace");
+                config::SecureBackend::EncryptedOnDisk(config)
+            }
             GITHUB => {
                 let repository_owner = self
                     .parameters
@@ -159,6 +179,7 @@ pair: "k0=v0;k1=v1;...".  The current supported formats are:
         an optional namespace: "namespace=NAMESPACE"
     InMemory: "backend=memory"
     OnDisk: "backend=disk;path=LOCAL_PATH"
+    EncryptedOnDisk: "backend=encrypted_disk;path=LOCAL_PATH;passphrase=PATH_TO_PASSPHRASE"
                 "#)
             )]
             pub $field_name: Option<SecureBackend>,
@@ -212,6 +233,28 @@ mod tests {
         assert!(storage(disk).is_err());
     }
 
+    #[test]
+    fn test_encrypted_disk() {
+        let path = aptos_temppath::TempPath::new();
+        let passphrase = aptos_temppath::TempPath::new();
+        passphrase.create_as_file().unwrap();
+        let mut file = File::create(passphrase.path()).unwrap();
+        file.write_all(b"disk_passphrase").unwrap();
+
+        let disk = format!(
+            "backend=encrypted_disk;path={};passphrase={}",
+            path.path().to_str().unwrap(),
+            passphrase.path().to_str().unwrap()
+        );
+        storage(&disk).unwrap();
+
+        let disk = format!(
+            "backend=encrypted_disk;path={}",
+            path.path().to_str().unwrap()
+        );
+        assert!(storage(&disk).is_err());
+    }
+
     #[test]
     fn test_github() {
         let path = aptos_temppath::TempPath::new();
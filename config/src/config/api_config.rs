@@ -18,11 +18,21 @@ pub struct ApiConfig {
     // optional for compatible with old configuration
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content_length_limit: Option<u64>,
+    // max gas a view function execution can use, optional for compatible with old configuration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_view_function_gas: Option<u64>,
+    // max state values of an account scanned by the requests without pagination, optional for
+    // compatible with old configuration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_unpaginated_account_state_values: Option<usize>,
 }
 
 pub const DEFAULT_ADDRESS: &str = "127.0.0.1";
 pub const DEFAULT_PORT: u16 = 8080;
 pub const DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT: u64 = 4 * 1024 * 1024; // 4mb
+pub const DEFAULT_MAX_VIEW_FUNCTION_GAS: u64 = 1_000_000;
+// same as the bound of the DB on the values fetched for a key prefix
+pub const DEFAULT_MAX_UNPAGINATED_ACCOUNT_STATE_VALUES: usize = 10_000;
 
 fn default_enabled() -> bool {
     true
@@ -38,6 +48,8 @@ impl Default for ApiConfig {
             tls_cert_path: None,
             tls_key_path: None,
             content_length_limit: None,
+            max_view_function_gas: None,
+            max_unpaginated_account_state_values: None,
         }
     }
 }
@@ -53,4 +65,14 @@ impl ApiConfig {
             None => DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT,
         }
     }
+
+    pub fn max_view_function_gas(&self) -> u64 {
+        self.max_view_function_gas
+            .unwrap_or(DEFAULT_MAX_VIEW_FUNCTION_GAS)
+    }
+
+    pub fn max_unpaginated_account_state_values(&self) -> usize {
+        self.max_unpaginated_account_state_values
+            .unwrap_or(DEFAULT_MAX_UNPAGINATED_ACCOUNT_STATE_VALUES)
+    }
 }
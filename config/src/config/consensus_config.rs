@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::config::SafetyRulesConfig;
+use crate::config::{invariant, Error, SafetyRulesConfig};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -9,6 +9,8 @@ use std::path::PathBuf;
 #[serde(default, deny_unknown_fields)]
 pub struct ConsensusConfig {
     pub max_block_size: u64,
+    // Max size of the transactions of a proposed block (in bytes)
+    pub max_block_bytes: u64,
     pub max_pruned_blocks_in_mem: usize,
     // Timeout for consensus to get an ack from mempool for executed transactions (in milliseconds)
     pub mempool_executed_txn_timeout_ms: u64,
@@ -27,12 +29,37 @@ pub struct ConsensusConfig {
     // the period = (poll_count - 1) * 30ms
     pub quorum_store_poll_count: u64,
     pub intra_consensus_channel_buffer_size: usize,
+    // How often the quorum store pulls a batch of transactions from mempool (in milliseconds)
+    pub quorum_store_batch_interval_ms: u64,
+    pub quorum_store_max_batch_size: u64,
+    // Maximum size of a batch in bytes, the batches received beyond either limit are rejected
+    pub quorum_store_max_batch_bytes: u64,
+    // Number of rounds after which a batch expires and is no longer included in proposals
+    pub quorum_store_batch_expiry_rounds: u64,
+    // Timeout for the quorum store to fetch a missing batch from a peer (in milliseconds)
+    pub quorum_store_batch_request_timeout_ms: u64,
+    // Limits applied to the proposals when blocks are ordered faster than they're committed,
+    // the limits with the highest threshold exceeded apply
+    pub pipeline_backpressure: Vec<PipelineBackpressureValues>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineBackpressureValues {
+    // Applies when more blocks than this are ordered but not committed yet
+    pub back_pressure_pending_blocks: u64,
+    pub max_block_size_override: u64,
+    pub max_block_bytes_override: u64,
+    // How long the proposer waits before pulling the payload (in milliseconds), this should be
+    // well below the round timeout
+    pub proposal_delay_ms: u64,
 }
 
 impl Default for ConsensusConfig {
     fn default() -> ConsensusConfig {
         ConsensusConfig {
             max_block_size: 3000,
+            max_block_bytes: 5 * 1024 * 1024,
             max_pruned_blocks_in_mem: 100,
             mempool_executed_txn_timeout_ms: 1000,
             mempool_txn_pull_timeout_ms: 1000,
@@ -44,6 +71,32 @@ impl Default for ConsensusConfig {
             quorum_store_pull_timeout_ms: 1000,
             quorum_store_poll_count: 20,
             intra_consensus_channel_buffer_size: 10,
+            quorum_store_batch_interval_ms: 100,
+            // A batch is proposed whole, so it must fit in the smallest backpressured block
+            quorum_store_max_batch_size: 50,
+            quorum_store_max_batch_bytes: 128 * 1024,
+            quorum_store_batch_expiry_rounds: 100,
+            quorum_store_batch_request_timeout_ms: 1000,
+            pipeline_backpressure: vec![
+                PipelineBackpressureValues {
+                    back_pressure_pending_blocks: 4,
+                    max_block_size_override: 1000,
+                    max_block_bytes_override: 2 * 1024 * 1024,
+                    proposal_delay_ms: 0,
+                },
+                PipelineBackpressureValues {
+                    back_pressure_pending_blocks: 6,
+                    max_block_size_override: 250,
+                    max_block_bytes_override: 512 * 1024,
+                    proposal_delay_ms: 100,
+                },
+                PipelineBackpressureValues {
+                    back_pressure_pending_blocks: 8,
+                    max_block_size_override: 50,
+                    max_block_bytes_override: 128 * 1024,
+                    proposal_delay_ms: 300,
+                },
+            ],
         }
     }
 }
@@ -52,6 +105,34 @@ impl ConsensusConfig {
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
         self.safety_rules.set_data_dir(data_dir);
     }
+
+    /// Checks that the quorum store batches fit in the blocks, including the blocks reduced by
+    /// the pipeline backpressure, otherwise the batches can't be proposed.
+    pub fn validate(&self) -> Result<(), Error> {
+        let block_limits = std::iter::once((self.max_block_size, self.max_block_bytes)).chain(
+            self.pipeline_backpressure.iter().map(|values| {
+                (
+                    values.max_block_size_override,
+                    values.max_block_bytes_override,
+                )
+            }),
+        );
+        for (max_block_size, max_block_bytes) in block_limits {
+            invariant(
+                self.quorum_store_max_batch_size <= max_block_size
+                    && self.quorum_store_max_batch_bytes <= max_block_bytes,
+                format!(
+                    "Quorum store batches of {} txns and {} bytes don't fit in blocks of {} txns \
+                     and {} bytes",
+                    self.quorum_store_max_batch_size,
+                    self.quorum_store_max_batch_bytes,
+                    max_block_size,
+                    max_block_bytes
+                ),
+            )?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -65,4 +146,26 @@ mod test {
 
         serde_yaml::from_str::<ConsensusConfig>(&s).unwrap();
     }
+
+    #[test]
+    fn test_validate_batch_size() {
+        let config = ConsensusConfig::default();
+        config.validate().unwrap();
+
+        let mut pipeline_backpressure = config.pipeline_backpressure.clone();
+        pipeline_backpressure[0].max_block_size_override = config.quorum_store_max_batch_size - 1;
+        assert!(ConsensusConfig {
+            pipeline_backpressure,
+            ..config.clone()
+        }
+        .validate()
+        .is_err());
+
+        assert!(ConsensusConfig {
+            quorum_store_max_batch_bytes: config.max_block_bytes + 1,
+            ..config
+        }
+        .validate()
+        .is_err());
+    }
 }
@@ -0,0 +1,46 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+
+/// The consensus observer lets fullnodes follow consensus instead of waiting for state sync:
+/// publishers (validators and their fullnodes) forward the ordered blocks and the commit
+/// decisions to their subscribers, and the observers verify, execute and commit them locally.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsensusObserverConfig {
+    // Forward the ordered blocks and the commit decisions to the subscribed peers on the fullnode
+    // networks, only on validators with decoupled execution.
+    pub publisher_enabled: bool,
+    // Follow the blocks of a publisher on the fullnode networks, only on fullnodes. State sync then
+    // only syncs the node when the observer falls behind or stops committing.
+    pub observer_enabled: bool,
+    // Max num of pending network messages
+    pub max_network_channel_size: u64,
+    // Max num of ordered blocks and commit decisions pending publication to the subscribers
+    pub max_publisher_channel_size: u64,
+    // Max num of ordered blocks pending commit before the observer falls back to state sync
+    pub max_pending_blocks: u64,
+    // How often the observer checks its subscription and its progress (in milliseconds)
+    pub progress_check_interval_ms: u64,
+    // How long the observer waits for a message before subscribing to another peer (in milliseconds)
+    pub subscription_timeout_ms: u64,
+    // How long state sync waits for the observer to commit before it continuously syncs the node
+    // itself, e.g., when there's no publisher to follow (in milliseconds)
+    pub state_sync_fallback_timeout_ms: u64,
+}
+
+impl Default for ConsensusObserverConfig {
+    fn default() -> Self {
+        Self {
+            publisher_enabled: false,
+            observer_enabled: false,
+            max_network_channel_size: 1000,
+            max_publisher_channel_size: 100,
+            max_pending_blocks: 100,
+            progress_check_interval_ms: 5_000,
+            subscription_timeout_ms: 15_000,
+            state_sync_fallback_timeout_ms: 30_000,
+        }
+    }
+}
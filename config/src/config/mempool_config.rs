@@ -8,6 +8,9 @@ use serde::{Deserialize, Serialize};
 pub struct MempoolConfig {
     pub capacity: usize,
     pub capacity_per_user: usize,
+    // minimum gas unit price increase, in percent, for a transaction to replace the one with the
+    // same sender and sequence number
+    pub replace_by_fee_min_bump_percent: u64,
     // number of failovers to broadcast to when the primary network is alive
     pub default_failovers: usize,
     pub max_broadcasts_per_peer: usize,
@@ -20,6 +23,10 @@ pub struct MempoolConfig {
     pub system_transaction_timeout_secs: u64,
     pub system_transaction_gc_interval_ms: u64,
     pub shared_mempool_validator_broadcast: bool,
+    // journal the transactions of mempool in the storage directory and restore them on restart
+    pub enable_persistence: bool,
+    // how often the journaled writes are flushed to disk (in milliseconds)
+    pub journal_flush_interval_ms: u64,
 }
 
 impl Default for MempoolConfig {
@@ -34,10 +41,13 @@ impl Default for MempoolConfig {
             mempool_snapshot_interval_secs: 180,
             capacity: 1_000_000,
             capacity_per_user: 100,
+            replace_by_fee_min_bump_percent: 10,
             default_failovers: 3,
             system_transaction_timeout_secs: 600,
             system_transaction_gc_interval_ms: 60_000,
             shared_mempool_validator_broadcast: true,
+            enable_persistence: false,
+            journal_flush_interval_ms: 1_000,
         }
     }
 }
@@ -18,6 +18,8 @@ use thiserror::Error;
 
 mod consensus_config;
 pub use consensus_config::*;
+mod consensus_observer_config;
+pub use consensus_observer_config::*;
 mod debug_interface_config;
 pub use debug_interface_config::*;
 mod error;
@@ -61,6 +63,8 @@ pub struct NodeConfig {
     #[serde(default)]
     pub consensus: ConsensusConfig,
     #[serde(default)]
+    pub consensus_observer: ConsensusObserverConfig,
+    #[serde(default)]
     pub debug_interface: DebugInterfaceConfig,
     #[serde(default)]
     pub execution: ExecutionConfig,
@@ -257,6 +261,8 @@ impl NodeConfig {
         let input_dir = RootPath::new(input_path);
         config.execution.load(&input_dir)?;
 
+        config.consensus.validate()?;
+        config.storage.storage_pruner_config.validate()?;
         let mut config = config.validate_network_configs()?;
         config.set_data_dir(config.data_dir().to_path_buf());
         Ok(config)
@@ -287,6 +293,10 @@ impl NodeConfig {
                 "Provided a validator network config for a full_node node".into(),
             )?;
         }
+        invariant(
+            !(self.base.role.is_validator() && self.consensus_observer.observer_enabled),
+            "Enabled the consensus observer on a validator node".into(),
+        )?;
 
         let mut network_ids = HashSet::new();
         if let Some(network) = &mut self.validator_network {
@@ -328,9 +328,39 @@ impl Default for PeerMonitoringServiceConfig {
 pub enum DiscoveryMethod {
     Onchain,
     File(PathBuf, Duration),
+    Dns(DnsDiscovery),
+    Rest(RestDiscovery),
     None,
 }
 
+/// Discovers peers from the DNS records of a domain name, resolved with the system resolver.
+/// Each TXT record of `name` holds the full `NetworkAddress` of a peer (including its
+/// `noise-ik` key), and each SRV record of `name` points at a peer whose host has a TXT record
+/// `noise-ik=<x25519 public key>`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DnsDiscovery {
+    pub name: String,
+    pub interval_secs: u64,
+    // Role given to the discovered peers
+    #[serde(default = "DnsDiscovery::default_role")]
+    pub role: PeerRole,
+}
+
+impl DnsDiscovery {
+    fn default_role() -> PeerRole {
+        PeerRole::Upstream
+    }
+}
+
+/// Discovers peers from an HTTP(S) endpoint returning a JSON `PeerSet`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RestDiscovery {
+    pub url: String,
+    pub interval_secs: u64,
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum Identity {
@@ -1,7 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::utils;
+use crate::{
+    config::{invariant, Error},
+    utils,
+};
 use serde::{Deserialize, Serialize};
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
@@ -46,12 +49,27 @@ pub struct StorageConfig {
     pub timeout_ms: u64,
     /// Rocksdb-specific configurations
     pub rocksdb_config: RocksdbConfig,
+    /// Whether to index the committed events by their Move type, which allows looking up all the
+    /// events of a type without scanning the ledger. Enabling it on an existing DB only indexes
+    /// the versions committed from then on, looking up the events of earlier versions fails.
+    pub enable_event_by_type_index: bool,
+    /// Where the backup service creates checkpoints of the DB on request, relative to the DB
+    /// directory unless absolute. It should be on the same file system as the DB so the
+    /// checkpoints can hard link the DB files. None disables the checkpoint endpoint, which is
+    /// unauthenticated and so must only be enabled if the backup service isn't exposed.
+    pub checkpoint_dir: Option<PathBuf>,
 }
 
 pub const NO_OP_STORAGE_PRUNER_CONFIG: StoragePrunerConfig = StoragePrunerConfig {
     state_store_prune_window: None,
     ledger_prune_window: None,
     pruning_batch_size: 10_000,
+    ledger_sub_pruner_windows: LedgerSubPrunerWindows {
+        transaction_store: None,
+        event_store: None,
+        write_set: None,
+        ledger_counter: None,
+    },
 };
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -67,6 +85,32 @@ pub struct StoragePrunerConfig {
     /// Batch size of the versions to be sent to the pruner - this is to avoid slowdown due to
     /// issuing too many DB calls and batch prune instead.
     pub pruning_batch_size: usize,
+    /// Windows of the individual ledger stores, overriding `ledger_prune_window`.
+    #[serde(default)]
+    pub ledger_sub_pruner_windows: LedgerSubPrunerWindows,
+}
+
+/// The history kept by a pruner, either as a number of versions or as a wall-clock duration.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PruneWindow {
+    /// Keeps the given number of latest versions.
+    Versions(u64),
+    /// Keeps the versions committed in the given number of seconds before now. The versions are
+    /// resolved through the block timestamps, so the events must be kept at least as long as the
+    /// longest time-based window.
+    Secs(u64),
+}
+
+/// Prune windows of the stores pruned by the ledger pruner. A store without a window of its own
+/// uses `ledger_prune_window`, and isn't pruned if that is None as well.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LedgerSubPrunerWindows {
+    pub transaction_store: Option<PruneWindow>,
+    pub event_store: Option<PruneWindow>,
+    pub write_set: Option<PruneWindow>,
+    pub ledger_counter: Option<PruneWindow>,
 }
 
 impl StoragePrunerConfig {
@@ -79,8 +123,64 @@ impl StoragePrunerConfig {
             state_store_prune_window,
             ledger_prune_window: ledger_store_prune_window,
             pruning_batch_size,
+            ledger_sub_pruner_windows: LedgerSubPrunerWindows::default(),
         }
     }
+
+    /// Returns the prune windows of the ledger stores, falling back to `ledger_prune_window` for
+    /// the stores without a window of their own.
+    pub fn effective_ledger_sub_pruner_windows(&self) -> LedgerSubPrunerWindows {
+        let default_window = self.ledger_prune_window.map(PruneWindow::Versions);
+        let windows = &self.ledger_sub_pruner_windows;
+        LedgerSubPrunerWindows {
+            transaction_store: windows.transaction_store.or(default_window),
+            event_store: windows.event_store.or(default_window),
+            write_set: windows.write_set.or(default_window),
+            ledger_counter: windows.ledger_counter.or(default_window),
+        }
+    }
+
+    /// Whether any of the ledger stores is pruned.
+    pub fn is_ledger_pruner_enabled(&self) -> bool {
+        let windows = self.effective_ledger_sub_pruner_windows();
+        windows.transaction_store.is_some()
+            || windows.event_store.is_some()
+            || windows.write_set.is_some()
+            || windows.ledger_counter.is_some()
+    }
+
+    /// Checks that the events, through which the time-based prune windows are resolved, are kept
+    /// at least as long as the longest time-based window.
+    pub fn validate(&self) -> Result<(), Error> {
+        let windows = self.effective_ledger_sub_pruner_windows();
+        let max_secs_window = [
+            windows.transaction_store,
+            windows.event_store,
+            windows.write_set,
+            windows.ledger_counter,
+        ]
+        .iter()
+        .filter_map(|window| match window {
+            Some(PruneWindow::Secs(secs)) => Some(*secs),
+            _ => None,
+        })
+        .max();
+        if let Some(max_secs_window) = max_secs_window {
+            invariant(
+                match windows.event_store {
+                    None => true,
+                    Some(PruneWindow::Secs(secs)) => secs >= max_secs_window,
+                    Some(PruneWindow::Versions(_)) => false,
+                },
+                format!(
+                    "The event store prune window {:?} doesn't keep the events of the last {} \
+                     seconds, which time-based prune windows need",
+                    windows.event_store, max_secs_window
+                ),
+            )?;
+        }
+        Ok(())
+    }
 }
 
 impl Default for StorageConfig {
@@ -100,11 +200,14 @@ impl Default for StorageConfig {
                 state_store_prune_window: Some(1_000_000),
                 ledger_prune_window: Some(10_000_000),
                 pruning_batch_size: 500,
+                ledger_sub_pruner_windows: LedgerSubPrunerWindows::default(),
             },
             data_dir: PathBuf::from("/opt/aptos/data"),
             // Default read/write/connection timeout, in milliseconds
             timeout_ms: 30_000,
             rocksdb_config: RocksdbConfig::default(),
+            enable_event_by_type_index: false,
+            checkpoint_dir: None,
         }
     }
 }
@@ -118,6 +221,12 @@ impl StorageConfig {
         }
     }
 
+    pub fn checkpoint_dir(&self) -> Option<PathBuf> {
+        self.checkpoint_dir
+            .as_ref()
+            .map(|checkpoint_dir| self.dir().join(checkpoint_dir))
+    }
+
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
         self.data_dir = data_dir;
     }
@@ -128,3 +237,28 @@ impl StorageConfig {
             .set_port(utils::get_available_port());
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_validate_time_based_prune_windows() {
+        let mut config = StorageConfig::default().storage_pruner_config;
+        config.validate().unwrap();
+
+        // the event store falls back to a prune window in versions
+        config.ledger_sub_pruner_windows.write_set = Some(PruneWindow::Secs(3600));
+        assert!(config.validate().is_err());
+
+        config.ledger_sub_pruner_windows.event_store = Some(PruneWindow::Secs(1800));
+        assert!(config.validate().is_err());
+
+        config.ledger_sub_pruner_windows.event_store = Some(PruneWindow::Secs(3600));
+        config.validate().unwrap();
+
+        config.ledger_prune_window = None;
+        config.ledger_sub_pruner_windows.event_store = None;
+        config.validate().unwrap();
+    }
+}
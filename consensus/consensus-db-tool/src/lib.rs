@@ -0,0 +1,342 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Inspects the ConsensusDB of a node, e.g. when it gets stuck on restart, and repairs it by
+//! pruning the blocks above a round.
+
+use anyhow::{ensure, Result};
+use aptos_config::config::{RocksdbConfig, NO_OP_STORAGE_PRUNER_CONFIG};
+use aptos_crypto::HashValue;
+use aptos_types::ledger_info::LedgerInfo;
+use aptosdb::AptosDB;
+use consensus::ConsensusDB;
+use consensus_types::{
+    block::Block, common::Round, timeout_2chain::TwoChainTimeoutCertificate, vote::Vote,
+};
+use serde_json::json;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+use storage_interface::DbReader;
+use structopt::StructOpt;
+
+/// Inspects and repairs the ConsensusDB of a node.
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "consensus-db-tool",
+    about = "Inspect the ConsensusDB of a node, or prune the blocks above a round to recover \
+             from a corrupted state."
+)]
+pub struct Command {
+    /// Storage dir of the node, containing both the ConsensusDB and the AptosDB.
+    #[structopt(long, parse(from_os_str))]
+    db_dir: PathBuf,
+
+    #[structopt(subcommand)]
+    cmd: SubCommand,
+}
+
+#[derive(Debug, StructOpt)]
+enum SubCommand {
+    /// Dumps the blocks, the quorum certs, the last vote and the highest timeout cert as JSON.
+    Dump,
+    /// Shows the block tree relative to the root committed in AptosDB.
+    Tree,
+    /// Deletes the blocks above the round, along with the quorum certs certifying them. The node
+    /// must be stopped.
+    Prune {
+        #[structopt(long)]
+        above_round: Round,
+    },
+}
+
+impl Command {
+    /// Runs the command, both the ConsensusDB and the AptosDB are opened read-only unless pruning.
+    pub fn run(self) -> Result<()> {
+        match self.cmd {
+            SubCommand::Dump => {
+                let db = ConsensusDB::new_readonly(&self.db_dir)?;
+                println!("{}", serde_json::to_string_pretty(&dump(&db)?)?);
+            }
+            SubCommand::Tree => {
+                let root = CommittedRoot::new(&self.db_dir)?;
+                let db = ConsensusDB::new_readonly(&self.db_dir)?;
+                let (_, _, blocks, quorum_certs) = db.get_data()?;
+                let certified: HashSet<_> = quorum_certs
+                    .iter()
+                    .map(|qc| qc.certified_block().id())
+                    .collect();
+                print_tree(&root, &blocks, &certified);
+            }
+            SubCommand::Prune { above_round } => {
+                let root = CommittedRoot::new(&self.db_dir)?;
+                ensure!(
+                    above_round >= root.round,
+                    "Round {} is below the committed root {}",
+                    above_round,
+                    root.round
+                );
+                let db = ConsensusDB::new(&self.db_dir);
+                let pruned = prune_blocks_above(&db, root.epoch, above_round)?;
+                println!("Pruned {} blocks above round {}", pruned.len(), above_round);
+                for block_id in pruned {
+                    println!("\t{}", block_id);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The root consensus recovers from on restart, see `LedgerRecoveryData::find_root`.
+struct CommittedRoot {
+    id: HashValue,
+    epoch: u64,
+    round: Round,
+    // The genesis of the next epoch, derived from the epoch ending ledger info and never stored.
+    is_virtual_genesis: bool,
+}
+
+impl CommittedRoot {
+    fn new(db_dir: &Path) -> Result<Self> {
+        let aptos_db = AptosDB::open(
+            db_dir,
+            true, /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            RocksdbConfig::default(),
+            false, /* enable_event_by_type_index */
+        )?;
+        Ok(Self::from_ledger_info(
+            aptos_db.get_latest_ledger_info()?.ledger_info(),
+        ))
+    }
+
+    fn from_ledger_info(ledger_info: &LedgerInfo) -> Self {
+        if ledger_info.ends_epoch() {
+            let genesis = Block::make_genesis_block_from_ledger_info(ledger_info);
+            Self {
+                id: genesis.id(),
+                epoch: genesis.epoch(),
+                round: genesis.round(),
+                is_virtual_genesis: true,
+            }
+        } else {
+            Self {
+                id: ledger_info.consensus_block_id(),
+                epoch: ledger_info.epoch(),
+                round: ledger_info.round(),
+                is_virtual_genesis: false,
+            }
+        }
+    }
+}
+
+fn dump(db: &ConsensusDB) -> Result<serde_json::Value> {
+    let (last_vote, highest_2chain_timeout_cert, mut blocks, mut quorum_certs) = db.get_data()?;
+    let last_vote = last_vote
+        .map(|bytes| bcs::from_bytes::<Vote>(&bytes))
+        .transpose()?;
+    let highest_2chain_timeout_cert = highest_2chain_timeout_cert
+        .map(|bytes| bcs::from_bytes::<TwoChainTimeoutCertificate>(&bytes))
+        .transpose()?;
+    blocks.sort_by_key(|block| (block.epoch(), block.round()));
+    quorum_certs.sort_by_key(|qc| (qc.certified_block().epoch(), qc.certified_block().round()));
+    // The block id isn't serialized as it's the hash of the block data.
+    let blocks: Vec<_> = blocks
+        .iter()
+        .map(|block| json!({ "id": block.id(), "block": block }))
+        .collect();
+    Ok(json!({
+        "last_vote": last_vote,
+        "highest_2chain_timeout_certificate": highest_2chain_timeout_cert,
+        "blocks": blocks,
+        "quorum_certs": quorum_certs,
+    }))
+}
+
+/// Splits the blocks into the ones descending from the root, along with their depth in the tree
+/// in depth-first order, and the ones that don't, which consensus prunes on restart.
+fn block_tree(root_id: HashValue, blocks: &[Block]) -> (Vec<(usize, &Block)>, Vec<&Block>) {
+    let mut children: HashMap<HashValue, Vec<&Block>> = HashMap::new();
+    for block in blocks {
+        children.entry(block.parent_id()).or_default().push(block);
+    }
+    for siblings in children.values_mut() {
+        siblings.sort_by_key(|block| (block.round(), block.id()));
+    }
+
+    let mut descendants = vec![];
+    let mut visited = HashSet::new();
+    let mut to_visit: Vec<_> = match blocks.iter().find(|block| block.id() == root_id) {
+        Some(root) => vec![(0, root)],
+        None => children.get(&root_id).map_or(vec![], |blocks| {
+            blocks.iter().rev().map(|b| (1, *b)).collect()
+        }),
+    };
+    while let Some((depth, block)) = to_visit.pop() {
+        if !visited.insert(block.id()) {
+            continue;
+        }
+        descendants.push((depth, block));
+        if let Some(blocks) = children.get(&block.id()) {
+            to_visit.extend(blocks.iter().rev().map(|b| (depth + 1, *b)));
+        }
+    }
+    let mut others: Vec<_> = blocks
+        .iter()
+        .filter(|block| !visited.contains(&block.id()))
+        .collect();
+    others.sort_by_key(|block| (block.epoch(), block.round(), block.id()));
+    (descendants, others)
+}
+
+fn print_tree(root: &CommittedRoot, blocks: &[Block], certified: &HashSet<HashValue>) {
+    let describe = |block: &Block| {
+        format!(
+            "[epoch: {}, round: {}, id: {}, parent: {}, certified: {}]",
+            block.epoch(),
+            block.round(),
+            block.id(),
+            block.parent_id(),
+            certified.contains(&block.id())
+        )
+    };
+    println!(
+        "Committed root in AptosDB: [epoch: {}, round: {}, id: {}]",
+        root.epoch, root.round, root.id
+    );
+    let (descendants, others) = block_tree(root.id, blocks);
+    if root.is_virtual_genesis {
+        println!("The root is the genesis of the epoch, derived from the epoch ending ledger info");
+    } else if !blocks.iter().any(|block| block.id() == root.id) {
+        println!("The root is missing, consensus can only recover from the ledger on restart");
+    }
+    println!("Blocks descending from the root:");
+    for (depth, block) in descendants {
+        println!("{}{}", "  ".repeat(depth), describe(block));
+    }
+    println!("Blocks not descending from the root, pruned on restart:");
+    for block in others {
+        println!("{}", describe(block));
+    }
+}
+
+/// Deletes the blocks of the epoch, or of later epochs, above the round along with the quorum
+/// certs certifying them. The last vote and the highest timeout cert are kept, consensus drops
+/// them on restart if they're stale.
+fn prune_blocks_above(db: &ConsensusDB, epoch: u64, round: Round) -> Result<Vec<HashValue>> {
+    let (_, _, blocks, _) = db.get_data()?;
+    let block_ids: Vec<_> = blocks
+        .iter()
+        .filter(|block| (block.epoch(), block.round()) > (epoch, round))
+        .map(|block| block.id())
+        .collect();
+    if !block_ids.is_empty() {
+        db.delete_blocks_and_quorum_certificates(block_ids.clone())?;
+    }
+    Ok(block_ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_temppath::TempPath;
+    use aptos_types::validator_signer::ValidatorSigner;
+    use consensus_types::{
+        block::block_test_utils::{certificate_for_genesis, placeholder_certificate_for_block},
+        common::Payload,
+    };
+
+    fn child_block(signer: &ValidatorSigner, parent: &Block, round: Round) -> Block {
+        let quorum_cert = placeholder_certificate_for_block(
+            vec![signer],
+            parent.id(),
+            parent.round(),
+            parent.quorum_cert().certified_block().id(),
+            parent.quorum_cert().certified_block().round(),
+        );
+        Block::new_proposal(
+            Payload::new_empty(),
+            round,
+            round,
+            quorum_cert,
+            signer,
+            vec![],
+        )
+    }
+
+    /// Returns genesis <- a1 <- a2 <- a3 and genesis <- b4, along with c3 whose parent is missing.
+    fn blocks(signer: &ValidatorSigner) -> Vec<Block> {
+        let genesis = Block::make_genesis_block();
+        let a1 = child_block(signer, &genesis, 1);
+        let a2 = child_block(signer, &a1, 2);
+        let a3 = child_block(signer, &a2, 3);
+        let b4 = child_block(signer, &genesis, 4);
+        let c3 = child_block(signer, &child_block(signer, &genesis, 2), 3);
+        vec![a3, b4, c3, a1, genesis, a2]
+    }
+
+    #[test]
+    fn test_block_tree() {
+        let signer = ValidatorSigner::random(None);
+        let blocks = blocks(&signer);
+        let (a3, b4, c3, a1, genesis, a2) = (
+            &blocks[0], &blocks[1], &blocks[2], &blocks[3], &blocks[4], &blocks[5],
+        );
+
+        let (descendants, others) = block_tree(genesis.id(), &blocks);
+        assert_eq!(
+            descendants,
+            vec![(0, genesis), (1, a1), (2, a2), (3, a3), (1, b4)]
+        );
+        assert_eq!(others, vec![c3]);
+
+        // The root isn't stored, e.g. the genesis of an epoch.
+        let unrooted: Vec<_> = blocks
+            .iter()
+            .filter(|block| block.id() != genesis.id())
+            .cloned()
+            .collect();
+        let (descendants, others) = block_tree(genesis.id(), &unrooted);
+        assert_eq!(descendants, vec![(1, a1), (2, a2), (3, a3), (1, b4)]);
+        assert_eq!(others, vec![c3]);
+    }
+
+    #[test]
+    fn test_prune_blocks_above() {
+        let tmp_dir = TempPath::new();
+        let db = ConsensusDB::new(&tmp_dir);
+        let signer = ValidatorSigner::random(None);
+        let mut blocks = blocks(&signer);
+        let quorum_certs: Vec<_> = blocks
+            .iter()
+            .map(|block| block.quorum_cert().clone())
+            .chain(std::iter::once(certificate_for_genesis()))
+            .collect();
+        db.save_blocks_and_quorum_certificates(blocks.clone(), quorum_certs)
+            .unwrap();
+
+        let mut pruned = prune_blocks_above(&db, 1, 2).unwrap();
+        let mut expected: Vec<_> = blocks
+            .iter()
+            .filter(|block| block.round() > 2)
+            .map(|block| block.id())
+            .collect();
+        pruned.sort();
+        expected.sort();
+        assert_eq!(pruned, expected);
+
+        let (_, _, mut remaining, remaining_qcs) = db.get_data().unwrap();
+        blocks.retain(|block| block.round() <= 2);
+        blocks.sort_by_key(|block| block.id());
+        remaining.sort_by_key(|block| block.id());
+        assert_eq!(remaining, blocks);
+        assert!(remaining_qcs
+            .iter()
+            .all(|qc| !pruned.contains(&qc.certified_block().id())));
+        assert!(prune_blocks_above(&db, 1, 2).unwrap().is_empty());
+    }
+}
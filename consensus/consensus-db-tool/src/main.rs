@@ -0,0 +1,10 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use consensus_db_tool::Command;
+use structopt::StructOpt;
+
+fn main() -> Result<()> {
+    Command::from_args().run()
+}
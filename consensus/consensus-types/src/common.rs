@@ -1,9 +1,15 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_types::{account_address::AccountAddress, transaction::SignedTransaction};
+use crate::proof_of_store::{BatchPayload, ProofOfStore};
+use anyhow::{ensure, Context};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_types::{
+    account_address::AccountAddress, transaction::SignedTransaction,
+    validator_verifier::ValidatorVerifier,
+};
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use std::{collections::HashSet, fmt};
 
 /// The round of a block is a consensus-internal counter, which starts with 0 and increases
 /// monotonically. It is used for the protocol safety and liveness (please see the detailed
@@ -28,6 +34,8 @@ impl fmt::Display for TransactionSummary {
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub enum Payload {
     DirectMempool(Vec<SignedTransaction>),
+    /// Proofs of the batches of transactions held by the quorum store, the transactions are
+    /// retrieved from the quorum store when the block is executed.
     InQuorumStore(Vec<ProofOfStore>),
 }
 
@@ -36,31 +44,64 @@ impl Payload {
         Payload::DirectMempool(Vec::new())
     }
 
+    /// Number of transactions in the payload.
     pub fn len(&self) -> usize {
         match self {
             Payload::DirectMempool(txns) => txns.len(),
-            Payload::InQuorumStore(_poavs) => todo!(),
+            Payload::InQuorumStore(proofs) => proofs
+                .iter()
+                .map(|proof| proof.info().num_txns() as usize)
+                .sum(),
         }
     }
 
     pub fn is_empty(&self) -> bool {
         match self {
             Payload::DirectMempool(txns) => txns.is_empty(),
-            Payload::InQuorumStore(_poavs) => todo!(),
+            Payload::InQuorumStore(proofs) => proofs.is_empty(),
         }
     }
-}
 
-// TODO: What I really want is an iterator that isn't necessarily a vector (e.g., read lazily from RocksDB). This doesn't seem like the way.
-impl IntoIterator for Payload {
-    type Item = SignedTransaction;
-    type IntoIter = std::vec::IntoIter<Self::Item>;
+    /// Verifies the signatures of the proofs of store, transactions are verified at execution.
+    pub fn verify(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
+        match self {
+            Payload::DirectMempool(_) => Ok(()),
+            Payload::InQuorumStore(proofs) => proofs.iter().try_for_each(|proof| {
+                proof
+                    .verify(validator)
+                    .with_context(|| format!("Invalid proof of store {}", proof.digest()))
+            }),
+        }
+    }
 
-    fn into_iter(self) -> Self::IntoIter {
+    /// Verifies that the transactions are the ones of the payload, i.e. the concatenation of
+    /// the batches referenced by the proofs of store.
+    pub fn verify_transactions(&self, txns: &[SignedTransaction]) -> anyhow::Result<()> {
         match self {
-            Payload::DirectMempool(txns) => txns.into_iter(),
-            Payload::InQuorumStore(_poavs) => todo!(),
+            Payload::DirectMempool(payload_txns) => ensure!(
+                payload_txns.as_slice() == txns,
+                "Transactions don't match the payload"
+            ),
+            Payload::InQuorumStore(proofs) => {
+                ensure!(
+                    self.len() == txns.len(),
+                    "Expected {} transactions, got {}",
+                    self.len(),
+                    txns.len()
+                );
+                let mut remaining = txns;
+                for proof in proofs {
+                    let (batch, rest) = remaining.split_at(proof.info().num_txns() as usize);
+                    ensure!(
+                        BatchPayload::new(batch.to_vec()).hash() == proof.digest(),
+                        "Transactions don't match the batch {}",
+                        proof.digest()
+                    );
+                    remaining = rest;
+                }
+            }
         }
+        Ok(())
     }
 }
 
@@ -70,7 +111,9 @@ impl fmt::Display for Payload {
             Payload::DirectMempool(txns) => {
                 write!(f, "InMemory txns: {}", txns.len())
             }
-            Payload::InQuorumStore(_poavs) => todo!(),
+            Payload::InQuorumStore(proofs) => {
+                write!(f, "InQuorumStore proofs: {}", proofs.len())
+            }
         }
     }
 }
@@ -79,7 +122,8 @@ impl fmt::Display for Payload {
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub enum PayloadFilter {
     DirectMempool(Vec<TransactionSummary>),
-    InQuorumStore(Vec<ProofOfStore>),
+    /// Digests of the batches already referenced by pending blocks.
+    InQuorumStore(HashSet<HashValue>),
 }
 
 impl From<&Vec<&Payload>> for PayloadFilter {
@@ -87,22 +131,31 @@ impl From<&Vec<&Payload>> for PayloadFilter {
         if exclude_payloads.is_empty() {
             return PayloadFilter::DirectMempool(vec![]);
         }
-        match exclude_payloads.first().unwrap() {
-            Payload::DirectMempool(_) => {
-                let mut exclude_txns = vec![];
-                for payload in exclude_payloads {
-                    if let Payload::DirectMempool(txns) = payload {
-                        for txn in txns {
-                            exclude_txns.push(TransactionSummary {
-                                sender: txn.sender(),
-                                sequence_number: txn.sequence_number(),
-                            });
-                        }
+        // empty payloads of reconfiguration suffixes are always DirectMempool
+        if exclude_payloads
+            .iter()
+            .any(|payload| matches!(payload, Payload::InQuorumStore(_)))
+        {
+            let mut exclude_digests = HashSet::new();
+            for payload in exclude_payloads {
+                if let Payload::InQuorumStore(proofs) = payload {
+                    exclude_digests.extend(proofs.iter().map(|proof| proof.digest()));
+                }
+            }
+            PayloadFilter::InQuorumStore(exclude_digests)
+        } else {
+            let mut exclude_txns = vec![];
+            for payload in exclude_payloads {
+                if let Payload::DirectMempool(txns) = payload {
+                    for txn in txns {
+                        exclude_txns.push(TransactionSummary {
+                            sender: txn.sender(),
+                            sequence_number: txn.sequence_number(),
+                        });
                     }
                 }
-                PayloadFilter::DirectMempool(exclude_txns)
             }
-            Payload::InQuorumStore(_) => todo!(),
+            PayloadFilter::DirectMempool(exclude_txns)
         }
     }
 }
@@ -117,13 +170,13 @@ impl fmt::Display for PayloadFilter {
                 }
                 write!(f, "{}", txns_str)
             }
-            PayloadFilter::InQuorumStore(_poavs) => todo!(),
+            PayloadFilter::InQuorumStore(excluded_digests) => {
+                let mut digests_str = "".to_string();
+                for digest in excluded_digests.iter() {
+                    digests_str += &format!("{} ", digest);
+                }
+                write!(f, "{}", digests_str)
+            }
         }
     }
 }
-
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Hash)]
-pub struct ProofOfStore {
-    // TODO: This is currently just a placeholder for a real ProofOfStore implementation.
-    placeholder: u64,
-}
@@ -0,0 +1,311 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{Author, Round};
+use anyhow::{ensure, Context};
+use aptos_crypto::{ed25519::Ed25519Signature, hash::CryptoHash, HashValue};
+use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use aptos_types::{
+    transaction::SignedTransaction, validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier,
+};
+use serde::{Deserialize, Serialize};
+use short_hex_str::AsShortHexStr;
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+};
+
+/// Logical time of the quorum store: a batch expires once a block of a later round of the same
+/// epoch is committed.
+#[derive(
+    Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash, Default,
+)]
+pub struct LogicalTime {
+    epoch: u64,
+    round: Round,
+}
+
+impl LogicalTime {
+    pub fn new(epoch: u64, round: Round) -> Self {
+        Self { epoch, round }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn round(&self) -> Round {
+        self.round
+    }
+}
+
+/// The transactions of a batch, the digest of a batch is the hash of its payload.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, CryptoHasher, BCSCryptoHash)]
+pub struct BatchPayload(Vec<SignedTransaction>);
+
+impl BatchPayload {
+    pub fn new(txns: Vec<SignedTransaction>) -> Self {
+        Self(txns)
+    }
+}
+
+/// A batch of transactions broadcast by its source ahead of the proposals referencing it.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Batch {
+    source: Author,
+    expiration: LogicalTime,
+    payload: BatchPayload,
+}
+
+impl Batch {
+    pub fn new(source: Author, expiration: LogicalTime, txns: Vec<SignedTransaction>) -> Self {
+        Self {
+            source,
+            expiration,
+            payload: BatchPayload(txns),
+        }
+    }
+
+    pub fn source(&self) -> Author {
+        self.source
+    }
+
+    pub fn expiration(&self) -> LogicalTime {
+        self.expiration
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.expiration.epoch()
+    }
+
+    pub fn digest(&self) -> HashValue {
+        self.payload.hash()
+    }
+
+    pub fn num_txns(&self) -> u64 {
+        self.payload.0.len() as u64
+    }
+
+    pub fn num_bytes(&self) -> u64 {
+        bcs::to_bytes(&self.payload)
+            .expect("Unable to serialize batch payload")
+            .len() as u64
+    }
+
+    pub fn txns(&self) -> &[SignedTransaction] {
+        &self.payload.0
+    }
+
+    pub fn into_txns(self) -> Vec<SignedTransaction> {
+        self.payload.0
+    }
+
+    /// The information signed by the validators storing the batch.
+    pub fn info(&self) -> SignedDigestInfo {
+        SignedDigestInfo::new(
+            self.digest(),
+            self.expiration,
+            self.num_txns(),
+            self.num_bytes(),
+        )
+    }
+
+    pub fn verify(&self) -> anyhow::Result<()> {
+        ensure!(
+            !self.payload.0.is_empty(),
+            "Empty batch from {}",
+            self.source
+        );
+        Ok(())
+    }
+}
+
+impl Display for Batch {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Batch: [source: {}, digest: {}, txns: {}, expiration: {:?}]",
+            self.source.short_str(),
+            self.digest(),
+            self.num_txns(),
+            self.expiration,
+        )
+    }
+}
+
+/// Request for a batch referenced by a proof of store but missing locally.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct BatchRequest {
+    epoch: u64,
+    digest: HashValue,
+}
+
+impl BatchRequest {
+    pub fn new(epoch: u64, digest: HashValue) -> Self {
+        Self { epoch, digest }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn digest(&self) -> HashValue {
+        self.digest
+    }
+}
+
+impl Display for BatchRequest {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "BatchRequest: [epoch: {}, digest: {}]",
+            self.epoch, self.digest
+        )
+    }
+}
+
+/// What a validator signs to certify that it stores a batch until its expiration.
+#[derive(
+    Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash, CryptoHasher, BCSCryptoHash,
+)]
+pub struct SignedDigestInfo {
+    digest: HashValue,
+    expiration: LogicalTime,
+    num_txns: u64,
+    num_bytes: u64,
+}
+
+impl SignedDigestInfo {
+    pub fn new(digest: HashValue, expiration: LogicalTime, num_txns: u64, num_bytes: u64) -> Self {
+        Self {
+            digest,
+            expiration,
+            num_txns,
+            num_bytes,
+        }
+    }
+
+    pub fn digest(&self) -> HashValue {
+        self.digest
+    }
+
+    pub fn expiration(&self) -> LogicalTime {
+        self.expiration
+    }
+
+    pub fn num_txns(&self) -> u64 {
+        self.num_txns
+    }
+
+    pub fn num_bytes(&self) -> u64 {
+        self.num_bytes
+    }
+}
+
+/// Signature of a single validator over a batch it stores, sent back to the source of the batch.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SignedDigest {
+    signer: Author,
+    info: SignedDigestInfo,
+    signature: Ed25519Signature,
+}
+
+impl SignedDigest {
+    pub fn new(info: SignedDigestInfo, validator_signer: &ValidatorSigner) -> Self {
+        let signature = validator_signer.sign(&info);
+        Self {
+            signer: validator_signer.author(),
+            info,
+            signature,
+        }
+    }
+
+    pub fn signer(&self) -> Author {
+        self.signer
+    }
+
+    pub fn info(&self) -> &SignedDigestInfo {
+        &self.info
+    }
+
+    pub fn digest(&self) -> HashValue {
+        self.info.digest
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.info.expiration.epoch()
+    }
+
+    pub fn signature(&self) -> &Ed25519Signature {
+        &self.signature
+    }
+
+    pub fn verify(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
+        validator
+            .verify(self.signer, &self.info, &self.signature)
+            .context("Failed to verify SignedDigest")
+    }
+}
+
+impl Display for SignedDigest {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "SignedDigest: [signer: {}, digest: {}]",
+            self.signer.short_str(),
+            self.info.digest
+        )
+    }
+}
+
+/// Certifies that a quorum of validators stores the batch with the given digest, so that a
+/// proposal can reference the batch instead of carrying its transactions.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+pub struct ProofOfStore {
+    info: SignedDigestInfo,
+    signatures: BTreeMap<Author, Ed25519Signature>,
+}
+
+impl ProofOfStore {
+    pub fn new(info: SignedDigestInfo, signatures: BTreeMap<Author, Ed25519Signature>) -> Self {
+        Self { info, signatures }
+    }
+
+    pub fn info(&self) -> &SignedDigestInfo {
+        &self.info
+    }
+
+    pub fn digest(&self) -> HashValue {
+        self.info.digest
+    }
+
+    pub fn expiration(&self) -> LogicalTime {
+        self.info.expiration
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.info.expiration.epoch()
+    }
+
+    /// The validators that signed the proof, all of them are expected to store the batch.
+    pub fn signers(&self) -> impl Iterator<Item = &Author> {
+        self.signatures.keys()
+    }
+
+    pub fn verify(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
+        validator
+            .verify_aggregated_struct_signature(&self.info, &self.signatures)
+            .context("Failed to verify ProofOfStore")
+    }
+}
+
+impl Display for ProofOfStore {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "ProofOfStore: [digest: {}, txns: {}, expiration: {:?}]",
+            self.info.digest, self.info.num_txns, self.info.expiration,
+        )
+    }
+}
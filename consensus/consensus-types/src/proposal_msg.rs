@@ -1,7 +1,12 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{block::Block, common::Author, sync_info::SyncInfo};
+use crate::{
+    block::Block,
+    common::{Author, Payload},
+    proof_of_store::LogicalTime,
+    sync_info::SyncInfo,
+};
 use anyhow::{anyhow, ensure, format_err, Context, Result};
 use aptos_types::validator_verifier::ValidatorVerifier;
 use serde::{Deserialize, Serialize};
@@ -76,6 +81,17 @@ impl ProposalMsg {
             "Proposal {} does not define an author",
             self.proposal
         );
+        if let Some(Payload::InQuorumStore(proofs)) = self.proposal.payload() {
+            let proposal_time = LogicalTime::new(self.proposal.epoch(), self.proposal.round());
+            for proof in proofs {
+                ensure!(
+                    proof.expiration() >= proposal_time,
+                    "Proposal {} references expired {}",
+                    self.proposal,
+                    proof
+                );
+            }
+        }
         Ok(())
     }
 
@@ -87,6 +103,10 @@ impl ProposalMsg {
         if let Some(tc) = self.sync_info.highest_2chain_timeout_cert() {
             tc.verify(validator).map_err(|e| format_err!("{:?}", e))?;
         }
+        // the batches referenced by the payload must be available to a quorum
+        if let Some(payload) = self.proposal.payload() {
+            payload.verify(validator)?;
+        }
         // Note that we postpone the verification of SyncInfo until it's being used.
         self.verify_well_formed()
     }
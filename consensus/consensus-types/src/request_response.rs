@@ -1,8 +1,12 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::{Payload, PayloadFilter, Round};
+use crate::{
+    common::{Payload, PayloadFilter, Round},
+    proof_of_store::ProofOfStore,
+};
 use anyhow::Result;
+use aptos_types::transaction::SignedTransaction;
 use futures::channel::oneshot;
 use std::{fmt, fmt::Formatter};
 
@@ -12,17 +16,28 @@ pub enum ConsensusRequest {
     GetBlockRequest(
         // max block size
         u64,
+        // max block size in bytes
+        u64,
         // block payloads to exclude from the requested block
         PayloadFilter,
         // callback to respond to
         oneshot::Sender<Result<ConsensusResponse>>,
     ),
+    /// Request to resolve the batches referenced by a block into their transactions.
+    GetTransactionsRequest(
+        // proofs of the batches, in the order of the block payload
+        Vec<ProofOfStore>,
+        // callback to respond to
+        oneshot::Sender<Result<ConsensusResponse>>,
+    ),
     /// Request to clean quorum store at commit logical time
     CleanRequest(
         // epoch
         u64,
         // round
         Round,
+        // payloads of the committed blocks
+        Vec<Payload>,
         // callback to respond to
         oneshot::Sender<Result<ConsensusResponse>>,
     ),
@@ -31,14 +46,17 @@ pub enum ConsensusRequest {
 impl fmt::Display for ConsensusRequest {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            ConsensusRequest::GetBlockRequest(block_size, excluded, _) => {
+            ConsensusRequest::GetBlockRequest(block_size, block_bytes, excluded, _) => {
                 write!(
                     f,
-                    "GetBlockRequest [block_size: {}, excluded: {}]",
-                    block_size, excluded
+                    "GetBlockRequest [block_size: {}, block_bytes: {}, excluded: {}]",
+                    block_size, block_bytes, excluded
                 )
             }
-            ConsensusRequest::CleanRequest(epoch, round, _) => {
+            ConsensusRequest::GetTransactionsRequest(proofs, _) => {
+                write!(f, "GetTransactionsRequest [batches: {}]", proofs.len())
+            }
+            ConsensusRequest::CleanRequest(epoch, round, _, _) => {
                 write!(f, "CleanRequest [epoch: {}, round: {}]", epoch, round)
             }
         }
@@ -47,5 +65,6 @@ impl fmt::Display for ConsensusRequest {
 
 pub enum ConsensusResponse {
     GetBlockResponse(Payload),
+    GetTransactionsResponse(Vec<SignedTransaction>),
     CleanResponse(),
 }
@@ -7,21 +7,27 @@ use crate::{
     epoch_manager::EpochManager,
     network::NetworkTask,
     network_interface::{ConsensusNetworkEvents, ConsensusNetworkSender},
+    observer::{
+        consensus_observer::{ConsensusObserver, ObserverCommitNotifier},
+        network::{ObserverMultiNetworkSender, ObserverNetworkEvents, ObserverNetworkSender},
+        publisher::Publisher,
+    },
     persistent_liveness_storage::StorageWriteProxy,
     state_computer::ExecutionProxy,
     txn_notifier::MempoolNotifier,
     util::time_service::ClockTimeService,
 };
-use aptos_config::config::NodeConfig;
+use aptos_config::{config::NodeConfig, network_id::NetworkId};
 use aptos_logger::prelude::*;
 use aptos_mempool::QuorumStoreRequest;
 use aptos_vm::AptosVM;
+use channel::{aptos_channel, message_queues::QueueStyle};
 use consensus_notifications::ConsensusNotificationSender;
 use event_notifications::ReconfigNotificationListener;
 use executor::block_executor::BlockExecutor;
 use futures::channel::mpsc;
-use network::application::storage::PeerMetadataStorage;
-use std::sync::Arc;
+use network::application::{interface::MultiNetworkSender, storage::PeerMetadataStorage};
+use std::{collections::HashMap, sync::Arc};
 use storage_interface::DbReaderWriter;
 use tokio::runtime::{self, Runtime};
 
@@ -35,6 +41,7 @@ pub fn start_consensus(
     aptos_db: DbReaderWriter,
     reconfig_events: ReconfigNotificationListener,
     peer_metadata_storage: Arc<PeerMetadataStorage>,
+    observer_network_handles: Vec<(NetworkId, ObserverNetworkSender, ObserverNetworkEvents)>,
 ) -> Runtime {
     let runtime = runtime::Builder::new_multi_thread()
         .thread_name("consensus")
@@ -42,6 +49,20 @@ pub fn start_consensus(
         .build()
         .expect("Failed to create Tokio runtime!");
     let storage = Arc::new(StorageWriteProxy::new(node_config, aptos_db.reader.clone()));
+    let observer_publisher = if node_config.consensus_observer.publisher_enabled {
+        let (network_sender, network_events) =
+            split_observer_network_handles(observer_network_handles);
+        let (publisher_tx, publisher_rx) = aptos_channel::new(
+            QueueStyle::FIFO,
+            node_config.consensus_observer.max_publisher_channel_size as usize,
+            Some(&counters::PENDING_CONSENSUS_OBSERVER_PUBLISHER_NOTIFICATIONS),
+        );
+        let publisher = Publisher::new(network_sender, aptos_db.reader.clone());
+        runtime.spawn(publisher.start(network_events, publisher_rx));
+        Some(publisher_tx)
+    } else {
+        None
+    };
     let txn_notifier = Arc::new(MempoolNotifier::new(
         consensus_to_mempool_sender.clone(),
         node_config.consensus.mempool_executed_txn_timeout_ms,
@@ -75,6 +96,7 @@ pub fn start_consensus(
         storage,
         reconfig_events,
         commit_notifier,
+        observer_publisher,
     );
 
     let (network_task, network_receiver) = NetworkTask::new(network_events, self_receiver);
@@ -85,3 +107,58 @@ pub fn start_consensus(
     debug!("Consensus started.");
     runtime
 }
+
+/// Helper function to start the consensus observer on a fullnode and return the runtime
+pub fn start_consensus_observer(
+    node_config: &NodeConfig,
+    network_handles: Vec<(NetworkId, ObserverNetworkSender, ObserverNetworkEvents)>,
+    state_sync_notifier: Arc<dyn ConsensusNotificationSender>,
+    consensus_to_mempool_sender: mpsc::Sender<QuorumStoreRequest>,
+    aptos_db: DbReaderWriter,
+    peer_metadata_storage: Arc<PeerMetadataStorage>,
+) -> Runtime {
+    let runtime = runtime::Builder::new_multi_thread()
+        .thread_name("consensus-observer")
+        .enable_all()
+        .build()
+        .expect("Failed to create Tokio runtime!");
+    let txn_notifier = Arc::new(MempoolNotifier::new(
+        consensus_to_mempool_sender,
+        node_config.consensus.mempool_executed_txn_timeout_ms,
+    ));
+    let execution_proxy = Arc::new(ExecutionProxy::new(
+        Box::new(BlockExecutor::<AptosVM>::new(aptos_db.clone())),
+        txn_notifier,
+        state_sync_notifier,
+        Arc::new(ObserverCommitNotifier),
+        runtime.handle(),
+    ));
+
+    let (network_sender, network_events) = split_observer_network_handles(network_handles);
+    let observer = ConsensusObserver::new(
+        node_config.consensus_observer.clone(),
+        network_sender,
+        peer_metadata_storage,
+        execution_proxy,
+        aptos_db.reader,
+    );
+    runtime.spawn(observer.start(network_events));
+
+    debug!("Consensus observer started.");
+    runtime
+}
+
+fn split_observer_network_handles(
+    network_handles: Vec<(NetworkId, ObserverNetworkSender, ObserverNetworkEvents)>,
+) -> (
+    ObserverMultiNetworkSender,
+    Vec<(NetworkId, ObserverNetworkEvents)>,
+) {
+    let mut network_senders = HashMap::new();
+    let mut network_events = vec![];
+    for (network_id, network_sender, events) in network_handles {
+        network_senders.insert(network_id, network_sender);
+        network_events.push((network_id, events));
+    }
+    (MultiNetworkSender::new(network_senders), network_events)
+}
@@ -3,7 +3,10 @@
 
 use super::*;
 use aptos_temppath::TempPath;
-use consensus_types::block::block_test_utils::certificate_for_genesis;
+use aptos_types::account_address::AccountAddress;
+use consensus_types::{
+    block::block_test_utils::certificate_for_genesis, proof_of_store::LogicalTime,
+};
 
 #[test]
 fn test_put_get() {
@@ -69,3 +72,20 @@ fn test_delete_block_and_qc() {
     assert_eq!(db.get_blocks().unwrap().len(), 0);
     assert_eq!(db.get_quorum_certificates().unwrap().len(), 0);
 }
+
+#[test]
+fn test_put_get_delete_batches() {
+    let tmp_dir = TempPath::new();
+    let db = ConsensusDB::new(&tmp_dir);
+
+    assert_eq!(db.get_batches().unwrap().len(), 0);
+
+    let batch = Batch::new(AccountAddress::random(), LogicalTime::new(1, 10), vec![]);
+    db.save_batch(&batch).unwrap();
+    let batches = db.get_batches().unwrap();
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches.get(&batch.digest()), Some(&batch));
+
+    db.delete_batches(vec![batch.digest()]).unwrap();
+    assert_eq!(db.get_batches().unwrap().len(), 0);
+}
@@ -7,6 +7,7 @@ mod schema;
 
 use crate::{
     consensusdb::schema::{
+        batch::BatchSchema,
         block::BlockSchema,
         quorum_certificate::QCSchema,
         single_entry::{SingleEntryKey, SingleEntrySchema},
@@ -16,30 +17,38 @@ use crate::{
 use anyhow::Result;
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
-use consensus_types::{block::Block, quorum_cert::QuorumCert};
-use schema::{BLOCK_CF_NAME, QC_CF_NAME, SINGLE_ENTRY_CF_NAME};
-use schemadb::{Options, ReadOptions, SchemaBatch, DB, DEFAULT_COLUMN_FAMILY_NAME};
+use consensus_types::{block::Block, proof_of_store::Batch, quorum_cert::QuorumCert};
+use schema::{BATCH_CF_NAME, BLOCK_CF_NAME, QC_CF_NAME, SINGLE_ENTRY_CF_NAME};
+use schemadb::{
+    ColumnFamilyName, Options, ReadOptions, SchemaBatch, DB, DEFAULT_COLUMN_FAMILY_NAME,
+};
 use std::{collections::HashMap, iter::Iterator, path::Path, time::Instant};
 
+/// Persists the blocks, quorum certs, last vote and highest timeout cert of consensus, along
+/// with the batches of the quorum store.
 pub struct ConsensusDB {
     db: DB,
 }
 
+fn column_families() -> Vec<ColumnFamilyName> {
+    vec![
+        /* UNUSED CF = */ DEFAULT_COLUMN_FAMILY_NAME,
+        BLOCK_CF_NAME,
+        QC_CF_NAME,
+        SINGLE_ENTRY_CF_NAME,
+        BATCH_CF_NAME,
+    ]
+}
+
+#[allow(missing_docs)]
 impl ConsensusDB {
     pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
-        let column_families = vec![
-            /* UNUSED CF = */ DEFAULT_COLUMN_FAMILY_NAME,
-            BLOCK_CF_NAME,
-            QC_CF_NAME,
-            SINGLE_ENTRY_CF_NAME,
-        ];
-
         let path = db_root_path.as_ref().join("consensusdb");
         let instant = Instant::now();
         let mut opts = Options::default();
         opts.create_if_missing(true);
         opts.create_missing_column_families(true);
-        let db = DB::open(path.clone(), "consensus", column_families, &opts)
+        let db = DB::open(path.clone(), "consensus", column_families(), &opts)
             .expect("ConsensusDB open failed; unable to continue");
 
         info!(
@@ -51,6 +60,13 @@ impl ConsensusDB {
         Self { db }
     }
 
+    /// Opens an existing ConsensusDB without write access, e.g. alongside a running node.
+    pub fn new_readonly<P: AsRef<Path>>(db_root_path: P) -> Result<Self> {
+        let path = db_root_path.as_ref().join("consensusdb");
+        let db = DB::open_cf_readonly(&Options::default(), path, "consensus", column_families())?;
+        Ok(Self { db })
+    }
+
     pub fn get_data(
         &self,
     ) -> Result<(
@@ -125,6 +141,27 @@ impl ConsensusDB {
         self.commit(batch)
     }
 
+    pub fn save_batch(&self, batch: &Batch) -> Result<(), DbError> {
+        let mut schema_batch = SchemaBatch::new();
+        schema_batch.put::<BatchSchema>(&batch.digest(), batch)?;
+        self.commit(schema_batch)
+    }
+
+    pub fn delete_batches(&self, digests: Vec<HashValue>) -> Result<(), DbError> {
+        let mut batch = SchemaBatch::new();
+        digests
+            .iter()
+            .try_for_each(|digest| batch.delete::<BatchSchema>(digest))?;
+        self.commit(batch)
+    }
+
+    /// Get all batches stored by the quorum store.
+    pub fn get_batches(&self) -> Result<HashMap<HashValue, Batch>, DbError> {
+        let mut iter = self.db.iter::<BatchSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        Ok(iter.collect::<Result<HashMap<HashValue, Batch>>>()?)
+    }
+
     /// Write the whole schema batch including all data necessary to mutate the ledger
     /// state of some transaction by leveraging rocksdb atomicity support.
     fn commit(&self, batch: SchemaBatch) -> Result<(), DbError> {
@@ -1,6 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+pub(crate) mod batch;
 pub(crate) mod block;
 pub(crate) mod quorum_certificate;
 pub(crate) mod single_entry;
@@ -8,6 +9,7 @@ pub(crate) mod single_entry;
 use anyhow::{ensure, Result};
 use schemadb::ColumnFamilyName;
 
+pub(super) const BATCH_CF_NAME: ColumnFamilyName = "batch";
 pub(super) const BLOCK_CF_NAME: ColumnFamilyName = "block";
 pub(super) const QC_CF_NAME: ColumnFamilyName = "quorum_certificate";
 pub(super) const SINGLE_ENTRY_CF_NAME: ColumnFamilyName = "single_entry";
@@ -226,6 +226,51 @@ pub static NUM_TXNS_PER_BLOCK: Lazy<Histogram> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Number of blocks ordered but not committed yet, as seen by the last proposal.
+pub static PIPELINE_PENDING_BLOCKS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_pipeline_pending_blocks",
+        "Number of blocks ordered but not committed yet, as seen by the last proposal."
+    )
+    .unwrap()
+});
+
+/// Count of the proposals limited by the pipeline backpressure.
+pub static PIPELINE_BACKPRESSURED_PROPOSALS_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_pipeline_backpressured_proposals_count",
+        "Count of the proposals limited by the pipeline backpressure."
+    )
+    .unwrap()
+});
+
+/// Max txns per block allowed by the pipeline backpressure for the last proposal.
+pub static PIPELINE_BACKPRESSURE_MAX_BLOCK_SIZE: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_pipeline_backpressure_max_block_size",
+        "Max txns per block allowed by the pipeline backpressure for the last proposal."
+    )
+    .unwrap()
+});
+
+/// Max bytes per block allowed by the pipeline backpressure for the last proposal.
+pub static PIPELINE_BACKPRESSURE_MAX_BLOCK_BYTES: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_pipeline_backpressure_max_block_bytes",
+        "Max bytes per block allowed by the pipeline backpressure for the last proposal."
+    )
+    .unwrap()
+});
+
+/// Delay of the last proposal due to the pipeline backpressure (in milliseconds).
+pub static PIPELINE_BACKPRESSURE_PROPOSAL_DELAY_MS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_pipeline_backpressure_proposal_delay_ms",
+        "Delay of the last proposal due to the pipeline backpressure (in milliseconds)."
+    )
+    .unwrap()
+});
+
 /// Traces block movement throughout the node
 pub static BLOCK_TRACING: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
@@ -330,3 +375,81 @@ pub static BLOCK_RETRIEVAL_CHANNEL_MSGS: Lazy<IntCounterVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+/// Counters(queued,dequeued,dropped) related to quorum store channel
+pub static QUORUM_STORE_CHANNEL_MSGS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_quorum_store_channel_msgs_count",
+        "Counters(queued,dequeued,dropped) related to quorum store channel",
+        &["state"]
+    )
+    .unwrap()
+});
+
+/// Counters(queued,dequeued,dropped) related to verified quorum store messages
+pub static QUORUM_STORE_MSGS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_quorum_store_msgs_count",
+        "Counters(queued,dequeued,dropped) related to verified quorum store messages",
+        &["state"]
+    )
+    .unwrap()
+});
+
+/// Counters(queued,dequeued,dropped) related to batch retrieval channel
+pub static BATCH_RETRIEVAL_CHANNEL_MSGS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_batch_retrieval_channel_msgs_count",
+        "Counters(queued,dequeued,dropped) related to batch retrieval channel",
+        &["state"]
+    )
+    .unwrap()
+});
+
+/// Counters(queued,dequeued,dropped) related to consensus observer network events
+pub static PENDING_CONSENSUS_OBSERVER_NETWORK_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_observer_pending_network_events",
+        "Counters(queued,dequeued,dropped) related to consensus observer network events",
+        &["state"]
+    )
+    .unwrap()
+});
+
+/// Number of peers subscribed to the ordered blocks and commit decisions of this node
+pub static CONSENSUS_OBSERVER_SUBSCRIBERS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_observer_subscribers",
+        "Number of peers subscribed to the ordered blocks and commit decisions of this node"
+    )
+    .unwrap()
+});
+
+/// Counters related to the notifications pending publication to the consensus observers
+pub static PENDING_CONSENSUS_OBSERVER_PUBLISHER_NOTIFICATIONS: Lazy<IntCounterVec> =
+    Lazy::new(|| {
+        register_int_counter_vec!(
+            "aptos_consensus_observer_pending_publisher_notifications",
+            "Counters(queued,dequeued,dropped) related to consensus observer publications",
+            &["state"]
+        )
+        .unwrap()
+    });
+
+/// The round of the last block committed by the consensus observer
+pub static CONSENSUS_OBSERVER_COMMITTED_ROUND: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_observer_committed_round",
+        "The round of the last block committed by the consensus observer"
+    )
+    .unwrap()
+});
+
+/// Number of times the consensus observer fell back to state sync
+pub static CONSENSUS_OBSERVER_STATE_SYNC_FALLBACKS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_observer_state_sync_fallbacks",
+        "Number of times the consensus observer fell back to state sync"
+    )
+    .unwrap()
+});
@@ -14,8 +14,8 @@ use crate::{
     liveness::{
         cached_proposer_election::CachedProposerElection,
         leader_reputation::{
-            ActiveInactiveHeuristic, AptosDBBackend, LeaderReputation, ProposerAndVoterHeuristic,
-            ReputationHeuristic,
+            ActiveInactiveHeuristic, AptosDBBackend, LeaderReputation, ParticipationHeuristic,
+            ProposerAndVoterHeuristic, ReputationHeuristic,
         },
         proposal_generator::ProposalGenerator,
         proposer_election::ProposerElection,
@@ -25,13 +25,20 @@ use crate::{
     },
     logging::{LogEvent, LogSchema},
     metrics_safety_rules::MetricsSafetyRules,
-    network::{IncomingBlockRetrievalRequest, NetworkReceivers, NetworkSender},
+    network::{
+        IncomingBatchRetrievalRequest, IncomingBlockRetrievalRequest, NetworkReceivers,
+        NetworkSender,
+    },
     network_interface::{ConsensusMsg, ConsensusNetworkSender},
+    observer::publisher::{PublisherHandle, PublisherNotification},
     payload_manager::QuorumStoreClient,
     persistent_liveness_storage::{LedgerRecoveryData, PersistentLivenessStorage, RecoveryData},
-    quorum_store::direct_mempool_quorum_store::DirectMempoolQuorumStore,
+    quorum_store::{
+        batch_quorum_store::BatchQuorumStore, batch_store::BatchStore,
+        direct_mempool_quorum_store::DirectMempoolQuorumStore,
+    },
     round_manager::{RoundManager, UnverifiedEvent, VerifiedEvent},
-    state_replication::StateComputer,
+    state_replication::{PayloadManager, StateComputer},
     util::time_service::TimeService,
 };
 use anyhow::{bail, ensure, Context};
@@ -40,20 +47,23 @@ use aptos_infallible::{duration_since_epoch, Mutex};
 use aptos_logger::prelude::*;
 use aptos_mempool::QuorumStoreRequest;
 use aptos_metrics_core::monitor;
+use aptos_secure_storage::Storage;
 use aptos_types::{
     account_address::AccountAddress,
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
     on_chain_config::{
         LeaderReputationType, OnChainConfigPayload, OnChainConsensusConfig, ProposerElectionType,
-        ValidatorSet,
+        StakeWeighting, ValidatorSet,
     },
+    validator_signer::ValidatorSigner,
     validator_verifier::ValidatorVerifier,
 };
 use channel::{aptos_channel, message_queues::QueueStyle};
 use consensus_types::{
     common::{Author, Round},
     epoch_retrieval::EpochRetrievalRequest,
+    proof_of_store::LogicalTime,
     request_response::ConsensusRequest,
 };
 use event_notifications::ReconfigNotificationListener;
@@ -66,7 +76,7 @@ use futures::{
     SinkExt, StreamExt,
 };
 use network::protocols::network::{ApplicationNetworkSender, Event};
-use safety_rules::SafetyRulesManager;
+use safety_rules::{PersistentSafetyStorage, SafetyRulesManager};
 use std::{
     cmp::Ordering,
     mem::{discriminant, Discriminant},
@@ -111,9 +121,14 @@ pub struct EpochManager {
     safety_rules_manager: SafetyRulesManager,
     reconfig_events: ReconfigNotificationListener,
     commit_notifier: Arc<dyn CommitNotifier>,
+    // channel to the consensus observer publisher
+    observer_publisher: Option<aptos_channel::Sender<(), PublisherNotification>>,
     // channels to buffer manager
     buffer_manager_msg_tx: Option<aptos_channel::Sender<AccountAddress, VerifiedEvent>>,
     buffer_manager_reset_tx: Option<UnboundedSender<ResetRequest>>,
+    // channel to quorum store
+    quorum_store_msg_tx:
+        Option<aptos_channel::Sender<AccountAddress, (AccountAddress, VerifiedEvent)>>,
     // channels to round manager
     round_manager_tx: Option<
         aptos_channel::Sender<(Author, Discriminant<VerifiedEvent>), (Author, VerifiedEvent)>,
@@ -133,6 +148,7 @@ impl EpochManager {
         storage: Arc<dyn PersistentLivenessStorage>,
         reconfig_events: ReconfigNotificationListener,
         commit_notifier: Arc<dyn CommitNotifier>,
+        observer_publisher: Option<aptos_channel::Sender<(), PublisherNotification>>,
     ) -> Self {
         let author = node_config.validator_network.as_ref().unwrap().peer_id();
         let config = node_config.consensus.clone();
@@ -151,8 +167,10 @@ impl EpochManager {
             safety_rules_manager,
             reconfig_events,
             commit_notifier,
+            observer_publisher,
             buffer_manager_msg_tx: None,
             buffer_manager_reset_tx: None,
+            quorum_store_msg_tx: None,
             round_manager_tx: None,
             epoch_state: None,
         }
@@ -203,7 +221,7 @@ impl EpochManager {
                 Box::new(RotatingProposer::new(vec![proposer], *contiguous_rounds))
             }
             ProposerElectionType::LeaderReputation(leader_reputation_type) => {
-                let (heuristic, window_size) = match &leader_reputation_type {
+                let (heuristic, window_size, stake_weighting) = match &leader_reputation_type {
                     LeaderReputationType::ActiveInactive(active_inactive_config) => {
                         let window_size = proposers.len()
                             * active_inactive_config.window_num_validators_multiplier;
@@ -214,7 +232,7 @@ impl EpochManager {
                                 active_inactive_config.inactive_weight,
                                 window_size,
                             ));
-                        (heuristic, window_size)
+                        (heuristic, window_size, StakeWeighting::Uniform)
                     }
                     LeaderReputationType::ProposerAndVoter(proposer_and_voter_config) => {
                         let proposer_window_size = proposers.len()
@@ -234,6 +252,29 @@ impl EpochManager {
                         (
                             heuristic,
                             std::cmp::max(proposer_window_size, voter_window_size),
+                            StakeWeighting::Uniform,
+                        )
+                    }
+                    LeaderReputationType::StakeWeighted(stake_weighted_config) => {
+                        let reputation_config = &stake_weighted_config.reputation;
+                        let proposer_window_size = proposers.len()
+                            * reputation_config.proposer_window_num_validators_multiplier;
+                        let voter_window_size = proposers.len()
+                            * reputation_config.voter_window_num_validators_multiplier;
+                        let heuristic: Box<dyn ReputationHeuristic> =
+                            Box::new(ParticipationHeuristic::new(
+                                self.author,
+                                reputation_config.active_weight,
+                                reputation_config.inactive_weight,
+                                reputation_config.failed_weight,
+                                reputation_config.failure_threshold_percent,
+                                voter_window_size,
+                                proposer_window_size,
+                            ));
+                        (
+                            heuristic,
+                            std::cmp::max(proposer_window_size, voter_window_size),
+                            stake_weighted_config.stake_weighting,
                         )
                     }
                 };
@@ -246,9 +287,20 @@ impl EpochManager {
                         + PROPSER_ROUND_BEHIND_STORAGE_BUFFER,
                     self.storage.aptos_db(),
                 ));
+                let voting_powers = proposers
+                    .iter()
+                    .map(|proposer| {
+                        epoch_state
+                            .verifier
+                            .get_voting_power(proposer)
+                            .expect("Proposers are part of the validator set")
+                    })
+                    .collect();
                 let proposer_election = Box::new(LeaderReputation::new(
                     epoch_state.epoch,
                     proposers,
+                    voting_powers,
+                    stake_weighting,
                     backend,
                     heuristic,
                     onchain_config.leader_reputation_exclude_round(),
@@ -358,16 +410,63 @@ impl EpochManager {
         Ok(())
     }
 
+    /// Loads the consensus key of the epoch from the safety rules storage, the quorum store
+    /// signs the digests of the batches it stores with it.
+    fn load_consensus_signer(&self, epoch_state: &EpochState) -> Option<ValidatorSigner> {
+        let public_key = epoch_state.verifier.get_public_key(&self.author)?;
+        let storage =
+            PersistentSafetyStorage::new(Storage::from(&self.config.safety_rules.backend), false);
+        match storage.consensus_key_for_version(public_key) {
+            Ok(private_key) => Some(ValidatorSigner::new(self.author, private_key)),
+            Err(error) => {
+                error!(
+                    epoch = epoch_state.epoch,
+                    error = ?error,
+                    "Unable to load the consensus key, batches won't be signed",
+                );
+                None
+            }
+        }
+    }
+
     fn spawn_quorum_store(
         &mut self,
         consensus_to_quorum_store_receiver: Receiver<ConsensusRequest>,
+        epoch_state: &EpochState,
+        network_sender: NetworkSender,
+        committed_round: Round,
     ) {
-        let quorum_store = DirectMempoolQuorumStore::new(
-            consensus_to_quorum_store_receiver,
-            self.quorum_store_to_mempool_sender.clone(),
-            self.config.mempool_txn_pull_timeout_ms,
-        );
-        tokio::spawn(quorum_store.start());
+        if self.config.use_quorum_store {
+            let (quorum_store_msg_tx, quorum_store_msg_rx) = aptos_channel::new(
+                QueueStyle::FIFO,
+                self.config.channel_size,
+                Some(&counters::QUORUM_STORE_MSGS),
+            );
+            self.quorum_store_msg_tx = Some(quorum_store_msg_tx);
+            let batch_store = Arc::new(BatchStore::new(
+                epoch_state.epoch,
+                self.storage.consensus_db(),
+            ));
+            let quorum_store = BatchQuorumStore::new(
+                LogicalTime::new(epoch_state.epoch, committed_round),
+                self.load_consensus_signer(epoch_state),
+                epoch_state.verifier.clone(),
+                network_sender,
+                self.quorum_store_to_mempool_sender.clone(),
+                batch_store,
+                &self.config,
+            );
+            tokio::spawn(
+                quorum_store.start(consensus_to_quorum_store_receiver, quorum_store_msg_rx),
+            );
+        } else {
+            let quorum_store = DirectMempoolQuorumStore::new(
+                consensus_to_quorum_store_receiver,
+                self.quorum_store_to_mempool_sender.clone(),
+                self.config.mempool_txn_pull_timeout_ms,
+            );
+            tokio::spawn(quorum_store.start());
+        }
     }
 
     /// this function spawns the phases and a buffer manager
@@ -376,6 +475,7 @@ impl EpochManager {
         &mut self,
         safety_rules_container: Arc<Mutex<MetricsSafetyRules>>,
         verifier: ValidatorVerifier,
+        payload_manager: Arc<dyn PayloadManager>,
     ) -> OrderingStateComputer {
         let network_sender = NetworkSender::new(
             self.author,
@@ -396,6 +496,11 @@ impl EpochManager {
         self.buffer_manager_msg_tx = Some(commit_msg_tx);
         self.buffer_manager_reset_tx = Some(reset_tx.clone());
 
+        let observer_publisher = self
+            .observer_publisher
+            .clone()
+            .map(|sender| PublisherHandle::new(sender, payload_manager));
+
         let (execution_phase, signing_phase, persisting_phase, buffer_manager) =
             prepare_phases_and_buffer_manager(
                 self.author,
@@ -407,6 +512,7 @@ impl EpochManager {
                 block_rx,
                 reset_rx,
                 verifier,
+                observer_publisher,
             );
 
         tokio::spawn(execution_phase.start());
@@ -429,6 +535,9 @@ impl EpochManager {
         }
         self.round_manager_tx = None;
 
+        // Stop the previous quorum store
+        self.quorum_store_msg_tx = None;
+
         // Shutdown the previous buffer manager, to release the SafetyRule client
         self.buffer_manager_msg_tx = None;
         if let Some(mut tx) = self.buffer_manager_reset_tx.take() {
@@ -461,6 +570,7 @@ impl EpochManager {
             "Starting new epoch",
         );
         let last_vote = recovery_data.last_vote();
+        let committed_round = recovery_data.root_block().round();
 
         info!(epoch = epoch, "Update SafetyRules");
 
@@ -491,20 +601,27 @@ impl EpochManager {
 
         let (consensus_to_quorum_store_sender, consensus_to_quorum_store_receiver) =
             mpsc::channel(self.config.intra_consensus_channel_buffer_size);
-        self.spawn_quorum_store(consensus_to_quorum_store_receiver);
-        let payload_manager = QuorumStoreClient::new(
+        self.spawn_quorum_store(
+            consensus_to_quorum_store_receiver,
+            &epoch_state,
+            network_sender.clone(),
+            committed_round,
+        );
+        let payload_manager: Arc<dyn PayloadManager> = Arc::new(QuorumStoreClient::new(
             consensus_to_quorum_store_sender.clone(),
             self.config.quorum_store_poll_count,
             self.config.quorum_store_pull_timeout_ms,
-        );
+        ));
         self.commit_notifier
             .new_epoch(consensus_to_quorum_store_sender);
 
-        self.commit_state_computer.new_epoch(&epoch_state);
+        self.commit_state_computer
+            .new_epoch(&epoch_state, payload_manager.clone());
         let state_computer = if onchain_config.decoupled_execution() {
             Arc::new(self.spawn_decoupled_execution(
                 safety_rules_container.clone(),
                 epoch_state.verifier.clone(),
+                payload_manager.clone(),
             ))
         } else {
             self.commit_state_computer.clone()
@@ -526,10 +643,12 @@ impl EpochManager {
         let proposal_generator = ProposalGenerator::new(
             self.author,
             block_store.clone(),
-            Arc::new(payload_manager),
+            payload_manager,
             self.time_service.clone(),
             self.config.max_block_size,
+            self.config.max_block_bytes,
             onchain_config.max_failed_authors_to_store(),
+            self.config.pipeline_backpressure.clone(),
         );
 
         let mut round_manager = RoundManager::new(
@@ -624,7 +743,10 @@ impl EpochManager {
             | ConsensusMsg::SyncInfo(_)
             | ConsensusMsg::VoteMsg(_)
             | ConsensusMsg::CommitVoteMsg(_)
-            | ConsensusMsg::CommitDecisionMsg(_) => {
+            | ConsensusMsg::CommitDecisionMsg(_)
+            | ConsensusMsg::BatchMsg(_)
+            | ConsensusMsg::SignedDigestMsg(_)
+            | ConsensusMsg::ProofOfStoreMsg(_) => {
                 let event: UnverifiedEvent = msg.into();
                 if event.epoch() == self.epoch() {
                     return Ok(Some(event));
@@ -684,6 +806,15 @@ impl EpochManager {
                     bail!("Commit Phase not started but received Commit Message (CommitVote/CommitDecision)");
                 }
             }
+            quorum_store_event @ (VerifiedEvent::Batch(_)
+            | VerifiedEvent::SignedDigest(_)
+            | VerifiedEvent::ProofOfStore(_)) => {
+                if let Some(sender) = &mut self.quorum_store_msg_tx {
+                    sender.push(peer_id, (peer_id, quorum_store_event))?;
+                } else {
+                    bail!("QuorumStore not started but received QuorumStore Message (Batch/SignedDigest/ProofOfStore)");
+                }
+            }
             round_manager_event => {
                 self.forward_to_round_manager(peer_id, round_manager_event);
             }
@@ -708,6 +839,17 @@ impl EpochManager {
         );
     }
 
+    fn process_batch_retrieval(&mut self, request: IncomingBatchRetrievalRequest) {
+        if let Some(sender) = &mut self.quorum_store_msg_tx {
+            let event = VerifiedEvent::BatchRetrievalRequest(Box::new(request));
+            if let Err(e) = sender.push(self.author, (self.author, event)) {
+                error!("Failed to send event to quorum store {:?}", e);
+            }
+        } else {
+            warn!("QuorumStore not started but received batch retrieval request");
+        }
+    }
+
     fn process_local_timeout(&mut self, round: u64) {
         self.forward_to_round_manager(self.author, VerifiedEvent::LocalTimeout(round));
     }
@@ -736,9 +878,17 @@ impl EpochManager {
                         error!(epoch = self.epoch(), error = ?e, kind = error_kind(&e));
                     }
                 }
+                Some((peer, msg)) = network_receivers.quorum_store_messages.next() => {
+                    if let Err(e) = self.process_message(peer, msg).await {
+                        error!(epoch = self.epoch(), error = ?e, kind = error_kind(&e));
+                    }
+                }
                 Some(request) = network_receivers.block_retrieval.next() => {
                     self.process_block_retrieval(request);
                 }
+                Some(request) = network_receivers.batch_retrieval.next() => {
+                    self.process_batch_retrieval(request);
+                }
                 Some(round) = round_timeout_sender_rx.next() => {
                     self.process_local_timeout(round);
                 }
@@ -32,6 +32,7 @@ use crate::{
     },
     network::NetworkSender,
     network_interface::ConsensusMsg,
+    observer::publisher::PublisherHandle,
     round_manager::VerifiedEvent,
     state_replication::StateComputerCommitCallBackType,
 };
@@ -95,6 +96,9 @@ pub struct BufferManager {
     verifier: ValidatorVerifier,
 
     ongoing_tasks: Arc<AtomicU64>,
+
+    // forwards the ordered blocks and the commit decisions to the consensus observers
+    observer_publisher: Option<PublisherHandle>,
 }
 
 impl BufferManager {
@@ -111,6 +115,7 @@ impl BufferManager {
         reset_rx: UnboundedReceiver<ResetRequest>,
         verifier: ValidatorVerifier,
         ongoing_tasks: Arc<AtomicU64>,
+        observer_publisher: Option<PublisherHandle>,
     ) -> Self {
         let buffer = Buffer::<BufferItem>::new();
 
@@ -138,6 +143,8 @@ impl BufferManager {
 
             verifier,
             ongoing_tasks,
+
+            observer_publisher,
         }
     }
 
@@ -151,6 +158,9 @@ impl BufferManager {
         } = ordered_blocks;
         debug!("Receive ordered block {}", ordered_proof.commit_info());
 
+        if let Some(publisher) = &self.observer_publisher {
+            publisher.publish_ordered_blocks(&ordered_blocks, &ordered_proof);
+        }
         let item = BufferItem::new_ordered(ordered_blocks, ordered_proof, callback);
         self.buffer.push_back(item);
     }
@@ -217,6 +227,9 @@ impl BufferManager {
             }
             if item.block_id() == target_block_id {
                 let aggregated_item = item.unwrap_aggregated();
+                if let Some(publisher) = &self.observer_publisher {
+                    publisher.publish_commit_decision(&aggregated_item.commit_proof);
+                }
                 if aggregated_item.commit_proof.ledger_info().ends_epoch() {
                     self.commit_msg_tx
                         .notify_epoch_change(EpochChangeProof::new(
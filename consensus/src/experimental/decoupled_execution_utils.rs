@@ -11,6 +11,7 @@ use crate::{
     },
     metrics_safety_rules::MetricsSafetyRules,
     network::NetworkSender,
+    observer::publisher::PublisherHandle,
     round_manager::VerifiedEvent,
     state_replication::StateComputer,
 };
@@ -32,6 +33,7 @@ pub fn prepare_phases_and_buffer_manager(
     block_rx: UnboundedReceiver<OrderedBlocks>,
     sync_rx: UnboundedReceiver<ResetRequest>,
     verifier: ValidatorVerifier,
+    observer_publisher: Option<PublisherHandle>,
 ) -> (
     PipelinePhase<ExecutionPhase>,
     PipelinePhase<SigningPhase>,
@@ -96,6 +98,7 @@ pub fn prepare_phases_and_buffer_manager(
             sync_rx,
             verifier,
             ongoing_tasks,
+            observer_publisher,
         ),
     )
 }
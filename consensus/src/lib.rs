@@ -44,6 +44,12 @@ pub mod consensus_provider;
 pub mod counters;
 /// AptosNet interface.
 pub mod network_interface;
+/// Consensus observer for fullnodes
+pub mod observer;
+
+/// The storage of the blocks, quorum certs and votes of consensus, e.g. for the
+/// consensus-db-tool
+pub use consensusdb::ConsensusDB;
 
 #[cfg(feature = "fuzzing")]
 pub use round_manager::round_manager_fuzzing;
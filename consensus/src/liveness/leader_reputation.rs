@@ -6,11 +6,14 @@ use crate::{
         COMMITTED_PROPOSALS_IN_WINDOW, COMMITTED_VOTES_IN_WINDOW, FAILED_PROPOSALS_IN_WINDOW,
         LEADER_REPUTATION_HISTORY_SIZE,
     },
-    liveness::proposer_election::{next, ProposerElection},
+    liveness::proposer_election::{next_in_range, ProposerElection},
 };
 use aptos_infallible::{Mutex, MutexGuard};
 use aptos_logger::prelude::*;
-use aptos_types::block_metadata::{new_block_event_key, NewBlockEvent};
+use aptos_types::{
+    block_metadata::{new_block_event_key, NewBlockEvent},
+    on_chain_config::StakeWeighting,
+};
 use consensus_types::common::{Author, Round};
 use std::{cmp::Ordering, collections::HashMap, convert::TryFrom, sync::Arc};
 use storage_interface::{DbReader, Order};
@@ -247,6 +250,11 @@ impl NewBlockEventAggregation {
         )
     }
 
+    /// Number of blocks in the voter window, i.e. of opportunities for each candidate to vote.
+    pub fn count_voter_window_blocks(&self, epoch: u64, history: &[NewBlockEvent]) -> u32 {
+        Self::history_iter(history, epoch, self.voter_window_size).count() as u32
+    }
+
     pub fn count_proposals(&self, epoch: u64, history: &[NewBlockEvent]) -> HashMap<Author, u32> {
         Self::history_iter(history, epoch, self.proposer_window_size).fold(
             HashMap::new(),
@@ -431,6 +439,103 @@ impl ReputationHeuristic for ProposerAndVoterHeuristic {
     }
 }
 
+/// Heuristic like `ProposerAndVoterHeuristic`, excluding the nodes failing their proposer rounds
+/// above the threshold, but rewarding the vote participation instead of any activity: an active
+/// node gets inactive_weight plus the share of (active_weight - inactive_weight) matching the
+/// share of the blocks of the voter window it voted for.
+///
+/// The weights are meant to be scaled by the voting power in `LeaderReputation`, so that the
+/// reputation of a node changes its odds relative to its stake.
+pub struct ParticipationHeuristic {
+    author: Author,
+    active_weight: u64,
+    inactive_weight: u64,
+    failed_weight: u64,
+    failure_threshold_percent: u32,
+    aggregation: NewBlockEventAggregation,
+}
+
+impl ParticipationHeuristic {
+    pub fn new(
+        author: Author,
+        active_weight: u64,
+        inactive_weight: u64,
+        failed_weight: u64,
+        failure_threshold_percent: u32,
+        voter_window_size: usize,
+        proposer_window_size: usize,
+    ) -> Self {
+        Self {
+            author,
+            active_weight,
+            inactive_weight,
+            failed_weight,
+            failure_threshold_percent,
+            aggregation: NewBlockEventAggregation::new(voter_window_size, proposer_window_size),
+        }
+    }
+}
+
+impl ReputationHeuristic for ParticipationHeuristic {
+    fn get_weights(
+        &self,
+        epoch: u64,
+        candidates: &[Author],
+        history: &[NewBlockEvent],
+    ) -> Vec<u64> {
+        let votes = self.aggregation.count_votes(epoch, candidates, history);
+        let voter_window_blocks = self.aggregation.count_voter_window_blocks(epoch, history);
+        let proposals = self.aggregation.count_proposals(epoch, history);
+        let failed_proposals = self
+            .aggregation
+            .count_failed_proposals(epoch, candidates, history);
+
+        COMMITTED_PROPOSALS_IN_WINDOW.set(*proposals.get(&self.author).unwrap_or(&0) as i64);
+        FAILED_PROPOSALS_IN_WINDOW.set(*failed_proposals.get(&self.author).unwrap_or(&0) as i64);
+        COMMITTED_VOTES_IN_WINDOW.set(*votes.get(&self.author).unwrap_or(&0) as i64);
+        LEADER_REPUTATION_HISTORY_SIZE.set(proposals.values().sum::<u32>() as i64);
+
+        let participation_weight = self.active_weight.saturating_sub(self.inactive_weight);
+        candidates
+            .iter()
+            .map(|author| {
+                let cur_votes = *votes.get(author).unwrap_or(&0);
+                let cur_proposals = *proposals.get(author).unwrap_or(&0);
+                let cur_failed_proposals = *failed_proposals.get(author).unwrap_or(&0);
+
+                if cur_failed_proposals * 100
+                    > (cur_proposals + cur_failed_proposals) * self.failure_threshold_percent
+                {
+                    self.failed_weight
+                } else if cur_votes > 0 {
+                    // votes are only counted within the voter window
+                    let participation = u128::from(participation_weight) * u128::from(cur_votes)
+                        / u128::from(voter_window_blocks);
+                    self.inactive_weight + participation as u64
+                } else {
+                    self.inactive_weight
+                }
+            })
+            .collect()
+    }
+}
+
+/// Integer square root, rounded down.
+pub(crate) fn sqrt(value: u64) -> u64 {
+    if value < 2 {
+        return value;
+    }
+    // Newton's method decreases monotonically to the root from any initial guess above it,
+    // ceil(value / 2) is below value for value >= 2 and doesn't overflow
+    let mut root = value;
+    let mut next = (value >> 1) + (value & 1);
+    while next < root {
+        root = next;
+        next = (root + value / root) >> 1;
+    }
+    root
+}
+
 /// Committed history based proposer election implementation that could help bias towards
 /// successful leaders to help improve performance.
 pub struct LeaderReputation {
@@ -439,12 +544,16 @@ pub struct LeaderReputation {
     backend: Box<dyn MetadataBackend>,
     heuristic: Box<dyn ReputationHeuristic>,
     exclude_round: u64,
+    // Scales the weight of each proposer from the heuristic
+    stake_weights: Vec<u64>,
 }
 
 impl LeaderReputation {
     pub fn new(
         epoch: u64,
         proposers: Vec<Author>,
+        voting_powers: Vec<u64>,
+        stake_weighting: StakeWeighting,
         backend: Box<dyn MetadataBackend>,
         heuristic: Box<dyn ReputationHeuristic>,
         exclude_round: u64,
@@ -455,6 +564,15 @@ impl LeaderReputation {
                 .map(|o| o != Ordering::Greater)
                 .unwrap_or(false)
         }));
+        assert_eq!(proposers.len(), voting_powers.len());
+        let stake_weights = voting_powers
+            .iter()
+            .map(|voting_power| match stake_weighting {
+                StakeWeighting::Uniform => 1,
+                StakeWeighting::Linear => *voting_power,
+                StakeWeighting::SquareRoot => sqrt(*voting_power),
+            })
+            .collect();
 
         Self {
             epoch,
@@ -462,6 +580,7 @@ impl LeaderReputation {
             backend,
             heuristic,
             exclude_round,
+            stake_weights,
         }
     }
 }
@@ -470,17 +589,22 @@ impl ProposerElection for LeaderReputation {
     fn get_valid_proposer(&self, round: Round) -> Author {
         let target_round = round.saturating_sub(self.exclude_round);
         let sliding_window = self.backend.get_block_metadata(target_round);
-        let mut weights = self
+        let weights = self
             .heuristic
             .get_weights(self.epoch, &self.proposers, &sliding_window);
         assert_eq!(weights.len(), self.proposers.len());
-        let mut total_weight = 0;
-        for w in &mut weights {
-            total_weight += *w;
-            *w = total_weight;
-        }
+        // the product of a weight and a voting power doesn't fit in u64
+        let mut total_weight = 0u128;
+        let weights: Vec<u128> = weights
+            .iter()
+            .zip(self.stake_weights.iter())
+            .map(|(w, stake_weight)| {
+                total_weight += u128::from(*w) * u128::from(*stake_weight);
+                total_weight
+            })
+            .collect();
         let mut state = round.to_le_bytes().to_vec();
-        let chosen_weight = next(&mut state) % total_weight;
+        let chosen_weight = next_in_range(&mut state, total_weight);
         let chosen_index = weights
             .binary_search_by(|w| {
                 if *w <= chosen_weight {
@@ -5,8 +5,8 @@ use std::{collections::HashMap, sync::Arc};
 
 use crate::liveness::{
     leader_reputation::{
-        ActiveInactiveHeuristic, LeaderReputation, MetadataBackend, NewBlockEventAggregation,
-        ReputationHeuristic,
+        sqrt, ActiveInactiveHeuristic, LeaderReputation, MetadataBackend, NewBlockEventAggregation,
+        ParticipationHeuristic, ReputationHeuristic,
     },
     proposer_election::{next, ProposerElection},
 };
@@ -18,6 +18,7 @@ use aptos_types::{
     block_metadata::{new_block_event_key, NewBlockEvent},
     contract_event::{ContractEvent, EventWithVersion},
     event::EventKey,
+    on_chain_config::StakeWeighting,
     transaction::Version,
     validator_signer::ValidatorSigner,
 };
@@ -278,6 +279,33 @@ fn test_proposer_and_voter_heuristic() {
     );
 }
 
+#[test]
+fn test_participation_heuristic() {
+    let mut example1 = Example1::new();
+    let validators = example1.validators.clone();
+    let heuristic = ParticipationHeuristic::new(validators[0], 100, 10, 1, 49, 2, 5);
+
+    example1.step1();
+    // validators[1] voted for 1 of the 2 blocks of the voter window
+    assert_eq!(
+        heuristic.get_weights(0, &validators, &example1.history),
+        vec![100, 55, 1, 1]
+    );
+
+    example1.step2();
+    // validators[3] proposed, but didn't vote within the voter window
+    assert_eq!(
+        heuristic.get_weights(0, &validators, &example1.history),
+        vec![100, 1, 1, 10]
+    );
+
+    example1.step3();
+    assert_eq!(
+        heuristic.get_weights(1, &validators, &example1.history),
+        vec![1, 100, 10, 10]
+    );
+}
+
 /// #### ActiveInactiveHeuristic tests ####
 
 #[test]
@@ -456,6 +484,8 @@ fn test_api() {
     let leader_reputation = LeaderReputation::new(
         0,
         proposers.clone(),
+        vec![1; proposers.len()],
+        StakeWeighting::Uniform,
         Box::new(MockHistory::new(1, history)),
         Box::new(ActiveInactiveHeuristic::new(
             proposers[0],
@@ -495,6 +525,74 @@ fn test_api() {
     assert!(!leader_reputation.is_valid_proposer(proposers[unexpected_index], 42));
 }
 
+fn count_first_proposer(
+    voting_powers: Vec<u64>,
+    stake_weighting: StakeWeighting,
+    num_rounds: u64,
+) -> u64 {
+    let proposers: Vec<AccountAddress> = (0..voting_powers.len())
+        .map(|_| AccountAddress::random())
+        .sorted()
+        .collect();
+    let leader_reputation = LeaderReputation::new(
+        0,
+        proposers.clone(),
+        voting_powers,
+        stake_weighting,
+        Box::new(MockHistory::new(1, vec![])),
+        Box::new(ActiveInactiveHeuristic::new(
+            proposers[0],
+            100,
+            1,
+            proposers.len(),
+        )),
+        4,
+    );
+    (0..num_rounds)
+        .filter(|round| leader_reputation.get_valid_proposer(*round) == proposers[0])
+        .count() as u64
+}
+
+#[test]
+fn test_stake_weighting() {
+    // the first proposer has 1/10 of the voting power, and 1/4 of its square root
+    assert!((400..600).contains(&count_first_proposer(
+        vec![1, 9],
+        StakeWeighting::Uniform,
+        1000
+    )));
+    assert!((50..150).contains(&count_first_proposer(
+        vec![1, 9],
+        StakeWeighting::Linear,
+        1000
+    )));
+    assert!((180..320).contains(&count_first_proposer(
+        vec![100, 900],
+        StakeWeighting::SquareRoot,
+        1000
+    )));
+    // proposers without voting power are never chosen
+    assert_eq!(
+        count_first_proposer(vec![0, 1, 1], StakeWeighting::Linear, 100),
+        0
+    );
+    // the total weight doesn't fit in u64
+    assert!((400..600).contains(&count_first_proposer(
+        vec![u64::max_value(), u64::max_value()],
+        StakeWeighting::Linear,
+        1000
+    )));
+}
+
+#[test]
+fn test_sqrt() {
+    let expected = [0, 1, 1, 1, 2, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 3, 4];
+    for (value, root) in expected.iter().enumerate() {
+        assert_eq!(sqrt(value as u64), *root, "sqrt({})", value);
+    }
+    assert_eq!(sqrt(u64::max_value()), u64::from(u32::max_value()));
+}
+
 struct MockDbReader {
     events: Mutex<Vec<EventWithVersion>>,
     random_address: Author,
@@ -2,20 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    block_storage::BlockReader, state_replication::PayloadManager, util::time_service::TimeService,
+    block_storage::BlockReader, counters, state_replication::PayloadManager,
+    util::time_service::TimeService,
 };
 use anyhow::{bail, ensure, format_err, Context};
+use aptos_config::config::PipelineBackpressureValues;
 use consensus_types::{
     block::Block,
     block_data::BlockData,
     common::{Author, Round},
+    proof_of_store::LogicalTime,
     quorum_cert::QuorumCert,
 };
 
 use aptos_infallible::Mutex;
 use consensus_types::common::{Payload, PayloadFilter};
 use futures::future::BoxFuture;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use super::{
     proposer_election::ProposerElection, unequivocal_proposer_election::UnequivocalProposerElection,
@@ -47,8 +50,13 @@ pub struct ProposalGenerator {
     time_service: Arc<dyn TimeService>,
     // Max number of transactions to be added to a proposed block.
     max_block_size: u64,
+    // Max size of the transactions to be added to a proposed block (in bytes).
+    max_block_bytes: u64,
     // Max number of failed authors to be added to a proposed block.
     max_failed_authors_to_store: usize,
+    // Smaller limits and delays for the proposals when the blocks are committed slower than they
+    // are ordered.
+    pipeline_backpressure: Vec<PipelineBackpressureValues>,
     // Last round that a proposal was generated
     last_round_generated: Mutex<Round>,
 }
@@ -60,7 +68,9 @@ impl ProposalGenerator {
         payload_manager: Arc<dyn PayloadManager>,
         time_service: Arc<dyn TimeService>,
         max_block_size: u64,
+        max_block_bytes: u64,
         max_failed_authors_to_store: usize,
+        pipeline_backpressure: Vec<PipelineBackpressureValues>,
     ) -> Self {
         Self {
             author,
@@ -68,7 +78,9 @@ impl ProposalGenerator {
             payload_manager,
             time_service,
             max_block_size,
+            max_block_bytes,
             max_failed_authors_to_store,
+            pipeline_backpressure,
             last_round_generated: Mutex::new(0),
         }
     }
@@ -143,21 +155,33 @@ impl ProposalGenerator {
                 .iter()
                 .any(|block| !block.payload().map_or(true, |txns| txns.is_empty()));
 
+            // Give the execution pipeline some slack before proposing when it's behind ordering.
+            let (max_block_size, max_block_bytes, proposal_delay) = self.pipeline_backpressure();
+            if !proposal_delay.is_zero() {
+                tokio::time::sleep(proposal_delay).await;
+            }
+
             // All proposed blocks in a branch are guaranteed to have increasing timestamps
             // since their predecessor block will not be added to the BlockStore until
             // the local time exceeds it.
             let timestamp = self.time_service.get_current_timestamp();
 
-            let payload = self
+            let mut payload = self
                 .payload_manager
                 .pull_payload(
-                    self.max_block_size,
+                    max_block_size,
+                    max_block_bytes,
                     payload_filter,
                     wait_callback,
                     pending_ordering,
                 )
                 .await
                 .context("Fail to retrieve payload")?;
+            // Batches expiring before this round can't be referenced by the proposal
+            if let Payload::InQuorumStore(proofs) = &mut payload {
+                let proposal_time = LogicalTime::new(hqc.certified_block().epoch(), round);
+                proofs.retain(|proof| proof.expiration() >= proposal_time);
+            }
 
             (payload, timestamp.as_micros() as u64)
         };
@@ -179,6 +203,38 @@ impl ProposalGenerator {
         ))
     }
 
+    /// Returns the max txns and bytes of the next proposal and the delay before pulling its
+    /// payload: the backpressure values with the highest threshold exceeded by the blocks ordered
+    /// but not committed yet apply, on top of the regular limits.
+    fn pipeline_backpressure(&self) -> (u64, u64, Duration) {
+        let pending_blocks = self
+            .block_store
+            .path_from_commit_root(self.block_store.ordered_root().id())
+            .map_or(0, |blocks| blocks.len()) as u64;
+        counters::PIPELINE_PENDING_BLOCKS.set(pending_blocks as i64);
+
+        let limits = match self
+            .pipeline_backpressure
+            .iter()
+            .filter(|values| pending_blocks > values.back_pressure_pending_blocks)
+            .max_by_key(|values| values.back_pressure_pending_blocks)
+        {
+            Some(values) => {
+                counters::PIPELINE_BACKPRESSURED_PROPOSALS_COUNT.inc();
+                (
+                    self.max_block_size.min(values.max_block_size_override),
+                    self.max_block_bytes.min(values.max_block_bytes_override),
+                    Duration::from_millis(values.proposal_delay_ms),
+                )
+            }
+            None => (self.max_block_size, self.max_block_bytes, Duration::ZERO),
+        };
+        counters::PIPELINE_BACKPRESSURE_MAX_BLOCK_SIZE.set(limits.0 as i64);
+        counters::PIPELINE_BACKPRESSURE_MAX_BLOCK_BYTES.set(limits.1 as i64);
+        counters::PIPELINE_BACKPRESSURE_PROPOSAL_DELAY_MS.set(limits.2.as_millis() as i64);
+        limits
+    }
+
     fn ensure_highest_quorum_cert(&self, round: Round) -> anyhow::Result<Arc<QuorumCert>> {
         let hqc = self.block_store.highest_quorum_cert();
         ensure!(
@@ -10,13 +10,15 @@ use crate::{
     test_utils::{build_empty_tree, MockPayloadManager, TreeInserter},
     util::mock_time_service::SimulatedTimeService,
 };
+use aptos_config::config::PipelineBackpressureValues;
 use aptos_types::validator_signer::ValidatorSigner;
 use consensus_types::{
     block::{block_test_utils::certificate_for_genesis, Block},
     common::Author,
+    executed_block::ExecutedBlock,
 };
 use futures::{future::BoxFuture, FutureExt};
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 fn empty_callback() -> BoxFuture<'static, ()> {
     async move {}.boxed()
@@ -32,7 +34,9 @@ async fn test_proposal_generation_empty_tree() {
         Arc::new(MockPayloadManager::new(None)),
         Arc::new(SimulatedTimeService::new()),
         1,
+        1024,
         10,
+        vec![],
     );
     let mut proposer_election =
         UnequivocalProposerElection::new(Box::new(RotatingProposer::new(vec![signer.author()], 1)));
@@ -67,7 +71,9 @@ async fn test_proposal_generation_parent() {
         Arc::new(MockPayloadManager::new(None)),
         Arc::new(SimulatedTimeService::new()),
         1,
+        1024,
         10,
+        vec![],
     );
     let mut proposer_election = UnequivocalProposerElection::new(Box::new(RotatingProposer::new(
         vec![inserter.signer().author()],
@@ -134,7 +140,9 @@ async fn test_old_proposal_generation() {
         Arc::new(MockPayloadManager::new(None)),
         Arc::new(SimulatedTimeService::new()),
         1,
+        1024,
         10,
+        vec![],
     );
     let mut proposer_election = UnequivocalProposerElection::new(Box::new(RotatingProposer::new(
         vec![inserter.signer().author()],
@@ -166,7 +174,9 @@ async fn test_correct_failed_authors() {
         Arc::new(MockPayloadManager::new(None)),
         Arc::new(SimulatedTimeService::new()),
         1,
+        1024,
         10,
+        vec![],
     );
     let mut proposer_election = UnequivocalProposerElection::new(Box::new(RotatingProposer::new(
         vec![author, peer1, peer2],
@@ -189,3 +199,67 @@ async fn test_correct_failed_authors() {
     assert_eq!(result.failed_authors().unwrap()[3], (4, peer1));
     assert_eq!(result.failed_authors().unwrap()[4], (5, peer2));
 }
+
+#[tokio::test]
+async fn test_pipeline_backpressure() {
+    let mut inserter = TreeInserter::default();
+    let block_store = inserter.block_store();
+    let proposal_generator = ProposalGenerator::new(
+        inserter.signer().author(),
+        block_store.clone(),
+        Arc::new(MockPayloadManager::new(None)),
+        Arc::new(SimulatedTimeService::new()),
+        100,
+        1024,
+        10,
+        vec![
+            PipelineBackpressureValues {
+                back_pressure_pending_blocks: 4,
+                max_block_size_override: 20,
+                max_block_bytes_override: 256,
+                proposal_delay_ms: 100,
+            },
+            PipelineBackpressureValues {
+                back_pressure_pending_blocks: 2,
+                max_block_size_override: 50,
+                max_block_bytes_override: 2048,
+                proposal_delay_ms: 0,
+            },
+        ],
+    );
+    let genesis = block_store.ordered_root();
+    let mut blocks = vec![genesis];
+    for round in 1..=6 {
+        let block = inserter
+            .insert_block(blocks.last().unwrap(), round, None)
+            .await;
+        blocks.push(block);
+    }
+    assert_eq!(
+        proposal_generator.pipeline_backpressure(),
+        (100, 1024, Duration::ZERO)
+    );
+
+    // The blocks are ordered, but never committed by the empty state computer.
+    let order_block = |block: &ExecutedBlock| {
+        inserter
+            .create_qc_for_block(block, Some(block.block_info()))
+            .ledger_info()
+            .clone()
+    };
+    block_store.commit(order_block(&blocks[2])).await.unwrap();
+    assert_eq!(
+        proposal_generator.pipeline_backpressure(),
+        (100, 1024, Duration::ZERO)
+    );
+    block_store.commit(order_block(&blocks[3])).await.unwrap();
+    assert_eq!(
+        proposal_generator.pipeline_backpressure(),
+        (50, 1024, Duration::ZERO)
+    );
+    block_store.commit(order_block(&blocks[5])).await.unwrap();
+    assert_eq!(
+        proposal_generator.pipeline_backpressure(),
+        (20, 256, Duration::from_millis(100))
+    );
+}
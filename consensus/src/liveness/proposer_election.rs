@@ -27,3 +27,13 @@ pub(crate) fn next(state: &mut Vec<u8>) -> u64 {
     // return state[0..8]
     u64::from_le_bytes(temp)
 }
+
+// next_in_range continuously mutates a state and returns a u128-index in [0, max),
+// consuming a single u64 when max fits in it
+pub(crate) fn next_in_range(state: &mut Vec<u8>, max: u128) -> u128 {
+    if max <= u64::max_value() as u128 {
+        return next(state) as u128 % max;
+    }
+    let high = next(state) as u128;
+    ((high << 64) | next(state) as u128) % max
+}
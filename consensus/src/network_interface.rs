@@ -14,6 +14,7 @@ use consensus_types::{
     block_retrieval::{BlockRetrievalRequest, BlockRetrievalResponse},
     epoch_retrieval::EpochRetrievalRequest,
     experimental::{commit_decision::CommitDecision, commit_vote::CommitVote},
+    proof_of_store::{Batch, BatchRequest, ProofOfStore, SignedDigest},
     proposal_msg::ProposalMsg,
     sync_info::SyncInfo,
     vote_msg::VoteMsg,
@@ -62,6 +63,16 @@ pub enum ConsensusMsg {
     /// than 2f + 1 signatures on the commit proposal. This part is not on the critical path, but
     /// it can save slow machines to quickly confirm the execution result.
     CommitDecisionMsg(Box<CommitDecision>),
+    /// Batch of transactions broadcast by the quorum store of its source, also the response to
+    /// a BatchRequestMsg.
+    BatchMsg(Box<Batch>),
+    /// SignedDigest is sent back to the source of a batch by the validators storing the batch.
+    SignedDigestMsg(Box<SignedDigest>),
+    /// ProofOfStore is broadcast by the source of a batch after collecting no fewer than 2f + 1
+    /// signed digests, proposals then reference the batch by its proof.
+    ProofOfStoreMsg(Box<ProofOfStore>),
+    /// RPC to get a batch missing locally from one of the signers of its proof.
+    BatchRequestMsg(Box<BatchRequest>),
 }
 
 /// The interface from Network to Consensus layer.
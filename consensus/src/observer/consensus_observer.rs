@@ -0,0 +1,535 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Follows consensus on a fullnode: the blocks ordered by the validators are executed as soon
+//! as they're received from the subscribed publisher, and committed with the commit decisions
+//! of the validators. State sync is only used when the observer falls behind, or when it stops
+//! committing altogether (e.g., there's no publisher to follow).
+
+use crate::{
+    commit_notifier::CommitNotifier,
+    counters,
+    error::QuorumStoreError,
+    observer::network::{
+        ObserverMessage, ObserverMultiNetworkSender, ObserverNetworkEvents, OrderedBlock,
+    },
+    state_replication::{PayloadManager, StateComputer},
+};
+use anyhow::anyhow;
+use aptos_config::{
+    config::ConsensusObserverConfig,
+    network_id::{NetworkId, PeerNetworkId},
+};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_types::{
+    epoch_change::{EpochChangeProof, Verifier},
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::SignedTransaction,
+};
+use consensus_types::{
+    block::Block,
+    common::{Payload, PayloadFilter, Round},
+    executed_block::ExecutedBlock,
+    experimental::commit_decision::CommitDecision,
+    request_response::ConsensusRequest,
+};
+use futures::{channel::mpsc, future::BoxFuture, stream::select_all, StreamExt};
+use netcore::transport::ConnectionOrigin;
+use network::{
+    application::storage::PeerMetadataStorage,
+    protocols::{network::Event, wire::handshake::v1::ProtocolId},
+};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use storage_interface::DbReader;
+
+#[cfg(test)]
+#[path = "consensus_observer_test.rs"]
+mod consensus_observer_test;
+
+/// Resolves the payloads of the blocks executed by the observer into the transactions sent by
+/// the publisher along with the blocks.
+#[derive(Default)]
+struct ObserverPayloadManager {
+    transactions: Mutex<HashMap<HashValue, Vec<SignedTransaction>>>,
+}
+
+impl ObserverPayloadManager {
+    fn insert(&self, block_id: HashValue, transactions: Vec<SignedTransaction>) {
+        self.transactions.lock().insert(block_id, transactions);
+    }
+
+    fn remove(&self, block_ids: impl Iterator<Item = HashValue>) {
+        let mut transactions = self.transactions.lock();
+        for block_id in block_ids {
+            transactions.remove(&block_id);
+        }
+    }
+
+    fn clear(&self) {
+        self.transactions.lock().clear();
+    }
+}
+
+#[async_trait::async_trait]
+impl PayloadManager for ObserverPayloadManager {
+    async fn pull_payload(
+        &self,
+        _max_size: u64,
+        _max_bytes: u64,
+        _exclude: PayloadFilter,
+        _wait_callback: BoxFuture<'static, ()>,
+        _pending_ordering: bool,
+    ) -> Result<Payload, QuorumStoreError> {
+        Err(anyhow!("The consensus observer doesn't propose blocks").into())
+    }
+
+    async fn get_transactions(
+        &self,
+        block: &Block,
+    ) -> Result<Vec<SignedTransaction>, QuorumStoreError> {
+        if block.payload().is_none() {
+            return Ok(vec![]);
+        }
+        self.transactions
+            .lock()
+            .get(&block.id())
+            .cloned()
+            .ok_or_else(|| anyhow!("Transactions of block {} not received", block.id()).into())
+    }
+}
+
+/// The observer has no quorum store to clean up on commit.
+pub struct ObserverCommitNotifier;
+
+#[async_trait::async_trait]
+impl CommitNotifier for ObserverCommitNotifier {
+    async fn notify_commit(
+        &self,
+        _epoch: u64,
+        _round: Round,
+        _payloads: Vec<Payload>,
+    ) -> Result<(), QuorumStoreError> {
+        Ok(())
+    }
+
+    fn new_epoch(&self, _quorum_store_commit_sender: mpsc::Sender<ConsensusRequest>) {}
+}
+
+struct Subscription {
+    peer: PeerNetworkId,
+    last_message: Instant,
+}
+
+pub struct ConsensusObserver {
+    config: ConsensusObserverConfig,
+    network_sender: ObserverMultiNetworkSender,
+    peer_metadata_storage: Arc<PeerMetadataStorage>,
+    execution_proxy: Arc<dyn StateComputer>,
+    payload_manager: Arc<ObserverPayloadManager>,
+    db: Arc<dyn DbReader>,
+    epoch_state: EpochState,
+    // The latest committed ledger info.
+    root: LedgerInfoWithSignatures,
+    // Blocks executed on top of the root, in order, waiting for their commit decisions.
+    pending_blocks: Vec<Arc<ExecutedBlock>>,
+    subscription: Option<Subscription>,
+    // When the publisher was last asked for the epoch change proof.
+    last_epoch_request: Option<Instant>,
+}
+
+impl ConsensusObserver {
+    pub fn new(
+        config: ConsensusObserverConfig,
+        network_sender: ObserverMultiNetworkSender,
+        peer_metadata_storage: Arc<PeerMetadataStorage>,
+        execution_proxy: Arc<dyn StateComputer>,
+        db: Arc<dyn DbReader>,
+    ) -> Self {
+        let startup_info = db
+            .get_startup_info()
+            .expect("Failed to read the startup info")
+            .expect("The node should be bootstrapped");
+        let epoch_state = startup_info.get_epoch_state().clone();
+        let payload_manager = Arc::new(ObserverPayloadManager::default());
+        execution_proxy.new_epoch(&epoch_state, payload_manager.clone());
+        Self {
+            config,
+            network_sender,
+            peer_metadata_storage,
+            execution_proxy,
+            payload_manager,
+            db,
+            epoch_state,
+            root: startup_info.latest_ledger_info,
+            pending_blocks: vec![],
+            subscription: None,
+            last_epoch_request: None,
+        }
+    }
+
+    /// Id, epoch and round of the last block executed or committed.
+    fn last_block(&self) -> (HashValue, u64, Round) {
+        match self.pending_blocks.last() {
+            Some(block) => (block.id(), block.epoch(), block.round()),
+            // The first block of an epoch extends the genesis block of the epoch.
+            None if self.root.ledger_info().ends_epoch() => {
+                let genesis = Block::make_genesis_block_from_ledger_info(self.root.ledger_info());
+                (genesis.id(), genesis.epoch(), genesis.round())
+            }
+            None => {
+                let commit_info = self.root.commit_info();
+                (commit_info.id(), commit_info.epoch(), commit_info.round())
+            }
+        }
+    }
+
+    async fn process_ordered_block(&mut self, ordered_block: OrderedBlock) {
+        if ordered_block.epoch() != self.epoch_state.epoch {
+            if ordered_block.epoch() > self.epoch_state.epoch {
+                self.request_epoch_change_proof();
+            }
+            return;
+        }
+        let (last_block_id, last_epoch, last_round) = self.last_block();
+        let commit_info = ordered_block.commit_info();
+        if (commit_info.epoch(), commit_info.round()) <= (last_epoch, last_round) {
+            return;
+        }
+        if let Err(e) = ordered_block.verify(&self.epoch_state) {
+            warn!(error = ?e, "[Observer] Invalid ordered block");
+            return;
+        }
+        let parent_id = ordered_block.blocks()[0].block().parent_id();
+        if parent_id != last_block_id {
+            // Some blocks were missed, the commit decision will tell where to sync to.
+            debug!(
+                "[Observer] {} doesn't extend block {}, waiting for the commit decision",
+                ordered_block, last_block_id
+            );
+            return;
+        }
+        if self.pending_blocks.len() + ordered_block.blocks().len()
+            > self.config.max_pending_blocks as usize
+        {
+            warn!(
+                "[Observer] Too many blocks pending commit, dropping {}",
+                ordered_block
+            );
+            return;
+        }
+
+        for observed_block in ordered_block.into_blocks() {
+            let (block, transactions) = observed_block.into_inner();
+            let (parent_id, _, _) = self.last_block();
+            self.payload_manager.insert(block.id(), transactions);
+            match self.execution_proxy.compute(&block, parent_id).await {
+                Ok(compute_result) => self
+                    .pending_blocks
+                    .push(Arc::new(ExecutedBlock::new(block, compute_result))),
+                Err(e) => {
+                    error!(error = ?e, "[Observer] Failed to execute block {}", block.id());
+                    self.payload_manager.remove(std::iter::once(block.id()));
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn process_commit_decision(&mut self, commit_decision: CommitDecision) {
+        if commit_decision.epoch() != self.epoch_state.epoch {
+            if commit_decision.epoch() > self.epoch_state.epoch {
+                self.request_epoch_change_proof();
+            }
+            return;
+        }
+        let commit_proof = commit_decision.ledger_info();
+        let commit_info = commit_proof.commit_info();
+        let root_info = self.root.commit_info();
+        if (commit_info.epoch(), commit_info.round()) <= (root_info.epoch(), root_info.round()) {
+            return;
+        }
+        if let Err(e) = self.epoch_state.verify(commit_proof) {
+            warn!(error = ?e, "[Observer] Invalid commit decision");
+            return;
+        }
+
+        let executed_index = self.pending_blocks.iter().position(|block| {
+            block.id() == commit_info.id()
+                && block.compute_result().root_hash() == commit_info.executed_state_id()
+                && block.compute_result().version() == commit_info.version()
+        });
+        match executed_index {
+            Some(index) => self.commit(index, commit_proof.clone()).await,
+            None => self.sync_to(commit_proof.clone()).await,
+        }
+    }
+
+    async fn process_epoch_change_proof(&mut self, proof: EpochChangeProof) {
+        match proof.verify(&self.epoch_state) {
+            Ok(ledger_info) => self.sync_to(ledger_info.clone()).await,
+            Err(e) => warn!(error = ?e, "[Observer] Invalid epoch change proof"),
+        }
+    }
+
+    /// Commits the pending blocks up to (including) the one at the given index.
+    async fn commit(&mut self, index: usize, commit_proof: LedgerInfoWithSignatures) {
+        let blocks: Vec<_> = self.pending_blocks.drain(..=index).collect();
+        if let Err(e) = self
+            .execution_proxy
+            .commit(
+                &blocks,
+                commit_proof.clone(),
+                Box::new(|_: &[Arc<ExecutedBlock>], _: LedgerInfoWithSignatures| {}),
+            )
+            .await
+        {
+            error!(error = ?e, "[Observer] Failed to commit {}", commit_proof.commit_info());
+            self.sync_to(commit_proof).await;
+            return;
+        }
+        self.payload_manager
+            .remove(blocks.iter().map(|block| block.id()));
+        self.update_root(commit_proof);
+    }
+
+    /// Drops the pending blocks and syncs to the target, the blocks are re-executed from there.
+    async fn sync_to(&mut self, target: LedgerInfoWithSignatures) {
+        // State sync keeps syncing the node while the observer is stalled, so the storage may
+        // already be past the target.
+        match self.db.get_startup_info() {
+            Ok(Some(startup_info))
+                if startup_info.latest_ledger_info.ledger_info().version()
+                    >= target.ledger_info().version() =>
+            {
+                return self
+                    .catch_up_with_storage(
+                        startup_info.get_epoch_state().clone(),
+                        startup_info.latest_ledger_info,
+                    )
+                    .await;
+            }
+            Ok(_) => (),
+            Err(e) => warn!(error = ?e, "[Observer] Failed to read the startup info"),
+        }
+        info!(
+            "[Observer] Falling back to state sync to {}",
+            target.commit_info()
+        );
+        counters::CONSENSUS_OBSERVER_STATE_SYNC_FALLBACKS.inc();
+        self.pending_blocks.clear();
+        self.payload_manager.clear();
+        if let Err(e) = self.execution_proxy.sync_to(target.clone()).await {
+            error!(error = ?e, "[Observer] Failed to sync to {}", target.commit_info());
+            return;
+        }
+        self.update_root(target);
+    }
+
+    /// Drops the pending blocks and restarts from the latest ledger info in storage.
+    async fn catch_up_with_storage(
+        &mut self,
+        epoch_state: EpochState,
+        latest_ledger_info: LedgerInfoWithSignatures,
+    ) {
+        info!(
+            "[Observer] Catching up with the storage at {}",
+            latest_ledger_info.commit_info()
+        );
+        self.pending_blocks.clear();
+        self.payload_manager.clear();
+        // Syncing to the latest ledger info in storage only resets the executor.
+        if let Err(e) = self
+            .execution_proxy
+            .sync_to(latest_ledger_info.clone())
+            .await
+        {
+            error!(
+                error = ?e,
+                "[Observer] Failed to catch up with {}",
+                latest_ledger_info.commit_info()
+            );
+            return;
+        }
+        if epoch_state.epoch != self.epoch_state.epoch {
+            info!("[Observer] New epoch {}", epoch_state);
+            self.epoch_state = epoch_state;
+            self.execution_proxy
+                .new_epoch(&self.epoch_state, self.payload_manager.clone());
+        }
+        counters::CONSENSUS_OBSERVER_COMMITTED_ROUND
+            .set(latest_ledger_info.commit_info().round() as i64);
+        self.root = latest_ledger_info;
+    }
+
+    fn update_root(&mut self, root: LedgerInfoWithSignatures) {
+        counters::CONSENSUS_OBSERVER_COMMITTED_ROUND.set(root.commit_info().round() as i64);
+        if let Some(next_epoch_state) = root.ledger_info().next_epoch_state() {
+            info!("[Observer] New epoch {}", next_epoch_state);
+            self.epoch_state = next_epoch_state.clone();
+            // The reconfiguration suffix is dropped, the new epoch starts from the root.
+            self.pending_blocks.clear();
+            self.payload_manager.clear();
+            self.execution_proxy
+                .new_epoch(&self.epoch_state, self.payload_manager.clone());
+        }
+        self.root = root;
+    }
+
+    /// Asks the publisher for the proof of the epoch changes since the epoch of the observer,
+    /// at most once per progress check interval.
+    fn request_epoch_change_proof(&mut self) {
+        let interval = Duration::from_millis(self.config.progress_check_interval_ms);
+        if matches!(self.last_epoch_request, Some(last) if last.elapsed() < interval) {
+            return;
+        }
+        if let Some(subscription) = &self.subscription {
+            self.last_epoch_request = Some(Instant::now());
+            self.send_to(
+                subscription.peer,
+                ObserverMessage::Subscribe {
+                    epoch: self.epoch_state.epoch,
+                },
+            );
+        }
+    }
+
+    fn send_to(&self, peer: PeerNetworkId, message: ObserverMessage) {
+        if let Err(e) = self.network_sender.send_to(peer, message) {
+            warn!(error = ?e, "[Observer] Failed to send message to {}", peer);
+        }
+    }
+
+    /// Subscribes to another publisher if the current one is unavailable or has been silent
+    /// for too long.
+    fn check_progress(&mut self) {
+        let timeout = Duration::from_millis(self.config.subscription_timeout_ms);
+        let current_peer = match &self.subscription {
+            Some(subscription)
+                if subscription.last_message.elapsed() < timeout
+                    && self.is_connected(subscription.peer) =>
+            {
+                return;
+            }
+            Some(subscription) => Some(subscription.peer),
+            None => None,
+        };
+
+        let publishers: Vec<_> = self
+            .peer_metadata_storage
+            .networks()
+            .flat_map(|network_id| {
+                self.peer_metadata_storage
+                    .read_filtered(network_id, |(_, peer_info)| {
+                        peer_info.is_connected()
+                            && peer_info.active_connection.origin == ConnectionOrigin::Outbound
+                            && peer_info.supports_protocol(ProtocolId::ConsensusObserverDirectSend)
+                    })
+                    .into_keys()
+            })
+            .collect();
+        // Rotate to another publisher, or retry the current one if there's no other.
+        let peer = match publishers
+            .iter()
+            .find(|peer| Some(**peer) != current_peer)
+            .or_else(|| publishers.first())
+        {
+            Some(peer) => *peer,
+            None => {
+                warn!("[Observer] No publisher to subscribe to");
+                return;
+            }
+        };
+
+        if let Some(current_peer) = current_peer.filter(|current_peer| *current_peer != peer) {
+            self.send_to(current_peer, ObserverMessage::Unsubscribe);
+        }
+        info!("[Observer] Subscribing to {}", peer);
+        self.send_to(
+            peer,
+            ObserverMessage::Subscribe {
+                epoch: self.epoch_state.epoch,
+            },
+        );
+        self.subscription = Some(Subscription {
+            peer,
+            last_message: Instant::now(),
+        });
+    }
+
+    fn is_connected(&self, peer: PeerNetworkId) -> bool {
+        self.peer_metadata_storage
+            .read(peer)
+            .map_or(false, |peer_info| peer_info.is_connected())
+    }
+
+    async fn process_network_event(
+        &mut self,
+        network_id: NetworkId,
+        event: Event<ObserverMessage>,
+    ) {
+        let (peer_id, message) = match event {
+            Event::Message(peer_id, message) => (peer_id, message),
+            _ => return,
+        };
+        let peer = PeerNetworkId::new(network_id, peer_id);
+        match &mut self.subscription {
+            Some(subscription) if subscription.peer == peer => {
+                subscription.last_message = Instant::now();
+            }
+            _ => {
+                // Either subscribed to another peer or the messages are not meant for us.
+                if !matches!(
+                    message,
+                    ObserverMessage::Subscribe { .. } | ObserverMessage::Unsubscribe
+                ) {
+                    self.send_to(peer, ObserverMessage::Unsubscribe);
+                }
+                return;
+            }
+        }
+        match message {
+            ObserverMessage::OrderedBlock(ordered_block) => {
+                self.process_ordered_block(*ordered_block).await
+            }
+            ObserverMessage::CommitDecision(commit_decision) => {
+                self.process_commit_decision(*commit_decision).await
+            }
+            ObserverMessage::EpochChangeProof(proof) => {
+                self.process_epoch_change_proof(*proof).await
+            }
+            ObserverMessage::Subscribe { .. } | ObserverMessage::Unsubscribe => {
+                debug!("[Observer] Ignoring subscription of {}", peer);
+            }
+        }
+    }
+
+    pub async fn start(mut self, network_events: Vec<(NetworkId, ObserverNetworkEvents)>) {
+        info!("Consensus observer starts at {}", self.root.commit_info());
+        // Combine `NetworkEvents` for each `NetworkId` into one stream
+        let mut events = select_all(
+            network_events
+                .into_iter()
+                .map(|(network_id, events)| events.map(move |e| (network_id, e))),
+        );
+        let mut progress_check_interval = tokio::time::interval(Duration::from_millis(
+            self.config.progress_check_interval_ms,
+        ));
+        loop {
+            tokio::select! {
+                Some((network_id, event)) = events.next() => {
+                    self.process_network_event(network_id, event).await;
+                }
+                _ = progress_check_interval.tick() => {
+                    self.check_progress();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    observer::{
+        consensus_observer::ConsensusObserver,
+        network::{ObservedBlock, ObserverMultiNetworkSender, OrderedBlock},
+        tests::ordered_chain,
+    },
+    test_utils::{MockStateComputer, MockStorage},
+};
+use aptos_config::{config::ConsensusObserverConfig, network_id::NetworkId};
+use aptos_crypto::{hash::ACCUMULATOR_PLACEHOLDER_HASH, HashValue};
+use aptos_infallible::Mutex;
+use aptos_types::{
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    on_chain_config::ValidatorSet,
+    transaction::{SignedTransaction, Version},
+    validator_signer::ValidatorSigner,
+    validator_verifier::random_validator_verifier,
+};
+use consensus_types::{
+    block::{block_test_utils::certificate_for_genesis, Block},
+    experimental::commit_decision::CommitDecision,
+};
+use futures::{channel::mpsc, StreamExt};
+use network::application::storage::PeerMetadataStorage;
+use std::{collections::HashMap, sync::Arc};
+use storage_interface::{DbReader, StartupInfo, TreeState};
+
+/// Storage of the observer, synced by state sync behind the back of the observer.
+struct MockDbReader {
+    startup_info: Mutex<StartupInfo>,
+}
+
+impl DbReader for MockDbReader {
+    fn get_startup_info(&self) -> anyhow::Result<Option<StartupInfo>> {
+        Ok(Some(self.startup_info.lock().clone()))
+    }
+}
+
+struct TestObserver {
+    observer: ConsensusObserver,
+    signers: Vec<ValidatorSigner>,
+    db: Arc<MockDbReader>,
+    // The transactions of the blocks committed by the observer.
+    committed_txns: mpsc::UnboundedReceiver<Vec<SignedTransaction>>,
+    // The ledger infos committed or synced to by the observer.
+    ledger_infos: mpsc::UnboundedReceiver<LedgerInfoWithSignatures>,
+}
+
+impl TestObserver {
+    /// Starts an observer at the genesis block of epoch 1.
+    fn new() -> Self {
+        let (signers, verifier) = random_validator_verifier(4, None, false);
+        let epoch_state = EpochState { epoch: 1, verifier };
+        let root = LedgerInfoWithSignatures::new(
+            LedgerInfo::new(
+                certificate_for_genesis().certified_block().clone(),
+                HashValue::zero(),
+            ),
+            Default::default(),
+        );
+        let db = Arc::new(MockDbReader {
+            startup_info: Mutex::new(StartupInfo::new(
+                root,
+                Some(epoch_state.clone()),
+                TreeState::new_empty(),
+                None,
+            )),
+        });
+        let (committed_txns_tx, committed_txns) = mpsc::unbounded();
+        let (ledger_infos_tx, ledger_infos) = mpsc::unbounded();
+        let (_, storage) = MockStorage::start_for_testing(ValidatorSet::empty());
+        let observer = ConsensusObserver::new(
+            ConsensusObserverConfig::default(),
+            ObserverMultiNetworkSender::new(HashMap::new()),
+            PeerMetadataStorage::new(&[NetworkId::Public]),
+            Arc::new(MockStateComputer::new(
+                committed_txns_tx,
+                ledger_infos_tx,
+                storage,
+            )),
+            db.clone(),
+        );
+        Self {
+            observer,
+            signers,
+            db,
+            committed_txns,
+            ledger_infos,
+        }
+    }
+
+    /// Executes a chain of `num_blocks` blocks extending the genesis block.
+    async fn execute_ordered_chain(&mut self, num_blocks: u64) -> Vec<ObservedBlock> {
+        let (blocks, ordered_proof) = ordered_chain(&self.signers, num_blocks);
+        self.observer
+            .process_ordered_block(OrderedBlock::new(blocks.clone(), ordered_proof))
+            .await;
+        assert_eq!(self.observer.pending_blocks.len(), num_blocks as usize);
+        blocks
+    }
+}
+
+/// Returns a ledger info signed by the signers, committing the block at the version.
+fn commit_proof(
+    signers: &[ValidatorSigner],
+    block: &Block,
+    version: Version,
+) -> LedgerInfoWithSignatures {
+    let ledger_info = LedgerInfo::new(
+        block.gen_block_info(*ACCUMULATOR_PLACEHOLDER_HASH, version, None),
+        HashValue::zero(),
+    );
+    let signatures = signers
+        .iter()
+        .map(|signer| (signer.author(), signer.sign(&ledger_info)))
+        .collect();
+    LedgerInfoWithSignatures::new(ledger_info, signatures)
+}
+
+#[tokio::test]
+async fn test_commit_pending_blocks() {
+    let mut t = TestObserver::new();
+    let blocks = t.execute_ordered_chain(3).await;
+
+    // The mock state computer executes the blocks at version 0.
+    let ledger_info = commit_proof(&t.signers, blocks[1].block(), 0);
+    t.observer
+        .process_commit_decision(CommitDecision::new(ledger_info.clone()))
+        .await;
+    let committed_txns: Vec<_> = blocks[..2]
+        .iter()
+        .flat_map(|block| block.clone().into_inner().1)
+        .collect();
+    assert_eq!(t.committed_txns.next().await.unwrap(), committed_txns);
+    assert_eq!(t.ledger_infos.next().await.unwrap(), ledger_info);
+    assert_eq!(t.observer.root, ledger_info);
+    assert_eq!(t.observer.pending_blocks.len(), 1);
+    assert_eq!(t.observer.pending_blocks[0].id(), blocks[2].block().id());
+
+    // Commit decisions not signed by the validators are ignored.
+    let (other_signers, _) = random_validator_verifier(4, None, false);
+    t.observer
+        .process_commit_decision(CommitDecision::new(commit_proof(
+            &other_signers,
+            blocks[2].block(),
+            0,
+        )))
+        .await;
+    assert_eq!(t.observer.root, ledger_info);
+    assert_eq!(t.observer.pending_blocks.len(), 1);
+}
+
+#[tokio::test]
+async fn test_commit_decision_falls_back_to_state_sync() {
+    let mut t = TestObserver::new();
+    let blocks = t.execute_ordered_chain(3).await;
+
+    // The validators executed the block to another version than the observer.
+    let ledger_info = commit_proof(&t.signers, blocks[2].block(), 10);
+    t.observer
+        .process_commit_decision(CommitDecision::new(ledger_info.clone()))
+        .await;
+    // Synced to, not committed.
+    assert_eq!(t.ledger_infos.next().await.unwrap(), ledger_info);
+    assert!(t.committed_txns.try_next().is_err());
+    assert_eq!(t.observer.root, ledger_info);
+    assert!(t.observer.pending_blocks.is_empty());
+}
+
+#[tokio::test]
+async fn test_commit_decision_catches_up_with_storage() {
+    let mut t = TestObserver::new();
+    let blocks = t.execute_ordered_chain(3).await;
+
+    // State sync took the storage past the commit decision while the observer was stalled.
+    let storage_ledger_info = commit_proof(&t.signers, blocks[2].block(), 20);
+    t.db.startup_info.lock().latest_ledger_info = storage_ledger_info.clone();
+    t.observer
+        .process_commit_decision(CommitDecision::new(commit_proof(
+            &t.signers,
+            blocks[1].block(),
+            10,
+        )))
+        .await;
+    // The executor is reset to the storage instead of syncing to the commit decision.
+    assert_eq!(t.ledger_infos.next().await.unwrap(), storage_ledger_info);
+    assert!(t.committed_txns.try_next().is_err());
+    assert_eq!(t.observer.root, storage_ledger_info);
+    assert!(t.observer.pending_blocks.is_empty());
+}
@@ -0,0 +1,14 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The consensus observer lets fullnodes follow the blocks ordered by the validators instead of
+//! waiting for them to be committed and synced. Validators publish the ordered blocks and the
+//! commit decisions to the subscribed observers, which verify, execute and commit them locally.
+
+pub(crate) mod consensus_observer;
+pub(crate) mod network;
+pub(crate) mod publisher;
+#[cfg(test)]
+mod tests;
+
+pub use network::{network_endpoint_config, ObserverNetworkEvents, ObserverNetworkSender};
@@ -0,0 +1,226 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Messages exchanged between the consensus publishers and observers.
+
+use crate::counters;
+use anyhow::{ensure, Context};
+use aptos_types::{
+    block_info::BlockInfo,
+    epoch_change::{EpochChangeProof, Verifier},
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::SignedTransaction,
+    PeerId,
+};
+use async_trait::async_trait;
+use channel::{aptos_channel, message_queues::QueueStyle};
+use consensus_types::{block::Block, experimental::commit_decision::CommitDecision};
+use network::{
+    application::interface::MultiNetworkSender,
+    error::NetworkError,
+    peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
+    protocols::network::{
+        AppConfig, ApplicationNetworkSender, NetworkEvents, NetworkSender, NewNetworkSender,
+        RpcError,
+    },
+    ProtocolId,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter},
+    time::Duration,
+};
+
+/// Network type for the consensus observer
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum ObserverMessage {
+    /// Subscribes to the ordered blocks and the commit decisions of the publisher, the observer
+    /// gets the proof of the later epochs if it's behind the given epoch.
+    Subscribe { epoch: u64 },
+    /// Stops the messages of the publisher.
+    Unsubscribe,
+    /// Blocks ordered by consensus, along with their transactions.
+    OrderedBlock(Box<OrderedBlock>),
+    /// Ledger info committing the executed ordered blocks.
+    CommitDecision(Box<CommitDecision>),
+    /// Epoch ending ledger infos from the epoch of the observer.
+    EpochChangeProof(Box<EpochChangeProof>),
+}
+
+/// A block along with its transactions, so that observers don't need to fetch the batches
+/// referenced by the block from the quorum store.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ObservedBlock {
+    block: Block,
+    transactions: Vec<SignedTransaction>,
+}
+
+impl ObservedBlock {
+    pub fn new(block: Block, transactions: Vec<SignedTransaction>) -> Self {
+        Self {
+            block,
+            transactions,
+        }
+    }
+
+    pub fn block(&self) -> &Block {
+        &self.block
+    }
+
+    pub fn into_inner(self) -> (Block, Vec<SignedTransaction>) {
+        (self.block, self.transactions)
+    }
+}
+
+/// Chain of blocks ordered by the proof, the last block is the one certified by the proof.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct OrderedBlock {
+    blocks: Vec<ObservedBlock>,
+    ordered_proof: LedgerInfoWithSignatures,
+}
+
+impl OrderedBlock {
+    pub fn new(blocks: Vec<ObservedBlock>, ordered_proof: LedgerInfoWithSignatures) -> Self {
+        Self {
+            blocks,
+            ordered_proof,
+        }
+    }
+
+    pub fn blocks(&self) -> &[ObservedBlock] {
+        &self.blocks
+    }
+
+    pub fn into_blocks(self) -> Vec<ObservedBlock> {
+        self.blocks
+    }
+
+    pub fn ordered_proof(&self) -> &LedgerInfoWithSignatures {
+        &self.ordered_proof
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.ordered_proof.ledger_info().epoch()
+    }
+
+    /// The block certified by the proof, i.e. the last block of the chain.
+    pub fn commit_info(&self) -> &BlockInfo {
+        self.ordered_proof.commit_info()
+    }
+
+    /// Verifies the proof against the validators of the epoch, and that the blocks and their
+    /// transactions are the ones certified by the proof.
+    pub fn verify(&self, epoch_state: &EpochState) -> anyhow::Result<()> {
+        ensure!(!self.blocks.is_empty(), "No blocks ordered by {}", self);
+        epoch_state
+            .verify(&self.ordered_proof)
+            .context("Failed to verify the ordered proof")?;
+        let last_block = self.blocks.last().expect("Blocks are not empty");
+        ensure!(
+            last_block.block.id() == self.commit_info().id(),
+            "Last block {} isn't the one ordered by {}",
+            last_block.block.id(),
+            self
+        );
+        for (parent, child) in self.blocks.iter().zip(self.blocks.iter().skip(1)) {
+            ensure!(
+                child.block.parent_id() == parent.block.id(),
+                "Block {} isn't the parent of {}",
+                parent.block.id(),
+                child.block.id()
+            );
+        }
+        for observed_block in self.blocks.iter() {
+            let block = &observed_block.block;
+            ensure!(
+                block.epoch() == epoch_state.epoch,
+                "Block {} is not in epoch {}",
+                block.id(),
+                epoch_state.epoch
+            );
+            match block.payload() {
+                Some(payload) => payload
+                    .verify_transactions(&observed_block.transactions)
+                    .with_context(|| format!("Invalid transactions for block {}", block.id()))?,
+                None => ensure!(
+                    observed_block.transactions.is_empty(),
+                    "Block {} carries no transactions",
+                    block.id()
+                ),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for OrderedBlock {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "OrderedBlock: [blocks: {}, {}]",
+            self.blocks.len(),
+            self.ordered_proof.commit_info()
+        )
+    }
+}
+
+/// The interface from the network layer to the consensus observer and publisher.
+pub type ObserverNetworkEvents = NetworkEvents<ObserverMessage>;
+
+/// Sends the messages to the peers of any network the consensus observer is registered on.
+pub type ObserverMultiNetworkSender = MultiNetworkSender<ObserverMessage, ObserverNetworkSender>;
+
+/// The interface from the consensus observer to the network layer, the messages are only sent
+/// to the directly connected peers.
+#[derive(Clone)]
+pub struct ObserverNetworkSender {
+    inner: NetworkSender<ObserverMessage>,
+}
+
+/// Configuration for the network endpoints of the consensus observer.
+pub fn network_endpoint_config(max_network_channel_size: u64) -> AppConfig {
+    AppConfig::p2p(
+        [ProtocolId::ConsensusObserverDirectSend],
+        aptos_channel::Config::new(max_network_channel_size as usize)
+            .queue_style(QueueStyle::FIFO)
+            .counters(&counters::PENDING_CONSENSUS_OBSERVER_NETWORK_EVENTS),
+    )
+}
+
+impl NewNetworkSender for ObserverNetworkSender {
+    fn new(
+        peer_mgr_reqs_tx: PeerManagerRequestSender,
+        connection_reqs_tx: ConnectionRequestSender,
+    ) -> Self {
+        Self {
+            inner: NetworkSender::new(peer_mgr_reqs_tx, connection_reqs_tx),
+        }
+    }
+}
+
+#[async_trait]
+impl ApplicationNetworkSender<ObserverMessage> for ObserverNetworkSender {
+    fn send_to(&self, recipient: PeerId, message: ObserverMessage) -> Result<(), NetworkError> {
+        self.inner
+            .send_to(recipient, ProtocolId::ConsensusObserverDirectSend, message)
+    }
+
+    fn send_to_many(
+        &self,
+        recipients: impl Iterator<Item = PeerId>,
+        message: ObserverMessage,
+    ) -> Result<(), NetworkError> {
+        self.inner
+            .send_to_many(recipients, ProtocolId::ConsensusObserverDirectSend, message)
+    }
+
+    async fn send_rpc(
+        &self,
+        _recipient: PeerId,
+        _req_msg: ObserverMessage,
+        _timeout: Duration,
+    ) -> Result<ObserverMessage, RpcError> {
+        unimplemented!("The consensus observer doesn't use rpcs")
+    }
+}
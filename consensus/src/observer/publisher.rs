@@ -0,0 +1,261 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Publishes the blocks ordered and committed by a validator to the subscribed observers.
+
+use crate::{
+    counters,
+    observer::network::{
+        ObservedBlock, ObserverMessage, ObserverMultiNetworkSender, ObserverNetworkEvents,
+        OrderedBlock,
+    },
+    state_replication::PayloadManager,
+};
+use anyhow::Context;
+use aptos_config::network_id::{NetworkId, PeerNetworkId};
+use aptos_logger::prelude::*;
+use aptos_types::ledger_info::LedgerInfoWithSignatures;
+use channel::aptos_channel;
+use consensus_types::{
+    block::Block, executed_block::ExecutedBlock, experimental::commit_decision::CommitDecision,
+};
+use futures::{
+    stream::{select_all, FuturesOrdered},
+    StreamExt,
+};
+use network::protocols::network::Event;
+use std::{collections::HashSet, sync::Arc, time::Duration};
+use storage_interface::DbReader;
+use tokio::time::timeout;
+
+/// Max time to resolve the transactions of all the blocks in an ordered block notification, they
+/// are usually available locally by the time the blocks are ordered.
+const GET_TRANSACTIONS_TIMEOUT_MS: u64 = 5_000;
+
+/// Max number of notifications resolved at the same time. Once reached, the notifications queue
+/// up in the channel, which drops them when full.
+const MAX_PENDING_NOTIFICATIONS: usize = 16;
+
+/// Notifications from the buffer manager to the publisher.
+pub enum PublisherNotification {
+    OrderedBlock {
+        blocks: Vec<Block>,
+        ordered_proof: LedgerInfoWithSignatures,
+        payload_manager: Arc<dyn PayloadManager>,
+    },
+    CommitDecision(CommitDecision),
+}
+
+/// The buffer manager's handle to the publisher for the current epoch. The notifications are
+/// dropped when the publisher falls behind, the subscribers then catch up with state sync.
+#[derive(Clone)]
+pub struct PublisherHandle {
+    sender: aptos_channel::Sender<(), PublisherNotification>,
+    payload_manager: Arc<dyn PayloadManager>,
+}
+
+impl PublisherHandle {
+    pub fn new(
+        sender: aptos_channel::Sender<(), PublisherNotification>,
+        payload_manager: Arc<dyn PayloadManager>,
+    ) -> Self {
+        Self {
+            sender,
+            payload_manager,
+        }
+    }
+
+    pub fn publish_ordered_blocks(
+        &self,
+        blocks: &[ExecutedBlock],
+        ordered_proof: &LedgerInfoWithSignatures,
+    ) {
+        self.notify(PublisherNotification::OrderedBlock {
+            blocks: blocks.iter().map(|b| b.block().clone()).collect(),
+            ordered_proof: ordered_proof.clone(),
+            payload_manager: self.payload_manager.clone(),
+        });
+    }
+
+    pub fn publish_commit_decision(&self, commit_proof: &LedgerInfoWithSignatures) {
+        self.notify(PublisherNotification::CommitDecision(CommitDecision::new(
+            commit_proof.clone(),
+        )));
+    }
+
+    fn notify(&self, notification: PublisherNotification) {
+        if let Err(e) = self.sender.push((), notification) {
+            warn!(error = ?e, "[Publisher] Failed to notify the publisher");
+        }
+    }
+}
+
+/// Forwards the ordered blocks, along with their transactions, and the commit decisions to the
+/// observers subscribed on any of the networks, and helps the observers lagging behind the
+/// epoch of the validator.
+pub struct Publisher {
+    network_sender: ObserverMultiNetworkSender,
+    db: Arc<dyn DbReader>,
+    subscribers: HashSet<PeerNetworkId>,
+}
+
+impl Publisher {
+    pub fn new(network_sender: ObserverMultiNetworkSender, db: Arc<dyn DbReader>) -> Self {
+        Self {
+            network_sender,
+            db,
+            subscribers: HashSet::new(),
+        }
+    }
+
+    fn publish(&self, message: ObserverMessage) {
+        if let Err(e) = self
+            .network_sender
+            .send_to_many(self.subscribers.iter().copied(), message)
+        {
+            warn!(error = ?e, "[Publisher] Failed to publish to the subscribers");
+        }
+    }
+
+    fn process_network_event(&mut self, network_id: NetworkId, event: Event<ObserverMessage>) {
+        match event {
+            Event::Message(peer_id, message) => {
+                let peer = PeerNetworkId::new(network_id, peer_id);
+                match message {
+                    ObserverMessage::Subscribe { epoch } => {
+                        info!("[Publisher] {} subscribed at epoch {}", peer, epoch);
+                        self.subscribers.insert(peer);
+                        if let Err(e) = self.send_epoch_change_proof(peer, epoch) {
+                            warn!(error = ?e, "[Publisher] Failed to help {} catch up", peer);
+                        }
+                    }
+                    ObserverMessage::Unsubscribe => {
+                        info!("[Publisher] {} unsubscribed", peer);
+                        self.subscribers.remove(&peer);
+                    }
+                    message => {
+                        warn!(
+                            "[Publisher] Unexpected message from {}: {:?}",
+                            peer, message
+                        );
+                    }
+                }
+            }
+            Event::LostPeer(metadata) => {
+                self.subscribers
+                    .remove(&PeerNetworkId::new(network_id, metadata.remote_peer_id));
+            }
+            _ => (),
+        }
+        counters::CONSENSUS_OBSERVER_SUBSCRIBERS.set(self.subscribers.len() as i64);
+    }
+
+    /// Sends the proof of the epoch changes since the epoch of the observer, if any.
+    fn send_epoch_change_proof(&self, peer: PeerNetworkId, epoch: u64) -> anyhow::Result<()> {
+        let latest_epoch = self
+            .db
+            .get_latest_ledger_info()?
+            .ledger_info()
+            .next_block_epoch();
+        if epoch >= latest_epoch {
+            return Ok(());
+        }
+        let proof = self
+            .db
+            .get_epoch_ending_ledger_infos(epoch, latest_epoch)
+            .context("Failed to get epoch proof")?;
+        self.network_sender
+            .send_to(peer, ObserverMessage::EpochChangeProof(Box::new(proof)))?;
+        Ok(())
+    }
+
+    pub async fn start(
+        mut self,
+        network_events: Vec<(NetworkId, ObserverNetworkEvents)>,
+        mut notifications: aptos_channel::Receiver<(), PublisherNotification>,
+    ) {
+        info!("Consensus publisher starts.");
+        // Combine `NetworkEvents` for each `NetworkId` into one stream
+        let mut events = select_all(
+            network_events
+                .into_iter()
+                .map(|(network_id, events)| events.map(move |e| (network_id, e))),
+        );
+        // The notifications are resolved into messages off the loop, so that getting the
+        // transactions doesn't hold up the subscriptions, and are published in order.
+        let mut pending_messages = FuturesOrdered::new();
+        loop {
+            tokio::select! {
+                Some(notification) = notifications.next(),
+                    if pending_messages.len() < MAX_PENDING_NOTIFICATIONS => {
+                    if !self.subscribers.is_empty() {
+                        pending_messages.push(resolve_notification(notification));
+                    }
+                }
+                Some(message) = pending_messages.next() => {
+                    if let Some(message) = message {
+                        self.publish(message);
+                    }
+                }
+                Some((network_id, event)) = events.next() => {
+                    self.process_network_event(network_id, event);
+                }
+                else => break,
+            }
+        }
+        info!("Consensus publisher stops.");
+    }
+}
+
+/// Resolves a notification into the message to publish, getting the transactions of the ordered
+/// blocks. Returns None if the transactions can't be resolved in time.
+async fn resolve_notification(notification: PublisherNotification) -> Option<ObserverMessage> {
+    match notification {
+        PublisherNotification::OrderedBlock {
+            blocks,
+            ordered_proof,
+            payload_manager,
+        } => {
+            let get_observed_blocks = async move {
+                let mut observed_blocks = Vec::with_capacity(blocks.len());
+                for block in blocks {
+                    match payload_manager.get_transactions(&block).await {
+                        Ok(transactions) => {
+                            observed_blocks.push(ObservedBlock::new(block, transactions))
+                        }
+                        Err(e) => {
+                            warn!(
+                                error = ?e,
+                                "[Publisher] Failed to get the transactions of block {}",
+                                block.id()
+                            );
+                            return None;
+                        }
+                    }
+                }
+                Some(observed_blocks)
+            };
+            match timeout(
+                Duration::from_millis(GET_TRANSACTIONS_TIMEOUT_MS),
+                get_observed_blocks,
+            )
+            .await
+            {
+                Ok(observed_blocks) => Some(ObserverMessage::OrderedBlock(Box::new(
+                    OrderedBlock::new(observed_blocks?, ordered_proof),
+                ))),
+                Err(_) => {
+                    warn!(
+                        "[Publisher] Timed out getting the transactions of the blocks ordered \
+                         by {}",
+                        ordered_proof.commit_info()
+                    );
+                    None
+                }
+            }
+        }
+        PublisherNotification::CommitDecision(commit_decision) => {
+            Some(ObserverMessage::CommitDecision(Box::new(commit_decision)))
+        }
+    }
+}
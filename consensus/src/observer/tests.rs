@@ -0,0 +1,170 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::observer::network::{ObservedBlock, OrderedBlock};
+use aptos_crypto::HashValue;
+use aptos_types::{
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    transaction::SignedTransaction,
+    validator_signer::ValidatorSigner,
+    validator_verifier::random_validator_verifier,
+};
+use consensus_types::{
+    block::{
+        block_test_utils::{
+            certificate_for_genesis, placeholder_certificate_for_block, random_payload,
+        },
+        Block,
+    },
+    common::Payload,
+    proof_of_store::{Batch, LogicalTime, ProofOfStore, SignedDigestInfo},
+};
+use std::collections::BTreeMap;
+
+fn payload_transactions(payload: &Payload) -> Vec<SignedTransaction> {
+    match payload {
+        Payload::DirectMempool(txns) => txns.clone(),
+        Payload::InQuorumStore(_) => unreachable!(),
+    }
+}
+
+/// Returns a chain of `num_blocks` observed blocks and the proof ordering them.
+pub(super) fn ordered_chain(
+    signers: &[ValidatorSigner],
+    num_blocks: u64,
+) -> (Vec<ObservedBlock>, LedgerInfoWithSignatures) {
+    let mut blocks: Vec<Block> = vec![];
+    for round in 1..=num_blocks {
+        let quorum_cert = match blocks.last() {
+            None => certificate_for_genesis(),
+            Some(parent) => placeholder_certificate_for_block(
+                signers.iter().collect(),
+                parent.id(),
+                parent.round(),
+                parent.quorum_cert().certified_block().id(),
+                parent.quorum_cert().certified_block().round(),
+            ),
+        };
+        blocks.push(Block::new_proposal(
+            random_payload(2),
+            round,
+            round,
+            quorum_cert,
+            &signers[0],
+            vec![],
+        ));
+    }
+    let ledger_info = LedgerInfo::new(
+        blocks
+            .last()
+            .unwrap()
+            .gen_block_info(HashValue::zero(), 0, None),
+        HashValue::zero(),
+    );
+    let signatures = signers
+        .iter()
+        .map(|signer| (signer.author(), signer.sign(&ledger_info)))
+        .collect();
+    let observed_blocks = blocks
+        .into_iter()
+        .map(|block| {
+            let txns = payload_transactions(block.payload().unwrap());
+            ObservedBlock::new(block, txns)
+        })
+        .collect();
+    (
+        observed_blocks,
+        LedgerInfoWithSignatures::new(ledger_info, signatures),
+    )
+}
+
+#[test]
+fn test_verify_ordered_block() {
+    let (signers, verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = EpochState { epoch: 1, verifier };
+    let (blocks, ordered_proof) = ordered_chain(&signers, 3);
+    OrderedBlock::new(blocks.clone(), ordered_proof.clone())
+        .verify(&epoch_state)
+        .unwrap();
+
+    // Not signed by the validators of the epoch.
+    let (other_signers, other_verifier) = random_validator_verifier(4, None, false);
+    let other_epoch_state = EpochState {
+        epoch: 1,
+        verifier: other_verifier,
+    };
+    assert!(OrderedBlock::new(blocks.clone(), ordered_proof.clone())
+        .verify(&other_epoch_state)
+        .is_err());
+
+    // Blocks missing, or not chained.
+    assert!(OrderedBlock::new(vec![], ordered_proof.clone())
+        .verify(&epoch_state)
+        .is_err());
+    assert!(
+        OrderedBlock::new(blocks[..2].to_vec(), ordered_proof.clone())
+            .verify(&epoch_state)
+            .is_err()
+    );
+    assert!(OrderedBlock::new(
+        vec![blocks[0].clone(), blocks[2].clone()],
+        ordered_proof.clone()
+    )
+    .verify(&epoch_state)
+    .is_err());
+    let (other_blocks, _) = ordered_chain(&other_signers, 3);
+    let mut mixed_blocks = other_blocks[..2].to_vec();
+    mixed_blocks.push(blocks[2].clone());
+    assert!(OrderedBlock::new(mixed_blocks, ordered_proof.clone())
+        .verify(&epoch_state)
+        .is_err());
+
+    // Transactions not matching the payload.
+    let mut tampered_blocks = blocks;
+    let (block, mut txns) = tampered_blocks.remove(0).into_inner();
+    txns.pop();
+    tampered_blocks.insert(0, ObservedBlock::new(block, txns));
+    assert!(OrderedBlock::new(tampered_blocks, ordered_proof)
+        .verify(&epoch_state)
+        .is_err());
+}
+
+#[test]
+fn test_verify_quorum_store_transactions() {
+    let signer = ValidatorSigner::random(None);
+    let batches: Vec<_> = [2, 3]
+        .iter()
+        .map(|num_txns| {
+            Batch::new(
+                signer.author(),
+                LogicalTime::new(1, 10),
+                payload_transactions(&random_payload(*num_txns)),
+            )
+        })
+        .collect();
+    let proofs = batches
+        .iter()
+        .map(|batch| {
+            ProofOfStore::new(
+                SignedDigestInfo::new(batch.digest(), LogicalTime::new(1, 10), batch.num_txns(), 0),
+                BTreeMap::new(),
+            )
+        })
+        .collect();
+    let payload = Payload::InQuorumStore(proofs);
+    let txns: Vec<_> = batches
+        .iter()
+        .flat_map(|batch| batch.txns().to_vec())
+        .collect();
+    payload.verify_transactions(&txns).unwrap();
+
+    // Missing, reordered or foreign transactions.
+    assert!(payload.verify_transactions(&txns[..4]).is_err());
+    let mut reordered_txns = txns.clone();
+    reordered_txns.swap(0, 4);
+    assert!(payload.verify_transactions(&reordered_txns).is_err());
+    let mut foreign_txns = txns;
+    foreign_txns[0] = payload_transactions(&random_payload(1)).remove(0);
+    assert!(payload.verify_transactions(&foreign_txns).is_err());
+}
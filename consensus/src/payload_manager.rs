@@ -5,7 +5,9 @@ use crate::{error::QuorumStoreError, state_replication::PayloadManager};
 use anyhow::Result;
 use aptos_logger::prelude::*;
 use aptos_metrics_core::monitor;
+use aptos_types::transaction::SignedTransaction;
 use consensus_types::{
+    block::Block,
     common::{Payload, PayloadFilter},
     request_response::{ConsensusRequest, ConsensusResponse},
 };
@@ -48,10 +50,16 @@ impl QuorumStoreClient {
     async fn pull_internal(
         &self,
         max_size: u64,
+        max_bytes: u64,
         exclude_payloads: PayloadFilter,
     ) -> Result<Payload, QuorumStoreError> {
         let (callback, callback_rcv) = oneshot::channel();
-        let req = ConsensusRequest::GetBlockRequest(max_size, exclude_payloads.clone(), callback);
+        let req = ConsensusRequest::GetBlockRequest(
+            max_size,
+            max_bytes,
+            exclude_payloads.clone(),
+            callback,
+        );
         // send to shared mempool
         self.consensus_to_quorum_store_sender
             .clone()
@@ -80,6 +88,7 @@ impl PayloadManager for QuorumStoreClient {
     async fn pull_payload(
         &self,
         max_size: u64,
+        max_bytes: u64,
         exclude_payloads: PayloadFilter,
         wait_callback: BoxFuture<'static, ()>,
         pending_ordering: bool,
@@ -93,7 +102,7 @@ impl PayloadManager for QuorumStoreClient {
         let payload = loop {
             count -= 1;
             let payload = self
-                .pull_internal(max_size, exclude_payloads.clone())
+                .pull_internal(max_size, max_bytes, exclude_payloads.clone())
                 .await?;
             if payload.is_empty() && !pending_ordering && count > 0 {
                 if let Some(callback) = callback_wrapper.take() {
@@ -110,4 +119,29 @@ impl PayloadManager for QuorumStoreClient {
         );
         Ok(payload)
     }
+
+    async fn get_transactions(
+        &self,
+        block: &Block,
+    ) -> Result<Vec<SignedTransaction>, QuorumStoreError> {
+        let proofs = match block.payload() {
+            None => return Ok(vec![]),
+            Some(Payload::DirectMempool(txns)) => return Ok(txns.clone()),
+            Some(Payload::InQuorumStore(proofs)) => proofs.clone(),
+        };
+        let (callback, callback_rcv) = oneshot::channel();
+        let req = ConsensusRequest::GetTransactionsRequest(proofs, callback);
+        self.consensus_to_quorum_store_sender
+            .clone()
+            .try_send(req)
+            .map_err(anyhow::Error::from)?;
+        // no timeout, the block can't be executed until its batches are available
+        match monitor!("get_transactions", callback_rcv.await).map_err(anyhow::Error::from)?? {
+            ConsensusResponse::GetTransactionsResponse(txns) => Ok(txns),
+            _ => Err(anyhow::anyhow!(
+                "[consensus] did not receive expected GetTransactionsResponse"
+            )
+            .into()),
+        }
+    }
 }
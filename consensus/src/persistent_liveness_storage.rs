@@ -50,6 +50,9 @@ pub trait PersistentLivenessStorage: Send + Sync {
 
     /// Returns a handle of the aptosdb.
     fn aptos_db(&self) -> Arc<dyn DbReader>;
+
+    /// Returns a handle of the consensusdb, used by the quorum store to persist batches.
+    fn consensus_db(&self) -> Arc<ConsensusDB>;
 }
 
 #[derive(Clone)]
@@ -434,4 +437,8 @@ impl PersistentLivenessStorage for StorageWriteProxy {
     fn aptos_db(&self) -> Arc<dyn DbReader> {
         self.aptos_db.clone()
     }
+
+    fn consensus_db(&self) -> Arc<ConsensusDB> {
+        self.db.clone()
+    }
 }
@@ -0,0 +1,552 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    network::{IncomingBatchRetrievalRequest, NetworkSender},
+    network_interface::ConsensusMsg,
+    quorum_store::{batch_store::BatchStore, counters},
+    round_manager::VerifiedEvent,
+};
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use aptos_config::config::ConsensusConfig;
+use aptos_crypto::{ed25519::Ed25519Signature, HashValue};
+use aptos_logger::prelude::*;
+use aptos_mempool::{QuorumStoreRequest, QuorumStoreResponse};
+use aptos_metrics_core::monitor;
+use aptos_types::{
+    transaction::SignedTransaction, validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier,
+};
+use bytes::Bytes;
+use channel::aptos_channel;
+use consensus_types::{
+    common::{Author, Payload, PayloadFilter, Round, TransactionSummary},
+    proof_of_store::{
+        Batch, BatchRequest, LogicalTime, ProofOfStore, SignedDigest, SignedDigestInfo,
+    },
+    request_response::{ConsensusRequest, ConsensusResponse},
+};
+use futures::{
+    channel::{
+        mpsc::{Receiver, Sender},
+        oneshot,
+    },
+    StreamExt,
+};
+use network::protocols::rpc::error::RpcError;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::time::{interval, timeout};
+
+/// A batch created by this validator, waiting for the signatures of the validators storing it.
+struct OwnBatch {
+    info: SignedDigestInfo,
+    txns: Vec<TransactionSummary>,
+    signatures: BTreeMap<Author, Ed25519Signature>,
+    certified: bool,
+}
+
+/// Quorum store disseminating the transactions ahead of the proposals: the transactions pulled
+/// from mempool are broadcast in batches, the validators storing a batch sign its digest, and
+/// the proofs of store aggregating 2f + 1 signatures are proposed in place of the transactions.
+pub struct BatchQuorumStore {
+    epoch: u64,
+    author: Author,
+    signer: Option<ValidatorSigner>,
+    validator_verifier: ValidatorVerifier,
+    network_sender: NetworkSender,
+    mempool_sender: Sender<QuorumStoreRequest>,
+    batch_store: Arc<BatchStore>,
+    mempool_txn_pull_timeout_ms: u64,
+    batch_interval_ms: u64,
+    max_batch_size: u64,
+    max_batch_bytes: u64,
+    batch_expiry_rounds: Round,
+    batch_request_timeout_ms: u64,
+    last_committed_round: Round,
+    own_batches: HashMap<HashValue, OwnBatch>,
+    // Proofs available for proposals
+    proofs: HashMap<HashValue, ProofOfStore>,
+    // Committed batches, kept until expiration to ignore late proofs
+    committed: HashMap<HashValue, LogicalTime>,
+}
+
+impl BatchQuorumStore {
+    pub fn new(
+        committed_time: LogicalTime,
+        signer: Option<ValidatorSigner>,
+        validator_verifier: ValidatorVerifier,
+        network_sender: NetworkSender,
+        mempool_sender: Sender<QuorumStoreRequest>,
+        batch_store: Arc<BatchStore>,
+        config: &ConsensusConfig,
+    ) -> Self {
+        if let Err(e) = batch_store.clear_expired(committed_time.round()) {
+            error!(error = ?e, "Failed to clear expired batches");
+        }
+        Self {
+            epoch: committed_time.epoch(),
+            author: network_sender.author(),
+            signer,
+            validator_verifier,
+            network_sender,
+            mempool_sender,
+            batch_store,
+            mempool_txn_pull_timeout_ms: config.mempool_txn_pull_timeout_ms,
+            batch_interval_ms: config.quorum_store_batch_interval_ms,
+            max_batch_size: config.quorum_store_max_batch_size,
+            max_batch_bytes: config.quorum_store_max_batch_bytes,
+            batch_expiry_rounds: config.quorum_store_batch_expiry_rounds,
+            batch_request_timeout_ms: config.quorum_store_batch_request_timeout_ms,
+            last_committed_round: committed_time.round(),
+            own_batches: HashMap::new(),
+            proofs: HashMap::new(),
+            committed: HashMap::new(),
+        }
+    }
+
+    async fn pull_internal(&self) -> Result<Vec<SignedTransaction>> {
+        let exclude_txns = self
+            .own_batches
+            .values()
+            .flat_map(|batch| batch.txns.iter().cloned())
+            .collect();
+        let (callback, callback_rcv) = oneshot::channel();
+        let msg = QuorumStoreRequest::GetBatchRequest(
+            self.max_batch_size,
+            self.max_batch_bytes,
+            exclude_txns,
+            callback,
+        );
+        self.mempool_sender
+            .clone()
+            .try_send(msg)
+            .map_err(anyhow::Error::from)?;
+        // wait for response
+        match monitor!(
+            "pull_txn",
+            timeout(
+                Duration::from_millis(self.mempool_txn_pull_timeout_ms),
+                callback_rcv
+            )
+            .await
+        ) {
+            Err(_) => Err(anyhow!(
+                "[batch_quorum_store] did not receive GetBatchResponse on time"
+            )),
+            Ok(resp) => match resp.map_err(anyhow::Error::from)?? {
+                QuorumStoreResponse::GetBatchResponse(txns) => Ok(txns),
+                _ => Err(anyhow!(
+                    "[batch_quorum_store] did not receive expected GetBatchResponse"
+                )),
+            },
+        }
+    }
+
+    /// Pulls a batch of transactions from mempool and broadcasts it, the transactions of the
+    /// batches not yet committed or expired are excluded.
+    async fn create_batch(&mut self) -> Result<()> {
+        let get_batch_start_time = Instant::now();
+        let result = self.pull_internal().await;
+        counters::quorum_store_service_latency(
+            counters::GET_BATCH_LABEL,
+            if result.is_ok() {
+                counters::REQUEST_SUCCESS_LABEL
+            } else {
+                counters::REQUEST_FAIL_LABEL
+            },
+            get_batch_start_time.elapsed(),
+        );
+        let txns = result?;
+        if txns.is_empty() {
+            return Ok(());
+        }
+
+        let summaries = txns
+            .iter()
+            .map(|txn| TransactionSummary {
+                sender: txn.sender(),
+                sequence_number: txn.sequence_number(),
+            })
+            .collect();
+        let expiration = LogicalTime::new(
+            self.epoch,
+            self.last_committed_round + self.batch_expiry_rounds,
+        );
+        let batch = Batch::new(self.author, expiration, txns);
+        debug!("Created {}", batch);
+        self.own_batches.insert(
+            batch.digest(),
+            OwnBatch {
+                info: batch.info(),
+                txns: summaries,
+                signatures: BTreeMap::new(),
+                certified: false,
+            },
+        );
+        counters::CREATED_BATCHES.inc();
+        self.network_sender
+            .broadcast(ConsensusMsg::BatchMsg(Box::new(batch)))
+            .await;
+        Ok(())
+    }
+
+    /// Stores the batch and sends the signed digest back to its source.
+    pub(crate) async fn process_batch(&mut self, peer_id: Author, batch: Batch) -> Result<()> {
+        ensure!(
+            batch.source() == peer_id,
+            "{} not sent by its source but by {}",
+            batch,
+            peer_id
+        );
+        ensure!(
+            batch.epoch() == self.epoch,
+            "{} not of the current epoch {}",
+            batch,
+            self.epoch
+        );
+        ensure!(
+            batch.num_txns() <= self.max_batch_size,
+            "{} has more than {} txns",
+            batch,
+            self.max_batch_size
+        );
+        ensure!(
+            batch.num_bytes() <= self.max_batch_bytes,
+            "{} has more than {} bytes",
+            batch,
+            self.max_batch_bytes
+        );
+        let expiration_round = batch.expiration().round();
+        ensure!(
+            expiration_round >= self.last_committed_round,
+            "{} already expired",
+            batch
+        );
+        ensure!(
+            expiration_round <= self.last_committed_round + 2 * self.batch_expiry_rounds,
+            "{} expires too late",
+            batch
+        );
+        let source = batch.source();
+        let info = batch.info();
+        self.batch_store.save(batch)?;
+        if let Some(signer) = &self.signer {
+            let msg = ConsensusMsg::SignedDigestMsg(Box::new(SignedDigest::new(info, signer)));
+            self.network_sender.send(msg, vec![source]).await;
+        }
+        Ok(())
+    }
+
+    /// Aggregates the signatures of an own batch and broadcasts the proof of store once the
+    /// signers hold enough voting power.
+    async fn process_signed_digest(&mut self, signed_digest: SignedDigest) -> Result<()> {
+        let own_batch = match self.own_batches.get_mut(&signed_digest.digest()) {
+            Some(own_batch) => own_batch,
+            // the batch is committed or expired
+            None => return Ok(()),
+        };
+        ensure!(
+            &own_batch.info == signed_digest.info(),
+            "{} doesn't match the batch",
+            signed_digest
+        );
+        if own_batch.certified {
+            return Ok(());
+        }
+        own_batch
+            .signatures
+            .insert(signed_digest.signer(), signed_digest.signature().clone());
+        if self
+            .validator_verifier
+            .check_voting_power(own_batch.signatures.keys())
+            .is_err()
+        {
+            return Ok(());
+        }
+        own_batch.certified = true;
+        let proof = ProofOfStore::new(
+            own_batch.info.clone(),
+            std::mem::take(&mut own_batch.signatures),
+        );
+        debug!("Created {}", proof);
+        counters::CREATED_PROOFS.inc();
+        self.network_sender
+            .broadcast(ConsensusMsg::ProofOfStoreMsg(Box::new(proof)))
+            .await;
+        Ok(())
+    }
+
+    fn process_proof(&mut self, proof: ProofOfStore) {
+        if proof.expiration().round() < self.last_committed_round
+            || self.committed.contains_key(&proof.digest())
+        {
+            return;
+        }
+        self.proofs.insert(proof.digest(), proof);
+        counters::PROOF_QUEUE_SIZE.set(self.proofs.len() as i64);
+    }
+
+    fn process_batch_retrieval(&self, request: IncomingBatchRetrievalRequest) -> Result<()> {
+        let digest = request.req.digest();
+        let response = match self.batch_store.get(&digest) {
+            Some(batch) => {
+                let response_bytes = request
+                    .protocol
+                    .to_bytes(&ConsensusMsg::BatchMsg(Box::new(batch)))?;
+                Ok(Bytes::from(response_bytes))
+            }
+            None => Err(RpcError::ApplicationError(anyhow!(
+                "Batch {} not found",
+                digest
+            ))),
+        };
+        request
+            .response_sender
+            .send(response)
+            .map_err(|e| anyhow!("{:?}", e))
+            .context("[QuorumStore] Failed to process batch retrieval")
+    }
+
+    async fn process_event(&mut self, peer_id: Author, event: VerifiedEvent) -> Result<()> {
+        match event {
+            VerifiedEvent::Batch(batch) => self.process_batch(peer_id, *batch).await,
+            VerifiedEvent::SignedDigest(signed_digest) => {
+                self.process_signed_digest(*signed_digest).await
+            }
+            VerifiedEvent::ProofOfStore(proof) => {
+                self.process_proof(*proof);
+                Ok(())
+            }
+            VerifiedEvent::BatchRetrievalRequest(request) => self.process_batch_retrieval(*request),
+            unexpected_event => bail!("Unexpected event: {:?}", unexpected_event),
+        }
+    }
+
+    /// Responds with the oldest proofs not referenced by the pending blocks, up to max_size
+    /// transactions and max_bytes in total.
+    fn handle_block_request(
+        &self,
+        max_size: u64,
+        max_bytes: u64,
+        payload_filter: PayloadFilter,
+        callback: oneshot::Sender<Result<ConsensusResponse>>,
+    ) {
+        let get_block_response_start_time = Instant::now();
+        let excluded_digests = match payload_filter {
+            PayloadFilter::InQuorumStore(digests) => digests,
+            PayloadFilter::DirectMempool(_) => HashSet::new(),
+        };
+        let mut candidates: Vec<_> = self
+            .proofs
+            .values()
+            .filter(|proof| {
+                !excluded_digests.contains(&proof.digest())
+                    && proof.expiration().round() > self.last_committed_round
+            })
+            .collect();
+        candidates.sort_by_key(|proof| proof.expiration());
+
+        let mut size = 0;
+        let mut bytes = 0;
+        let mut proofs = vec![];
+        for proof in candidates {
+            // a smaller proof may still fit
+            if size + proof.info().num_txns() > max_size
+                || bytes + proof.info().num_bytes() > max_bytes
+            {
+                continue;
+            }
+            size += proof.info().num_txns();
+            bytes += proof.info().num_bytes();
+            proofs.push(proof.clone());
+        }
+
+        let payload = Payload::InQuorumStore(proofs);
+        let result = match callback.send(Ok(ConsensusResponse::GetBlockResponse(payload))) {
+            Err(_) => {
+                error!("Callback failed");
+                counters::CALLBACK_FAIL_LABEL
+            }
+            Ok(_) => counters::CALLBACK_SUCCESS_LABEL,
+        };
+        counters::quorum_store_service_latency(
+            counters::GET_BLOCK_RESPONSE_LABEL,
+            result,
+            get_block_response_start_time.elapsed(),
+        );
+    }
+
+    /// Resolves the proofs in a separate task, as the missing batches are fetched from peers.
+    fn handle_transactions_request(
+        &self,
+        proofs: Vec<ProofOfStore>,
+        callback: oneshot::Sender<Result<ConsensusResponse>>,
+    ) {
+        let batch_store = self.batch_store.clone();
+        let network_sender = self.network_sender.clone();
+        let author = self.author;
+        let request_timeout = Duration::from_millis(self.batch_request_timeout_ms);
+        tokio::spawn(async move {
+            let get_transactions_start_time = Instant::now();
+            let response =
+                get_transactions(proofs, batch_store, network_sender, author, request_timeout)
+                    .await
+                    .map(ConsensusResponse::GetTransactionsResponse);
+            let result = match callback.send(response) {
+                Err(_) => {
+                    error!("Callback failed");
+                    counters::CALLBACK_FAIL_LABEL
+                }
+                Ok(_) => counters::CALLBACK_SUCCESS_LABEL,
+            };
+            counters::quorum_store_service_latency(
+                counters::GET_TRANSACTIONS_LABEL,
+                result,
+                get_transactions_start_time.elapsed(),
+            );
+        });
+    }
+
+    /// Drops the committed and expired batches and proofs.
+    fn handle_clean_request(
+        &mut self,
+        epoch: u64,
+        round: Round,
+        payloads: Vec<Payload>,
+        callback: oneshot::Sender<Result<ConsensusResponse>>,
+    ) {
+        if epoch == self.epoch {
+            self.last_committed_round = std::cmp::max(self.last_committed_round, round);
+            for payload in payloads {
+                if let Payload::InQuorumStore(proofs) = payload {
+                    for proof in proofs {
+                        self.proofs.remove(&proof.digest());
+                        self.own_batches.remove(&proof.digest());
+                        self.committed.insert(proof.digest(), proof.expiration());
+                    }
+                }
+            }
+            let committed_round = self.last_committed_round;
+            self.proofs
+                .retain(|_, proof| proof.expiration().round() >= committed_round);
+            self.own_batches
+                .retain(|_, batch| batch.info.expiration().round() >= committed_round);
+            self.committed
+                .retain(|_, expiration| expiration.round() >= committed_round);
+            counters::PROOF_QUEUE_SIZE.set(self.proofs.len() as i64);
+            if let Err(e) = self.batch_store.clear_expired(committed_round) {
+                error!(error = ?e, "Failed to clear expired batches");
+            }
+        }
+
+        if callback
+            .send(Ok(ConsensusResponse::CleanResponse()))
+            .is_err()
+        {
+            error!("Callback failed");
+        }
+    }
+
+    fn handle_consensus_request(&mut self, req: ConsensusRequest) {
+        match req {
+            ConsensusRequest::GetBlockRequest(max_size, max_bytes, payload_filter, callback) => {
+                self.handle_block_request(max_size, max_bytes, payload_filter, callback);
+            }
+            ConsensusRequest::GetTransactionsRequest(proofs, callback) => {
+                self.handle_transactions_request(proofs, callback);
+            }
+            ConsensusRequest::CleanRequest(epoch, round, payloads, callback) => {
+                self.handle_clean_request(epoch, round, payloads, callback);
+            }
+        }
+    }
+
+    pub async fn start(
+        mut self,
+        mut consensus_receiver: Receiver<ConsensusRequest>,
+        mut message_receiver: aptos_channel::Receiver<Author, (Author, VerifiedEvent)>,
+    ) {
+        info!(epoch = self.epoch, "BatchQuorumStore started");
+        let mut batch_interval = interval(Duration::from_millis(self.batch_interval_ms));
+        loop {
+            let _timer = counters::MAIN_LOOP.start_timer();
+            tokio::select! {
+                maybe_request = consensus_receiver.next() => match maybe_request {
+                    Some(request) => self.handle_consensus_request(request),
+                    None => break,
+                },
+                maybe_message = message_receiver.next() => match maybe_message {
+                    Some((peer_id, event)) => {
+                        if let Err(e) = self.process_event(peer_id, event).await {
+                            error!(remote_peer = peer_id, error = ?e, "Failed to process quorum store message");
+                        }
+                    }
+                    None => break,
+                },
+                _ = batch_interval.tick() => {
+                    if let Err(e) = self.create_batch().await {
+                        error!(error = ?e, "Failed to create batch");
+                    }
+                }
+            }
+        }
+        info!(epoch = self.epoch, "BatchQuorumStore stopped");
+    }
+}
+
+/// Resolves the proofs into the transactions of their batches, in order.
+async fn get_transactions(
+    proofs: Vec<ProofOfStore>,
+    batch_store: Arc<BatchStore>,
+    network_sender: NetworkSender,
+    author: Author,
+    request_timeout: Duration,
+) -> Result<Vec<SignedTransaction>> {
+    let mut txns = vec![];
+    for proof in proofs {
+        let batch = match batch_store.get(&proof.digest()) {
+            Some(batch) => batch,
+            None => {
+                let batch = fetch_batch(&proof, &network_sender, author, request_timeout).await?;
+                batch_store.save(batch.clone())?;
+                batch
+            }
+        };
+        txns.extend(batch.into_txns());
+    }
+    Ok(txns)
+}
+
+/// Fetches a missing batch from the validators that signed its proof.
+async fn fetch_batch(
+    proof: &ProofOfStore,
+    network_sender: &NetworkSender,
+    author: Author,
+    request_timeout: Duration,
+) -> Result<Batch> {
+    let request = BatchRequest::new(proof.epoch(), proof.digest());
+    for signer in proof.signers().filter(|signer| **signer != author) {
+        match network_sender
+            .request_batch(request.clone(), *signer, request_timeout)
+            .await
+        {
+            Ok(batch) => {
+                counters::FETCHED_BATCHES
+                    .with_label_values(&[counters::REQUEST_SUCCESS_LABEL])
+                    .inc();
+                return Ok(batch);
+            }
+            Err(e) => {
+                counters::FETCHED_BATCHES
+                    .with_label_values(&[counters::REQUEST_FAIL_LABEL])
+                    .inc();
+                warn!(remote_peer = *signer, error = ?e, "Failed to fetch {}", request);
+            }
+        }
+    }
+    bail!("Unable to fetch {} from the signers of its proof", request)
+}
@@ -0,0 +1,79 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{consensusdb::ConsensusDB, error::DbError};
+use anyhow::{ensure, Result};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use consensus_types::{common::Round, proof_of_store::Batch};
+use std::{collections::HashMap, sync::Arc};
+
+/// The batches of the current epoch held by the quorum store. Every batch is persisted in the
+/// ConsensusDB before it is signed, so that the batches certified by the proofs of store survive
+/// restarts.
+pub struct BatchStore {
+    epoch: u64,
+    db: Arc<ConsensusDB>,
+    batches: Mutex<HashMap<HashValue, Batch>>,
+}
+
+impl BatchStore {
+    /// Loads the persisted batches, the batches of other epochs are deleted.
+    pub fn new(epoch: u64, db: Arc<ConsensusDB>) -> Self {
+        let (batches, stale): (HashMap<_, _>, HashMap<_, _>) = db
+            .get_batches()
+            .expect("Failed to read batches from ConsensusDB")
+            .into_iter()
+            .partition(|(_, batch)| batch.epoch() == epoch);
+        if !stale.is_empty() {
+            if let Err(e) = db.delete_batches(stale.into_keys().collect()) {
+                error!(error = ?e, "Failed to delete batches of previous epochs");
+            }
+        }
+        info!(epoch = epoch, "Loaded {} batches", batches.len());
+        Self {
+            epoch,
+            db,
+            batches: Mutex::new(batches),
+        }
+    }
+
+    pub fn save(&self, batch: Batch) -> Result<()> {
+        ensure!(
+            batch.epoch() == self.epoch,
+            "{} is not from epoch {}",
+            batch,
+            self.epoch
+        );
+        if !self.batches.lock().contains_key(&batch.digest()) {
+            self.db.save_batch(&batch)?;
+            self.batches.lock().insert(batch.digest(), batch);
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, digest: &HashValue) -> Option<Batch> {
+        self.batches.lock().get(digest).cloned()
+    }
+
+    /// Removes the batches expiring before the given committed round.
+    pub fn clear_expired(&self, committed_round: Round) -> Result<(), DbError> {
+        let expired: Vec<_> = self
+            .batches
+            .lock()
+            .iter()
+            .filter(|(_, batch)| batch.expiration().round() < committed_round)
+            .map(|(digest, _)| *digest)
+            .collect();
+        if expired.is_empty() {
+            return Ok(());
+        }
+        self.db.delete_batches(expired.clone())?;
+        let mut batches = self.batches.lock();
+        for digest in expired {
+            batches.remove(&digest);
+        }
+        Ok(())
+    }
+}
@@ -1,13 +1,16 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 use aptos_metrics_core::{
-    op_counters::DurationHistogram, register_histogram, register_histogram_vec, HistogramVec,
+    op_counters::DurationHistogram, register_histogram, register_histogram_vec,
+    register_int_counter, register_int_counter_vec, register_int_gauge, HistogramVec, IntCounter,
+    IntCounterVec, IntGauge,
 };
 use once_cell::sync::Lazy;
 use std::time::Duration;
 
 pub const GET_BATCH_LABEL: &str = "get_batch";
 pub const GET_BLOCK_RESPONSE_LABEL: &str = "get_block_response";
+pub const GET_TRANSACTIONS_LABEL: &str = "get_transactions";
 
 pub const REQUEST_FAIL_LABEL: &str = "fail";
 pub const REQUEST_SUCCESS_LABEL: &str = "success";
@@ -42,3 +45,40 @@ pub static MAIN_LOOP: Lazy<DurationHistogram> = Lazy::new(|| {
         .unwrap(),
     )
 });
+
+/// Count of the batches created by this validator.
+pub static CREATED_BATCHES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "quorum_store_created_batches_count",
+        "Count of the batches created by this validator"
+    )
+    .unwrap()
+});
+
+/// Count of the proofs of store formed for the batches of this validator.
+pub static CREATED_PROOFS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "quorum_store_created_proofs_count",
+        "Count of the proofs of store formed for the batches of this validator"
+    )
+    .unwrap()
+});
+
+/// Count of the batches fetched from peers, by result.
+pub static FETCHED_BATCHES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "quorum_store_fetched_batches_count",
+        "Count of the batches fetched from peers",
+        &["result"]
+    )
+    .unwrap()
+});
+
+/// Number of proofs of store available for proposals.
+pub static PROOF_QUEUE_SIZE: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "quorum_store_proof_queue_size",
+        "Number of proofs of store available for proposals"
+    )
+    .unwrap()
+});
@@ -1,6 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+/// Disseminates batches of transactions ahead of the proposals, which reference the batches by
+/// their proofs of store.
+pub mod batch_quorum_store;
+pub mod batch_store;
 /// Equivalent to directly fetching blocks from mempool without a quorum store.
 pub mod direct_mempool_quorum_store;
 
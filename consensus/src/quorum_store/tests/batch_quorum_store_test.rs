@@ -0,0 +1,125 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consensusdb::ConsensusDB,
+    network::NetworkSender,
+    network_interface::ConsensusNetworkSender,
+    quorum_store::{batch_quorum_store::BatchQuorumStore, batch_store::BatchStore},
+};
+use aptos_config::config::ConsensusConfig;
+use aptos_temppath::TempPath;
+use aptos_types::{account_address::AccountAddress, validator_verifier::random_validator_verifier};
+use channel::{aptos_channel, message_queues::QueueStyle};
+use consensus_types::{
+    block::block_test_utils::random_payload,
+    common::Payload,
+    proof_of_store::{Batch, LogicalTime},
+};
+use futures::channel::mpsc;
+use network::{
+    peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
+    protocols::network::NewNetworkSender,
+};
+use std::sync::Arc;
+
+const EPOCH: u64 = 1;
+
+fn batch_quorum_store(config: &ConsensusConfig) -> (BatchQuorumStore, Arc<BatchStore>, TempPath) {
+    let (_, validators) = random_validator_verifier(1, None, false);
+    let (network_reqs_tx, _) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+    let (connection_reqs_tx, _) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+    let network_sender = NetworkSender::new(
+        AccountAddress::random(),
+        ConsensusNetworkSender::new(
+            PeerManagerRequestSender::new(network_reqs_tx),
+            ConnectionRequestSender::new(connection_reqs_tx),
+        ),
+        channel::new_test(8).0,
+        validators.clone(),
+    );
+    let tmp_dir = TempPath::new();
+    let batch_store = Arc::new(BatchStore::new(EPOCH, Arc::new(ConsensusDB::new(&tmp_dir))));
+    let quorum_store = BatchQuorumStore::new(
+        LogicalTime::new(EPOCH, 0),
+        None, /* signer */
+        validators,
+        network_sender,
+        mpsc::channel(8).0,
+        batch_store.clone(),
+        config,
+    );
+    (quorum_store, batch_store, tmp_dir)
+}
+
+fn batch(source: AccountAddress, epoch: u64, num_txns: usize) -> Batch {
+    let txns = match random_payload(num_txns) {
+        Payload::DirectMempool(txns) => txns,
+        _ => unreachable!(),
+    };
+    Batch::new(source, LogicalTime::new(epoch, 10), txns)
+}
+
+#[tokio::test]
+async fn test_process_batch_of_other_epoch() {
+    let (mut quorum_store, batch_store, _tmp_dir) = batch_quorum_store(&ConsensusConfig::default());
+    let source = AccountAddress::random();
+
+    let old_batch = batch(source, EPOCH - 1, 2);
+    assert!(quorum_store
+        .process_batch(source, old_batch.clone())
+        .await
+        .is_err());
+    let future_batch = batch(source, EPOCH + 1, 2);
+    assert!(quorum_store
+        .process_batch(source, future_batch.clone())
+        .await
+        .is_err());
+    assert_eq!(batch_store.get(&old_batch.digest()), None);
+    assert_eq!(batch_store.get(&future_batch.digest()), None);
+
+    let batch = batch(source, EPOCH, 2);
+    quorum_store
+        .process_batch(source, batch.clone())
+        .await
+        .unwrap();
+    assert_eq!(batch_store.get(&batch.digest()), Some(batch));
+}
+
+#[tokio::test]
+async fn test_process_oversized_batch() {
+    let source = AccountAddress::random();
+    let full_batch = batch(source, EPOCH, 5);
+    let config = ConsensusConfig {
+        quorum_store_max_batch_size: 5,
+        quorum_store_max_batch_bytes: full_batch.num_bytes(),
+        ..ConsensusConfig::default()
+    };
+    let (mut quorum_store, batch_store, _tmp_dir) = batch_quorum_store(&config);
+
+    // too many txns
+    let batch_of_6 = batch(source, EPOCH, 6);
+    assert!(quorum_store
+        .process_batch(source, batch_of_6.clone())
+        .await
+        .is_err());
+    assert_eq!(batch_store.get(&batch_of_6.digest()), None);
+
+    quorum_store
+        .process_batch(source, full_batch.clone())
+        .await
+        .unwrap();
+    assert_eq!(batch_store.get(&full_batch.digest()), Some(full_batch));
+
+    // too many bytes
+    let (mut quorum_store, batch_store, _tmp_dir) = batch_quorum_store(&ConsensusConfig {
+        quorum_store_max_batch_bytes: config.quorum_store_max_batch_bytes - 1,
+        ..config
+    });
+    let batch_of_5 = batch(source, EPOCH, 5);
+    assert!(quorum_store
+        .process_batch(source, batch_of_5.clone())
+        .await
+        .is_err());
+    assert_eq!(batch_store.get(&batch_of_5.digest()), None);
+}
@@ -0,0 +1,66 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{consensusdb::ConsensusDB, quorum_store::batch_store::BatchStore};
+use aptos_temppath::TempPath;
+use aptos_types::account_address::AccountAddress;
+use consensus_types::{
+    block::block_test_utils::random_payload,
+    common::Payload,
+    proof_of_store::{Batch, LogicalTime},
+};
+use std::sync::Arc;
+
+fn batch(epoch: u64, expiration_round: u64) -> Batch {
+    let txns = match random_payload(2) {
+        Payload::DirectMempool(txns) => txns,
+        _ => unreachable!(),
+    };
+    Batch::new(
+        AccountAddress::random(),
+        LogicalTime::new(epoch, expiration_round),
+        txns,
+    )
+}
+
+#[test]
+fn test_save_and_reload() {
+    let tmp_dir = TempPath::new();
+    let db = Arc::new(ConsensusDB::new(&tmp_dir));
+    let store = BatchStore::new(1, db.clone());
+
+    let batch_1 = batch(1, 10);
+    store.save(batch_1.clone()).unwrap();
+    assert!(store.save(batch(2, 10)).is_err());
+    assert_eq!(store.get(&batch_1.digest()), Some(batch_1.clone()));
+
+    // the batches of the epoch survive a restart
+    let store = BatchStore::new(1, db.clone());
+    assert_eq!(store.get(&batch_1.digest()), Some(batch_1.clone()));
+
+    // the batches of previous epochs are deleted
+    let store = BatchStore::new(2, db.clone());
+    assert_eq!(store.get(&batch_1.digest()), None);
+    assert!(db.get_batches().unwrap().is_empty());
+}
+
+#[test]
+fn test_clear_expired() {
+    let tmp_dir = TempPath::new();
+    let db = Arc::new(ConsensusDB::new(&tmp_dir));
+    let store = BatchStore::new(1, db.clone());
+
+    let batch_1 = batch(1, 10);
+    let batch_2 = batch(1, 20);
+    store.save(batch_1.clone()).unwrap();
+    store.save(batch_2.clone()).unwrap();
+
+    // a batch is available until a block of a later round is committed
+    store.clear_expired(10).unwrap();
+    assert_eq!(store.get(&batch_1.digest()), Some(batch_1.clone()));
+
+    store.clear_expired(11).unwrap();
+    assert_eq!(store.get(&batch_1.digest()), None);
+    assert_eq!(store.get(&batch_2.digest()), Some(batch_2.clone()));
+    assert_eq!(db.get_batches().unwrap().len(), 1);
+}
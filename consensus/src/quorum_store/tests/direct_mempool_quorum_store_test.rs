@@ -31,18 +31,26 @@ async fn test_block_request_no_txns() {
     consensus_to_quorum_store_sender
         .try_send(ConsensusRequest::GetBlockRequest(
             100,
+            1000,
             PayloadFilter::DirectMempool(vec![]),
             consensus_callback,
         ))
         .unwrap();
 
-    if let QuorumStoreRequest::GetBatchRequest(_max_batch_size, _exclude_txns, callback) = timeout(
+    if let QuorumStoreRequest::GetBatchRequest(
+        max_batch_size,
+        max_batch_bytes,
+        _exclude_txns,
+        callback,
+    ) = timeout(
         Duration::from_millis(1_000),
         quorum_store_to_mempool_receiver.select_next_some(),
     )
     .await
     .unwrap()
     {
+        assert_eq!(max_batch_size, 100);
+        assert_eq!(max_batch_bytes, 1000);
         callback
             .send(Ok(QuorumStoreResponse::GetBatchResponse(vec![])))
             .unwrap();
@@ -16,7 +16,7 @@ use crate::{
     },
     logging::{LogEvent, LogSchema},
     metrics_safety_rules::MetricsSafetyRules,
-    network::{IncomingBlockRetrievalRequest, NetworkSender},
+    network::{IncomingBatchRetrievalRequest, IncomingBlockRetrievalRequest, NetworkSender},
     network_interface::ConsensusMsg,
     pending_votes::VoteReceptionResult,
     persistent_liveness_storage::PersistentLivenessStorage,
@@ -35,6 +35,7 @@ use consensus_types::{
     block_retrieval::{BlockRetrievalResponse, BlockRetrievalStatus},
     common::{Author, Round},
     experimental::{commit_decision::CommitDecision, commit_vote::CommitVote},
+    proof_of_store::{Batch, ProofOfStore, SignedDigest},
     proposal_msg::ProposalMsg,
     quorum_cert::QuorumCert,
     sync_info::SyncInfo,
@@ -58,6 +59,9 @@ pub enum UnverifiedEvent {
     SyncInfo(Box<SyncInfo>),
     CommitVote(Box<CommitVote>),
     CommitDecision(Box<CommitDecision>),
+    Batch(Box<Batch>),
+    SignedDigest(Box<SignedDigest>),
+    ProofOfStore(Box<ProofOfStore>),
 }
 
 impl UnverifiedEvent {
@@ -81,6 +85,18 @@ impl UnverifiedEvent {
                 cd.verify(validator)?;
                 VerifiedEvent::CommitDecision(cd)
             }
+            UnverifiedEvent::Batch(b) => {
+                b.verify()?;
+                VerifiedEvent::Batch(b)
+            }
+            UnverifiedEvent::SignedDigest(sd) => {
+                sd.verify(validator)?;
+                VerifiedEvent::SignedDigest(sd)
+            }
+            UnverifiedEvent::ProofOfStore(p) => {
+                p.verify(validator)?;
+                VerifiedEvent::ProofOfStore(p)
+            }
         })
     }
 
@@ -91,6 +107,9 @@ impl UnverifiedEvent {
             UnverifiedEvent::SyncInfo(s) => s.epoch(),
             UnverifiedEvent::CommitVote(cv) => cv.epoch(),
             UnverifiedEvent::CommitDecision(cd) => cd.epoch(),
+            UnverifiedEvent::Batch(b) => b.epoch(),
+            UnverifiedEvent::SignedDigest(sd) => sd.epoch(),
+            UnverifiedEvent::ProofOfStore(p) => p.epoch(),
         }
     }
 }
@@ -103,6 +122,9 @@ impl From<ConsensusMsg> for UnverifiedEvent {
             ConsensusMsg::SyncInfo(m) => UnverifiedEvent::SyncInfo(m),
             ConsensusMsg::CommitVoteMsg(m) => UnverifiedEvent::CommitVote(m),
             ConsensusMsg::CommitDecisionMsg(m) => UnverifiedEvent::CommitDecision(m),
+            ConsensusMsg::BatchMsg(m) => UnverifiedEvent::Batch(m),
+            ConsensusMsg::SignedDigestMsg(m) => UnverifiedEvent::SignedDigest(m),
+            ConsensusMsg::ProofOfStoreMsg(m) => UnverifiedEvent::ProofOfStore(m),
             _ => unreachable!("Unexpected conversion"),
         }
     }
@@ -117,6 +139,10 @@ pub enum VerifiedEvent {
     CommitVote(Box<CommitVote>),
     CommitDecision(Box<CommitDecision>),
     BlockRetrievalRequest(Box<IncomingBlockRetrievalRequest>),
+    Batch(Box<Batch>),
+    SignedDigest(Box<SignedDigest>),
+    ProofOfStore(Box<ProofOfStore>),
+    BatchRetrievalRequest(Box<IncomingBatchRetrievalRequest>),
     // local messages
     LocalTimeout(Round),
     Shutdown(oneshot::Sender<()>),
@@ -145,7 +145,9 @@ fn create_node_for_fuzzing() -> RoundManager {
         Arc::new(MockPayloadManager::new(None)),
         time_service,
         1,
+        1024,
         10,
+        vec![],
     );
 
     //
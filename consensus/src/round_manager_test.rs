@@ -204,7 +204,9 @@ impl NodeSetup {
             Arc::new(MockPayloadManager::new(None)),
             time_service.clone(),
             1,
+            1024,
             10,
+            vec![],
         );
 
         let round_state = Self::create_round_state(time_service);
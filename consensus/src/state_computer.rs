@@ -6,7 +6,7 @@ use crate::{
     commit_notifier::CommitNotifier,
     counters,
     error::StateSyncError,
-    state_replication::{StateComputer, StateComputerCommitCallBackType},
+    state_replication::{PayloadManager, StateComputer, StateComputerCommitCallBackType},
     txn_notifier::TxnNotifier,
 };
 use anyhow::Result;
@@ -15,11 +15,18 @@ use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_metrics_core::monitor;
 use aptos_types::{
-    account_address::AccountAddress, contract_event::ContractEvent, epoch_state::EpochState,
-    ledger_info::LedgerInfoWithSignatures, transaction::Transaction,
+    account_address::AccountAddress,
+    contract_event::ContractEvent,
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{SignedTransaction, Transaction},
 };
 use consensus_notifications::ConsensusNotificationSender;
-use consensus_types::{block::Block, common::Round, executed_block::ExecutedBlock};
+use consensus_types::{
+    block::Block,
+    common::{Payload, Round},
+    executed_block::ExecutedBlock,
+};
 use executor_types::{BlockExecutorTrait, Error as ExecutionError, StateComputeResult};
 use fail::fail_point;
 use futures::{SinkExt, StreamExt};
@@ -31,7 +38,7 @@ type NotificationType = (
     Vec<ContractEvent>,
 );
 
-type CommitType = (u64, Round);
+type CommitType = (u64, Round, Vec<Payload>);
 
 /// Basic communication with the Execution module;
 /// implements StateComputer traits.
@@ -42,6 +49,7 @@ pub struct ExecutionProxy {
     async_state_sync_notifier: channel::Sender<NotificationType>,
     async_commit_notifier: channel::Sender<CommitType>,
     validators: Mutex<Vec<AccountAddress>>,
+    payload_manager: Mutex<Option<Arc<dyn PayloadManager>>>,
 }
 
 impl ExecutionProxy {
@@ -71,10 +79,11 @@ impl ExecutionProxy {
             channel::new::<CommitType>(10, &counters::PENDING_QUORUM_STORE_COMMIT_NOTIFICATION);
         let notifier = commit_notifier.clone();
         handle.spawn(async move {
-            while let Some((epoch, round)) = commit_rx.next().await {
-                if let Err(e) =
-                    monitor!("notify_commit", notifier.notify_commit(epoch, round).await)
-                {
+            while let Some((epoch, round, payloads)) = commit_rx.next().await {
+                if let Err(e) = monitor!(
+                    "notify_commit",
+                    notifier.notify_commit(epoch, round, payloads).await
+                ) {
                     error!(error = ?e, "Failed to notify commit notifier");
                 }
             }
@@ -86,8 +95,32 @@ impl ExecutionProxy {
             async_state_sync_notifier: tx,
             async_commit_notifier: commit_tx,
             validators: Mutex::new(vec![]),
+            payload_manager: Mutex::new(None),
         }
     }
+
+    fn payload_manager(&self) -> Result<Arc<dyn PayloadManager>, ExecutionError> {
+        self.payload_manager
+            .lock()
+            .clone()
+            .ok_or_else(|| ExecutionError::InternalError {
+                error: "Payload manager not set, epoch not started".into(),
+            })
+    }
+
+    /// Resolves the user transactions of the block, fetching the batches it references.
+    async fn get_transactions(
+        &self,
+        block: &Block,
+    ) -> Result<Vec<SignedTransaction>, ExecutionError> {
+        monitor!(
+            "get_transactions",
+            self.payload_manager()?.get_transactions(block).await
+        )
+        .map_err(|e| ExecutionError::InternalError {
+            error: format!("Failed to get transactions of block {}: {}", block.id(), e),
+        })
+    }
 }
 
 #[async_trait::async_trait]
@@ -110,23 +143,22 @@ impl StateComputer for ExecutionProxy {
             "Executing block",
         );
 
+        let txns = self.get_transactions(block).await?;
+        let transactions_to_execute =
+            block.transactions_to_execute(&self.validators.lock(), txns.clone());
+
         // TODO: figure out error handling for the prologue txn
         let compute_result = monitor!(
             "execute_block",
-            self.executor.execute_block(
-                (
-                    block.id(),
-                    block.transactions_to_execute(&self.validators.lock())
-                ),
-                parent_block_id
-            )
+            self.executor
+                .execute_block((block.id(), transactions_to_execute), parent_block_id)
         )?;
         observe_block(block.timestamp_usecs(), BlockStage::EXECUTED);
 
         // notify mempool about failed transaction
         if let Err(e) = self
             .txn_notifier
-            .notify_failed_txn(block, &compute_result)
+            .notify_failed_txn(&txns, &compute_result)
             .await
         {
             error!(
@@ -149,10 +181,13 @@ impl StateComputer for ExecutionProxy {
         let skip_clean = blocks.is_empty();
         let mut latest_epoch: u64 = 0;
         let mut latest_round: u64 = 0;
+        let mut payloads = Vec::new();
 
         for block in blocks {
             block_ids.push(block.id());
-            txns.extend(block.transactions_to_commit(&self.validators.lock()));
+            let block_txns = self.get_transactions(block.block()).await?;
+            txns.extend(block.transactions_to_commit(&self.validators.lock(), block_txns));
+            payloads.extend(block.payload().cloned());
             reconfig_events.extend(block.reconfig_event());
 
             if block.epoch() > latest_epoch {
@@ -186,7 +221,7 @@ impl StateComputer for ExecutionProxy {
         }
         self.async_commit_notifier
             .clone()
-            .send((latest_epoch, latest_round))
+            .send((latest_epoch, latest_round, payloads))
             .await
             .expect("Failed to send async commit notification");
         Ok(())
@@ -216,10 +251,11 @@ impl StateComputer for ExecutionProxy {
         })
     }
 
-    fn new_epoch(&self, epoch_state: &EpochState) {
+    fn new_epoch(&self, epoch_state: &EpochState, payload_manager: Arc<dyn PayloadManager>) {
         *self.validators.lock() = epoch_state
             .verifier
             .get_ordered_account_addresses_iter()
             .collect();
+        *self.payload_manager.lock() = Some(payload_manager);
     }
 }
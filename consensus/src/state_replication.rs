@@ -4,7 +4,9 @@
 use crate::error::{QuorumStoreError, StateSyncError};
 use anyhow::Result;
 use aptos_crypto::HashValue;
-use aptos_types::{epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures};
+use aptos_types::{
+    epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures, transaction::SignedTransaction,
+};
 use consensus_types::{
     block::Block,
     common::{Payload, PayloadFilter},
@@ -22,11 +24,30 @@ pub trait PayloadManager: Send + Sync {
     async fn pull_payload(
         &self,
         max_size: u64,
+        max_bytes: u64,
         exclude: PayloadFilter,
         wait_callback: BoxFuture<'static, ()>,
         pending_ordering: bool,
     ) -> Result<Payload, QuorumStoreError>;
 
+    /// Resolves the payload of the block into its transactions, a payload referencing batches
+    /// can only be resolved by the quorum store.
+    async fn get_transactions(
+        &self,
+        block: &Block,
+    ) -> Result<Vec<SignedTransaction>, QuorumStoreError> {
+        match block.payload() {
+            None => Ok(vec![]),
+            Some(Payload::DirectMempool(txns)) => Ok(txns.clone()),
+            Some(payload) => Err(anyhow::anyhow!(
+                "Unable to resolve payload of block {}: {}",
+                block.id(),
+                payload
+            )
+            .into()),
+        }
+    }
+
     fn trace_payloads(&self) {}
 }
 
@@ -60,6 +81,7 @@ pub trait StateComputer: Send + Sync {
     /// can assume there were no modifications to the storage made.
     async fn sync_to(&self, target: LedgerInfoWithSignatures) -> Result<(), StateSyncError>;
 
-    // Reconfigure to execute transactions for a new epoch.
-    fn new_epoch(&self, epoch_state: &EpochState);
+    // Reconfigure to execute transactions for a new epoch, resolving the block payloads with
+    // the payload manager of the epoch.
+    fn new_epoch(&self, epoch_state: &EpochState, payload_manager: Arc<dyn PayloadManager>);
 }
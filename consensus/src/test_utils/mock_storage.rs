@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    consensusdb::ConsensusDB,
     epoch_manager::LivenessStorageData,
     persistent_liveness_storage::{
         LedgerRecoveryData, PersistentLivenessStorage, RecoveryData, RootMetadata,
@@ -229,6 +230,10 @@ impl PersistentLivenessStorage for MockStorage {
     fn aptos_db(&self) -> Arc<dyn DbReader> {
         unimplemented!()
     }
+
+    fn consensus_db(&self) -> Arc<ConsensusDB> {
+        unimplemented!()
+    }
 }
 
 /// A storage that ignores any requests, used in the tests that don't care about the storage.
@@ -296,4 +301,8 @@ impl PersistentLivenessStorage for EmptyStorage {
     fn aptos_db(&self) -> Arc<dyn DbReader> {
         unimplemented!()
     }
+
+    fn consensus_db(&self) -> Arc<ConsensusDB> {
+        unimplemented!()
+    }
 }
@@ -2,4 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod basic_twins_test;
+mod randomized_twins_test;
+mod scenario_generator;
 mod twins_node;
@@ -0,0 +1,126 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::twins::scenario_generator::{
+    partitions, RoundConfig, ScenarioGenerator, ScenarioSource, TwinsFailure, TwinsScenario,
+};
+
+/// Number of scenarios sampled by each run of `randomized_twins_test`.
+const NUM_SAMPLED_SCENARIOS: u64 = 3;
+
+fn run_scenario(scenario: &TwinsScenario) {
+    if let Err(failure) = scenario.run() {
+        panic!(
+            "[TwinsTest] {} in scenario {}: {:?}",
+            failure, scenario.source, scenario
+        );
+    }
+}
+
+#[test]
+fn partitions_test() {
+    // 1 + (2^4 - 1) ways to split 5 ids into at most 2 partitions
+    let two_partitions = partitions(5, 2);
+    assert_eq!(two_partitions.len(), 16);
+    for split in &two_partitions {
+        let mut ids = split.concat();
+        ids.sort_unstable();
+        assert_eq!(ids, (0..5).collect::<Vec<_>>());
+        assert!(split.iter().all(|partition| !partition.is_empty()));
+    }
+    // Bell number B4
+    assert_eq!(partitions(4, 4).len(), 15);
+    assert_eq!(partitions(4, 1), vec![vec![vec![0, 1, 2, 3]]]);
+}
+
+#[test]
+fn scenario_generator_test() {
+    let generator = ScenarioGenerator::new(4, 1, 3, 2);
+    // 4 leaders times 16 ways to split the 5 nodes, for each round
+    assert_eq!(generator.num_scenarios(), 64u64.pow(3));
+    assert_eq!(generator.sample(7), generator.sample(7));
+    assert_eq!(generator.sample(7).rounds.len(), 3);
+
+    let scenarios: Vec<_> = generator.enumerate().take(65).collect();
+    assert_eq!(scenarios[5], generator.nth(5));
+    assert_eq!(scenarios[0].rounds[1..], scenarios[63].rounds[1..]);
+    assert_ne!(scenarios[0].rounds[0], scenarios[63].rounds[0]);
+    assert_eq!(scenarios[0].rounds[0], scenarios[64].rounds[0]);
+    assert_ne!(scenarios[0].rounds[1], scenarios[64].rounds[1]);
+}
+
+#[test]
+/// This test samples scenarios over 4 nodes, 1 of which has a twin. The twin doesn't
+/// exceed the byzantine tolerance, so all the nodes are expected to commit the same blocks
+/// and to make progress once the network heals.
+///
+/// Run the test:
+/// cargo xtest -p consensus randomized_twins_test -- --nocapture
+///
+/// The scenarios are sampled from fixed seeds, so that the same partitions and leaders are
+/// tested on every run. The runs themselves aren't deterministic: the network playground runs
+/// in real time with 1s round timeouts, so the timing of the messages may differ between runs
+/// of the same scenario. Rerun a scenario, e.g. a failed one, from its seed:
+/// TWINS_SEED=<seed> cargo xtest -p consensus randomized_twins_test -- --nocapture
+fn randomized_twins_test() {
+    let generator = ScenarioGenerator::new(4, 1, 4, 2);
+    let seeds: Vec<u64> = match std::env::var("TWINS_SEED") {
+        Ok(seed) => vec![seed.parse().expect("TWINS_SEED should be a u64")],
+        Err(_) => (0..NUM_SAMPLED_SCENARIOS).collect(),
+    };
+    for seed in seeds {
+        println!("[TwinsTest] Running scenario [seed: {}]", seed);
+        run_scenario(&generator.sample(seed));
+    }
+}
+
+#[test]
+#[ignore]
+/// This test runs all the single round scenarios over 4 nodes, 1 of which has a twin.
+///
+/// Run the test:
+/// cargo xtest -p consensus exhaustive_twins_test -- --ignored --nocapture
+fn exhaustive_twins_test() {
+    let generator = ScenarioGenerator::new(4, 1, 1, 2);
+    for scenario in generator.enumerate() {
+        println!("[TwinsTest] Running scenario {}", scenario.source);
+        run_scenario(&scenario);
+    }
+}
+
+#[test]
+/// This test checks that the checker catches a safety violation when the twins exceed the
+/// byzantine tolerance.
+///
+/// Setup:
+///
+/// 4 honest nodes (n0, n1, n2, n3), and 2 twins (twin0, twin1)
+///
+/// Split 2 partitions in the first 9 rounds, both led by n0 or twin0:
+/// p1 = [n0, n1, n2], p2 = [n3, twin0, twin1]
+///
+/// Each partition has a quorum and commits its own chain.
+///
+/// Run the test:
+/// cargo xtest -p consensus twins_safety_violation_test -- --nocapture
+fn twins_safety_violation_test() {
+    let partitions = vec![vec![0, 1, 2], vec![3, 4, 5]];
+    let scenario = TwinsScenario {
+        source: ScenarioSource::Handwritten,
+        num_nodes: 4,
+        num_twins: 2,
+        rounds: (0..9)
+            .map(|_| RoundConfig {
+                leader: 0,
+                partitions: partitions.clone(),
+            })
+            .collect(),
+    };
+    match scenario.run() {
+        Err(TwinsFailure::Safety { first, second, .. }) => {
+            let side = |node: usize| partitions[0].contains(&node);
+            assert_ne!(side(first.0), side(second.0));
+        }
+        result => panic!("Expected a safety violation, got {:?}", result),
+    }
+}
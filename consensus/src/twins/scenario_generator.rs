@@ -0,0 +1,358 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generates Twins scenarios, i.e. the leaders and the network partitions of each round over
+//! validators some of which have a twin sharing their identity, and runs them over the
+//! `NetworkPlayground` checking the safety and the liveness of the commits.
+
+use crate::{
+    network_tests::{NetworkPlayground, TwinId},
+    test_utils::consensus_runtime,
+    twins::twins_node::SMRNode,
+};
+use aptos_crypto::HashValue;
+use aptos_types::{
+    block_info::BlockInfo, ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::ProposerElectionType::RoundProposer,
+};
+use consensus_types::{block::Block, common::Round};
+use futures::{
+    stream::{select, select_all},
+    StreamExt,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{cmp::max, collections::HashMap, fmt, time::Duration};
+use tokio::time::timeout;
+
+/// Rounds without partitions and led by the nodes without a twin, closing every scenario.
+const NUM_SYNC_ROUNDS: u64 = 10;
+/// The nodes time out of the rounds without a quorum in their partition.
+const ROUND_TIMEOUT_MS: u64 = 1_000;
+/// Bound on the time for all the nodes to commit a block of the synchronous rounds.
+const LIVENESS_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Identifies a scenario of a `ScenarioGenerator`, to reproduce it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScenarioSource {
+    Sampled { seed: u64 },
+    Enumerated { index: u64 },
+    Handwritten,
+}
+
+impl fmt::Display for ScenarioSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScenarioSource::Sampled { seed } => write!(f, "[seed: {}]", seed),
+            ScenarioSource::Enumerated { index } => write!(f, "[index: {}]", index),
+            ScenarioSource::Handwritten => write!(f, "[handwritten]"),
+        }
+    }
+}
+
+/// The leader and the partitions of a round. The nodes are identified by their index: node `i`
+/// is the `i`th node, and the twin of node `i` is node `num_nodes + i`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoundConfig {
+    /// The twin of the leader, if any, proposes as well
+    pub leader: usize,
+    pub partitions: Vec<Vec<usize>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TwinsScenario {
+    pub source: ScenarioSource,
+    pub num_nodes: usize,
+    /// The first `num_twins` nodes have a twin
+    pub num_twins: usize,
+    /// Configs of the rounds starting from round 1, followed by `NUM_SYNC_ROUNDS` synchronous
+    /// rounds.
+    pub rounds: Vec<RoundConfig>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TwinsFailure {
+    /// Two nodes committed different blocks on top of the same parent, i.e. their committed
+    /// chains aren't prefixes of each other
+    Safety {
+        parent: HashValue,
+        first: (usize, HashValue),
+        second: (usize, HashValue),
+    },
+    /// Some nodes didn't commit a block of the synchronous rounds in time
+    Liveness { lagging_nodes: Vec<usize> },
+}
+
+impl fmt::Display for TwinsFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TwinsFailure::Safety {
+                parent,
+                first,
+                second,
+            } => write!(
+                f,
+                "Safety violation: node {} committed {} and node {} committed {} on top of {}",
+                first.0, first.1, second.0, second.1, parent
+            ),
+            TwinsFailure::Liveness { lagging_nodes } => write!(
+                f,
+                "Liveness violation: nodes {:?} didn't commit a block of the synchronous rounds \
+                 in {:?}",
+                lagging_nodes, LIVENESS_TIMEOUT
+            ),
+        }
+    }
+}
+
+/// Returns all the ways to split the nodes `0..num_ids` into at most `max_partitions` non-empty
+/// partitions, a single partition meaning the network isn't split.
+pub fn partitions(num_ids: usize, max_partitions: usize) -> Vec<Vec<Vec<usize>>> {
+    assert!(num_ids > 0 && max_partitions > 0);
+    // Enumerates the restricted growth strings: the partition of each id is at most one more
+    // than the highest partition of the ids before it.
+    let mut assignment = vec![0; num_ids];
+    let mut result = vec![];
+    loop {
+        let num_partitions = assignment.iter().max().unwrap() + 1;
+        let mut partitions = vec![vec![]; num_partitions];
+        for (id, partition) in assignment.iter().enumerate() {
+            partitions[*partition].push(id);
+        }
+        result.push(partitions);
+
+        let next = (1..num_ids).rev().find(|i| {
+            assignment[*i] + 1 < max_partitions
+                && assignment[*i] <= *assignment[..*i].iter().max().unwrap()
+        });
+        match next {
+            Some(i) => {
+                assignment[i] += 1;
+                assignment[i + 1..].iter_mut().for_each(|p| *p = 0);
+            }
+            None => return result,
+        }
+    }
+}
+
+/// Generates the scenarios of `num_rounds` rounds over `num_nodes` nodes, the first `num_twins`
+/// of which have a twin, with up to `max_partitions` partitions per round.
+pub struct ScenarioGenerator {
+    num_nodes: usize,
+    num_twins: usize,
+    num_rounds: u64,
+    round_configs: Vec<RoundConfig>,
+}
+
+impl ScenarioGenerator {
+    pub fn new(num_nodes: usize, num_twins: usize, num_rounds: u64, max_partitions: usize) -> Self {
+        assert!(
+            num_twins < num_nodes,
+            "The synchronous rounds need leaders without a twin"
+        );
+        let partitions = partitions(num_nodes + num_twins, max_partitions);
+        let round_configs = (0..num_nodes)
+            .flat_map(|leader| {
+                partitions.iter().map(move |partitions| RoundConfig {
+                    leader,
+                    partitions: partitions.clone(),
+                })
+            })
+            .collect();
+        Self {
+            num_nodes,
+            num_twins,
+            num_rounds,
+            round_configs,
+        }
+    }
+
+    /// Number of scenarios enumerated, saturating at `u64::MAX`.
+    pub fn num_scenarios(&self) -> u64 {
+        (0..self.num_rounds).fold(1u64, |num_scenarios, _| {
+            num_scenarios.saturating_mul(self.round_configs.len() as u64)
+        })
+    }
+
+    /// Picks the config of each round at random, deterministically from the seed.
+    pub fn sample(&self, seed: u64) -> TwinsScenario {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let rounds = (0..self.num_rounds)
+            .map(|_| self.round_configs[rng.gen_range(0, self.round_configs.len())].clone())
+            .collect();
+        self.scenario(ScenarioSource::Sampled { seed }, rounds)
+    }
+
+    /// Returns the scenario at `index` of the enumeration, where the config of the first round
+    /// changes the fastest.
+    pub fn nth(&self, index: u64) -> TwinsScenario {
+        let num_configs = self.round_configs.len() as u64;
+        let mut remaining = index;
+        let rounds = (0..self.num_rounds)
+            .map(|_| {
+                let config = self.round_configs[(remaining % num_configs) as usize].clone();
+                remaining /= num_configs;
+                config
+            })
+            .collect();
+        self.scenario(ScenarioSource::Enumerated { index }, rounds)
+    }
+
+    pub fn enumerate(&self) -> impl Iterator<Item = TwinsScenario> + '_ {
+        (0..self.num_scenarios()).map(move |index| self.nth(index))
+    }
+
+    fn scenario(&self, source: ScenarioSource, rounds: Vec<RoundConfig>) -> TwinsScenario {
+        TwinsScenario {
+            source,
+            num_nodes: self.num_nodes,
+            num_twins: self.num_twins,
+            rounds,
+        }
+    }
+}
+
+impl TwinsScenario {
+    fn first_sync_round(&self) -> Round {
+        self.rounds.len() as Round + 1
+    }
+
+    /// Leaders of the rounds, the synchronous rounds are led by the nodes without a twin in turn.
+    fn round_proposers(&self) -> HashMap<Round, usize> {
+        let num_honest_nodes = self.num_nodes - self.num_twins;
+        let sync_rounds = (0..NUM_SYNC_ROUNDS).map(|i| {
+            (
+                self.first_sync_round() + i,
+                self.num_twins + i as usize % num_honest_nodes,
+            )
+        });
+        self.rounds
+            .iter()
+            .enumerate()
+            .map(|(i, config)| (i as Round + 1, config.leader))
+            .chain(sync_rounds)
+            .collect()
+    }
+
+    /// Runs the scenario over the `NetworkPlayground` until all the nodes commit a block of the
+    /// synchronous rounds, failing as soon as the committed chains of two nodes diverge.
+    pub fn run(&self) -> Result<(), TwinsFailure> {
+        let runtime = consensus_runtime();
+        let mut playground = NetworkPlayground::new(runtime.handle().clone());
+        let mut nodes = SMRNode::start_num_nodes_with_twins_and_timeout(
+            self.num_nodes,
+            self.num_twins,
+            &mut playground,
+            RoundProposer(HashMap::new()),
+            Some(self.round_proposers()),
+            ROUND_TIMEOUT_MS,
+        );
+        let twin_ids: Vec<TwinId> = nodes.iter().map(|node| node.id).collect();
+        let round_partitions: HashMap<Round, Vec<Vec<TwinId>>> = self
+            .rounds
+            .iter()
+            .enumerate()
+            .filter(|(_, config)| config.partitions.len() > 1)
+            .map(|(i, config)| {
+                let partitions = config
+                    .partitions
+                    .iter()
+                    .map(|partition| partition.iter().map(|node| twin_ids[*node]).collect())
+                    .collect();
+                (i as Round + 1, partitions)
+            })
+            .collect();
+        assert!(playground.split_network_round(&round_partitions));
+        runtime.spawn(playground.start());
+
+        let first_sync_round = self.first_sync_round();
+        let mut checker = CommitChecker::new(nodes.len());
+        runtime.block_on(async {
+            let mut commits = select_all(nodes.iter_mut().enumerate().map(|(node, smr_node)| {
+                let ledger_infos = smr_node
+                    .commit_cb_receiver
+                    .by_ref()
+                    .map(CommitEvent::LedgerInfo);
+                let blocks = smr_node
+                    .committed_blocks_receiver
+                    .by_ref()
+                    .map(CommitEvent::Blocks);
+                select(ledger_infos, blocks).map(move |event| (node, event))
+            }));
+            let result = timeout(LIVENESS_TIMEOUT, async {
+                while let Some((node, event)) = commits.next().await {
+                    match event {
+                        CommitEvent::Blocks(blocks) => checker.record_blocks(node, &blocks)?,
+                        CommitEvent::LedgerInfo(ledger_info) => {
+                            checker.record_ledger_info(node, ledger_info.commit_info());
+                            if checker.lagging_nodes(first_sync_round).is_empty() {
+                                return Ok(());
+                            }
+                        }
+                    }
+                }
+                Err(TwinsFailure::Liveness {
+                    lagging_nodes: checker.lagging_nodes(first_sync_round),
+                })
+            })
+            .await;
+            result.unwrap_or_else(|_| {
+                Err(TwinsFailure::Liveness {
+                    lagging_nodes: checker.lagging_nodes(first_sync_round),
+                })
+            })
+        })
+    }
+}
+
+enum CommitEvent {
+    /// Blocks committed by the node, in order
+    Blocks(Vec<Block>),
+    /// Ledger info the node committed or synced to
+    LedgerInfo(LedgerInfoWithSignatures),
+}
+
+/// Tracks the commits of the nodes. Every committed block is recorded along with its parent:
+/// the committed chains of the nodes are prefixes of each other iff no two different blocks are
+/// committed on top of the same parent.
+struct CommitChecker {
+    // The first block committed on top of each block, and the node which committed it
+    committed_children: HashMap<HashValue, (usize, HashValue)>,
+    highest_committed_rounds: Vec<Round>,
+}
+
+impl CommitChecker {
+    fn new(num_nodes: usize) -> Self {
+        Self {
+            committed_children: HashMap::new(),
+            highest_committed_rounds: vec![0; num_nodes],
+        }
+    }
+
+    fn record_blocks(&mut self, node: usize, blocks: &[Block]) -> Result<(), TwinsFailure> {
+        for block in blocks {
+            let first = *self
+                .committed_children
+                .entry(block.parent_id())
+                .or_insert((node, block.id()));
+            if first.1 != block.id() {
+                return Err(TwinsFailure::Safety {
+                    parent: block.parent_id(),
+                    first,
+                    second: (node, block.id()),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn record_ledger_info(&mut self, node: usize, commit_info: &BlockInfo) {
+        self.highest_committed_rounds[node] =
+            max(self.highest_committed_rounds[node], commit_info.round());
+    }
+
+    fn lagging_nodes(&self, round: Round) -> Vec<usize> {
+        (0..self.highest_committed_rounds.len())
+            .filter(|node| self.highest_committed_rounds[*node] < round)
+            .collect()
+    }
+}
@@ -5,9 +5,11 @@ use crate::{
     commit_notifier::QuorumStoreCommitNotifier,
     counters,
     epoch_manager::EpochManager,
+    error::StateSyncError,
     network::NetworkTask,
     network_interface::{ConsensusNetworkEvents, ConsensusNetworkSender},
     network_tests::{NetworkPlayground, TwinId},
+    state_replication::{PayloadManager, StateComputer, StateComputerCommitCallBackType},
     test_utils::{MockStateComputer, MockStorage},
     util::time_service::ClockTimeService,
 };
@@ -16,8 +18,10 @@ use aptos_config::{
     generator::{self, ValidatorSwarm},
     network_id::NetworkId,
 };
+use aptos_crypto::HashValue;
 use aptos_mempool::mocks::MockSharedMempool;
 use aptos_types::{
+    epoch_state::EpochState,
     ledger_info::LedgerInfoWithSignatures,
     on_chain_config::{
         ConsensusConfigV1, OnChainConfig, OnChainConfigPayload, OnChainConsensusConfig,
@@ -29,8 +33,13 @@ use aptos_types::{
     waypoint::Waypoint,
 };
 use channel::{self, aptos_channel, message_queues::QueueStyle};
-use consensus_types::common::{Author, Round};
+use consensus_types::{
+    block::Block,
+    common::{Author, Round},
+    executed_block::ExecutedBlock,
+};
 use event_notifications::{ReconfigNotification, ReconfigNotificationListener};
+use executor_types::{Error as ExecutionError, StateComputeResult};
 use futures::channel::mpsc;
 use network::{
     peer_manager::{conn_notifs_channel, ConnectionRequestSender, PeerManagerRequestSender},
@@ -44,11 +53,55 @@ use network::{
 use std::{collections::HashMap, iter::FromIterator, sync::Arc};
 use tokio::runtime::{Builder, Runtime};
 
+/// Forwards the blocks committed by the node, so that the committed chains of the nodes can be
+/// checked against each other.
+struct CommittedBlocksStateComputer {
+    inner: MockStateComputer,
+    committed_blocks_sender: mpsc::UnboundedSender<Vec<Block>>,
+}
+
+#[async_trait::async_trait]
+impl StateComputer for CommittedBlocksStateComputer {
+    async fn compute(
+        &self,
+        block: &Block,
+        parent_block_id: HashValue,
+    ) -> Result<StateComputeResult, ExecutionError> {
+        self.inner.compute(block, parent_block_id).await
+    }
+
+    async fn commit(
+        &self,
+        blocks: &[Arc<ExecutedBlock>],
+        finality_proof: LedgerInfoWithSignatures,
+        callback: StateComputerCommitCallBackType,
+    ) -> Result<(), ExecutionError> {
+        let committed_blocks = blocks.iter().map(|block| block.block().clone()).collect();
+        self.inner.commit(blocks, finality_proof, callback).await?;
+        // it may fail during shutdown
+        let _ = self
+            .committed_blocks_sender
+            .unbounded_send(committed_blocks);
+        Ok(())
+    }
+
+    async fn sync_to(&self, target: LedgerInfoWithSignatures) -> Result<(), StateSyncError> {
+        self.inner.sync_to(target).await
+    }
+
+    fn new_epoch(&self, epoch_state: &EpochState, payload_manager: Arc<dyn PayloadManager>) {
+        self.inner.new_epoch(epoch_state, payload_manager)
+    }
+}
+
 /// Auxiliary struct that is preparing SMR for the test
 pub struct SMRNode {
     pub id: TwinId,
     pub storage: Arc<MockStorage>,
     pub commit_cb_receiver: mpsc::UnboundedReceiver<LedgerInfoWithSignatures>,
+    /// Every block committed by the node, in order, unlike the ledger infos of
+    /// `commit_cb_receiver` which skip the blocks committed along with their descendants.
+    pub committed_blocks_receiver: mpsc::UnboundedReceiver<Vec<Block>>,
     _runtime: Runtime,
     _shared_mempool: MockSharedMempool,
     _state_sync: mpsc::UnboundedReceiver<Vec<SignedTransaction>>,
@@ -82,13 +135,17 @@ impl SMRNode {
 
         let (state_sync_client, state_sync) = mpsc::unbounded();
         let (commit_cb_sender, commit_cb_receiver) = mpsc::unbounded::<LedgerInfoWithSignatures>();
+        let (committed_blocks_sender, committed_blocks_receiver) = mpsc::unbounded();
         let shared_mempool = MockSharedMempool::new();
         let (quorum_store_to_mempool_sender, _) = mpsc::channel(1_024);
-        let state_computer = Arc::new(MockStateComputer::new(
-            state_sync_client,
-            commit_cb_sender,
-            Arc::clone(&storage),
-        ));
+        let state_computer = Arc::new(CommittedBlocksStateComputer {
+            inner: MockStateComputer::new(
+                state_sync_client,
+                commit_cb_sender,
+                Arc::clone(&storage),
+            ),
+            committed_blocks_sender,
+        });
         let (reconfig_sender, reconfig_events) = aptos_channel::new(QueueStyle::LIFO, 1, None);
         let reconfig_listener = ReconfigNotificationListener {
             notification_receiver: reconfig_events,
@@ -143,6 +200,7 @@ impl SMRNode {
             storage.clone(),
             reconfig_listener,
             commit_notifier,
+            None,
         );
         let (network_task, network_receiver) = NetworkTask::new(network_events, self_receiver);
 
@@ -152,6 +210,7 @@ impl SMRNode {
             id: twin_id,
             _runtime: runtime,
             commit_cb_receiver,
+            committed_blocks_receiver,
             storage,
             _shared_mempool: shared_mempool,
             _state_sync: state_sync,
@@ -165,6 +224,27 @@ impl SMRNode {
         playground: &mut NetworkPlayground,
         proposer_type: ProposerElectionType,
         round_proposers_idx: Option<HashMap<Round, usize>>,
+    ) -> Vec<Self> {
+        // Disable timeout in twins test to avoid flakiness
+        Self::start_num_nodes_with_twins_and_timeout(
+            num_nodes,
+            num_twins,
+            playground,
+            proposer_type,
+            round_proposers_idx,
+            2_000_000,
+        )
+    }
+
+    /// Starts a given number of nodes and their twins, the nodes time out of the rounds without
+    /// progress after `round_initial_timeout_ms`.
+    pub fn start_num_nodes_with_twins_and_timeout(
+        num_nodes: usize,
+        num_twins: usize,
+        playground: &mut NetworkPlayground,
+        proposer_type: ProposerElectionType,
+        round_proposers_idx: Option<HashMap<Round, usize>>,
+        round_initial_timeout_ms: u64,
     ) -> Vec<Self> {
         assert!(num_nodes >= num_twins);
         let ValidatorSwarm {
@@ -235,8 +315,7 @@ impl SMRNode {
                 .waypoint = Some(waypoint);
             config.base.waypoint = WaypointConfig::FromConfig(waypoint);
             config.consensus.safety_rules.verify_vote_proposal_signature = false;
-            // Disable timeout in twins test to avoid flakiness
-            config.consensus.round_initial_timeout_ms = 2_000_000;
+            config.consensus.round_initial_timeout_ms = round_initial_timeout_ms;
 
             let author = author_from_config(&config);
 
@@ -122,6 +122,7 @@ impl GenesisInfo {
             false,
             NO_OP_STORAGE_PRUNER_CONFIG,
             RocksdbConfig::default(),
+            false, /* enable_event_by_type_index */
         )?;
         let db_rw = DbReaderWriter::new(aptosdb);
         executor::db_bootstrapper::generate_waypoint::<AptosVM>(&db_rw, genesis)
@@ -192,6 +192,22 @@ impl Client {
         self.json(response).await
     }
 
+    pub async fn get_pending_transactions(
+        &self,
+        limit: Option<u64>,
+    ) -> Result<Response<Vec<Transaction>>> {
+        let url = self.base_url.join("transactions/pending")?;
+
+        let mut request = self.inner.get(url);
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)])
+        }
+
+        let response = request.send().await?;
+
+        self.json(response).await
+    }
+
     pub async fn get_transaction(&self, hash: HashValue) -> Result<Response<Transaction>> {
         self.json(
             self.get_transaction_by_version_or_hash(hash.to_hex_literal())
@@ -333,7 +349,7 @@ impl Client {
     ) -> Result<(reqwest::Response, State)> {
         if !response.status().is_success() {
             let error_response = response.json::<RestError>().await?;
-            return Err(anyhow::anyhow!("Request failed: {:?}", error_response));
+            return Err(error_response.into());
         }
         let state = State::from_headers(response.headers())?;
 
@@ -5,7 +5,14 @@ use crate::{
     common::EmptyRequest,
     types::{
         AccountBalanceRequest, AccountBalanceResponse, BlockRequest, BlockResponse,
+        ConstructionCombineRequest, ConstructionCombineResponse, ConstructionDeriveRequest,
+        ConstructionDeriveResponse, ConstructionHashRequest, ConstructionMetadataRequest,
+        ConstructionMetadataResponse, ConstructionParseRequest, ConstructionParseResponse,
+        ConstructionPayloadsRequest, ConstructionPayloadsResponse, ConstructionPreprocessRequest,
+        ConstructionPreprocessResponse, ConstructionSubmitRequest, ConstructionSubmitResponse,
+        MempoolRequest, MempoolResponse, MempoolTransactionRequest, MempoolTransactionResponse,
         NetworkListResponse, NetworkOptionsResponse, NetworkRequest, NetworkStatusResponse,
+        SearchTransactionsRequest, SearchTransactionsResponse, TransactionIdentifierResponse,
     },
 };
 use aptos_rest_client::aptos_api_types::mime_types::JSON;
@@ -53,6 +60,153 @@ impl RosettaClient {
         self.json(response).await
     }
 
+    pub async fn construction_combine(
+        &self,
+        request: &ConstructionCombineRequest,
+    ) -> anyhow::Result<ConstructionCombineResponse> {
+        let response = self
+            .inner
+            .post(self.address.join("construction/combine").unwrap())
+            .header(CONTENT_TYPE, JSON)
+            .body(serde_json::to_string(request)?)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
+    pub async fn construction_derive(
+        &self,
+        request: &ConstructionDeriveRequest,
+    ) -> anyhow::Result<ConstructionDeriveResponse> {
+        let response = self
+            .inner
+            .post(self.address.join("construction/derive").unwrap())
+            .header(CONTENT_TYPE, JSON)
+            .body(serde_json::to_string(request)?)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
+    pub async fn construction_hash(
+        &self,
+        request: &ConstructionHashRequest,
+    ) -> anyhow::Result<TransactionIdentifierResponse> {
+        let response = self
+            .inner
+            .post(self.address.join("construction/hash").unwrap())
+            .header(CONTENT_TYPE, JSON)
+            .body(serde_json::to_string(request)?)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
+    pub async fn construction_metadata(
+        &self,
+        request: &ConstructionMetadataRequest,
+    ) -> anyhow::Result<ConstructionMetadataResponse> {
+        let response = self
+            .inner
+            .post(self.address.join("construction/metadata").unwrap())
+            .header(CONTENT_TYPE, JSON)
+            .body(serde_json::to_string(request)?)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
+    pub async fn construction_parse(
+        &self,
+        request: &ConstructionParseRequest,
+    ) -> anyhow::Result<ConstructionParseResponse> {
+        let response = self
+            .inner
+            .post(self.address.join("construction/parse").unwrap())
+            .header(CONTENT_TYPE, JSON)
+            .body(serde_json::to_string(request)?)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
+    pub async fn construction_payloads(
+        &self,
+        request: &ConstructionPayloadsRequest,
+    ) -> anyhow::Result<ConstructionPayloadsResponse> {
+        let response = self
+            .inner
+            .post(self.address.join("construction/payloads").unwrap())
+            .header(CONTENT_TYPE, JSON)
+            .body(serde_json::to_string(request)?)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
+    pub async fn construction_preprocess(
+        &self,
+        request: &ConstructionPreprocessRequest,
+    ) -> anyhow::Result<ConstructionPreprocessResponse> {
+        let response = self
+            .inner
+            .post(self.address.join("construction/preprocess").unwrap())
+            .header(CONTENT_TYPE, JSON)
+            .body(serde_json::to_string(request)?)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
+    pub async fn construction_submit(
+        &self,
+        request: &ConstructionSubmitRequest,
+    ) -> anyhow::Result<ConstructionSubmitResponse> {
+        let response = self
+            .inner
+            .post(self.address.join("construction/submit").unwrap())
+            .header(CONTENT_TYPE, JSON)
+            .body(serde_json::to_string(request)?)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
+    pub async fn mempool(&self, request: &MempoolRequest) -> anyhow::Result<MempoolResponse> {
+        let response = self
+            .inner
+            .post(self.address.join("mempool").unwrap())
+            .header(CONTENT_TYPE, JSON)
+            .body(serde_json::to_string(request)?)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
+    pub async fn mempool_transaction(
+        &self,
+        request: &MempoolTransactionRequest,
+    ) -> anyhow::Result<MempoolTransactionResponse> {
+        let response = self
+            .inner
+            .post(self.address.join("mempool/transaction").unwrap())
+            .header(CONTENT_TYPE, JSON)
+            .body(serde_json::to_string(request)?)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
     pub async fn network_list(&self) -> anyhow::Result<NetworkListResponse> {
         let response = self
             .inner
@@ -95,6 +249,21 @@ impl RosettaClient {
         self.json(response).await
     }
 
+    pub async fn search_transactions(
+        &self,
+        request: &SearchTransactionsRequest,
+    ) -> anyhow::Result<SearchTransactionsResponse> {
+        let response = self
+            .inner
+            .post(self.address.join("search/transactions").unwrap())
+            .header(CONTENT_TYPE, JSON)
+            .body(serde_json::to_string(request)?)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
     async fn json<T: serde::de::DeserializeOwned>(
         &self,
         response: reqwest::Response,
@@ -0,0 +1,739 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rosetta Construction API
+//!
+//! The Construction API builds, signs and submits coin transfers and account creations.  Only
+//! `/construction/metadata` and `/construction/submit` need a connection to a full node, all
+//! other calls can run in offline mode.
+//!
+//! The implementation can be checked against a local node with
+//! `rosetta-cli check:construction --configuration-file rosetta_cli.json`, which expects an
+//! online server on port 8082 and an offline server on port 8083.
+//!
+//! See: [Construction API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html)
+//!
+
+use crate::{
+    common::{check_network, get_account, handle_request, strip_hex_prefix, with_context},
+    error::{ApiError, ApiResult},
+    types::{
+        AccountIdentifier, Amount, ConstructionCombineRequest, ConstructionCombineResponse,
+        ConstructionDeriveRequest, ConstructionDeriveResponse, ConstructionHashRequest,
+        ConstructionMetadata, ConstructionMetadataRequest, ConstructionMetadataResponse,
+        ConstructionParseRequest, ConstructionParseResponse, ConstructionPayloadsRequest,
+        ConstructionPayloadsResponse, ConstructionPreprocessRequest,
+        ConstructionPreprocessResponse, ConstructionSubmitRequest, ConstructionSubmitResponse,
+        Currency, CurveType, MetadataOptions, Operation, OperationIdentifier, OperationMetadata,
+        OperationStatusType, OperationType, PublicKey, SignatureType, SigningPayload,
+        TransactionIdentifier, TransactionIdentifierResponse,
+    },
+    RosettaContext,
+};
+use aptos_crypto::{
+    ed25519::{Ed25519PublicKey, Ed25519Signature},
+    ValidCryptoMaterialStringExt,
+};
+use aptos_logger::{debug, trace};
+use aptos_rest_client::aptos_api_types::{
+    Address, HashValue, TransactionPayload as RestTransactionPayload, UserTransactionRequest, U64,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{
+        authenticator::AuthenticationKey, RawTransaction, SignedTransaction, TransactionPayload,
+    },
+    utility_coin::TEST_COIN_TYPE,
+};
+use cached_framework_packages::aptos_stdlib;
+use move_deps::move_core_types::language_storage::TypeTag;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{convert::TryFrom, str::FromStr};
+use warp::Filter;
+
+/// Maximum number of gas units a constructed transaction can use
+pub const DEFAULT_MAX_GAS_AMOUNT: u64 = 1000;
+/// Price of a gas unit for constructed transactions
+pub const DEFAULT_GAS_UNIT_PRICE: u64 = 1;
+/// How long a constructed transaction stays valid after its metadata was retrieved
+pub const TRANSACTION_EXPIRATION_SECS: u64 = 300;
+
+pub fn routes(
+    server_context: RosettaContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::post()
+        .and(
+            warp::path!("construction" / "combine")
+                .and(warp::body::json())
+                .and(with_context(server_context.clone()))
+                .and_then(handle_request(construction_combine)),
+        )
+        .or(warp::path!("construction" / "derive")
+            .and(warp::body::json())
+            .and(with_context(server_context.clone()))
+            .and_then(handle_request(construction_derive)))
+        .or(warp::path!("construction" / "hash")
+            .and(warp::body::json())
+            .and(with_context(server_context.clone()))
+            .and_then(handle_request(construction_hash)))
+        .or(warp::path!("construction" / "metadata")
+            .and(warp::body::json())
+            .and(with_context(server_context.clone()))
+            .and_then(handle_request(construction_metadata)))
+        .or(warp::path!("construction" / "parse")
+            .and(warp::body::json())
+            .and(with_context(server_context.clone()))
+            .and_then(handle_request(construction_parse)))
+        .or(warp::path!("construction" / "payloads")
+            .and(warp::body::json())
+            .and(with_context(server_context.clone()))
+            .and_then(handle_request(construction_payloads)))
+        .or(warp::path!("construction" / "preprocess")
+            .and(warp::body::json())
+            .and(with_context(server_context.clone()))
+            .and_then(handle_request(construction_preprocess)))
+        .or(warp::path!("construction" / "submit")
+            .and(warp::body::json())
+            .and(with_context(server_context))
+            .and_then(handle_request(construction_submit)))
+}
+
+/// Combines an unsigned transaction with its signature into a signed transaction
+///
+/// This should be able to run without a running full node connection
+///
+/// [API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructioncombine)
+async fn construction_combine(
+    request: ConstructionCombineRequest,
+    server_context: RosettaContext,
+) -> ApiResult<ConstructionCombineResponse> {
+    debug!("/construction/combine");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "construction_combine",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let unsigned_txn: RawTransaction =
+        decode_bcs(&request.unsigned_transaction, "UnsignedTransaction")?;
+
+    // Only single signer Ed25519 transactions are supported
+    if request.signatures.len() != 1 {
+        return Err(ApiError::BadSignatureCount);
+    }
+    let signature = request.signatures.first().unwrap();
+    if signature.signature_type != SignatureType::Ed25519 {
+        return Err(ApiError::BadSignatureType);
+    }
+
+    let public_key = decode_public_key(&signature.public_key)?;
+    let signature = Ed25519Signature::from_encoded_string(&signature.hex_bytes)
+        .map_err(|_| ApiError::BadSignature)?;
+
+    let signed_txn = SignedTransaction::new(unsigned_txn, public_key, signature);
+    signed_txn
+        .clone()
+        .check_signature()
+        .map_err(|_| ApiError::BadSignature)?;
+
+    Ok(ConstructionCombineResponse {
+        signed_transaction: encode_bcs(&signed_txn)?,
+    })
+}
+
+/// Derives an account address from a public key
+///
+/// This should be able to run without a running full node connection
+///
+/// [API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructionderive)
+async fn construction_derive(
+    request: ConstructionDeriveRequest,
+    server_context: RosettaContext,
+) -> ApiResult<ConstructionDeriveResponse> {
+    debug!("/construction/derive");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "construction_derive",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let public_key = decode_public_key(&request.public_key)?;
+    let address = AuthenticationKey::ed25519(&public_key).derived_address();
+
+    Ok(ConstructionDeriveResponse {
+        account_identifier: Some(address.into()),
+    })
+}
+
+/// Computes the hash a signed transaction will have once committed
+///
+/// This should be able to run without a running full node connection
+///
+/// [API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructionhash)
+async fn construction_hash(
+    request: ConstructionHashRequest,
+    server_context: RosettaContext,
+) -> ApiResult<TransactionIdentifierResponse> {
+    debug!("/construction/hash");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "construction_hash",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let signed_txn: SignedTransaction =
+        decode_bcs(&request.signed_transaction, "SignedTransaction")?;
+    let hash = HashValue::from(signed_txn.committed_hash());
+
+    Ok(TransactionIdentifierResponse {
+        transaction_identifier: TransactionIdentifier {
+            hash: hash.to_string(),
+        },
+    })
+}
+
+/// Retrieves the on-chain information needed to build the transaction, the sequence number of
+/// the sender in our case
+///
+/// A running full node is required for this API
+///
+/// [API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructionmetadata)
+async fn construction_metadata(
+    request: ConstructionMetadataRequest,
+    server_context: RosettaContext,
+) -> ApiResult<ConstructionMetadataResponse> {
+    debug!("/construction/metadata");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "construction_metadata",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let rest_client = server_context.rest_client()?;
+    let address = AccountAddress::from_str(strip_hex_prefix(&request.options.sender_address))?;
+    let response = get_account(rest_client, address).await?;
+
+    // The expiration is based on the ledger time, so a lagging local clock doesn't matter
+    let expiration_timestamp_secs =
+        response.state().timestamp_usecs / 1_000_000 + TRANSACTION_EXPIRATION_SECS;
+    let metadata = ConstructionMetadata {
+        sequence_number: response.inner().sequence_number,
+        max_gas_amount: DEFAULT_MAX_GAS_AMOUNT,
+        gas_unit_price: DEFAULT_GAS_UNIT_PRICE,
+        expiration_timestamp_secs,
+    };
+    let suggested_fee = Amount {
+        value: (metadata.max_gas_amount * metadata.gas_unit_price).to_string(),
+        currency: Currency::test_coin(),
+    };
+
+    Ok(ConstructionMetadataResponse {
+        metadata,
+        suggested_fee: Some(vec![suggested_fee]),
+    })
+}
+
+/// Parses the operations back out of a signed or unsigned transaction
+///
+/// This should be able to run without a running full node connection
+///
+/// [API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructionparse)
+async fn construction_parse(
+    request: ConstructionParseRequest,
+    server_context: RosettaContext,
+) -> ApiResult<ConstructionParseResponse> {
+    debug!("/construction/parse");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "construction_parse",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let (raw_txn, account_identifier_signers) = if request.signed {
+        let signed_txn: SignedTransaction = decode_bcs(&request.transaction, "SignedTransaction")?;
+        let signers = vec![signed_txn.sender().into()];
+        (signed_txn.into_raw_transaction(), Some(signers))
+    } else {
+        let raw_txn: RawTransaction = decode_bcs(&request.transaction, "UnsignedTransaction")?;
+        (raw_txn, None)
+    };
+
+    let operation = InternalOperation::from_payload(raw_txn.sender(), raw_txn.into_payload())?;
+
+    Ok(ConstructionParseResponse {
+        operations: operation.into_operations(None),
+        account_identifier_signers,
+    })
+}
+
+/// Builds the unsigned transaction and the payload the sender has to sign
+///
+/// This should be able to run without a running full node connection
+///
+/// [API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructionpayloads)
+async fn construction_payloads(
+    request: ConstructionPayloadsRequest,
+    server_context: RosettaContext,
+) -> ApiResult<ConstructionPayloadsResponse> {
+    debug!("/construction/payloads");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "construction_payloads",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let operation = InternalOperation::extract(&request.operations)?;
+    let metadata = request.metadata.ok_or(ApiError::MissingPayloadMetadata)?;
+    let sender = operation.sender();
+
+    let unsigned_txn = RawTransaction::new(
+        sender,
+        metadata.sequence_number,
+        operation.payload(),
+        metadata.max_gas_amount,
+        metadata.gas_unit_price,
+        metadata.expiration_timestamp_secs,
+        server_context.chain_id,
+    );
+    let signing_payload = SigningPayload {
+        address: None,
+        account_identifier: Some(sender.into()),
+        hex_bytes: hex::encode(unsigned_txn.signing_message()),
+        signature_type: Some(SignatureType::Ed25519),
+    };
+
+    Ok(ConstructionPayloadsResponse {
+        unsigned_transaction: encode_bcs(&unsigned_txn)?,
+        payloads: vec![signing_payload],
+    })
+}
+
+/// Determines which on-chain information `/construction/metadata` has to look up for the
+/// operations
+///
+/// This should be able to run without a running full node connection
+///
+/// [API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructionpreprocess)
+async fn construction_preprocess(
+    request: ConstructionPreprocessRequest,
+    server_context: RosettaContext,
+) -> ApiResult<ConstructionPreprocessResponse> {
+    debug!("/construction/preprocess");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "construction_preprocess",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let sender = InternalOperation::extract(&request.operations)?.sender();
+
+    Ok(ConstructionPreprocessResponse {
+        options: Some(MetadataOptions {
+            sender_address: format!("{:#x}", sender),
+        }),
+        required_public_keys: Some(vec![sender.into()]),
+    })
+}
+
+/// Submits a signed transaction to the connected full node
+///
+/// A running full node is required for this API
+///
+/// [API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructionsubmit)
+async fn construction_submit(
+    request: ConstructionSubmitRequest,
+    server_context: RosettaContext,
+) -> ApiResult<ConstructionSubmitResponse> {
+    debug!("/construction/submit");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "construction_submit",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let rest_client = server_context.rest_client()?;
+    let signed_txn: SignedTransaction =
+        decode_bcs(&request.signed_transaction, "SignedTransaction")?;
+    let response = rest_client.submit(&signed_txn).await?;
+
+    Ok(ConstructionSubmitResponse {
+        transaction_identifier: TransactionIdentifier {
+            hash: response.inner().hash.to_string(),
+        },
+    })
+}
+
+/// The transactions that can be built with the Construction API
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum InternalOperation {
+    /// Creates `new_account`, paid for by `sender`
+    CreateAccount {
+        sender: AccountAddress,
+        new_account: AccountAddress,
+    },
+    /// Moves `amount` of the test coin from `sender` to `receiver`
+    Transfer {
+        sender: AccountAddress,
+        receiver: AccountAddress,
+        amount: u64,
+    },
+}
+
+impl InternalOperation {
+    /// Reads the operations of a Construction API request
+    ///
+    /// An account creation is a single `create_account` operation on the new account with the
+    /// paying account in the metadata.  A transfer is a pair of `transfer` operations, a
+    /// withdrawal (negative amount) from the sender and a deposit of the same amount to the
+    /// receiver.
+    fn extract(operations: &[Operation]) -> ApiResult<Self> {
+        match operations {
+            [operation] => {
+                match OperationType::from_str(&operation.operation_type)? {
+                    OperationType::CreateAccount => {}
+                    OperationType::Transfer => {
+                        return Err(ApiError::BadTransferOperations(
+                            "Transfer requires a withdraw and a deposit operation".to_string(),
+                        ))
+                    }
+                }
+                let new_account = operation_account(operation)?;
+                let sender = operation
+                    .metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.sender.as_ref())
+                    .ok_or_else(|| {
+                        ApiError::BadTransferOperations(
+                            "Create account requires a sender in the metadata".to_string(),
+                        )
+                    })?
+                    .account_address()?;
+                Ok(InternalOperation::CreateAccount {
+                    sender,
+                    new_account,
+                })
+            }
+            [first, second] => {
+                for operation in operations {
+                    if OperationType::from_str(&operation.operation_type)?
+                        != OperationType::Transfer
+                    {
+                        return Err(ApiError::BadTransferOperations(format!(
+                            "Unexpected operation {} in a transfer",
+                            operation.operation_type
+                        )));
+                    }
+                }
+                let first_amount = operation_amount(first)?;
+                let second_amount = operation_amount(second)?;
+                let (sender, receiver, amount) = match (first_amount, second_amount) {
+                    (withdrawn, deposited)
+                        if withdrawn < 0 && withdrawn.checked_add(deposited) == Some(0) =>
+                    {
+                        (first, second, deposited)
+                    }
+                    (deposited, withdrawn)
+                        if withdrawn < 0 && withdrawn.checked_add(deposited) == Some(0) =>
+                    {
+                        (second, first, deposited)
+                    }
+                    _ => {
+                        return Err(ApiError::BadTransferOperations(
+                            "Transfer must withdraw and deposit the same amount".to_string(),
+                        ))
+                    }
+                };
+                let amount = u64::try_from(amount).map_err(|_| {
+                    ApiError::BadTransferOperations("Transfer amount is too large".to_string())
+                })?;
+                Ok(InternalOperation::Transfer {
+                    sender: operation_account(sender)?,
+                    receiver: operation_account(receiver)?,
+                    amount,
+                })
+            }
+            _ => Err(ApiError::BadTransferOperations(format!(
+                "Unsupported number of operations: {}",
+                operations.len()
+            ))),
+        }
+    }
+
+    /// Reads the operation back out of a transaction payload built by [`Self::payload`]
+    fn from_payload(sender: AccountAddress, payload: TransactionPayload) -> ApiResult<Self> {
+        let script_function = match payload {
+            TransactionPayload::ScriptFunction(script_function) => script_function,
+            _ => return Err(ApiError::BadTransactionPayload),
+        };
+        let (module, function, ty_args, args) = script_function.into_inner();
+        if module.address() != &AccountAddress::ONE {
+            return Err(ApiError::BadTransactionScript);
+        }
+        match (
+            module.name().as_str(),
+            function.as_str(),
+            ty_args.as_slice(),
+            args.as_slice(),
+        ) {
+            ("Account", "create_account", [], [new_account]) => {
+                Ok(InternalOperation::CreateAccount {
+                    sender,
+                    new_account: bcs::from_bytes(new_account)?,
+                })
+            }
+            ("Coin", "transfer", [coin_type], [receiver, amount]) => {
+                if coin_type != &*TEST_COIN_TYPE {
+                    return Err(ApiError::BadCoin);
+                }
+                Ok(InternalOperation::Transfer {
+                    sender,
+                    receiver: bcs::from_bytes(receiver)?,
+                    amount: bcs::from_bytes(amount)?,
+                })
+            }
+            _ => Err(ApiError::BadTransactionScript),
+        }
+    }
+
+    /// Reads the operation out of a transaction returned by the REST API, if it is one of the
+    /// transactions the Construction API builds
+    pub(crate) fn from_request(request: &UserTransactionRequest) -> Option<Self> {
+        let payload = match &request.payload {
+            RestTransactionPayload::ScriptFunctionPayload(payload) => payload,
+            _ => return None,
+        };
+        let module = &payload.function.module;
+        if AccountAddress::from(&module.address) != AccountAddress::ONE {
+            return None;
+        }
+        let sender = AccountAddress::from(&request.sender);
+        match (
+            module.name.as_str(),
+            payload.function.name.as_str(),
+            payload.type_arguments.as_slice(),
+            payload.arguments.as_slice(),
+        ) {
+            ("Account", "create_account", [], [new_account]) => {
+                Some(InternalOperation::CreateAccount {
+                    sender,
+                    new_account: json_address(new_account)?,
+                })
+            }
+            ("Coin", "transfer", [coin_type], [receiver, amount]) => {
+                if TypeTag::try_from(coin_type.clone()).ok()? != *TEST_COIN_TYPE {
+                    return None;
+                }
+                Some(InternalOperation::Transfer {
+                    sender,
+                    receiver: json_address(receiver)?,
+                    amount: serde_json::from_value::<U64>(amount.clone()).ok()?.0,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn sender(&self) -> AccountAddress {
+        match self {
+            InternalOperation::CreateAccount { sender, .. } => *sender,
+            InternalOperation::Transfer { sender, .. } => *sender,
+        }
+    }
+
+    fn payload(&self) -> TransactionPayload {
+        match self {
+            InternalOperation::CreateAccount { new_account, .. } => {
+                aptos_stdlib::encode_account_create_account(*new_account)
+            }
+            InternalOperation::Transfer {
+                receiver, amount, ..
+            } => aptos_stdlib::encode_test_coin_transfer(*receiver, *amount),
+        }
+    }
+
+    /// Converts to operations in the same shape [`Self::extract`] reads them
+    ///
+    /// The status must only be set for committed transactions
+    pub(crate) fn into_operations(self, status: Option<OperationStatusType>) -> Vec<Operation> {
+        let status = status.map(|status| status.to_string());
+        match self {
+            InternalOperation::CreateAccount {
+                sender,
+                new_account,
+            } => vec![Operation {
+                operation_identifier: OperationIdentifier {
+                    index: 0,
+                    network_index: None,
+                },
+                related_operations: None,
+                operation_type: OperationType::CreateAccount.to_string(),
+                status,
+                account: Some(new_account.into()),
+                amount: None,
+                metadata: Some(OperationMetadata {
+                    sender: Some(sender.into()),
+                }),
+            }],
+            InternalOperation::Transfer {
+                sender,
+                receiver,
+                amount,
+            } => vec![
+                transfer_operation(0, None, sender, format!("-{}", amount), status.clone()),
+                transfer_operation(1, Some(0), receiver, amount.to_string(), status),
+            ],
+        }
+    }
+}
+
+fn transfer_operation(
+    index: u64,
+    related_index: Option<u64>,
+    account: AccountAddress,
+    value: String,
+    status: Option<String>,
+) -> Operation {
+    Operation {
+        operation_identifier: OperationIdentifier {
+            index,
+            network_index: None,
+        },
+        related_operations: related_index.map(|index| {
+            vec![OperationIdentifier {
+                index,
+                network_index: None,
+            }]
+        }),
+        operation_type: OperationType::Transfer.to_string(),
+        status,
+        account: Some(account.into()),
+        amount: Some(Amount {
+            value,
+            currency: Currency::test_coin(),
+        }),
+        metadata: None,
+    }
+}
+
+fn operation_account(operation: &Operation) -> ApiResult<AccountAddress> {
+    operation
+        .account
+        .as_ref()
+        .map(AccountIdentifier::account_address)
+        .transpose()?
+        .ok_or_else(|| ApiError::BadTransferOperations("Operation requires an account".to_string()))
+}
+
+/// Signed amount of the test coin in an operation
+fn operation_amount(operation: &Operation) -> ApiResult<i128> {
+    let amount = operation.amount.as_ref().ok_or_else(|| {
+        ApiError::BadTransferOperations("Transfer operation requires an amount".to_string())
+    })?;
+    if amount.currency != Currency::test_coin() {
+        return Err(ApiError::BadCoin);
+    }
+    i128::from_str(&amount.value)
+        .map_err(|_| ApiError::BadTransferOperations(format!("Invalid amount {}", amount.value)))
+}
+
+fn json_address(value: &serde_json::Value) -> Option<AccountAddress> {
+    serde_json::from_value::<Address>(value.clone())
+        .ok()
+        .map(Into::into)
+}
+
+fn decode_public_key(public_key: &PublicKey) -> ApiResult<Ed25519PublicKey> {
+    if public_key.curve_type != CurveType::Edwards25519 {
+        return Err(ApiError::BadSignatureType);
+    }
+    Ed25519PublicKey::from_encoded_string(&public_key.hex_bytes)
+        .map_err(|_| ApiError::deserialization_failed("Ed25519PublicKey"))
+}
+
+/// Decodes a hex encoded BCS blob
+fn decode_bcs<T: DeserializeOwned>(hex_str: &str, type_name: &str) -> ApiResult<T> {
+    let bytes = hex::decode(strip_hex_prefix(hex_str))?;
+    bcs::from_bytes(&bytes).map_err(|_| ApiError::deserialization_failed(type_name))
+}
+
+/// Encodes a value as a hex encoded BCS blob
+fn encode_bcs<T: Serialize>(value: &T) -> ApiResult<String> {
+    Ok(hex::encode(bcs::to_bytes(value)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SENDER: AccountAddress = AccountAddress::new([1; AccountAddress::LENGTH]);
+    const RECEIVER: AccountAddress = AccountAddress::new([2; AccountAddress::LENGTH]);
+
+    fn transfer(first_amount: String, second_amount: String) -> Vec<Operation> {
+        vec![
+            transfer_operation(0, None, SENDER, first_amount, None),
+            transfer_operation(1, Some(0), RECEIVER, second_amount, None),
+        ]
+    }
+
+    #[test]
+    fn test_extract_transfer() {
+        let expected = InternalOperation::Transfer {
+            sender: SENDER,
+            receiver: RECEIVER,
+            amount: 5,
+        };
+        assert_eq!(
+            InternalOperation::extract(&transfer("-5".to_string(), "5".to_string())).unwrap(),
+            expected
+        );
+        assert_eq!(
+            InternalOperation::extract(&expected.clone().into_operations(None)).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_extract_transfer_extreme_amounts() {
+        let (min, max) = (i128::MIN.to_string(), i128::MAX.to_string());
+        for (first_amount, second_amount) in [
+            (min.clone(), min.clone()),
+            (min.clone(), max.clone()),
+            (max.clone(), min.clone()),
+            (max.clone(), max.clone()),
+            (min.clone(), "-1".to_string()),
+            ("-1".to_string(), min.clone()),
+            (min.clone(), "0".to_string()),
+            // Balanced, but too large for a coin amount
+            (format!("-{}", max), max.clone()),
+        ] {
+            assert!(
+                matches!(
+                    InternalOperation::extract(&transfer(
+                        first_amount.clone(),
+                        second_amount.clone()
+                    )),
+                    Err(ApiError::BadTransferOperations(_))
+                ),
+                "{} {}",
+                first_amount,
+                second_amount
+            );
+        }
+    }
+}
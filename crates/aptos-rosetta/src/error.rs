@@ -40,6 +40,12 @@ pub enum ApiError {
     HistoricBalancesUnsupported,
     #[error("node is offline")]
     NodeIsOffline,
+    #[error("missing payload metadata")]
+    MissingPayloadMetadata,
+    #[error("transaction not found")]
+    TransactionNotFound,
+    #[error("bad search request: {0}")]
+    BadSearchRequest(String),
 }
 
 impl ApiError {
@@ -60,6 +66,9 @@ impl ApiError {
             BadSignatureCount,
             HistoricBalancesUnsupported,
             NodeIsOffline,
+            MissingPayloadMetadata,
+            TransactionNotFound,
+            BadSearchRequest(String::new()),
         ]
     }
 
@@ -80,17 +89,23 @@ impl ApiError {
             BadSignatureCount => 160,
             HistoricBalancesUnsupported => 170,
             NodeIsOffline => 180,
+            MissingPayloadMetadata => 190,
+            TransactionNotFound => 200,
+            BadSearchRequest(_) => 210,
         }
     }
 
     pub fn retriable(&self) -> bool {
-        matches!(self, ApiError::AccountNotFound)
+        matches!(
+            self,
+            ApiError::AccountNotFound | ApiError::TransactionNotFound
+        )
     }
 
     pub fn status_code(&self) -> StatusCode {
         use ApiError::*;
         match self {
-            AccountNotFound => StatusCode::NOT_FOUND,
+            AccountNotFound | TransactionNotFound => StatusCode::NOT_FOUND,
             _ => StatusCode::BAD_REQUEST,
         }
     }
@@ -23,7 +23,10 @@ use warp::{
 
 mod account;
 mod block;
+mod construction;
+mod mempool;
 mod network;
+mod search;
 
 pub mod client;
 pub mod common;
@@ -103,7 +106,10 @@ pub fn routes(
 ) -> impl Filter<Extract = impl Reply, Error = Infallible> + Clone {
     account::routes(context.clone())
         .or(block::routes(context.clone()))
-        .or(network::routes(context))
+        .or(construction::routes(context.clone()))
+        .or(mempool::routes(context.clone()))
+        .or(network::routes(context.clone()))
+        .or(search::routes(context))
         // TODO: Add health check?
         .with(
             warp::cors()
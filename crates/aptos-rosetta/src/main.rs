@@ -105,6 +105,8 @@ impl ServerArgs for OfflineArgs {
             tls_cert_path: self.tls_cert_path.clone(),
             tls_key_path: self.tls_key_path.clone(),
             content_length_limit: self.content_length_limit,
+            max_view_function_gas: None,
+            max_unpaginated_account_state_values: None,
         }
     }
 
@@ -0,0 +1,135 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rosetta Mempool API
+//!
+//! See: [Mempool API Spec](https://www.rosetta-api.org/docs/MempoolApi.html)
+//!
+
+use crate::{
+    common::{check_network, handle_request, strip_hex_prefix, with_context},
+    construction::InternalOperation,
+    error::{ApiError, ApiResult},
+    types::{
+        MempoolRequest, MempoolResponse, MempoolTransactionRequest, MempoolTransactionResponse,
+        Transaction, TransactionIdentifier,
+    },
+    RosettaContext,
+};
+use aptos_crypto::HashValue;
+use aptos_logger::{debug, trace};
+use aptos_rest_client::{aptos_api_types::PendingTransaction, RestError};
+use std::str::FromStr;
+use warp::Filter;
+
+/// Maximum number of pending transactions listed by `/mempool`
+pub const MAX_MEMPOOL_TRANSACTIONS: u64 = 1000;
+
+pub fn routes(
+    server_context: RosettaContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::post()
+        .and(
+            warp::path!("mempool")
+                .and(warp::body::json())
+                .and(with_context(server_context.clone()))
+                .and_then(handle_request(mempool)),
+        )
+        .or(warp::path!("mempool" / "transaction")
+            .and(warp::body::json())
+            .and(with_context(server_context))
+            .and_then(handle_request(mempool_transaction)))
+}
+
+/// Lists the hashes of the transactions waiting in the mempool of the connected full node
+///
+/// Only the transactions ready to be included in a block are listed, highest gas price first.
+///
+/// [API Spec](https://www.rosetta-api.org/docs/MempoolApi.html#mempool)
+async fn mempool(
+    request: MempoolRequest,
+    server_context: RosettaContext,
+) -> ApiResult<MempoolResponse> {
+    debug!("/mempool");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "mempool",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let rest_client = server_context.rest_client()?;
+    let response = rest_client
+        .get_pending_transactions(Some(MAX_MEMPOOL_TRANSACTIONS))
+        .await?;
+    let transaction_identifiers = response
+        .into_inner()
+        .iter()
+        .filter_map(|txn| match txn {
+            aptos_rest_client::Transaction::PendingTransaction(txn) => {
+                Some(TransactionIdentifier {
+                    hash: txn.hash.to_string(),
+                })
+            }
+            _ => None,
+        })
+        .collect();
+
+    Ok(MempoolResponse {
+        transaction_identifiers,
+    })
+}
+
+/// Retrieves a transaction waiting in the mempool by hash
+///
+/// Committed transactions are not found, they are retrieved with `/block` or
+/// `/search/transactions` instead.
+///
+/// [API Spec](https://www.rosetta-api.org/docs/MempoolApi.html#mempooltransaction)
+async fn mempool_transaction(
+    request: MempoolTransactionRequest,
+    server_context: RosettaContext,
+) -> ApiResult<MempoolTransactionResponse> {
+    debug!("/mempool/transaction");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "mempool_transaction",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let rest_client = server_context.rest_client()?;
+    // Allow 0x in front of hash
+    let hash = HashValue::from_str(strip_hex_prefix(&request.transaction_identifier.hash))
+        .map_err(|err| ApiError::AptosError(err.to_string()))?;
+    let response = rest_client.get_transaction(hash).await.map_err(|err| {
+        match err.downcast_ref::<RestError>() {
+            Some(error) if error.code == 404 => ApiError::TransactionNotFound,
+            _ => err.into(),
+        }
+    })?;
+
+    match response.into_inner() {
+        aptos_rest_client::Transaction::PendingTransaction(txn) => Ok(MempoolTransactionResponse {
+            transaction: pending_transaction(txn),
+        }),
+        _ => Err(ApiError::TransactionNotFound),
+    }
+}
+
+fn pending_transaction(txn: PendingTransaction) -> Transaction {
+    // Pending operations have no status, they can still fail
+    let operations = InternalOperation::from_request(&txn.request)
+        .map(|operation| operation.into_operations(None))
+        .unwrap_or_default();
+
+    Transaction {
+        transaction_identifier: TransactionIdentifier {
+            hash: txn.hash.to_string(),
+        },
+        operations,
+        related_transactions: None,
+    }
+}
@@ -0,0 +1,195 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rosetta Search API
+//!
+//! See: [Search API Spec](https://www.rosetta-api.org/docs/SearchApi.html)
+//!
+
+use crate::{
+    common::{check_network, get_account, handle_request, strip_hex_prefix, with_context},
+    construction::InternalOperation,
+    error::{ApiError, ApiResult},
+    types::{
+        BlockIdentifier, BlockTransaction, OperationStatusType, Operator,
+        SearchTransactionsRequest, SearchTransactionsResponse, Transaction,
+    },
+    RosettaContext,
+};
+use aptos_crypto::HashValue;
+use aptos_logger::{debug, trace};
+use aptos_types::account_address::AccountAddress;
+use std::str::FromStr;
+use warp::Filter;
+
+/// Number of transactions returned when the request has no limit
+pub const DEFAULT_SEARCH_LIMIT: u64 = 25;
+/// Maximum number of transactions returned at once, same as the REST API page size limit
+pub const MAX_SEARCH_LIMIT: u64 = 1000;
+
+pub fn routes(
+    server_context: RosettaContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::post().and(
+        warp::path!("search" / "transactions")
+            .and(warp::body::json())
+            .and(with_context(server_context))
+            .and_then(handle_request(search_transactions)),
+    )
+}
+
+/// Searches committed transactions by hash or by sending account
+///
+/// Searching by hash uses the transaction by hash index, and searching by account uses the
+/// transactions by account index of the full node.  For account searches, `offset` is the
+/// sequence number of the first transaction, and `total_count` is the number of transactions
+/// sent by the account, the `max_block` and `success` conditions only filter the page.
+///
+/// [API Spec](https://www.rosetta-api.org/docs/SearchApi.html#searchtransactions)
+async fn search_transactions(
+    request: SearchTransactionsRequest,
+    server_context: RosettaContext,
+) -> ApiResult<SearchTransactionsResponse> {
+    debug!("/search/transactions");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "search_transactions",
+    );
+
+    check_network(request.network_identifier.clone(), &server_context)?;
+
+    let rest_client = server_context.rest_client()?;
+    let offset = request.offset.unwrap_or(0);
+    let limit = request.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    if limit == 0 || limit > MAX_SEARCH_LIMIT {
+        return Err(ApiError::BadSearchRequest(format!(
+            "limit must be between 1 and {}",
+            MAX_SEARCH_LIMIT
+        )));
+    }
+    let sender = request
+        .account_identifier
+        .as_ref()
+        .map(|account| account.account_address())
+        .transpose()?;
+
+    match (&request.transaction_identifier, sender) {
+        (Some(transaction_identifier), sender) => {
+            if sender.is_some() && request.operator == Some(Operator::Or) {
+                return Err(ApiError::BadSearchRequest(
+                    "or operator is not supported".to_string(),
+                ));
+            }
+            // Allow 0x in front of hash
+            let hash = HashValue::from_str(strip_hex_prefix(&transaction_identifier.hash))
+                .map_err(|err| ApiError::AptosError(err.to_string()))?;
+            // Pending and unknown transactions simply don't match
+            let txn = rest_client
+                .get_transaction(hash)
+                .await
+                .ok()
+                .and_then(|response| SearchedTransaction::new(response.inner()))
+                .filter(|txn| sender.is_none() || txn.sender == sender)
+                .filter(|txn| txn.matches(&request));
+            let transactions: Vec<_> = txn
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .map(|txn| txn.txn)
+                .collect();
+
+            Ok(SearchTransactionsResponse {
+                total_count: transactions.len() as u64,
+                transactions,
+                next_offset: None,
+            })
+        }
+        (None, Some(sender)) => {
+            let total_count = get_account(rest_client, sender)
+                .await?
+                .inner()
+                .sequence_number;
+            let txns = rest_client
+                .get_account_transactions(sender, Some(offset), Some(limit))
+                .await?
+                .into_inner();
+
+            // Transactions are in version order, nothing after `max_block` can match anymore
+            let past_max_block = txns.last().map_or(false, |txn| {
+                request.max_block.map_or(false, |max_block| {
+                    txn.version().map_or(false, |version| version > max_block)
+                })
+            });
+            let next_offset = offset + txns.len() as u64;
+            let next_offset = if past_max_block || next_offset >= total_count {
+                None
+            } else {
+                Some(next_offset)
+            };
+            let transactions = txns
+                .iter()
+                .filter_map(SearchedTransaction::new)
+                .filter(|txn| txn.matches(&request))
+                .map(|txn| txn.txn)
+                .collect();
+
+            Ok(SearchTransactionsResponse {
+                transactions,
+                total_count,
+                next_offset,
+            })
+        }
+        (None, None) => Err(ApiError::BadSearchRequest(
+            "transaction_identifier or account_identifier is required".to_string(),
+        )),
+    }
+}
+
+/// A committed transaction along with the fields the search conditions apply to
+struct SearchedTransaction {
+    txn: BlockTransaction,
+    sender: Option<AccountAddress>,
+    success: bool,
+}
+
+impl SearchedTransaction {
+    fn new(txn: &aptos_rest_client::Transaction) -> Option<Self> {
+        let info = txn.transaction_info().ok()?;
+        let status = if info.success {
+            OperationStatusType::Success
+        } else {
+            OperationStatusType::Failure
+        };
+        let (operations, sender) = match txn {
+            aptos_rest_client::Transaction::UserTransaction(txn) => (
+                InternalOperation::from_request(&txn.request)
+                    .map(|operation| operation.into_operations(Some(status)))
+                    .unwrap_or_default(),
+                Some(AccountAddress::from(&txn.request.sender)),
+            ),
+            _ => (vec![], None),
+        };
+
+        Some(SearchedTransaction {
+            txn: BlockTransaction {
+                block_identifier: BlockIdentifier::from(info),
+                transaction: Transaction {
+                    operations,
+                    ..Transaction::from(info)
+                },
+            },
+            sender,
+            success: info.success,
+        })
+    }
+
+    /// Checks the conditions that are not used to look up the transactions
+    fn matches(&self, request: &SearchTransactionsRequest) -> bool {
+        request.max_block.map_or(true, |max_block| {
+            self.txn.block_identifier.index <= max_block
+        }) && request
+            .success
+            .map_or(true, |success| success == self.success)
+    }
+}
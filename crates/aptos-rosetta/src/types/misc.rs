@@ -79,14 +79,16 @@ pub struct Version {
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum OperationType {
+    CreateAccount,
     Transfer,
 }
 
 impl OperationType {
+    const CREATE_ACCOUNT: &'static str = "create_account";
     const TRANSFER: &'static str = "transfer";
 
     pub fn all() -> Vec<OperationType> {
-        vec![OperationType::Transfer]
+        vec![OperationType::CreateAccount, OperationType::Transfer]
     }
 }
 
@@ -95,6 +97,7 @@ impl FromStr for OperationType {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().trim() {
+            Self::CREATE_ACCOUNT => Ok(OperationType::CreateAccount),
             Self::TRANSFER => Ok(OperationType::Transfer),
             _ => Err(ApiError::DeserializationFailed(format!(
                 "Invalid OperationType: {}",
@@ -107,6 +110,7 @@ impl FromStr for OperationType {
 impl Display for OperationType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_str(match self {
+            OperationType::CreateAccount => Self::CREATE_ACCOUNT,
             OperationType::Transfer => Self::TRANSFER,
         })
     }
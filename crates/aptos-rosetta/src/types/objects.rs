@@ -132,9 +132,9 @@ pub enum BlockEventType {
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct BlockTransaction {
     /// Block associated with transaction
-    block_identifier: BlockIdentifier,
+    pub block_identifier: BlockIdentifier,
     /// Transaction associated with block
-    transaction: Transaction,
+    pub transaction: Transaction,
 }
 
 /// Tells what cases are supported in hashes. Having no value is case insensitive.
@@ -232,6 +232,17 @@ pub struct Operation {
     /// TODO: Determine if this is required
     #[serde(skip_serializing_if = "Option::is_none")]
     pub amount: Option<Amount>,
+    /// Operation specific information e.g. the sender of a `create_account` operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<OperationMetadata>,
+}
+
+/// Extra information about an [`Operation`] that doesn't fit in the generic fields
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct OperationMetadata {
+    /// The account paying for the creation of the operation's account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender: Option<AccountIdentifier>,
 }
 
 /// Used for query operations to apply conditions.  Defaults to [`Operator::And`] if no value is
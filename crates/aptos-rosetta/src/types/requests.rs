@@ -2,9 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::types::{
-    AccountIdentifier, Allow, Amount, Block, BlockIdentifier, Currency, NetworkIdentifier,
-    Operation, PartialBlockIdentifier, Peer, PublicKey, Signature, SigningPayload, SyncStatus,
-    Transaction, TransactionIdentifier, Version,
+    AccountIdentifier, Allow, Amount, Block, BlockIdentifier, BlockTransaction, Currency,
+    NetworkIdentifier, Operation, Operator, PartialBlockIdentifier, Peer, PublicKey, Signature,
+    SigningPayload, SyncStatus, Transaction, TransactionIdentifier, Version,
 };
 use aptos_types::chain_id::ChainId;
 use serde::{Deserialize, Serialize};
@@ -159,6 +159,12 @@ pub struct ConstructionMetadataResponse {
 pub struct ConstructionMetadata {
     /// Sequence number of the sending account
     pub sequence_number: u64,
+    /// Maximum amount of gas units the transaction can use
+    pub max_gas_amount: u64,
+    /// Price paid per gas unit
+    pub gas_unit_price: u64,
+    /// Time in seconds since the UNIX_EPOCH after which the transaction can't be committed
+    pub expiration_timestamp_secs: u64,
 }
 
 /// Request to parse a signed or unsigned transaction into operations
@@ -343,6 +349,49 @@ pub struct NetworkStatusResponse {
     pub peers: Vec<Peer>,
 }
 
+/// Request to search for committed transactions matching the given conditions
+///
+/// Only searching by `transaction_identifier` and by `account_identifier` is supported.  An
+/// account matches the transactions it sent, starting at `offset` in sequence number order.
+///
+/// [API Spec](https://www.rosetta-api.org/docs/models/SearchTransactionsRequest.html)
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SearchTransactionsRequest {
+    pub network_identifier: NetworkIdentifier,
+    /// How to combine the conditions, defaults to [`Operator::And`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operator: Option<Operator>,
+    /// Only transactions at or before this version are returned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_block: Option<u64>,
+    /// Offset into the matching transactions to start the page at
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    /// Maximum number of transactions to return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_identifier: Option<TransactionIdentifier>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_identifier: Option<AccountIdentifier>,
+    /// Only return transactions that succeeded or failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+}
+
+/// Response with the page of matching transactions
+///
+/// [API Spec](https://www.rosetta-api.org/docs/models/SearchTransactionsResponse.html)
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SearchTransactionsResponse {
+    pub transactions: Vec<BlockTransaction>,
+    /// Number of transactions matching the conditions, regardless of the page
+    pub total_count: u64,
+    /// Offset of the next page, not set on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<u64>,
+}
+
 /// Response with a transaction that was hashed or submitted
 ///
 /// [API Spec](https://www.rosetta-api.org/docs/models/TransactionIdentifierResponse.html)
@@ -1,32 +1,48 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{ensure, format_err, Context, Result};
+use anyhow::{bail, ensure, format_err, Context, Result};
 use aptos_config::config::{RocksdbConfig, NO_OP_STORAGE_PRUNER_CONFIG};
 use aptos_temppath::TempPath;
-use aptos_types::{transaction::Transaction, waypoint::Waypoint};
+use aptos_types::{
+    transaction::Transaction,
+    trusted_state::{TrustedState, TrustedStateChange},
+    waypoint::Waypoint,
+};
 use aptos_vm::AptosVM;
 use aptosdb::{AptosDB, LEDGER_DB_NAME, STATE_MERKLE_DB_NAME};
 use executor::db_bootstrapper::calculate_genesis;
 use std::{
-    fs::File,
+    fs::{self, File},
     io::Read,
     path::{Path, PathBuf},
 };
-use storage_interface::DbReaderWriter;
+use storage_interface::{DbReader, DbReaderWriter};
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
 #[structopt(
     name = "db-bootstrapper",
-    about = "Calculate, verify and commit the genesis to local DB without a consensus among validators."
+    about = "Calculate, verify and commit the genesis to local DB without a consensus among validators, \
+             or bootstrap the local DB from a checkpoint of another node's DB."
 )]
 struct Opt {
     #[structopt(parse(from_os_str))]
     db_dir: PathBuf,
 
-    #[structopt(short, long, parse(from_os_str))]
-    genesis_txn_file: PathBuf,
+    #[structopt(
+        short,
+        long,
+        parse(from_os_str),
+        required_unless("checkpoint-dir"),
+        conflicts_with("checkpoint-dir")
+    )]
+    genesis_txn_file: Option<PathBuf>,
+
+    /// A checkpoint created by the backup service of a node. It's verified against
+    /// `--waypoint-to-verify` and, with `--commit`, moved into `db_dir`.
+    #[structopt(long, parse(from_os_str), requires("waypoint-to-verify"))]
+    checkpoint_dir: Option<PathBuf>,
 
     #[structopt(short, long)]
     waypoint_to_verify: Option<Waypoint>,
@@ -38,7 +54,15 @@ struct Opt {
 fn main() -> Result<()> {
     let opt = Opt::from_args();
 
-    let genesis_txn = load_genesis_txn(&opt.genesis_txn_file)
+    if let Some(checkpoint_dir) = &opt.checkpoint_dir {
+        return bootstrap_from_checkpoint(&opt, checkpoint_dir);
+    }
+
+    let genesis_txn_file = opt
+        .genesis_txn_file
+        .as_ref()
+        .expect("Required unless bootstrapping from a checkpoint.");
+    let genesis_txn = load_genesis_txn(genesis_txn_file)
         .with_context(|| format_err!("Failed loading genesis txn."))?;
     assert!(
         matches!(genesis_txn, Transaction::GenesisTransaction(_)),
@@ -53,6 +77,7 @@ fn main() -> Result<()> {
             false,
             NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
             RocksdbConfig::default(),
+            false, /* enable_event_by_type_index */
         )
     } else {
         // When not committing, we open the DB as secondary so the tool is usable along side a
@@ -108,6 +133,85 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+fn bootstrap_from_checkpoint(opt: &Opt, checkpoint_dir: &Path) -> Result<()> {
+    let waypoint = opt
+        .waypoint_to_verify
+        .expect("Required when bootstrapping from a checkpoint.");
+
+    let version = {
+        let db = AptosDB::open(
+            checkpoint_dir,
+            true, /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            RocksdbConfig::default(),
+            false, /* enable_event_by_type_index */
+        )
+        .with_context(|| format_err!("Failed to open checkpoint."))?;
+        verify_checkpoint(&db, waypoint)?
+    };
+    println!("Checkpoint at version {} verified.", version);
+
+    if opt.commit {
+        let names = [LEDGER_DB_NAME, STATE_MERKLE_DB_NAME];
+        for name in names {
+            let target = opt.db_dir.join(name);
+            ensure!(!target.exists(), "{:?} already exists.", target);
+        }
+        fs::create_dir_all(&opt.db_dir)?;
+        for name in names {
+            let target = opt.db_dir.join(name);
+            fs::rename(checkpoint_dir.join(name), &target)
+                .with_context(|| format_err!("Failed to move {} into {:?}.", name, target))?;
+        }
+        println!("Successfully bootstrapped DB from checkpoint.");
+    }
+
+    Ok(())
+}
+
+/// Verifies the latest ledger info in the checkpoint against the waypoint, and the latest state
+/// snapshot in the checkpoint against the ledger info. Returns the version of the state snapshot.
+fn verify_checkpoint(db: &AptosDB, waypoint: Waypoint) -> Result<u64> {
+    let state_proof = db
+        .get_state_proof(waypoint.version())
+        .with_context(|| format_err!("Failed to get state proof."))?;
+    let ledger_info = state_proof.latest_ledger_info();
+    let ledger_version = ledger_info.version();
+    match TrustedState::from_epoch_waypoint(waypoint)
+        .verify_and_ratchet(&state_proof)
+        .with_context(|| format_err!("Waypoint verification failed."))?
+    {
+        TrustedStateChange::Epoch { new_state, .. } => ensure!(
+            new_state.version() == ledger_version,
+            "Epoch change proof only reaches version {}, latest ledger info is at version {}.",
+            new_state.version(),
+            ledger_version,
+        ),
+        _ => bail!("Expecting an epoch change from the waypoint."),
+    }
+
+    let (version, root_hash) = db
+        .get_state_snapshot_before(ledger_version + 1)?
+        .ok_or_else(|| format_err!("No state snapshot in checkpoint."))?;
+    let txn_list =
+        db.get_transactions(version, 1, ledger_version, false /* fetch_events */)?;
+    txn_list.verify(ledger_info, Some(version))?;
+    let expected_root_hash = txn_list
+        .proof
+        .transaction_infos
+        .first()
+        .and_then(|txn_info| txn_info.state_checkpoint_hash());
+    ensure!(
+        expected_root_hash == Some(root_hash),
+        "State snapshot at version {} has root hash {}, expected {:?}.",
+        version,
+        root_hash,
+        expected_root_hash,
+    );
+
+    Ok(version)
+}
+
 fn load_genesis_txn(path: &Path) -> Result<Transaction> {
     let mut file = File::open(&path)?;
     let mut buffer = vec![];
@@ -43,6 +43,7 @@ pub fn run(
             false,                 /* readonly */
             storage_pruner_config, /* pruner */
             RocksdbConfig::default(),
+            false, /* enable_event_by_type_index */
         )
         .expect("DB should open."),
     );
@@ -31,6 +31,7 @@ pub fn init_db_and_executor(config: &NodeConfig) -> (DbReaderWriter, BlockExecut
             false,                       /* readonly */
             NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
             RocksdbConfig::default(),
+            false, /* enable_event_by_type_index */
         )
         .expect("DB should open."),
     );
@@ -60,6 +61,7 @@ pub fn run_benchmark(
         true,                        /* readonly */
         NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
         RocksdbConfig::default(),
+        false, /* enable_event_by_type_index */
     )
     .expect("db open failure.")
     .create_checkpoint(checkpoint_dir.as_ref())
@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_config::config::StoragePrunerConfig;
+use aptos_config::config::{LedgerSubPrunerWindows, StoragePrunerConfig};
 use aptos_secure_push_metrics::MetricsPusher;
 use aptos_vm::AptosVM;
 use std::path::PathBuf;
@@ -20,6 +20,7 @@ struct PrunerOpt {
 
     #[structopt(long, default_value = "500")]
     pruning_batch_size: usize,
+    ledger_sub_pruner_windows: LedgerSubPrunerWindows::default(),
 }
 
 impl PrunerOpt {
@@ -36,6 +37,7 @@ impl PrunerOpt {
                 Some(self.ledger_prune_window as u64)
             },
             pruning_batch_size: self.pruning_batch_size,
+            ledger_sub_pruner_windows: LedgerSubPrunerWindows::default(),
         }
     }
 }
@@ -0,0 +1,140 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the on-disk journal of mempool, so that pending transactions survive
+//! node restarts.
+//!
+//! Signed transactions identified by sender and sequence number.
+//! ```text
+//! |<---------key--------->|<----value---->|
+//! | sender | sequence num |  transaction  |
+//! ```
+
+use anyhow::{ensure, Result};
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_types::{account_address::AccountAddress, transaction::SignedTransaction};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+    ColumnFamilyName, Options, ReadOptions, SchemaBatch, DB, DEFAULT_COLUMN_FAMILY_NAME,
+};
+use std::{
+    convert::{TryFrom, TryInto},
+    path::Path,
+    time::Instant,
+};
+
+const TRANSACTION_CF_NAME: ColumnFamilyName = "transaction";
+
+define_schema!(
+    TransactionSchema,
+    (AccountAddress, u64),
+    SignedTransaction,
+    TRANSACTION_CF_NAME
+);
+
+impl KeyCodec<TransactionSchema> for (AccountAddress, u64) {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut encoded = self.0.to_vec();
+        encoded.extend_from_slice(&self.1.to_be_bytes());
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() == AccountAddress::LENGTH + 8,
+            "Unexpected key length: {}",
+            data.len()
+        );
+        let address = AccountAddress::try_from(&data[..AccountAddress::LENGTH])?;
+        let sequence_number = u64::from_be_bytes(data[AccountAddress::LENGTH..].try_into()?);
+        Ok((address, sequence_number))
+    }
+}
+
+impl ValueCodec<TransactionSchema> for SignedTransaction {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
+
+/// MempoolJournal records every transaction inserted into and removed from mempool, the
+/// transactions left in the journal are the content of mempool at the time the node stopped.
+/// The writes are buffered and flushed periodically, so that they don't hold the mempool lock;
+/// the writes since the last flush are lost if the node crashes.
+pub struct MempoolJournal {
+    db: DB,
+    // writes since the last flush, None if there are none
+    pending: Mutex<Option<SchemaBatch>>,
+}
+
+impl MempoolJournal {
+    pub fn new<P: AsRef<Path>>(db_root_path: P) -> Self {
+        let column_families = vec![
+            /* UNUSED CF = */ DEFAULT_COLUMN_FAMILY_NAME,
+            TRANSACTION_CF_NAME,
+        ];
+
+        let path = db_root_path.as_ref().join("mempooldb");
+        let instant = Instant::now();
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open(path.clone(), "mempool", column_families, &opts)
+            .expect("MempoolJournal open failed; unable to continue");
+
+        info!(
+            "Opened MempoolJournal at {:?} in {} ms",
+            path,
+            instant.elapsed().as_millis()
+        );
+
+        Self {
+            db,
+            pending: Mutex::new(None),
+        }
+    }
+
+    pub fn put(&self, txn: &SignedTransaction) -> Result<()> {
+        self.pending
+            .lock()
+            .get_or_insert_with(SchemaBatch::new)
+            .put::<TransactionSchema>(&(txn.sender(), txn.sequence_number()), txn)
+    }
+
+    pub fn delete(&self, address: AccountAddress, sequence_number: u64) -> Result<()> {
+        self.pending
+            .lock()
+            .get_or_insert_with(SchemaBatch::new)
+            .delete::<TransactionSchema>(&(address, sequence_number))
+    }
+
+    /// Writes the pending puts and deletes to disk.
+    pub fn flush(&self) -> Result<()> {
+        let pending = self.pending.lock().take();
+        match pending {
+            Some(batch) => self.db.write_schemas(batch),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the transactions on disk, ordered by sender and sequence number.
+    pub fn transactions(&self) -> Result<Vec<SignedTransaction>> {
+        let mut iter = self.db.iter::<TransactionSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        iter.map(|entry| entry.map(|(_, txn)| txn)).collect()
+    }
+}
+
+impl Drop for MempoolJournal {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            error!(error = ?e, "Failed to flush the MempoolJournal");
+        }
+    }
+}
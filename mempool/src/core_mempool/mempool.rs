@@ -6,6 +6,7 @@
 use crate::{
     core_mempool::{
         index::TxnPointer,
+        journal::MempoolJournal,
         transaction::{MempoolTransaction, TimelineState},
         transaction_store::TransactionStore,
         ttl_cache::TtlCache,
@@ -25,6 +26,7 @@ use aptos_types::{
 use std::{
     cmp::max,
     collections::HashSet,
+    sync::Arc,
     time::{Duration, SystemTime},
 };
 
@@ -42,8 +44,13 @@ pub struct Mempool {
 
 impl Mempool {
     pub fn new(config: &NodeConfig) -> Self {
+        let journal = if config.mempool.enable_persistence {
+            Some(Arc::new(MempoolJournal::new(config.storage.dir())))
+        } else {
+            None
+        };
         Mempool {
-            transactions: TransactionStore::new(&config.mempool),
+            transactions: TransactionStore::new(&config.mempool, journal),
             sequence_number_cache: TtlCache::new(config.mempool.capacity, Duration::from_secs(100)),
             metrics_cache: TtlCache::new(config.mempool.capacity, Duration::from_secs(100)),
             system_transaction_timeout: Duration::from_secs(
@@ -179,12 +186,14 @@ impl Mempool {
 
     /// Fetches next block of transactions for consensus.
     /// `batch_size` - size of requested block.
+    /// `max_bytes` - max total serialized size of the transactions in the block.
     /// `seen_txns` - transactions that were sent to Consensus but were not committed yet,
     ///  mempool should filter out such transactions.
     #[allow(clippy::explicit_counter_loop)]
     pub(crate) fn get_batch(
         &self,
         batch_size: u64,
+        max_bytes: u64,
         mut seen: HashSet<TxnPointer>,
     ) -> Vec<SignedTransaction> {
         let mut result = vec![];
@@ -237,15 +246,20 @@ impl Mempool {
             }
         }
         let result_size = result.len();
-        // convert transaction pointers to real values
+        // convert transaction pointers to real values, up to max_bytes
         let mut block_log = TxnsLog::new();
-        let block: Vec<_> = result
-            .into_iter()
-            .filter_map(|(address, tx_seq)| {
+        let mut block = vec![];
+        let mut block_bytes = 0;
+        for (address, tx_seq) in result {
+            if let Some(txn) = self.transactions.get(&address, tx_seq) {
+                block_bytes += bcs::serialized_size(&txn).expect("Unable to serialize txn") as u64;
+                if block_bytes > max_bytes {
+                    break;
+                }
                 block_log.add(address, tx_seq);
-                self.transactions.get(&address, tx_seq)
-            })
-            .collect();
+                block.push(txn);
+            }
+        }
 
         debug!(
             LogSchema::new(LogEntry::GetBlock).txns(block_log),
@@ -281,6 +295,15 @@ impl Mempool {
             .gc_by_expiration_time(block_time, &self.metrics_cache);
     }
 
+    pub(crate) fn journal(&self) -> Option<Arc<MempoolJournal>> {
+        self.transactions.journal()
+    }
+
+    /// Returns the transactions left in the journal by the previous run of the node.
+    pub(crate) fn journaled_transactions(&self) -> Vec<SignedTransaction> {
+        self.transactions.journaled_transactions()
+    }
+
     /// Read `count` transactions from timeline since `timeline_id`.
     /// Returns block of transactions and new last_timeline_id.
     pub(crate) fn read_timeline(
@@ -296,6 +319,30 @@ impl Mempool {
         self.transactions.timeline_range(start_id, end_id)
     }
 
+    /// Returns the gas unit prices of the `count` highest ranked ready transactions,
+    /// from highest to lowest.
+    pub(crate) fn gas_unit_prices(&self, count: usize) -> Vec<u64> {
+        self.transactions
+            .iter_queue()
+            .take(count)
+            .map(|key| key.gas_ranking_score)
+            .collect()
+    }
+
+    /// Returns the `count` highest ranked ready transactions, from highest to lowest.
+    pub(crate) fn pending_transactions(&self, count: usize) -> Vec<SignedTransaction> {
+        self.transactions
+            .iter_queue()
+            .take(count)
+            .filter_map(|key| {
+                self.transactions.get(
+                    &key.address,
+                    key.sequence_number.transaction_sequence_number,
+                )
+            })
+            .collect()
+    }
+
     pub fn gen_snapshot(&self) -> TxnsLog {
         self.transactions.gen_snapshot(&self.metrics_cache)
     }
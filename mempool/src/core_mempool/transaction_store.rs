@@ -7,6 +7,7 @@ use crate::{
             AccountTransactions, ParkingLotIndex, PriorityIndex, PriorityQueueIter, TTLIndex,
             TimelineIndex,
         },
+        journal::MempoolJournal,
         transaction::{MempoolTransaction, TimelineState},
         ttl_cache::TtlCache,
     },
@@ -25,6 +26,7 @@ use aptos_types::{
 use std::{
     collections::HashMap,
     ops::Bound,
+    sync::Arc,
     time::{Duration, SystemTime},
 };
 
@@ -52,13 +54,17 @@ pub struct TransactionStore {
     // one valid hash.
     hash_index: HashMap<HashValue, (AccountAddress, u64)>,
 
+    // optional on-disk copy of the transactions, restored on restart
+    journal: Option<Arc<MempoolJournal>>,
+
     // configuration
     capacity: usize,
     capacity_per_user: usize,
+    replace_by_fee_min_bump_percent: u64,
 }
 
 impl TransactionStore {
-    pub(crate) fn new(config: &MempoolConfig) -> Self {
+    pub(crate) fn new(config: &MempoolConfig, journal: Option<Arc<MempoolJournal>>) -> Self {
         Self {
             // main DS
             transactions: HashMap::new(),
@@ -72,10 +78,12 @@ impl TransactionStore {
             timeline_index: TimelineIndex::new(),
             parking_lot_index: ParkingLotIndex::new(),
             hash_index: HashMap::new(),
+            journal,
 
             // configuration
             capacity: config.capacity,
             capacity_per_user: config.capacity_per_user,
+            replace_by_fee_min_bump_percent: config.replace_by_fee_min_bump_percent,
         }
     }
 
@@ -121,7 +129,8 @@ impl TransactionStore {
 
         // check if transaction is already present in Mempool
         // e.g. given request is update
-        // we allow increase in gas price to speed up process.
+        // we allow replacing it with any transaction paying a sufficiently higher gas price,
+        // to speed up process or to cancel it.
         // ignores the case transaction hash is same for retrying submit transaction.
         if let Some(txns) = self.transactions.get_mut(&address) {
             if let Some(current_version) =
@@ -130,18 +139,31 @@ impl TransactionStore {
                 if current_version.txn == txn.txn {
                     return MempoolStatus::new(MempoolStatusCode::Accepted);
                 }
-                if current_version.txn.max_gas_amount() == txn.txn.max_gas_amount()
-                    && current_version.txn.payload() == txn.txn.payload()
-                    && current_version.txn.expiration_timestamp_secs()
-                        == txn.txn.expiration_timestamp_secs()
-                    && current_version.get_gas_price() < txn.get_gas_price()
+                let min_gas_price = replacement_min_gas_price(
+                    current_version.get_gas_price(),
+                    self.replace_by_fee_min_bump_percent,
+                );
+                if txn.get_gas_price() < min_gas_price {
+                    return MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(
+                        format!(
+                            "Transaction already in mempool, replacing it requires a gas unit price of at least {}",
+                            min_gas_price
+                        ),
+                    );
+                }
+                if let Some(current_version) =
+                    txns.remove(&sequence_number.transaction_sequence_number)
                 {
-                    if let Some(txn) = txns.remove(&txn.sequence_info.transaction_sequence_number) {
-                        self.index_remove(&txn);
-                    }
-                } else {
-                    return MempoolStatus::new(MempoolStatusCode::InvalidUpdate)
-                        .with_message("Transaction already in mempool".to_string());
+                    debug!(
+                        LogSchema::new(LogEntry::ReplacedTxn).txns(TxnsLog::new_txn(
+                            address,
+                            sequence_number.transaction_sequence_number
+                        )),
+                        old_gas_price = current_version.get_gas_price(),
+                        new_gas_price = txn.get_gas_price(),
+                    );
+                    counters::CORE_MEMPOOL_REPLACED_TXNS.inc();
+                    self.index_remove(&current_version);
                 }
             }
         }
@@ -188,6 +210,12 @@ impl TransactionStore {
                     sequence_number.transaction_sequence_number,
                 ),
             );
+            if let Some(journal) = &self.journal {
+                if let Err(e) = journal.put(&txn.txn) {
+                    error!(LogSchema::new(LogEntry::JournalError).error(&e));
+                    counters::JOURNAL_ERROR.inc();
+                }
+            }
             txns.insert(sequence_number.transaction_sequence_number, txn);
             self.track_indices();
         }
@@ -223,7 +251,8 @@ impl TransactionStore {
     }
 
     /// Checks if Mempool is full.
-    /// If it's full, tries to free some space by evicting transactions from the ParkingLot.
+    /// If it's full, tries to free some space by evicting transactions from the ParkingLot, or
+    /// else the ready transaction with the lowest gas price if it pays less than the new one.
     /// We only evict on attempt to insert a transaction that would be ready for broadcast upon insertion.
     fn check_is_full_after_eviction(
         &mut self,
@@ -246,13 +275,56 @@ impl TransactionStore {
                             txn.sequence_info.transaction_sequence_number
                         ))
                     );
+                    counters::CORE_MEMPOOL_EVICTED_TXNS
+                        .with_label_values(&[counters::EVICT_PARKED_TXN_LABEL])
+                        .inc();
                     self.index_remove(&txn);
                 }
+            } else {
+                self.evict_lowest_gas_price_txn(txn);
             }
         }
         self.system_ttl_index.size() >= self.capacity
     }
 
+    /// Evicts the ready transaction ranked last in the PriorityIndex, if it pays a lower gas
+    /// price than `txn` and is sent by another account. The following transactions of the
+    /// evicted transaction's account are not ready anymore and get parked.
+    fn evict_lowest_gas_price_txn(&mut self, txn: &MempoolTransaction) {
+        let sender = txn.get_sender();
+        let lowest = self
+            .priority_index
+            .iter()
+            .rev()
+            .take_while(|key| key.gas_ranking_score < txn.ranking_score)
+            .find(|key| key.address != sender)
+            .map(|key| (key.address, key.sequence_number.transaction_sequence_number));
+        let (address, sequence_number) = match lowest {
+            Some(lowest) => lowest,
+            None => return,
+        };
+
+        if let Some(txns) = self.transactions.get_mut(&address) {
+            // mark all following txns as non-ready, i.e. park them
+            for (_, t) in txns.range((Bound::Excluded(sequence_number), Bound::Unbounded)) {
+                self.parking_lot_index.insert(t);
+                self.priority_index.remove(t);
+                self.timeline_index.remove(t);
+            }
+            if let Some(txn) = txns.remove(&sequence_number) {
+                debug!(
+                    LogSchema::new(LogEntry::MempoolFullEvictedTxn)
+                        .txns(TxnsLog::new_txn(address, sequence_number)),
+                    gas_price = txn.get_gas_price(),
+                );
+                counters::CORE_MEMPOOL_EVICTED_TXNS
+                    .with_label_values(&[counters::EVICT_LOW_FEE_TXN_LABEL])
+                    .inc();
+                self.index_remove(&txn);
+            }
+        }
+    }
+
     /// Check if a transaction would be ready for broadcast in mempool upon insertion (without inserting it).
     /// Two ways this can happen:
     /// 1. txn sequence number == curr_sequence_number
@@ -410,9 +482,36 @@ impl TransactionStore {
         self.timeline_index.remove(txn);
         self.parking_lot_index.remove(txn);
         self.hash_index.remove(&txn.get_committed_hash());
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.delete(
+                txn.get_sender(),
+                txn.sequence_info.transaction_sequence_number,
+            ) {
+                error!(LogSchema::new(LogEntry::JournalError).error(&e));
+                counters::JOURNAL_ERROR.inc();
+            }
+        }
         self.track_indices();
     }
 
+    pub(crate) fn journal(&self) -> Option<Arc<MempoolJournal>> {
+        self.journal.clone()
+    }
+
+    /// Returns the transactions left in the journal by the previous run of the node, they have
+    /// to be validated again before being added back.
+    pub(crate) fn journaled_transactions(&self) -> Vec<SignedTransaction> {
+        match self.journal.as_ref().map(|journal| journal.transactions()) {
+            Some(Ok(txns)) => txns,
+            Some(Err(e)) => {
+                error!(LogSchema::new(LogEntry::JournalError).error(&e));
+                counters::JOURNAL_ERROR.inc();
+                vec![]
+            }
+            None => vec![],
+        }
+    }
+
     /// Read `count` transactions from timeline since `timeline_id`.
     /// Returns block of transactions and new last_timeline_id.
     pub(crate) fn read_timeline(
@@ -570,3 +669,10 @@ impl TransactionStore {
         self.parking_lot_index.size()
     }
 }
+
+/// Returns the gas unit price a transaction must pay at least to replace a transaction paying
+/// `gas_price`: `min_bump_percent` percent more, and no less than one more.
+fn replacement_min_gas_price(gas_price: u64, min_bump_percent: u64) -> u64 {
+    let bump = (gas_price as u128 * min_bump_percent as u128 + 99) / 100;
+    gas_price.saturating_add(std::cmp::max(bump, 1).min(u64::MAX as u128) as u64)
+}
@@ -32,6 +32,10 @@ pub const GC_CLIENT_EXP_LABEL: &str = "client_expiration";
 pub const GC_ACTIVE_TXN_LABEL: &str = "active";
 pub const GC_PARKED_TXN_LABEL: &str = "parked";
 
+// Core mempool eviction type labels
+pub const EVICT_PARKED_TXN_LABEL: &str = "parked";
+pub const EVICT_LOW_FEE_TXN_LABEL: &str = "low_fee";
+
 // Mempool service request type labels
 pub const GET_BLOCK_LABEL: &str = "get_block";
 pub const COMMIT_STATE_SYNC_LABEL: &str = "commit_accepted";
@@ -52,6 +56,8 @@ pub const SUCCESS_LABEL: &str = "success";
 // Bounded executor task labels
 pub const CLIENT_EVENT_LABEL: &str = "client_event";
 pub const CLIENT_EVENT_GET_TXN_LABEL: &str = "client_event_get_txn";
+pub const CLIENT_EVENT_GET_GAS_PRICES_LABEL: &str = "client_event_get_gas_prices";
+pub const CLIENT_EVENT_GET_PENDING_TXNS_LABEL: &str = "client_event_get_pending_txns";
 pub const RECONFIG_EVENT_LABEL: &str = "reconfig";
 pub const PEER_BROADCAST_EVENT_LABEL: &str = "peer_broadcast";
 
@@ -101,6 +107,26 @@ pub static CORE_MEMPOOL_REMOVED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter tracking number of txns replaced by a txn with the same sequence number and a higher
+/// gas unit price
+pub static CORE_MEMPOOL_REPLACED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "core_mempool_replaced_txns_count",
+        "Number of txns replaced by a txn with a higher gas unit price"
+    )
+    .unwrap()
+});
+
+/// Counter tracking number of txns evicted to make room for new txns when core mempool is full
+pub static CORE_MEMPOOL_EVICTED_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "core_mempool_evicted_txns_count",
+        "Number of txns evicted from a full core mempool",
+        &["type"]
+    )
+    .unwrap()
+});
+
 /// Counter tracking latency of txns reaching various stages in committing
 /// (e.g. time from txn entering core mempool to being pulled in consensus block)
 pub static CORE_MEMPOOL_TXN_COMMIT_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
@@ -440,6 +466,15 @@ pub static DB_ERROR: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for number of times writing to or reading from the mempool journal failed
+pub static JOURNAL_ERROR: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "mempool_journal_error_count",
+        "Number of times a mempool journal error was encountered"
+    )
+    .unwrap()
+});
+
 /// Counter for the current number of active upstream peers mempool can
 /// broadcast to, summed across each of its networks
 static ACTIVE_UPSTREAM_PEERS_COUNT: Lazy<IntGaugeVec> = Lazy::new(|| {
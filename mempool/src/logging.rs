@@ -130,6 +130,8 @@ pub enum LogEntry {
     ReconfigUpdate,
     JsonRpc,
     GetTransaction,
+    GetGasUnitPrices,
+    GetPendingTransactions,
     GetBlock,
     QuorumStore,
     StateSyncCommit,
@@ -140,11 +142,14 @@ pub enum LogEntry {
     AddTxn,
     RemoveTxn,
     MempoolFullEvictedTxn,
+    ReplacedTxn,
     GCRemoveTxns,
     CleanCommittedTxn,
     CleanRejectedTxn,
     ProcessReadyTxns,
     DBError,
+    JournalError,
+    RestoreJournal,
     UnexpectedNetworkMsg,
     MempoolSnapshot,
 }
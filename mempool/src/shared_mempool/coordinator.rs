@@ -3,7 +3,7 @@
 
 //! Processes that are directly spawned by shared mempool runtime initialization
 use crate::{
-    core_mempool::{CoreMempool, TimelineState},
+    core_mempool::{CoreMempool, MempoolJournal, TimelineState},
     counters,
     logging::{LogEntry, LogEvent, LogSchema},
     network::{MempoolNetworkEvents, MempoolSyncMsg},
@@ -148,6 +148,48 @@ async fn handle_client_request<V>(
                 ))
                 .await;
         }
+        MempoolClientRequest::GetGasUnitPrices(count, callback) => {
+            // This timer measures how long it took for the bounded executor to *schedule* the
+            // task.
+            let _timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_GAS_PRICES_LABEL,
+                counters::SPAWN_LABEL,
+            );
+            // This timer measures how long it took for the task to go from scheduled to started.
+            let task_start_timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_GAS_PRICES_LABEL,
+                counters::START_LABEL,
+            );
+            bounded_executor
+                .spawn(tasks::process_client_get_gas_unit_prices(
+                    smp.clone(),
+                    count,
+                    callback,
+                    task_start_timer,
+                ))
+                .await;
+        }
+        MempoolClientRequest::GetPendingTransactions(count, callback) => {
+            // This timer measures how long it took for the bounded executor to *schedule* the
+            // task.
+            let _timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_PENDING_TXNS_LABEL,
+                counters::SPAWN_LABEL,
+            );
+            // This timer measures how long it took for the task to go from scheduled to started.
+            let task_start_timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_PENDING_TXNS_LABEL,
+                counters::START_LABEL,
+            );
+            bounded_executor
+                .spawn(tasks::process_client_get_pending_transactions(
+                    smp.clone(),
+                    count,
+                    callback,
+                    task_start_timer,
+                ))
+                .await;
+        }
     }
 }
 
@@ -347,6 +389,17 @@ pub(crate) async fn gc_coordinator(mempool: Arc<Mutex<CoreMempool>>, gc_interval
     ));
 }
 
+/// Periodically flushes the writes to the mempool journal, outside of the mempool lock.
+pub(crate) async fn journal_flush_job(journal: Arc<MempoolJournal>, flush_interval_ms: u64) {
+    let mut interval = IntervalStream::new(interval(Duration::from_millis(flush_interval_ms)));
+    while let Some(_interval) = interval.next().await {
+        if let Err(e) = journal.flush() {
+            error!(LogSchema::new(LogEntry::JournalError).error(&e));
+            counters::JOURNAL_ERROR.inc();
+        }
+    }
+}
+
 /// Periodically logs a snapshot of transactions in core mempool.
 /// In the future we may want an interactive way to directly query mempool's internal state.
 /// For now, we will rely on this periodic snapshot to observe the internal state.
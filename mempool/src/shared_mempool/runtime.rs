@@ -5,7 +5,8 @@ use crate::{
     core_mempool::CoreMempool,
     network::{MempoolNetworkEvents, MempoolNetworkSender},
     shared_mempool::{
-        coordinator::{coordinator, gc_coordinator, snapshot_job},
+        coordinator::{coordinator, gc_coordinator, journal_flush_job, snapshot_job},
+        tasks::restore_journaled_transactions,
         types::{MempoolEventsReceiver, SharedMempool, SharedMempoolNotification},
     },
     QuorumStoreRequest,
@@ -62,6 +63,8 @@ pub(crate) fn start_shared_mempool<V>(
         config.base.role,
         peer_metadata_storage,
     );
+    restore_journaled_transactions(&smp);
+    let journal = mempool.lock().journal();
 
     executor.spawn(coordinator(
         smp,
@@ -82,6 +85,13 @@ pub(crate) fn start_shared_mempool<V>(
         mempool,
         config.mempool.mempool_snapshot_interval_secs,
     ));
+
+    if let Some(journal) = journal {
+        executor.spawn(journal_flush_job(
+            journal,
+            config.mempool.journal_flush_interval_ms,
+        ));
+    }
 }
 
 pub fn bootstrap(
@@ -142,6 +142,48 @@ pub(crate) async fn process_client_get_transaction<V>(
     }
 }
 
+/// Processes get gas unit prices request by client.
+pub(crate) async fn process_client_get_gas_unit_prices<V>(
+    smp: SharedMempool<V>,
+    count: usize,
+    callback: oneshot::Sender<Vec<u64>>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let gas_unit_prices = smp.mempool.lock().gas_unit_prices(count);
+
+    if callback.send(gas_unit_prices).is_err() {
+        error!(LogSchema::event_log(
+            LogEntry::GetGasUnitPrices,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Processes get pending transactions request by client.
+pub(crate) async fn process_client_get_pending_transactions<V>(
+    smp: SharedMempool<V>,
+    count: usize,
+    callback: oneshot::Sender<Vec<SignedTransaction>>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let txns = smp.mempool.lock().pending_transactions(count);
+
+    if callback.send(txns).is_err() {
+        error!(LogSchema::event_log(
+            LogEntry::GetPendingTransactions,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
 /// Processes transactions from other nodes.
 pub(crate) async fn process_transaction_broadcast<V>(
     smp: SharedMempool<V>,
@@ -324,6 +366,58 @@ where
     statuses
 }
 
+/// Adds back the transactions journaled by the previous run of the node. Expired transactions
+/// are dropped, the others go through the same checks as newly submitted transactions since the
+/// ledger may have moved on while the node was down. The transactions are only removed from the
+/// journal once they're dropped, so that a crash while restoring them doesn't lose them.
+pub(crate) fn restore_journaled_transactions<V>(smp: &SharedMempool<V>)
+where
+    V: TransactionValidation,
+{
+    let (journal, journaled_transactions) = {
+        let mempool = smp.mempool.lock();
+        match mempool.journal() {
+            Some(journal) => (journal, mempool.journaled_transactions()),
+            None => return,
+        }
+    };
+    let now = aptos_infallible::duration_since_epoch().as_secs();
+    let (transactions, expired): (Vec<_>, Vec<_>) = journaled_transactions
+        .into_iter()
+        .partition(|txn| txn.expiration_timestamp_secs() > now);
+    if transactions.is_empty() && expired.is_empty() {
+        return;
+    }
+
+    let journaled = transactions.len() + expired.len();
+    // The accepted transactions are journaled again on insertion, under the same keys.
+    let rejected = if transactions.is_empty() {
+        vec![]
+    } else {
+        process_incoming_transactions(smp, transactions, TimelineState::NotReady)
+            .into_iter()
+            .filter(|(_, (status, _))| status.code != MempoolStatusCode::Accepted)
+            .map(|(txn, _)| txn)
+            .collect()
+    };
+    let restored = journaled - expired.len() - rejected.len();
+    for txn in expired.iter().chain(rejected.iter()) {
+        if let Err(e) = journal.delete(txn.sender(), txn.sequence_number()) {
+            error!(LogSchema::new(LogEntry::JournalError).error(&e));
+            counters::JOURNAL_ERROR.inc();
+        }
+    }
+    if let Err(e) = journal.flush() {
+        error!(LogSchema::new(LogEntry::JournalError).error(&e));
+        counters::JOURNAL_ERROR.inc();
+    }
+    info!(
+        LogSchema::event_log(LogEntry::RestoreJournal, LogEvent::Success),
+        journaled = journaled,
+        restored = restored,
+    );
+}
+
 fn log_txn_process_results(results: &[SubmissionStatusBundle], sender: Option<PeerNetworkId>) {
     let network = match sender {
         Some(peer) => peer.network_id().to_string(),
@@ -371,7 +465,12 @@ pub(crate) fn process_quorum_store_request<V: TransactionValidation>(
     debug!(LogSchema::event_log(LogEntry::QuorumStore, LogEvent::Received).quorum_store_msg(&req));
 
     let (resp, callback, counter_label) = match req {
-        QuorumStoreRequest::GetBatchRequest(max_batch_size, transactions, callback) => {
+        QuorumStoreRequest::GetBatchRequest(
+            max_batch_size,
+            max_batch_bytes,
+            transactions,
+            callback,
+        ) => {
             let exclude_transactions: HashSet<TxnPointer> = transactions
                 .iter()
                 .map(|txn| (txn.sender, txn.sequence_number))
@@ -384,7 +483,7 @@ pub(crate) fn process_quorum_store_request<V: TransactionValidation>(
                 let curr_time = aptos_infallible::duration_since_epoch();
                 mempool.gc_by_expiration_time(curr_time);
                 let batch_size = cmp::max(max_batch_size, 1);
-                txns = mempool.get_batch(batch_size, exclude_transactions);
+                txns = mempool.get_batch(batch_size, max_batch_bytes, exclude_transactions);
             }
             counters::mempool_service_transactions(counters::GET_BLOCK_LABEL, txns.len());
 
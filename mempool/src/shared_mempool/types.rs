@@ -154,6 +154,8 @@ pub enum QuorumStoreRequest {
     GetBatchRequest(
         // max batch size
         u64,
+        // max batch size in bytes
+        u64,
         // transactions to exclude from the requested batch
         Vec<TransactionSummary>,
         // callback to respond to
@@ -171,14 +173,14 @@ pub enum QuorumStoreRequest {
 impl fmt::Display for QuorumStoreRequest {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let payload = match self {
-            QuorumStoreRequest::GetBatchRequest(batch_size, excluded_txns, _) => {
+            QuorumStoreRequest::GetBatchRequest(batch_size, batch_bytes, excluded_txns, _) => {
                 let mut txns_str = "".to_string();
                 for tx in excluded_txns.iter() {
                     txns_str += &format!("{} ", tx);
                 }
                 format!(
-                    "GetBatchRequest [batch_size: {}, excluded_txns: {}]",
-                    batch_size, txns_str
+                    "GetBatchRequest [batch_size: {}, batch_bytes: {}, excluded_txns: {}]",
+                    batch_size, batch_bytes, txns_str
                 )
             }
             QuorumStoreRequest::RejectNotification(rejected_txns, _) => {
@@ -208,6 +210,11 @@ pub type SubmissionStatusBundle = (SignedTransaction, SubmissionStatus);
 pub enum MempoolClientRequest {
     SubmitTransaction(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>),
     GetTransactionByHash(HashValue, oneshot::Sender<Option<SignedTransaction>>),
+    /// Gas unit prices of up to the given number of the highest ranked ready transactions,
+    /// from highest to lowest.
+    GetGasUnitPrices(usize, oneshot::Sender<Vec<u64>>),
+    /// Up to the given number of the highest ranked ready transactions, from highest to lowest.
+    GetPendingTransactions(usize, oneshot::Sender<Vec<SignedTransaction>>),
 }
 
 pub type MempoolClientSender = mpsc::Sender<MempoolClientRequest>;
@@ -10,6 +10,7 @@ use crate::{
 };
 use aptos_config::config::NodeConfig;
 use aptos_crypto::HashValue;
+use aptos_temppath::TempPath;
 use aptos_types::{account_config::AccountSequenceInfo, transaction::SignedTransaction};
 use std::{
     collections::HashSet,
@@ -66,6 +67,29 @@ fn test_transaction_ordering_only_seqnos() {
     }
 }
 
+#[test]
+fn test_pending_transactions() {
+    let (mut mempool, _) = setup_mempool();
+    let transactions = add_txns_to_mempool(
+        &mut mempool,
+        vec![
+            TestTransaction::new(0, 0, 3),
+            TestTransaction::new(1, 0, 5),
+            // not ready because of the sequence number gap
+            TestTransaction::new(2, 1, 7),
+        ],
+    );
+
+    assert_eq!(
+        mempool.pending_transactions(10),
+        vec![transactions[1].clone(), transactions[0].clone()]
+    );
+    assert_eq!(
+        mempool.pending_transactions(1),
+        vec![transactions[1].clone()]
+    );
+}
+
 #[test]
 fn test_transaction_ordering_only_crsns() {
     let (mut mempool, mut consensus) = setup_mempool();
@@ -272,13 +296,13 @@ fn test_update_invalid_transaction_in_mempool() {
         vec![TestTransaction::new(0, 0, 1), TestTransaction::new(1, 0, 2)],
     );
     let updated_txn = TestTransaction::make_signed_transaction_with_max_gas_amount(
-        &TestTransaction::new(0, 0, 5),
+        &TestTransaction::new(0, 0, 1),
         200,
     );
     let _added_tnx = add_signed_txn(&mut mempool, updated_txn);
 
-    // Since both gas price and mas gas amount were updated, the ordering should not have changed.
-    // The second transaction with gas price 2 should come first.
+    // Since the gas price wasn't increased, the update is rejected and the ordering should not
+    // have changed. The second transaction with gas price 2 should come first.
     assert_eq!(consensus.get_block(&mut mempool, 1), vec![txns[1].clone()]);
     let next_tnx = consensus.get_block(&mut mempool, 1);
     assert_eq!(next_tnx, vec![txns[0].clone()]);
@@ -296,19 +320,72 @@ fn test_update_invalid_transaction_in_mempool_crsn() {
         ],
     );
     let updated_txn = TestTransaction::make_signed_transaction_with_max_gas_amount(
-        &TestTransaction::new(0, 0, 5).crsn(0),
+        &TestTransaction::new(0, 0, 1).crsn(0),
         200,
     );
     let _added_tnx = add_signed_txn(&mut mempool, updated_txn);
 
-    // Since both gas price and mas gas amount were updated, the ordering should not have changed.
-    // The second transaction with gas price 2 should come first.
+    // Since the gas price wasn't increased, the update is rejected and the ordering should not
+    // have changed. The second transaction with gas price 2 should come first.
     assert_eq!(consensus.get_block(&mut mempool, 1), vec![txns[1].clone()]);
     let next_tnx = consensus.get_block(&mut mempool, 1);
     assert_eq!(next_tnx, vec![txns[0].clone()]);
     assert_eq!(next_tnx[0].gas_unit_price(), 1);
 }
 
+#[test]
+fn test_replace_by_fee() {
+    let (mut mempool, mut consensus) = setup_mempool();
+    let _ = add_txns_to_mempool(&mut mempool, vec![TestTransaction::new(0, 0, 10)]);
+
+    // Not enough of a gas price increase to replace a different transaction
+    let updated_txn =
+        TestTransaction::new(0, 0, 10).make_signed_transaction_with_max_gas_amount(200);
+    assert!(add_signed_txn(&mut mempool, updated_txn).is_err());
+
+    // Any transaction paying at least 10% more replaces it
+    let updated_txn =
+        TestTransaction::new(0, 0, 11).make_signed_transaction_with_max_gas_amount(200);
+    add_signed_txn(&mut mempool, updated_txn.clone()).unwrap();
+    assert_eq!(
+        consensus.get_block(&mut mempool, 1),
+        vec![updated_txn.clone()]
+    );
+    assert_eq!(
+        mempool.get_by_hash(updated_txn.committed_hash()),
+        Some(updated_txn)
+    );
+}
+
+#[test]
+fn test_capacity_evicts_lowest_gas_price() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 3;
+    let mut pool = CoreMempool::new(&config);
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 1),
+            TestTransaction::new(1, 0, 5),
+        ],
+    );
+
+    // The last transaction of the lowest paying account is evicted
+    add_txn(&mut pool, TestTransaction::new(2, 0, 3)).unwrap();
+    let mut gas_prices: Vec<_> = pool
+        .get_batch(5, u64::MAX, HashSet::new())
+        .iter()
+        .map(SignedTransaction::gas_unit_price)
+        .collect();
+    gas_prices.sort_unstable();
+    assert_eq!(gas_prices, vec![1, 3, 5]);
+    assert_eq!(pool.get_by_hash(txns[1].committed_hash()), None);
+
+    // Transactions paying no more than the lowest ready transaction are rejected
+    assert!(add_txn(&mut pool, TestTransaction::new(3, 0, 1)).is_err());
+}
+
 #[test]
 fn test_remove_transaction() {
     let (mut pool, mut consensus) = setup_mempool();
@@ -375,7 +452,7 @@ fn test_system_ttl() {
 
     // GC routine should clear transaction from first insert but keep last one.
     mempool.gc();
-    let batch = mempool.get_batch(1, HashSet::new());
+    let batch = mempool.get_batch(1, u64::MAX, HashSet::new());
     assert_eq!(vec![transaction.make_signed_transaction()], batch);
 }
 
@@ -387,11 +464,11 @@ fn test_commit_callback() {
     let txns = add_txns_to_mempool(&mut pool, vec![TestTransaction::new(1, 6, 1)]);
 
     // Check that pool is empty.
-    assert!(pool.get_batch(1, HashSet::new()).is_empty());
+    assert!(pool.get_batch(1, u64::MAX, HashSet::new()).is_empty());
     // Transaction 5 got back from consensus.
     pool.remove_transaction(&TestTransaction::get_address(1), 5, false);
     // Verify that we can execute transaction 6.
-    assert_eq!(pool.get_batch(1, HashSet::new())[0], txns[0]);
+    assert_eq!(pool.get_batch(1, u64::MAX, HashSet::new())[0], txns[0]);
 }
 
 #[test]
@@ -405,7 +482,7 @@ fn test_sequence_number_cache() {
     // for AC is 0).
     add_txns_to_mempool(&mut pool, vec![TestTransaction::new(1, 6, 1)]);
     // Verify that we can execute transaction 6.
-    assert_eq!(pool.get_batch(1, HashSet::new()).len(), 1);
+    assert_eq!(pool.get_batch(1, u64::MAX, HashSet::new()).len(), 1);
 }
 
 #[test]
@@ -502,7 +579,7 @@ fn test_parking_lot_eviction() {
     }
     // Make sure that we have correct txns in Mempool.
     let mut txns: Vec<_> = pool
-        .get_batch(5, HashSet::new())
+        .get_batch(5, u64::MAX, HashSet::new())
         .iter()
         .map(SignedTransaction::sequence_number)
         .collect();
@@ -531,7 +608,7 @@ fn test_parking_lot_evict_only_for_ready_txn_insertion() {
 
     // Make sure that we have correct txns in Mempool.
     let mut txns: Vec<_> = pool
-        .get_batch(5, HashSet::new())
+        .get_batch(5, u64::MAX, HashSet::new())
         .iter()
         .map(SignedTransaction::sequence_number)
         .collect();
@@ -573,7 +650,7 @@ fn test_gc_ready_transaction() {
     pool.gc_by_expiration_time(Duration::from_secs(1));
 
     // Make sure txns 2 and 3 became not ready and we can't read them from any API.
-    let block = pool.get_batch(10, HashSet::new());
+    let block = pool.get_batch(10, u64::MAX, HashSet::new());
     assert_eq!(block.len(), 1);
     assert_eq!(block[0].sequence_number(), 0);
 
@@ -597,11 +674,31 @@ fn test_clean_stuck_transactions() {
         AccountSequenceInfo::Sequential(db_sequence_number),
         TimelineState::NotReady,
     );
-    let block = pool.get_batch(10, HashSet::new());
+    let block = pool.get_batch(10, u64::MAX, HashSet::new());
     assert_eq!(block.len(), 1);
     assert_eq!(block[0].sequence_number(), 10);
 }
 
+#[test]
+fn test_get_batch_max_bytes() {
+    let mut pool = setup_mempool().0;
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 1),
+            TestTransaction::new(0, 2, 1),
+        ],
+    );
+    let txn_bytes = bcs::serialized_size(&txns[0]).unwrap() as u64;
+
+    // The transactions exceeding the byte limit are left out, in order.
+    let block = pool.get_batch(10, txn_bytes * 2 + 1, HashSet::new());
+    assert_eq!(block, txns[..2].to_vec());
+    assert!(pool.get_batch(10, txn_bytes - 1, HashSet::new()).is_empty());
+    assert_eq!(pool.get_batch(10, u64::MAX, HashSet::new()), txns);
+}
+
 #[test]
 fn test_ttl_cache() {
     let mut cache = TtlCache::new(2, Duration::from_secs(1));
@@ -677,3 +774,32 @@ fn test_get_transaction_by_hash_after_the_txn_is_updated() {
     let txn_by_new_hash = pool.get_by_hash(new_txn_hash);
     assert_eq!(txn_by_new_hash, Some(new_txn));
 }
+
+#[test]
+fn test_journal_restores_transactions() {
+    let temp_dir = TempPath::new();
+    temp_dir.create_as_dir().unwrap();
+    let mut config = NodeConfig::random();
+    config.mempool.enable_persistence = true;
+    config.storage.dir = temp_dir.path().to_path_buf();
+
+    let mut pool = CoreMempool::new(&config);
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 1),
+            TestTransaction::new(1, 0, 1),
+        ],
+    );
+    // Committed transactions are removed from the journal.
+    pool.remove_transaction(&txns[2].sender(), 0, false);
+    drop(pool);
+
+    // The pending writes are flushed when the journal is dropped.
+    let pool = CoreMempool::new(&config);
+    let journaled = vec![txns[0].clone(), txns[1].clone()];
+    assert_eq!(pool.journaled_transactions(), journaled);
+    // Reading the journal doesn't remove the transactions from it.
+    assert_eq!(pool.journaled_transactions(), journaled);
+}
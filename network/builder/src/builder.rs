@@ -42,6 +42,7 @@ use std::{
     clone::Clone,
     collections::{HashMap, HashSet},
     sync::Arc,
+    time::Duration,
 };
 use tokio::runtime::Handle;
 
@@ -394,6 +395,21 @@ impl NetworkBuilder {
                 *interval_duration,
                 self.time_service.clone(),
             ),
+            DiscoveryMethod::Dns(dns) => DiscoveryChangeListener::dns(
+                self.network_context,
+                conn_mgr_reqs_tx,
+                dns.name.clone(),
+                dns.role,
+                Duration::from_secs(dns.interval_secs),
+                self.time_service.clone(),
+            ),
+            DiscoveryMethod::Rest(rest) => DiscoveryChangeListener::rest(
+                self.network_context,
+                conn_mgr_reqs_tx,
+                rest.url.clone(),
+                Duration::from_secs(rest.interval_secs),
+                self.time_service.clone(),
+            ),
             DiscoveryMethod::None => return,
         };
 
@@ -0,0 +1,301 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::DiscoveryError;
+use aptos_config::config::{Peer, PeerRole, PeerSet, HANDSHAKE_VERSION};
+use aptos_crypto::{x25519, ValidCryptoMaterialStringExt};
+use aptos_logger::prelude::*;
+use aptos_time_service::{Interval, TimeService, TimeServiceTrait};
+use aptos_types::{account_address::from_identity_public_key, network_address::NetworkAddress};
+use futures::{future::BoxFuture, Future, Stream};
+use std::{
+    collections::HashSet,
+    pin::Pin,
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use trust_dns_resolver::{error::ResolveErrorKind, TokioAsyncResolver};
+
+/// Prefix of the TXT record holding the noise key of a host pointed at by an SRV record
+const NOISE_KEY_PREFIX: &str = "noise-ik=";
+
+/// The DNS lookups of the discovery, missing records are looked up as empty.
+pub(crate) trait DnsResolver: Send + Sync {
+    fn txt_records(&self, name: &str) -> BoxFuture<'static, Result<Vec<String>, DiscoveryError>>;
+
+    /// Returns the target and port of each SRV record.
+    fn srv_records(
+        &self,
+        name: &str,
+    ) -> BoxFuture<'static, Result<Vec<(String, u16)>, DiscoveryError>>;
+}
+
+/// Resolves with the system configuration, read again for each lookup so that its changes are
+/// picked up.
+struct SystemDnsResolver;
+
+impl SystemDnsResolver {
+    fn resolver() -> Result<TokioAsyncResolver, DiscoveryError> {
+        TokioAsyncResolver::tokio_from_system_conf()
+            .map_err(|err| DiscoveryError::Dns(err.to_string()))
+    }
+}
+
+impl DnsResolver for SystemDnsResolver {
+    fn txt_records(&self, name: &str) -> BoxFuture<'static, Result<Vec<String>, DiscoveryError>> {
+        let name = name.to_string();
+        Box::pin(async move {
+            match Self::resolver()?.txt_lookup(name).await {
+                Ok(lookup) => Ok(lookup
+                    .iter()
+                    .map(|txt| {
+                        txt.txt_data()
+                            .iter()
+                            .map(|data| String::from_utf8_lossy(data))
+                            .collect::<String>()
+                    })
+                    .collect()),
+                Err(err) if is_not_found(err.kind()) => Ok(vec![]),
+                Err(err) => Err(DiscoveryError::Dns(err.to_string())),
+            }
+        })
+    }
+
+    fn srv_records(
+        &self,
+        name: &str,
+    ) -> BoxFuture<'static, Result<Vec<(String, u16)>, DiscoveryError>> {
+        let name = name.to_string();
+        Box::pin(async move {
+            match Self::resolver()?.srv_lookup(name).await {
+                Ok(lookup) => Ok(lookup
+                    .iter()
+                    .map(|srv| (srv.target().to_utf8(), srv.port()))
+                    .collect()),
+                Err(err) if is_not_found(err.kind()) => Ok(vec![]),
+                Err(err) => Err(DiscoveryError::Dns(err.to_string())),
+            }
+        })
+    }
+}
+
+pub struct DnsStream {
+    name: String,
+    role: PeerRole,
+    resolver: Arc<dyn DnsResolver>,
+    interval: Pin<Box<Interval>>,
+    pending_lookup: Option<BoxFuture<'static, Result<PeerSet, DiscoveryError>>>,
+}
+
+impl DnsStream {
+    pub(crate) fn new(
+        name: String,
+        role: PeerRole,
+        interval_duration: Duration,
+        time_service: TimeService,
+    ) -> Self {
+        Self::with_resolver(
+            name,
+            role,
+            Arc::new(SystemDnsResolver),
+            interval_duration,
+            time_service,
+        )
+    }
+
+    pub(crate) fn with_resolver(
+        name: String,
+        role: PeerRole,
+        resolver: Arc<dyn DnsResolver>,
+        interval_duration: Duration,
+        time_service: TimeService,
+    ) -> Self {
+        DnsStream {
+            name,
+            role,
+            resolver,
+            interval: Box::pin(time_service.interval(interval_duration)),
+            pending_lookup: None,
+        }
+    }
+}
+
+impl Stream for DnsStream {
+    type Item = Result<PeerSet, DiscoveryError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // Wait for delay before starting the next lookup
+        if self.pending_lookup.is_none() {
+            futures::ready!(self.interval.as_mut().poll_next(cx));
+            let lookup = lookup_peers(self.resolver.clone(), self.name.clone(), self.role);
+            self.pending_lookup = Some(Box::pin(lookup));
+        }
+
+        let result = futures::ready!(self.pending_lookup.as_mut().unwrap().as_mut().poll(cx));
+        self.pending_lookup = None;
+        Poll::Ready(Some(result))
+    }
+}
+
+/// Resolves the TXT and SRV records of `name` into the set of peers. The hosts pointed at by the
+/// SRV records without a valid noise key are skipped.
+async fn lookup_peers(
+    resolver: Arc<dyn DnsResolver>,
+    name: String,
+    role: PeerRole,
+) -> Result<PeerSet, DiscoveryError> {
+    let mut addresses = resolver
+        .txt_records(&name)
+        .await?
+        .iter()
+        .filter(|record| record.starts_with('/'))
+        .map(|record| parse_address(record))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (target, port) in resolver.srv_records(&name).await? {
+        let host = target.trim_end_matches('.');
+        let key = match noise_key(resolver.txt_records(&target).await?, host) {
+            Ok(key) => key,
+            Err(err) => {
+                warn!("Skipping the DNS discovery of {}: {:?}", host, err);
+                continue;
+            }
+        };
+        let address = parse_address(&format!("/dns/{}/tcp/{}", host, port))?;
+        addresses.push(address.append_prod_protos(key, HANDSHAKE_VERSION));
+    }
+
+    Ok(peers_from_addresses(addresses, role))
+}
+
+fn noise_key(txt_records: Vec<String>, host: &str) -> Result<x25519::PublicKey, DiscoveryError> {
+    let key = txt_records
+        .iter()
+        .find_map(|record| record.strip_prefix(NOISE_KEY_PREFIX))
+        .ok_or_else(|| DiscoveryError::Parsing(format!("No noise key TXT record for {}", host)))?;
+    x25519::PublicKey::from_encoded_string(key)
+        .map_err(|err| DiscoveryError::Parsing(err.to_string()))
+}
+
+fn is_not_found(kind: &ResolveErrorKind) -> bool {
+    matches!(kind, ResolveErrorKind::NoRecordsFound { .. })
+}
+
+fn parse_address(address: &str) -> Result<NetworkAddress, DiscoveryError> {
+    NetworkAddress::from_str(address).map_err(|err| DiscoveryError::Parsing(err.to_string()))
+}
+
+/// Groups the addresses by noise key, the peer id being derived from the key. Addresses without
+/// a noise key can't be authenticated and are skipped.
+pub(crate) fn peers_from_addresses(addresses: Vec<NetworkAddress>, role: PeerRole) -> PeerSet {
+    let mut peers = PeerSet::new();
+    for address in addresses {
+        if let Some(key) = address.find_noise_proto() {
+            let peer = peers
+                .entry(from_identity_public_key(key))
+                .or_insert_with(|| Peer::new(vec![], HashSet::new(), role));
+            peer.keys.insert(key);
+            peer.addresses.push(address);
+        }
+    }
+    peers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use std::collections::HashMap;
+
+    const KEY: &str = "080e287879c918794170e258bfaddd75acac5b3e350419044655e4983a487120";
+
+    #[derive(Default)]
+    struct StubResolver {
+        txt: HashMap<String, Vec<String>>,
+        srv: HashMap<String, Vec<(String, u16)>>,
+    }
+
+    impl DnsResolver for StubResolver {
+        fn txt_records(
+            &self,
+            name: &str,
+        ) -> BoxFuture<'static, Result<Vec<String>, DiscoveryError>> {
+            let records = self.txt.get(name).cloned().unwrap_or_default();
+            Box::pin(async move { Ok(records) })
+        }
+
+        fn srv_records(
+            &self,
+            name: &str,
+        ) -> BoxFuture<'static, Result<Vec<(String, u16)>, DiscoveryError>> {
+            let records = self.srv.get(name).cloned().unwrap_or_default();
+            Box::pin(async move { Ok(records) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dns_stream() {
+        let name = "peers.example.com";
+        let txt_addr = format!("/ip4/1.2.3.4/tcp/6180/noise-ik/{}/handshake/0", KEY);
+        let mut resolver = StubResolver::default();
+        resolver.txt.insert(
+            name.to_string(),
+            vec![txt_addr.clone(), "v=spf1 -all".to_string()],
+        );
+        resolver.srv.insert(
+            name.to_string(),
+            vec![
+                ("node.example.com.".to_string(), 6180),
+                ("no-key.example.com.".to_string(), 6180),
+            ],
+        );
+        resolver.txt.insert(
+            "node.example.com.".to_string(),
+            vec![format!("{}{}", NOISE_KEY_PREFIX, KEY)],
+        );
+
+        let mut stream = DnsStream::with_resolver(
+            name.to_string(),
+            PeerRole::Upstream,
+            Arc::new(resolver),
+            Duration::from_millis(1),
+            TimeService::real(),
+        );
+        let peers = stream.next().await.unwrap().unwrap();
+
+        // the host without a noise key is skipped
+        let key = x25519::PublicKey::from_encoded_string(KEY).unwrap();
+        let srv_addr = NetworkAddress::from_str("/dns/node.example.com/tcp/6180")
+            .unwrap()
+            .append_prod_protos(key, HANDSHAKE_VERSION);
+        let expected = peers_from_addresses(
+            vec![NetworkAddress::from_str(&txt_addr).unwrap(), srv_addr],
+            PeerRole::Upstream,
+        );
+        assert_eq!(expected, peers);
+        assert_eq!(peers[&from_identity_public_key(key)].addresses.len(), 2);
+    }
+
+    #[test]
+    fn test_peers_from_addresses() {
+        let addr = NetworkAddress::from_str("/ip4/1.2.3.4/tcp/6180/noise-ik/080e287879c918794170e258bfaddd75acac5b3e350419044655e4983a487120/handshake/0").unwrap();
+        let other_addr = NetworkAddress::from_str("/dns/example.com/tcp/6180/noise-ik/080e287879c918794170e258bfaddd75acac5b3e350419044655e4983a487120/handshake/0").unwrap();
+        let no_key_addr = NetworkAddress::from_str("/ip4/1.2.3.5/tcp/6180").unwrap();
+        let key = addr.find_noise_proto().unwrap();
+
+        let peers = peers_from_addresses(
+            vec![addr.clone(), other_addr.clone(), no_key_addr],
+            PeerRole::Upstream,
+        );
+        let mut keys = HashSet::new();
+        keys.insert(key);
+        let mut expected = PeerSet::new();
+        expected.insert(
+            from_identity_public_key(key),
+            Peer::new(vec![addr, other_addr], keys, PeerRole::Upstream),
+        );
+        assert_eq!(expected, peers);
+    }
+}
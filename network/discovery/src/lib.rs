@@ -1,8 +1,14 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{counters::DISCOVERY_COUNTS, file::FileStream, validator_set::ValidatorSetStream};
-use aptos_config::{config::PeerSet, network_id::NetworkContext};
+use crate::{
+    counters::DISCOVERY_COUNTS, dns::DnsStream, file::FileStream, rest::RestStream,
+    validator_set::ValidatorSetStream,
+};
+use aptos_config::{
+    config::{PeerRole, PeerSet},
+    network_id::NetworkContext,
+};
 use aptos_crypto::x25519;
 use aptos_logger::prelude::*;
 use aptos_time_service::TimeService;
@@ -22,13 +28,17 @@ use std::{
 use tokio::runtime::Handle;
 
 mod counters;
+mod dns;
 mod file;
+mod rest;
 mod validator_set;
 
 #[derive(Debug)]
 pub enum DiscoveryError {
     IO(std::io::Error),
     Parsing(String),
+    Dns(String),
+    Rest(String),
 }
 
 /// A union type for all implementations of `DiscoveryChangeListenerTrait`
@@ -42,6 +52,8 @@ pub struct DiscoveryChangeListener {
 enum DiscoveryChangeStream {
     ValidatorSet(ValidatorSetStream),
     File(FileStream),
+    Dns(DnsStream),
+    Rest(RestStream),
 }
 
 impl Stream for DiscoveryChangeStream {
@@ -51,6 +63,8 @@ impl Stream for DiscoveryChangeStream {
         match self.get_mut() {
             Self::ValidatorSet(stream) => Pin::new(stream).poll_next(cx),
             Self::File(stream) => Pin::new(stream).poll_next(cx),
+            Self::Dns(stream) => Pin::new(stream).poll_next(cx),
+            Self::Rest(stream) => Pin::new(stream).poll_next(cx),
         }
     }
 }
@@ -95,6 +109,41 @@ impl DiscoveryChangeListener {
         }
     }
 
+    pub fn dns(
+        network_context: NetworkContext,
+        update_channel: channel::Sender<ConnectivityRequest>,
+        name: String,
+        role: PeerRole,
+        interval_duration: Duration,
+        time_service: TimeService,
+    ) -> Self {
+        let source_stream =
+            DiscoveryChangeStream::Dns(DnsStream::new(name, role, interval_duration, time_service));
+        DiscoveryChangeListener {
+            discovery_source: DiscoverySource::Dns,
+            network_context,
+            update_channel,
+            source_stream,
+        }
+    }
+
+    pub fn rest(
+        network_context: NetworkContext,
+        update_channel: channel::Sender<ConnectivityRequest>,
+        url: String,
+        interval_duration: Duration,
+        time_service: TimeService,
+    ) -> Self {
+        let source_stream =
+            DiscoveryChangeStream::Rest(RestStream::new(url, interval_duration, time_service));
+        DiscoveryChangeListener {
+            discovery_source: DiscoverySource::Rest,
+            network_context,
+            update_channel,
+            source_stream,
+        }
+    }
+
     pub fn start(self, executor: &Handle) {
         executor.spawn(Box::pin(self).run());
     }
@@ -0,0 +1,139 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::DiscoveryError;
+use aptos_config::config::PeerSet;
+use aptos_time_service::{Interval, TimeService, TimeServiceTrait};
+use futures::{future::BoxFuture, Future, Stream};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// Requests taking longer than this are abandoned, the next attempt is made at the next interval
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct RestStream {
+    url: String,
+    client: reqwest::Client,
+    interval: Pin<Box<Interval>>,
+    pending_request: Option<BoxFuture<'static, Result<PeerSet, DiscoveryError>>>,
+}
+
+impl RestStream {
+    pub(crate) fn new(url: String, interval_duration: Duration, time_service: TimeService) -> Self {
+        RestStream {
+            url,
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .expect("Unable to build REST discovery client"),
+            interval: Box::pin(time_service.interval(interval_duration)),
+            pending_request: None,
+        }
+    }
+}
+
+impl Stream for RestStream {
+    type Item = Result<PeerSet, DiscoveryError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // Wait for delay before sending the next request
+        if self.pending_request.is_none() {
+            futures::ready!(self.interval.as_mut().poll_next(cx));
+            let request = fetch_peers(self.client.clone(), self.url.clone());
+            self.pending_request = Some(Box::pin(request));
+        }
+
+        let result = futures::ready!(self.pending_request.as_mut().unwrap().as_mut().poll(cx));
+        self.pending_request = None;
+        Poll::Ready(Some(result))
+    }
+}
+
+/// Retrieves the JSON encoded `PeerSet` served at `url`
+async fn fetch_peers(client: reqwest::Client, url: String) -> Result<PeerSet, DiscoveryError> {
+    client
+        .get(&url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| DiscoveryError::Rest(err.to_string()))?
+        .json()
+        .await
+        .map_err(|err| DiscoveryError::Parsing(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DiscoveryChangeListener;
+    use aptos_config::{
+        config::{Peer, PeerRole},
+        network_id::NetworkContext,
+    };
+    use aptos_types::{network_address::NetworkAddress, PeerId};
+    use futures::StreamExt;
+    use network::connectivity_manager::{ConnectivityRequest, DiscoverySource};
+    use std::{collections::HashSet, str::FromStr};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    /// Serves `body` as JSON to every request
+    async fn serve(body: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/peers", listener.local_addr().unwrap());
+        tokio::task::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = [0u8; 1024];
+                let _ = socket.read(&mut request).await.unwrap();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_rest_listener() {
+        let mut peers = PeerSet::new();
+        let addr = NetworkAddress::from_str("/ip4/1.2.3.4/tcp/6180/noise-ik/080e287879c918794170e258bfaddd75acac5b3e350419044655e4983a487120/handshake/0").unwrap();
+        let key = addr.find_noise_proto().unwrap();
+        let mut keys = HashSet::new();
+        keys.insert(key);
+        peers.insert(
+            PeerId::random(),
+            Peer::new(vec![addr], keys, PeerRole::Upstream),
+        );
+        let url = serve(serde_json::to_string(&peers).unwrap()).await;
+
+        let (conn_mgr_reqs_tx, mut conn_mgr_reqs_rx) =
+            channel::new(1, &network::counters::PENDING_CONNECTIVITY_MANAGER_REQUESTS);
+        let listener = DiscoveryChangeListener::rest(
+            NetworkContext::mock(),
+            conn_mgr_reqs_tx,
+            url,
+            Duration::from_millis(5),
+            TimeService::real(),
+        );
+        tokio::task::spawn(Box::pin(listener).run());
+
+        if let Some(ConnectivityRequest::UpdateDiscoveredPeers(
+            DiscoverySource::Rest,
+            actual_peers,
+        )) = conn_mgr_reqs_rx.next().await
+        {
+            assert_eq!(peers, actual_peers)
+        } else {
+            panic!("No message sent by discovery")
+        }
+    }
+}
@@ -127,6 +127,8 @@ pub struct ConnectivityManager<TBackoff> {
 pub enum DiscoverySource {
     OnChainValidatorSet,
     File,
+    Rest,
+    Dns,
     Config,
 }
 
@@ -144,6 +146,8 @@ impl fmt::Display for DiscoverySource {
             match self {
                 DiscoverySource::OnChainValidatorSet => "OnChainValidatorSet",
                 DiscoverySource::File => "File",
+                DiscoverySource::Rest => "Rest",
+                DiscoverySource::Dns => "Dns",
                 DiscoverySource::Config => "Config",
             }
         )
@@ -52,6 +52,7 @@ pub enum ProtocolId {
     StorageServiceRpc = 8,
     MempoolRpc = 9,
     PeerMonitoringServiceRpc = 10,
+    ConsensusObserverDirectSend = 11,
 }
 
 /// The encoding types for Protocols
@@ -75,6 +76,7 @@ impl ProtocolId {
             StorageServiceRpc => "StorageServiceRpc",
             MempoolRpc => "MempoolRpc",
             PeerMonitoringServiceRpc => "PeerMonitoringServiceRpc",
+            ConsensusObserverDirectSend => "ConsensusObserverDirectSend",
         }
     }
 
@@ -91,6 +93,7 @@ impl ProtocolId {
             ProtocolId::StorageServiceRpc,
             ProtocolId::MempoolRpc,
             ProtocolId::PeerMonitoringServiceRpc,
+            ProtocolId::ConsensusObserverDirectSend,
         ]
     }
 
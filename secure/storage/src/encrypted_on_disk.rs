@@ -0,0 +1,294 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{from_base64, to_base64, CryptoKVStorage, Error, GetResponse, KVStorage};
+use aes_gcm::{
+    aead::{Aead, NewAead, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use aptos_temppath::TempPath;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::{rngs::OsRng, RngCore};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+use zeroize::Zeroizing;
+
+/// Version of the on disk format, bumped whenever the layout or the algorithms change.
+const FORMAT_VERSION: u32 = 1;
+/// Argon2id memory cost in KiB, time cost and parallelism used for newly derived keys.
+const KDF_MEMORY_COST: u32 = 19 * 1024;
+const KDF_TIME_COST: u32 = 2;
+const KDF_PARALLELISM: u32 = 1;
+/// Upper bounds of the Argon2id parameters read from a storage file, so that a tampered file
+/// can't make the key derivation exhaust the memory or the CPU of the node.
+const KDF_MAX_MEMORY_COST: u32 = 1024 * 1024;
+const KDF_MAX_TIME_COST: u32 = 16;
+const KDF_MAX_PARALLELISM: u32 = 16;
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const SALT_LENGTH: usize = 16;
+
+/// EncryptedOnDiskStorage is a key value store persisted to a single file on the local
+/// filesystem, like OnDiskStorage, except that the file is encrypted with AES-256-GCM using a key
+/// derived from a passphrase with Argon2id. The key derivation parameters and salt are stored in
+/// plaintext next to the ciphertext, so only the passphrase has to be provided to open the
+/// storage, and the derived key is zeroed once dropped. Every write re-encrypts the whole file
+/// with a fresh nonce and atomically replaces it.
+///
+/// Like OnDiskStorage it is intended for single threads (or must be wrapped by a Arc<RwLock<>>)
+/// and offers no permission checks, but it allows operators without Vault to keep their keys
+/// encrypted at rest.
+pub struct EncryptedOnDiskStorage {
+    file_path: PathBuf,
+    temp_path: TempPath,
+    time_service: TimeService,
+    kdf: KdfParams,
+    key: Zeroizing<[u8; KEY_LENGTH]>,
+}
+
+/// The content of the storage file.
+#[derive(Deserialize, Serialize)]
+struct EncryptedFile {
+    #[serde(flatten)]
+    header: Header,
+    #[serde(serialize_with = "to_base64", deserialize_with = "from_base64")]
+    ciphertext: Vec<u8>,
+}
+
+/// The plaintext part of the storage file. It is authenticated as the associated data of the
+/// ciphertext, so tampering with any of its fields makes the decryption fail.
+#[derive(Deserialize, Serialize)]
+struct Header {
+    version: u32,
+    kdf: KdfParams,
+    #[serde(serialize_with = "to_base64", deserialize_with = "from_base64")]
+    nonce: Vec<u8>,
+}
+
+impl Header {
+    fn associated_data(&self) -> Result<Vec<u8>, Error> {
+        Ok(bcs::to_bytes(self)?)
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct KdfParams {
+    memory_cost: u32,
+    time_cost: u32,
+    parallelism: u32,
+    #[serde(serialize_with = "to_base64", deserialize_with = "from_base64")]
+    salt: Vec<u8>,
+}
+
+impl KdfParams {
+    fn generate() -> Self {
+        let mut salt = vec![0; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        Self {
+            memory_cost: KDF_MEMORY_COST,
+            time_cost: KDF_TIME_COST,
+            parallelism: KDF_PARALLELISM,
+            salt,
+        }
+    }
+
+    fn derive_key(&self, passphrase: &str) -> Result<Zeroizing<[u8; KEY_LENGTH]>, Error> {
+        if self.memory_cost > KDF_MAX_MEMORY_COST
+            || self.time_cost > KDF_MAX_TIME_COST
+            || self.parallelism > KDF_MAX_PARALLELISM
+        {
+            return Err(Error::InternalError(format!(
+                "Key derivation parameters exceed the maximum: memory cost {}, time cost {}, \
+                parallelism {}",
+                self.memory_cost, self.time_cost, self.parallelism
+            )));
+        }
+        let params = Params::new(
+            self.memory_cost,
+            self.time_cost,
+            self.parallelism,
+            Some(KEY_LENGTH),
+        )
+        .map_err(|e| Error::InternalError(format!("Invalid key derivation parameters: {}", e)))?;
+        let mut key = Zeroizing::new([0; KEY_LENGTH]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &self.salt, &mut *key)
+            .map_err(|e| Error::InternalError(format!("Unable to derive key: {}", e)))?;
+        Ok(key)
+    }
+}
+
+impl EncryptedOnDiskStorage {
+    pub fn new(file_path: PathBuf, passphrase: &str) -> Self {
+        Self::new_with_time_service(file_path, passphrase, TimeService::real())
+    }
+
+    fn new_with_time_service(
+        file_path: PathBuf,
+        passphrase: &str,
+        time_service: TimeService,
+    ) -> Self {
+        // The parent will be one when only a filename is supplied. Therefore use the current
+        // working directory provided by PathBuf::new().
+        let file_dir = file_path
+            .parent()
+            .map_or(PathBuf::new(), |p| p.to_path_buf());
+
+        // An existing file keeps its salt so that the passphrase derives the same key, a wrong
+        // passphrase is only detected when decrypting.
+        let existing = Self::read_file(&file_path).expect("Unable to read storage");
+        let kdf = existing
+            .as_ref()
+            .map_or_else(KdfParams::generate, |file| file.header.kdf.clone());
+        let key = kdf
+            .derive_key(passphrase)
+            .expect("Unable to create storage");
+
+        let storage = Self {
+            file_path,
+            temp_path: TempPath::new_with_temp_dir(file_dir),
+            time_service,
+            kdf,
+            key,
+        };
+        if existing.is_none() {
+            storage
+                .write(&HashMap::new())
+                .expect("Unable to create storage");
+        }
+        storage
+    }
+
+    /// Re-encrypts the storage with a key derived from `new_passphrase` and a fresh salt. The
+    /// storage must be readable with the current passphrase.
+    pub fn rotate_passphrase(&mut self, new_passphrase: &str) -> Result<(), Error> {
+        let data = self.read()?;
+        let kdf = KdfParams::generate();
+        let key = kdf.derive_key(new_passphrase)?;
+        let (old_kdf, old_key) = (
+            std::mem::replace(&mut self.kdf, kdf),
+            std::mem::replace(&mut self.key, key),
+        );
+        if let Err(e) = self.write(&data) {
+            self.kdf = old_kdf;
+            self.key = old_key;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn read_file(file_path: &Path) -> Result<Option<EncryptedFile>, Error> {
+        if !file_path.exists() {
+            return Ok(None);
+        }
+        let mut file = File::open(file_path)?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        if contents.is_empty() {
+            return Ok(None);
+        }
+        let file: EncryptedFile = serde_json::from_str(&contents)?;
+        if file.header.version != FORMAT_VERSION {
+            return Err(Error::InternalError(format!(
+                "Unsupported storage format version: {}",
+                file.header.version
+            )));
+        }
+        Ok(Some(file))
+    }
+
+    fn read(&self) -> Result<HashMap<String, Value>, Error> {
+        let file = match Self::read_file(&self.file_path)? {
+            Some(file) => file,
+            None => return Ok(HashMap::new()),
+        };
+        if file.header.nonce.len() != NONCE_LENGTH {
+            return Err(Error::SerializationError("Invalid nonce length".into()));
+        }
+        // The cached key is derived from our salt, a different salt means the passphrase was
+        // rotated by another instance and the key is of no use anymore.
+        if file.header.kdf.salt != self.kdf.salt {
+            return Err(Error::PermissionDenied);
+        }
+        let payload = Payload {
+            msg: &file.ciphertext,
+            aad: &file.header.associated_data()?,
+        };
+        let contents = Aes256Gcm::new(Key::from_slice(&*self.key))
+            .decrypt(Nonce::from_slice(&file.header.nonce), payload)
+            .map_err(|_| Error::PermissionDenied)?;
+        let data = serde_json::from_slice(&contents)?;
+        Ok(data)
+    }
+
+    fn write(&self, data: &HashMap<String, Value>) -> Result<(), Error> {
+        let contents = serde_json::to_vec(data)?;
+        let mut nonce = vec![0; NONCE_LENGTH];
+        OsRng.fill_bytes(&mut nonce);
+        let header = Header {
+            version: FORMAT_VERSION,
+            kdf: self.kdf.clone(),
+            nonce,
+        };
+        let payload = Payload {
+            msg: &contents,
+            aad: &header.associated_data()?,
+        };
+        let ciphertext = Aes256Gcm::new(Key::from_slice(&*self.key))
+            .encrypt(Nonce::from_slice(&header.nonce), payload)
+            .map_err(|_| Error::InternalError("Unable to encrypt storage".into()))?;
+        let file = EncryptedFile { header, ciphertext };
+
+        // Write everything to a temporary file in the same directory and rename it over the
+        // storage file, so that a crash never leaves a partially written storage behind.
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut temp_file = options.open(self.temp_path.path())?;
+        temp_file.write_all(&serde_json::to_vec(&file)?)?;
+        temp_file.sync_all()?;
+        fs::rename(&self.temp_path, &self.file_path)?;
+        Ok(())
+    }
+}
+
+impl KVStorage for EncryptedOnDiskStorage {
+    fn available(&self) -> Result<(), Error> {
+        self.read().map(|_| ())
+    }
+
+    fn get<V: DeserializeOwned>(&self, key: &str) -> Result<GetResponse<V>, Error> {
+        let mut data = self.read()?;
+        data.remove(key)
+            .ok_or_else(|| Error::KeyNotSet(key.to_string()))
+            .and_then(|value| serde_json::from_value(value).map_err(|e| e.into()))
+    }
+
+    fn set<V: Serialize>(&mut self, key: &str, value: V) -> Result<(), Error> {
+        let now = self.time_service.now_secs();
+        let mut data = self.read()?;
+        data.insert(
+            key.to_string(),
+            serde_json::to_value(&GetResponse::new(value, now))?,
+        );
+        self.write(&data)
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    fn reset_and_clear(&mut self) -> Result<(), Error> {
+        self.write(&HashMap::new())
+    }
+}
+
+impl CryptoKVStorage for EncryptedOnDiskStorage {}
@@ -0,0 +1,71 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{tests::suite, EncryptedOnDiskStorage, Error, KVStorage, Storage};
+use aptos_temppath::TempPath;
+
+const PASSPHRASE: &str = "correct horse battery staple";
+
+#[test]
+fn encrypted_on_disk() {
+    let path_buf = TempPath::new().path().to_path_buf();
+    let mut storage = Storage::from(EncryptedOnDiskStorage::new(path_buf, PASSPHRASE));
+    suite::execute_all_storage_tests(&mut storage);
+}
+
+#[test]
+fn encrypted_on_disk_reopen_and_rotate() {
+    let temp_path = TempPath::new();
+    let path_buf = temp_path.path().to_path_buf();
+    let mut storage = EncryptedOnDiskStorage::new(path_buf.clone(), PASSPHRASE);
+    storage.set("key", "secret value").unwrap();
+
+    // Nothing is readable from the file itself
+    let contents = std::fs::read_to_string(&path_buf).unwrap();
+    assert!(!contents.contains("secret value"));
+
+    // Reopening requires the same passphrase
+    let storage = EncryptedOnDiskStorage::new(path_buf.clone(), PASSPHRASE);
+    assert_eq!(storage.get::<String>("key").unwrap().value, "secret value");
+    let wrong = EncryptedOnDiskStorage::new(path_buf.clone(), "wrong passphrase");
+    assert_eq!(wrong.available(), Err(Error::PermissionDenied));
+    assert_eq!(wrong.get::<String>("key"), Err(Error::PermissionDenied));
+
+    // After a rotation only the new passphrase opens the storage
+    let mut storage = EncryptedOnDiskStorage::new(path_buf.clone(), PASSPHRASE);
+    storage.rotate_passphrase("new passphrase").unwrap();
+    assert_eq!(storage.get::<String>("key").unwrap().value, "secret value");
+    let old = EncryptedOnDiskStorage::new(path_buf.clone(), PASSPHRASE);
+    assert_eq!(old.available(), Err(Error::PermissionDenied));
+    let new = EncryptedOnDiskStorage::new(path_buf, "new passphrase");
+    assert_eq!(new.get::<String>("key").unwrap().value, "secret value");
+}
+
+#[test]
+fn encrypted_on_disk_tampered_header() {
+    let temp_path = TempPath::new();
+    let path_buf = temp_path.path().to_path_buf();
+    let mut storage = EncryptedOnDiskStorage::new(path_buf.clone(), PASSPHRASE);
+    storage.set("key", "secret value").unwrap();
+
+    // The header is authenticated along with the ciphertext
+    let mut file: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path_buf).unwrap()).unwrap();
+    file["kdf"]["time_cost"] = serde_json::json!(3);
+    std::fs::write(&path_buf, serde_json::to_vec(&file).unwrap()).unwrap();
+    assert_eq!(storage.available(), Err(Error::PermissionDenied));
+}
+
+#[test]
+#[should_panic(expected = "Key derivation parameters exceed the maximum")]
+fn encrypted_on_disk_excessive_kdf_params() {
+    let temp_path = TempPath::new();
+    let path_buf = temp_path.path().to_path_buf();
+    EncryptedOnDiskStorage::new(path_buf.clone(), PASSPHRASE);
+
+    let mut file: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path_buf).unwrap()).unwrap();
+    file["kdf"]["memory_cost"] = serde_json::json!(u32::MAX);
+    std::fs::write(&path_buf, serde_json::to_vec(&file).unwrap()).unwrap();
+    EncryptedOnDiskStorage::new(path_buf, PASSPHRASE);
+}
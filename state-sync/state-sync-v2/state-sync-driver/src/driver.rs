@@ -16,7 +16,7 @@ use crate::{
     storage_synchronizer::StorageSynchronizerInterface,
     utils,
 };
-use aptos_config::config::{RoleType, StateSyncDriverConfig};
+use aptos_config::config::{ConsensusObserverConfig, RoleType, StateSyncDriverConfig};
 use aptos_data_client::AptosDataClient;
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
@@ -47,14 +47,23 @@ pub struct DriverConfiguration {
 
     // The trusted waypoint for the node
     pub waypoint: Waypoint,
+
+    // The config of the consensus observer, which may execute the blocks of the full node
+    pub consensus_observer_config: ConsensusObserverConfig,
 }
 
 impl DriverConfiguration {
-    pub fn new(config: StateSyncDriverConfig, role: RoleType, waypoint: Waypoint) -> Self {
+    pub fn new(
+        config: StateSyncDriverConfig,
+        role: RoleType,
+        waypoint: Waypoint,
+        consensus_observer_config: ConsensusObserverConfig,
+    ) -> Self {
         Self {
             config,
             role,
             waypoint,
+            consensus_observer_config,
         }
     }
 }
@@ -94,6 +103,9 @@ pub struct StateSyncDriver<DataClient, MempoolNotifier, StorageSyncer, Streaming
     // The timestamp at which the driver started executing
     start_time: Option<SystemTime>,
 
+    // The timestamp of the last commit notification sent by consensus (or the observer)
+    last_consensus_commit_time: Option<SystemTime>,
+
     // The interface to read from storage
     storage: Arc<dyn DbReader>,
 }
@@ -143,6 +155,7 @@ impl<
             event_subscription_service,
             mempool_notification_handler,
             start_time: None,
+            last_consensus_commit_time: None,
             storage,
         }
     }
@@ -180,9 +193,10 @@ impl<
 
     /// Handles a notification sent by consensus
     async fn handle_consensus_notification(&mut self, notification: ConsensusNotification) {
-        // Verify the notification: full nodes shouldn't receive notifications
-        // and consensus should only send notifications after bootstrapping!
-        let result = if self.driver_configuration.role == RoleType::FullNode {
+        // Verify the notification: full nodes shouldn't receive notifications (unless
+        // they run the consensus observer) and consensus should only send notifications
+        // after bootstrapping!
+        let result = if !self.is_consensus_driven() {
             Err(Error::FullNodeConsensusNotification(format!(
                 "Received consensus notification: {:?}",
                 notification
@@ -251,6 +265,7 @@ impl<
             ))
         );
         self.update_consensus_commit_metrics(&consensus_commit_notification);
+        self.last_consensus_commit_time = Some(SystemTime::now());
 
         // TODO(joshlind): can we get consensus to forward the events?
 
@@ -460,11 +475,38 @@ impl<
         self.driver_configuration.role == RoleType::Validator
     }
 
+    /// Returns true iff consensus (or the consensus observer) executes the
+    /// blocks of this node, and only asks state sync to sync when it falls behind
+    fn is_consensus_driven(&self) -> bool {
+        self.is_validator()
+            || self
+                .driver_configuration
+                .consensus_observer_config
+                .observer_enabled
+    }
+
+    /// Returns true iff the consensus observer of this full node hasn't committed
+    /// anything for too long (e.g., because there's no publisher to follow). In
+    /// this case, the node is continuously synced until the observer catches up.
+    fn is_consensus_observer_stalled(&self) -> bool {
+        let observer_config = &self.driver_configuration.consensus_observer_config;
+        if self.is_validator() || !observer_config.observer_enabled {
+            return false;
+        }
+
+        // Before the observer's first commit, the driver's start counts as progress
+        utils::is_consensus_observer_stalled(
+            self.last_consensus_commit_time.or(self.start_time),
+            Duration::from_millis(observer_config.state_sync_fallback_timeout_ms),
+        )
+    }
+
     /// Returns true iff consensus is currently executing
     fn check_if_consensus_executing(&self) -> bool {
-        self.is_validator()
+        self.is_consensus_driven()
             && self.bootstrapper.is_bootstrapped()
             && !self.consensus_notification_handler.active_sync_request()
+            && !self.is_consensus_observer_stalled()
     }
 
     /// Checks if the connection deadline has passed. If so, validators with
@@ -90,6 +90,7 @@ impl DriverFactory {
             node_config.state_sync.state_sync_driver,
             node_config.base.role,
             waypoint,
+            node_config.consensus_observer.clone(),
         );
 
         // Create the state sync driver
@@ -7,6 +7,7 @@ use crate::{
         create_event, create_ledger_info_at_version, create_transaction,
         verify_mempool_and_event_notification,
     },
+    utils::is_consensus_observer_stalled,
 };
 use aptos_config::config::{NodeConfig, RoleType};
 use aptos_data_client::aptosnet::AptosNetDataClient;
@@ -33,7 +34,11 @@ use executor_test_helpers::bootstrap_genesis;
 use futures::{FutureExt, StreamExt};
 use mempool_notifications::MempoolNotificationListener;
 use network::application::{interface::MultiNetworkSender, storage::PeerMetadataStorage};
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 use storage_interface::{DbReader, DbReaderWriter};
 use storage_service_client::StorageServiceClient;
 
@@ -182,6 +187,62 @@ async fn test_consensus_sync_request() {
     assert_err!(result);
 }
 
+#[tokio::test]
+async fn test_consensus_observer_notifications() {
+    // Create a driver for a full node running the consensus observer
+    let mut node_config = NodeConfig::default();
+    node_config.base.role = RoleType::FullNode;
+    node_config.consensus_observer.observer_enabled = true;
+    let (_full_node_driver, consensus_notifier, _, _, _) =
+        create_driver_for_tests(node_config, Waypoint::default(), None).await;
+
+    // Verify that the notifications are accepted, but the node isn't bootstrapped
+    for result in [
+        consensus_notifier
+            .notify_new_commit(vec![create_transaction()], vec![])
+            .await,
+        consensus_notifier
+            .sync_to_target(create_ledger_info_at_version(0))
+            .await,
+    ] {
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("BootstrapNotComplete"), "{}", error);
+    }
+}
+
+#[test]
+fn test_consensus_observer_stalled() {
+    let fallback_timeout = Duration::from_millis(
+        NodeConfig::default()
+            .consensus_observer
+            .state_sync_fallback_timeout_ms,
+    );
+
+    // Verify the observer isn't stalled before the driver starts
+    assert!(!is_consensus_observer_stalled(None, fallback_timeout));
+
+    // Verify the observer isn't stalled while it keeps committing
+    let last_commit_time = SystemTime::now();
+    assert!(!is_consensus_observer_stalled(
+        Some(last_commit_time),
+        fallback_timeout
+    ));
+
+    // Verify the observer is stalled (i.e., the node is continuously synced)
+    // once it hasn't committed for the fallback timeout
+    let last_commit_time = last_commit_time - fallback_timeout;
+    assert!(is_consensus_observer_stalled(
+        Some(last_commit_time),
+        fallback_timeout
+    ));
+
+    // Verify the node switches back to the observer once it commits again
+    assert!(!is_consensus_observer_stalled(
+        Some(SystemTime::now()),
+        fallback_timeout
+    ));
+}
+
 /// Creates a state sync driver for a validator node
 async fn create_validator_driver(
     event_key_subscriptions: Option<Vec<EventKey>>,
@@ -23,7 +23,10 @@ use data_streaming_service::{
 use event_notifications::EventSubscriptionService;
 use futures::StreamExt;
 use mempool_notifications::MempoolNotificationSender;
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 use storage_interface::{DbReader, StartupInfo};
 use tokio::time::timeout;
 
@@ -288,3 +291,18 @@ pub async fn handle_committed_transactions<M: MempoolNotificationSender>(
             .message("Failed to handle a transaction commit notification!"));
     }
 }
+
+/// Returns true iff the consensus observer hasn't committed since
+/// `last_progress_time` for at least `fallback_timeout`. In this case, the
+/// node is continuously synced until the observer commits again.
+pub fn is_consensus_observer_stalled(
+    last_progress_time: Option<SystemTime>,
+    fallback_timeout: Duration,
+) -> bool {
+    match last_progress_time {
+        Some(last_progress_time) => SystemTime::now()
+            .duration_since(last_progress_time)
+            .map_or(false, |elapsed| elapsed >= fallback_timeout),
+        None => false,
+    }
+}
@@ -201,6 +201,7 @@ mod tests {
             false,
             NO_OP_STORAGE_PRUNER_CONFIG,
             RocksdbConfig::default(),
+            false, /* enable_event_by_type_index */
         )
         .unwrap();
         let (_, db_rw) = DbReaderWriter::wrap(db);
@@ -2,14 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    error_if_version_is_pruned, get_first_seq_num_and_limit,
-    pruner::{Pruner, PrunerIndex},
+    error_if_ledger_store_version_is_pruned, error_if_version_is_pruned,
+    get_first_seq_num_and_limit,
+    jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+    pruner::{Pruner, PrunerIndex, PrunerProgress, EVENT_STORE_PRUNER_NAME, WRITE_SET_PRUNER_NAME},
     test_helper,
     test_helper::{arb_blocks_to_commit, put_as_state_root, put_transaction_info},
     AptosDB, ROCKSDB_PROPERTIES,
 };
-use aptos_config::config::StoragePrunerConfig;
+use aptos_config::config::{LedgerSubPrunerWindows, StoragePrunerConfig};
 use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_jellyfish_merkle::node_type::NodeKey;
 use aptos_temppath::TempPath;
 use aptos_types::{
     proof::SparseMerkleLeafNode,
@@ -88,6 +91,7 @@ fn test_error_if_version_is_pruned() {
             state_store_prune_window: Some(0),
             ledger_prune_window: Some(0),
             pruning_batch_size: 1,
+            ledger_sub_pruner_windows: LedgerSubPrunerWindows::default(),
         },
     );
     pruner.testonly_update_min_version(&[Some(5), Some(10)]);
@@ -101,16 +105,47 @@ fn test_error_if_version_is_pruned() {
     assert!(
         error_if_version_is_pruned(&pruner, PrunerIndex::StateStorePrunerIndex, "State", 5).is_ok()
     );
+}
+
+#[test]
+fn test_error_if_ledger_store_version_is_pruned() {
+    let tmp_dir = TempPath::new();
+    let aptos_db = AptosDB::new_for_test(&tmp_dir);
+    let mut pruner = Pruner::new(
+        Arc::clone(&aptos_db.ledger_db),
+        Arc::clone(&aptos_db.state_merkle_db),
+        StoragePrunerConfig {
+            state_store_prune_window: None,
+            ledger_prune_window: Some(0),
+            pruning_batch_size: 1,
+            ledger_sub_pruner_windows: LedgerSubPrunerWindows::default(),
+        },
+    );
+    let progress = |name: &str, min_readable_version| PrunerProgress {
+        name: name.to_string(),
+        min_readable_version,
+        target_version: min_readable_version,
+    };
+    pruner.testonly_update_progress(vec![
+        progress(WRITE_SET_PRUNER_NAME, 10),
+        progress(EVENT_STORE_PRUNER_NAME, 5),
+    ]);
+    let pruner = Some(pruner);
     assert_eq!(
-        error_if_version_is_pruned(&pruner, PrunerIndex::LedgerPrunerIndex, "Transaction", 9)
+        error_if_ledger_store_version_is_pruned(&pruner, WRITE_SET_PRUNER_NAME, "Write set", 9)
             .unwrap_err()
             .to_string(),
-        "Transaction version 9 is pruned, min available version is 10."
+        "Write set version 9 is pruned, min available version is 10."
     );
+    // the events are kept longer than the write sets
     assert!(
-        error_if_version_is_pruned(&pruner, PrunerIndex::LedgerPrunerIndex, "Transaction", 10)
+        error_if_ledger_store_version_is_pruned(&pruner, EVENT_STORE_PRUNER_NAME, "Event", 9)
             .is_ok()
     );
+    assert!(
+        error_if_ledger_store_version_is_pruned(&pruner, EVENT_STORE_PRUNER_NAME, "Event", 4)
+            .is_err()
+    );
 }
 
 #[test]
@@ -158,6 +193,53 @@ fn test_get_latest_tree_state() {
     );
 }
 
+#[test]
+fn test_create_checkpoint() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let key = StateKey::Raw(String::from("test_key").into_bytes());
+    let value = StateValue::from(String::from("test_val").into_bytes());
+    let hash = SparseMerkleLeafNode::new(key.hash(), value.hash()).hash();
+
+    put_as_state_root(&db, PRE_GENESIS_VERSION, key.clone(), value.clone());
+    put_as_state_root(&db, 0, key.clone(), value.clone());
+    let txn_info = TransactionInfo::new(
+        HashValue::random(),
+        HashValue::random(),
+        HashValue::random(),
+        Some(hash),
+        0,
+        ExecutionStatus::MiscellaneousError(None),
+    );
+    put_transaction_info(&db, 0, &txn_info);
+    // The state of version 1 is written, but not its transaction, as in the middle of a commit.
+    put_as_state_root(&db, 1, key, value);
+
+    let checkpoint_dir = TempPath::new();
+    assert_eq!(db.create_checkpoint(&checkpoint_dir).unwrap(), Some(0));
+
+    let checkpoint = AptosDB::new_for_test(&checkpoint_dir);
+    assert_eq!(
+        checkpoint.get_latest_tree_state().unwrap(),
+        TreeState::new(
+            1,
+            vec![txn_info.hash()],
+            txn_info.state_checkpoint_hash().unwrap(),
+            Some(0),
+        ),
+    );
+    assert_eq!(
+        checkpoint.get_state_snapshot_before(2).unwrap(),
+        Some((0, hash))
+    );
+    // The pre-genesis state is kept.
+    assert!(checkpoint
+        .state_merkle_db
+        .get::<JellyfishMerkleNodeSchema>(&NodeKey::new_empty_path(PRE_GENESIS_VERSION))
+        .unwrap()
+        .is_some());
+}
+
 #[test]
 fn test_rocksdb_properties_reporter() {
     fn get_metric() -> i64 {
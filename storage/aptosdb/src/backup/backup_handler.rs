@@ -8,11 +8,13 @@ use crate::{
         BACKUP_EPOCH_ENDING_EPOCH, BACKUP_STATE_SNAPSHOT_LEAF_IDX, BACKUP_STATE_SNAPSHOT_VERSION,
         BACKUP_TXN_VERSION,
     },
+    pruner::PrunerProgress,
     state_store::StateStore,
     transaction_store::TransactionStore,
 };
 use anyhow::{ensure, Result};
 use aptos_crypto::hash::HashValue;
+use aptos_infallible::Mutex;
 use aptos_types::{
     contract_event::ContractEvent,
     ledger_info::LedgerInfoWithSignatures,
@@ -31,6 +33,8 @@ pub struct BackupHandler {
     transaction_store: Arc<TransactionStore>,
     state_store: Arc<StateStore>,
     event_store: Arc<EventStore>,
+    /// None if pruning is disabled.
+    pruner_progress: Option<Arc<Mutex<Vec<PrunerProgress>>>>,
 }
 
 impl BackupHandler {
@@ -39,12 +43,14 @@ impl BackupHandler {
         transaction_store: Arc<TransactionStore>,
         state_store: Arc<StateStore>,
         event_store: Arc<EventStore>,
+        pruner_progress: Option<Arc<Mutex<Vec<PrunerProgress>>>>,
     ) -> Self {
         Self {
             ledger_store,
             transaction_store,
             state_store,
             event_store,
+            pruner_progress,
         }
     }
 
@@ -150,6 +156,13 @@ impl BackupHandler {
             .transpose()
     }
 
+    /// Gets the progress of the pruners, empty if pruning is disabled.
+    pub fn get_pruner_progress(&self) -> Vec<PrunerProgress> {
+        self.pruner_progress
+            .as_ref()
+            .map_or_else(Vec::new, |progress| progress.lock().clone())
+    }
+
     /// Gets the proof of the state root at specified version.
     /// N.B. the `LedgerInfo` returned will always be in the same epoch of the version.
     pub fn get_state_root_proof(
@@ -11,8 +11,12 @@ use crate::{
     errors::AptosDbError,
     ledger_counters::{LedgerCounter, LedgerCounterBumps},
     schema::{
-        event::EventSchema, event_accumulator::EventAccumulatorSchema,
-        event_by_key::EventByKeySchema, event_by_version::EventByVersionSchema,
+        db_metadata::{DbMetadataKey, DbMetadataSchema},
+        event::EventSchema,
+        event_accumulator::EventAccumulatorSchema,
+        event_by_key::EventByKeySchema,
+        event_by_type::EventByTypeSchema,
+        event_by_version::EventByVersionSchema,
     },
 };
 use accumulator::{HashReader, MerkleAccumulator};
@@ -26,6 +30,7 @@ use aptos_types::{
     block_metadata::new_block_event_key, contract_event::ContractEvent, event::EventKey,
     proof::position::Position, transaction::Version,
 };
+use move_deps::move_core_types::language_storage::TypeTag;
 use schemadb::{schema::ValueCodec, ReadOptions, SchemaBatch, SchemaIterator, DB};
 use std::{
     collections::{hash_map::Entry, HashMap, HashSet},
@@ -37,11 +42,33 @@ use std::{
 #[derive(Debug)]
 pub struct EventStore {
     db: Arc<DB>,
+    // Whether the events are indexed by type at commit.
+    enable_event_by_type_index: bool,
 }
 
 impl EventStore {
-    pub fn new(db: Arc<DB>) -> Self {
-        Self { db }
+    pub fn new(db: Arc<DB>, enable_event_by_type_index: bool) -> Self {
+        Self {
+            db,
+            enable_event_by_type_index,
+        }
+    }
+
+    /// Records `next_version` as the first version indexed by type if the index is enabled and
+    /// wasn't already. Forgets it if the index is disabled, since the events committed in the
+    /// meantime aren't indexed, so that enabling the index again restarts it.
+    pub fn init_event_by_type_index(&self, next_version: Version) -> Result<()> {
+        let key = DbMetadataKey::EventByTypeIndexStartVersion;
+        if self.enable_event_by_type_index {
+            if self.db.get::<DbMetadataSchema>(&key)?.is_none() {
+                self.db.put::<DbMetadataSchema>(&key, &next_version)?;
+            }
+        } else {
+            let mut batch = SchemaBatch::new();
+            batch.delete::<DbMetadataSchema>(&key)?;
+            self.db.write_schemas(batch)?;
+        }
+        Ok(())
     }
 
     /// Get all of the events given a transaction version.
@@ -180,6 +207,56 @@ impl EventStore {
         Ok(result)
     }
 
+    /// Returns the first version indexed by type, `None` if the index is not enabled.
+    pub fn event_by_type_index_start_version(&self) -> Result<Option<Version>> {
+        if !self.enable_event_by_type_index {
+            return Ok(None);
+        }
+        self.db
+            .get::<DbMetadataSchema>(&DbMetadataKey::EventByTypeIndexStartVersion)
+    }
+
+    /// Given `type_tag` and `start_version`, returns up to `limit` events of the type identified
+    /// by transaction version and index among all events emitted by the same transaction. Result
+    /// won't contain records with a transaction version > `ledger_version` and is in ascending
+    /// order. Fails if `start_version` is before the first version indexed, since the events of
+    /// the versions committed before the index was enabled aren't indexed.
+    pub fn lookup_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<
+        Vec<(
+            Version, // transaction version it belongs to
+            u64,     // index among events for the same transaction
+        )>,
+    > {
+        let index_start_version = self
+            .event_by_type_index_start_version()?
+            .ok_or_else(|| format_err!("The event by type index is not enabled."))?;
+        ensure!(
+            start_version >= index_start_version,
+            "Events of version {} aren't indexed by type, the index starts at version {}.",
+            start_version,
+            index_start_version,
+        );
+        let mut iter = self.db.iter::<EventByTypeSchema>(ReadOptions::default())?;
+        iter.seek(&(type_tag.clone(), start_version, 0))?;
+
+        let mut result = Vec::new();
+        for res in iter.take(limit as usize) {
+            let ((typ, ver, idx), ()) = res?;
+            if &typ != type_tag || ver > ledger_version {
+                break;
+            }
+            result.push((ver, idx));
+        }
+
+        Ok(result)
+    }
+
     fn lookup_event_by_key(
         &self,
         event_key: &EventKey,
@@ -224,6 +301,12 @@ impl EventStore {
                     &(*event.key(), version, event.sequence_number()),
                     &(idx as u64),
                 )?;
+                if self.enable_event_by_type_index {
+                    cs.batch.put::<EventByTypeSchema>(
+                        &(event.type_tag().clone(), version, idx as u64),
+                        &(),
+                    )?;
+                }
                 Ok(())
             })?;
 
@@ -397,6 +480,13 @@ impl EventStore {
                     event.sequence_number(),
                 ))?;
                 db_batch.delete::<EventByKeySchema>(&(*event.key(), event.sequence_number()))?;
+                // Deleted even if the index is disabled, it might have been enabled when the
+                // events were committed.
+                db_batch.delete::<EventByTypeSchema>(&(
+                    event.type_tag().clone(),
+                    current_version as u64,
+                    current_index as u64,
+                ))?;
                 db_batch.delete::<EventSchema>(&(current_version as u64, current_index as u64))?;
             }
             current_version += 1;
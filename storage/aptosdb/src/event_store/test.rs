@@ -197,6 +197,8 @@ fn test_index_get_impl(event_batches: Vec<Vec<ContractEvent>>) {
         event_batches,
     );
 
+    check_events_by_type(store, &event_batches);
+
     // Calculate expected event sequence per access_path.
     let mut events_by_event_key = HashMap::new();
     event_batches
@@ -256,6 +258,81 @@ fn test_index_get_impl(event_batches: Vec<Vec<ContractEvent>>) {
         });
 }
 
+fn check_events_by_type(store: &EventStore, event_batches: &[Vec<ContractEvent>]) {
+    let mut events_by_type: HashMap<TypeTag, Vec<(Version, u64)>> = HashMap::new();
+    for (ver, batch) in event_batches.iter().enumerate() {
+        for (idx, event) in batch.iter().enumerate() {
+            events_by_type
+                .entry(event.type_tag().clone())
+                .or_default()
+                .push((ver as Version, idx as u64));
+        }
+    }
+
+    let ledger_version = event_batches.len() as Version;
+    for (type_tag, expected) in events_by_type {
+        assert_eq!(
+            store
+                .lookup_events_by_type(&type_tag, 0, u64::MAX, ledger_version)
+                .unwrap(),
+            expected,
+        );
+
+        // Starting after the first event, up to the version of the last event but one.
+        let (first_ver, _) = expected[0];
+        let (last_ver, _) = *expected.last().unwrap();
+        let expected_range: Vec<_> = expected
+            .iter()
+            .cloned()
+            .filter(|(ver, _)| *ver > first_ver && *ver < last_ver)
+            .collect();
+        assert_eq!(
+            store
+                .lookup_events_by_type(
+                    &type_tag,
+                    first_ver + 1,
+                    expected_range.len() as u64,
+                    last_ver.saturating_sub(1),
+                )
+                .unwrap(),
+            expected_range,
+        );
+    }
+}
+
+#[test]
+fn test_events_by_type_disabled() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = EventStore::new(Arc::clone(&db.ledger_db), false);
+    let event = ContractEvent::new(EventKey::random(), 0, TypeTag::Bool, vec![]);
+    save(&store, 0, &[event.clone()]);
+
+    // Events are not indexed and can't be looked up by type.
+    assert!(store
+        .lookup_events_by_type(&TypeTag::Bool, 0, 1, 0)
+        .is_err());
+    let indexed = EventStore::new(Arc::clone(&db.ledger_db), true);
+    assert!(indexed
+        .lookup_events_by_type(&TypeTag::Bool, 0, 1, 0)
+        .unwrap()
+        .is_empty());
+
+    // Once the index is disabled, enabling it again only indexes the versions committed since.
+    store.init_event_by_type_index(1).unwrap();
+    indexed.init_event_by_type_index(1).unwrap();
+    assert!(indexed
+        .lookup_events_by_type(&TypeTag::Bool, 0, 1, 0)
+        .is_err());
+    save(&indexed, 1, &[event]);
+    assert_eq!(
+        indexed
+            .lookup_events_by_type(&TypeTag::Bool, 1, 1, 1)
+            .unwrap(),
+        vec![(1, 0)],
+    );
+}
+
 prop_compose! {
     fn arb_new_block_events()(
         address in any::<AccountAddress>(),
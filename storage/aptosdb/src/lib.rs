@@ -46,19 +46,25 @@ use crate::{
         API_LATENCY_SECONDS, COMMITTED_TXNS, LATEST_TXN_VERSION, LEDGER_VERSION, NEXT_BLOCK_EPOCH,
         OTHER_TIMERS_SECONDS, ROCKSDB_PROPERTIES, STATE_ITEM_COUNT,
     },
-    pruner::{utils, Pruner, PrunerIndex},
-    schema::*,
+    pruner::{
+        utils, Pruner, PrunerIndex, EVENT_STORE_PRUNER_NAME, TRANSACTION_STORE_PRUNER_NAME,
+        WRITE_SET_PRUNER_NAME,
+    },
+    schema::{
+        jellyfish_merkle_node::JellyfishMerkleNodeSchema, stale_node_index::StaleNodeIndexSchema, *,
+    },
     state_store::StateStore,
     system_store::SystemStore,
     transaction_store::TransactionStore,
 };
-use anyhow::{ensure, Result};
+use anyhow::{bail, ensure, format_err, Result};
 use aptos_config::config::{RocksdbConfig, StoragePrunerConfig, NO_OP_STORAGE_PRUNER_CONFIG};
 use aptos_crypto::hash::{HashValue, SPARSE_MERKLE_PLACEHOLDER_HASH};
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_types::{
     account_address::AccountAddress,
+    block_metadata::{new_block_event_key, BlockMetadata},
     contract_event::EventWithVersion,
     epoch_change::EpochChangeProof,
     event::EventKey,
@@ -82,6 +88,7 @@ use aptos_types::{
     write_set::WriteSet,
 };
 use itertools::zip_eq;
+use move_deps::move_core_types::language_storage::TypeTag;
 use once_cell::sync::Lazy;
 use schemadb::{SchemaBatch, DB};
 use std::{
@@ -94,8 +101,8 @@ use std::{
     time::{Duration, Instant},
 };
 use storage_interface::{
-    jmt_update_refs, jmt_updates, DbReader, DbWriter, Order, StartupInfo, StateSnapshotReceiver,
-    TreeState,
+    jmt_update_refs, jmt_updates, CommittedBlock, DbReader, DbWriter, Order, StartupInfo,
+    StateSnapshotReceiver, TreeState,
 };
 
 pub const LEDGER_DB_NAME: &str = "ledger_db";
@@ -160,18 +167,39 @@ fn error_if_version_is_pruned(
     data_type: &str,
     version: Version,
 ) -> Result<()> {
-    if let Some(pruner) = pruner.as_ref() {
-        if let Some(min_readable_version) =
-            pruner.get_min_readable_version_by_pruner_index(pruner_index)
-        {
-            ensure!(
-                version >= min_readable_version,
-                "{} version {} is pruned, min available version is {}.",
-                data_type,
-                version,
-                min_readable_version
-            );
-        }
+    let min_readable_version = pruner
+        .as_ref()
+        .and_then(|pruner| pruner.get_min_readable_version_by_pruner_index(pruner_index));
+    error_if_version_is_below(min_readable_version, data_type, version)
+}
+
+/// Like `error_if_version_is_pruned`, for one of the stores pruned by the ledger pruner, which
+/// are pruned up to different versions.
+fn error_if_ledger_store_version_is_pruned(
+    pruner: &Option<Pruner>,
+    store_name: &str,
+    data_type: &str,
+    version: Version,
+) -> Result<()> {
+    let min_readable_version = pruner
+        .as_ref()
+        .and_then(|pruner| pruner.get_min_readable_ledger_store_version(store_name));
+    error_if_version_is_below(min_readable_version, data_type, version)
+}
+
+fn error_if_version_is_below(
+    min_readable_version: Option<Version>,
+    data_type: &str,
+    version: Version,
+) -> Result<()> {
+    if let Some(min_readable_version) = min_readable_version {
+        ensure!(
+            version >= min_readable_version,
+            "{} version {} is pruned, min available version is {}.",
+            data_type,
+            version,
+            min_readable_version
+        );
     }
     Ok(())
 }
@@ -263,11 +291,12 @@ impl AptosDB {
         ledger_rocksdb: DB,
         state_merkle_rocksdb: DB,
         storage_pruner_config: StoragePrunerConfig,
+        enable_event_by_type_index: bool,
     ) -> Self {
         let arc_ledger_rocksdb = Arc::new(ledger_rocksdb);
         let arc_state_merkle_rocksdb = Arc::new(state_merkle_rocksdb);
         let pruner_config = storage_pruner_config;
-        let pruner = if pruner_config.ledger_prune_window.is_none()
+        let pruner = if !pruner_config.is_ledger_pruner_enabled()
             && pruner_config.state_store_prune_window.is_none()
         {
             None
@@ -281,7 +310,10 @@ impl AptosDB {
         AptosDB {
             ledger_db: Arc::clone(&arc_ledger_rocksdb),
             state_merkle_db: Arc::clone(&arc_state_merkle_rocksdb),
-            event_store: Arc::new(EventStore::new(Arc::clone(&arc_ledger_rocksdb))),
+            event_store: Arc::new(EventStore::new(
+                Arc::clone(&arc_ledger_rocksdb),
+                enable_event_by_type_index,
+            )),
             ledger_store: Arc::new(LedgerStore::new(Arc::clone(&arc_ledger_rocksdb))),
             state_store: Arc::new(StateStore::new(
                 Arc::clone(&arc_ledger_rocksdb),
@@ -304,6 +336,7 @@ impl AptosDB {
         readonly: bool,
         storage_pruner_config: StoragePrunerConfig,
         rocksdb_config: RocksdbConfig,
+        enable_event_by_type_index: bool,
     ) -> Result<Self> {
         ensure!(
             storage_pruner_config.eq(&NO_OP_STORAGE_PRUNER_CONFIG) || !readonly,
@@ -350,7 +383,19 @@ impl AptosDB {
             )
         };
 
-        let ret = Self::new_with_dbs(ledger_db, state_merkle_db, storage_pruner_config);
+        let ret = Self::new_with_dbs(
+            ledger_db,
+            state_merkle_db,
+            storage_pruner_config,
+            enable_event_by_type_index,
+        );
+        if !readonly {
+            let next_version = ret
+                .ledger_store
+                .get_latest_transaction_info_option()?
+                .map_or(0, |(version, _)| version + 1);
+            ret.event_store.init_event_by_type_index(next_version)?;
+        }
         info!(
             ledger_db_path = ledger_db_path,
             state_merkle_db_path = state_merkle_db_path,
@@ -391,6 +436,7 @@ impl AptosDB {
                 state_merkle_db_column_families(),
             )?,
             NO_OP_STORAGE_PRUNER_CONFIG,
+            false, /* enable_event_by_type_index */
         ))
     }
 
@@ -402,6 +448,7 @@ impl AptosDB {
             false,                       /* readonly */
             NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
             RocksdbConfig::default(),
+            true, /* enable_event_by_type_index */
         )
         .expect("Unable to open AptosDB")
     }
@@ -481,12 +528,20 @@ impl AptosDB {
         ledger_version: Version,
         fetch_events: bool,
     ) -> Result<TransactionWithProof> {
-        error_if_version_is_pruned(
+        error_if_ledger_store_version_is_pruned(
             &self.pruner,
-            PrunerIndex::LedgerPrunerIndex,
+            TRANSACTION_STORE_PRUNER_NAME,
             "Transaction",
             version,
         )?;
+        if fetch_events {
+            error_if_ledger_store_version_is_pruned(
+                &self.pruner,
+                EVENT_STORE_PRUNER_NAME,
+                "Event",
+                version,
+            )?;
+        }
         let proof = self
             .ledger_store
             .get_transaction_info_with_proof(version, ledger_version)?;
@@ -507,6 +562,43 @@ impl AptosDB {
         })
     }
 
+    /// Returns the block starting at `first_version`, the following block being the one whose
+    /// `NewBlockEvent` has sequence number `next_block_seq_num`. A block not followed by any
+    /// block yet spans up to `ledger_version`.
+    fn get_committed_block(
+        &self,
+        first_version: Version,
+        metadata: Option<BlockMetadata>,
+        next_block_seq_num: u64,
+        ledger_version: Version,
+    ) -> Result<CommittedBlock> {
+        let next_block_version = self
+            .event_store
+            .lookup_events_by_key(
+                &new_block_event_key(),
+                next_block_seq_num,
+                1,
+                ledger_version,
+            )?
+            .first()
+            .map(|(_seq, version, _idx)| *version);
+        let last_version = match next_block_version {
+            Some(version) => version.checked_sub(1).ok_or_else(|| {
+                format_err!("A block with non-zero seq num started at version 0.")
+            })?,
+            None => ledger_version,
+        };
+
+        Ok(CommittedBlock {
+            // The height of a block is one more than the sequence number of its `NewBlockEvent`,
+            // as the genesis transaction is the block at height 0.
+            height: next_block_seq_num,
+            first_version,
+            last_version,
+            metadata,
+        })
+    }
+
     fn get_tree_state(&self, version: Option<Version>) -> Result<TreeState> {
         let num_transactions = version.map_or(0, |v| v + 1);
 
@@ -540,23 +632,64 @@ impl AptosDB {
             Arc::clone(&self.transaction_store),
             Arc::clone(&self.state_store),
             Arc::clone(&self.event_store),
+            self.pruner.as_ref().map(Pruner::get_progress_handle),
         )
     }
 
     /// Creates new physical DB checkpoint in directory specified by `path`.
-    pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+    ///
+    /// It's safe to call while transactions are being committed: a commit writes the state merkle
+    /// DB before the ledger DB, so checkpointing the ledger DB first guarantees that the state
+    /// merkle DB checkpoint has the JMT nodes of every transaction in the ledger DB checkpoint. The
+    /// nodes of the transactions committed in between are then removed from the checkpoint.
+    /// Returns the version of the last transaction in the checkpoint, if any.
+    pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<Option<Version>> {
         let start = Instant::now();
         let ledger_db_path = path.as_ref().join(LEDGER_DB_NAME);
         let state_merkle_db_path = path.as_ref().join(STATE_MERKLE_DB_NAME);
+        std::fs::create_dir_all(path.as_ref())?;
         self.ledger_db.create_checkpoint(&ledger_db_path)?;
         self.state_merkle_db
             .create_checkpoint(&state_merkle_db_path)?;
+        let version = Self::truncate_state_merkle_db_checkpoint(path.as_ref())?;
         info!(
             path = path.as_ref(),
+            version = ?version,
             time_ms = %start.elapsed().as_millis(),
             "Made AptosDB checkpoint."
         );
-        Ok(())
+        Ok(version)
+    }
+
+    /// Removes the JMT nodes newer than the last transaction in the ledger DB from the checkpoint
+    /// at `path`, along with their stale node indices, and returns the version of that transaction.
+    fn truncate_state_merkle_db_checkpoint(path: &Path) -> Result<Option<Version>> {
+        let checkpoint = Self::open(
+            path,
+            false, /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            RocksdbConfig::default(),
+            false, /* enable_event_by_type_index */
+        )?;
+        let version = checkpoint
+            .ledger_store
+            .get_latest_transaction_info_option()?
+            .map(|(version, _txn_info)| version);
+        // Nodes of the pre-genesis state, at version `PRE_GENESIS_VERSION`, are kept.
+        let first_version_to_remove = version.map_or(0, |version| version + 1);
+        checkpoint
+            .state_merkle_db
+            .range_delete::<JellyfishMerkleNodeSchema, (Version, u8)>(
+                &(first_version_to_remove, 0),
+                &(PRE_GENESIS_VERSION, 0),
+            )?;
+        checkpoint
+            .state_merkle_db
+            .range_delete::<StaleNodeIndexSchema, Version>(
+                &first_version_to_remove,
+                &PRE_GENESIS_VERSION,
+            )?;
+        Ok(version)
     }
 
     // ================================== Private APIs ==================================
@@ -609,6 +742,12 @@ impl AptosDB {
         let mut events_with_version = event_indices
             .into_iter()
             .map(|(seq, ver, idx)| {
+                error_if_ledger_store_version_is_pruned(
+                    &self.pruner,
+                    EVENT_STORE_PRUNER_NAME,
+                    "Event",
+                    ver,
+                )?;
                 let event = self.event_store.get_event_by_version_and_index(ver, idx)?;
                 ensure!(
                     seq == event.sequence_number(),
@@ -759,6 +898,19 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_state_value_chunk_by_key_prefix(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        first_key: Option<&StateKey>,
+        limit: usize,
+        version: Version,
+    ) -> Result<(Vec<(StateKey, StateValue)>, Option<StateKey>)> {
+        gauged_api("get_state_value_chunk_by_key_prefix", || {
+            self.state_store
+                .get_value_chunk_by_key_prefix(key_prefix, first_key, limit, version)
+        })
+    }
+
     fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
         gauged_api("get_latest_ledger_info_option", || {
             Ok(self.ledger_store.get_latest_ledger_info_option())
@@ -860,12 +1012,20 @@ impl DbReader for AptosDB {
                 return Ok(TransactionListWithProof::new_empty());
             }
 
-            error_if_version_is_pruned(
+            error_if_ledger_store_version_is_pruned(
                 &self.pruner,
-                PrunerIndex::LedgerPrunerIndex,
+                TRANSACTION_STORE_PRUNER_NAME,
                 "Transaction",
                 start_version,
             )?;
+            if fetch_events {
+                error_if_ledger_store_version_is_pruned(
+                    &self.pruner,
+                    EVENT_STORE_PRUNER_NAME,
+                    "Event",
+                    start_version,
+                )?;
+            }
 
             let limit = std::cmp::min(limit, ledger_version - start_version + 1);
 
@@ -905,11 +1065,12 @@ impl DbReader for AptosDB {
     /// Get the first version that txn starts existent.
     fn get_first_txn_version(&self) -> Result<Option<Version>> {
         gauged_api("get_first_txn_version", || {
-            if let Some(pruner) = self.pruner.as_ref() {
+            match self.pruner.as_ref().and_then(|pruner| {
+                pruner.get_min_readable_ledger_store_version(TRANSACTION_STORE_PRUNER_NAME)
+            }) {
                 // If pruning is enabled, we can get the min readable version from the pruner.
-                Ok(pruner.get_min_readable_ledger_version())
-            } else {
-                self.transaction_store.get_first_txn_version()
+                Some(min_readable_version) => Ok(Some(min_readable_version)),
+                None => self.transaction_store.get_first_txn_version(),
             }
         })
     }
@@ -917,11 +1078,12 @@ impl DbReader for AptosDB {
     /// Get the first version that write set starts existent.
     fn get_first_write_set_version(&self) -> Result<Option<Version>> {
         gauged_api("get_first_write_set_version", || {
-            if let Some(pruner) = self.pruner.as_ref() {
+            match self.pruner.as_ref().and_then(|pruner| {
+                pruner.get_min_readable_ledger_store_version(WRITE_SET_PRUNER_NAME)
+            }) {
                 // If pruning is enabled, we can get the min readable version from the pruner.
-                Ok(pruner.get_min_readable_ledger_version())
-            } else {
-                self.transaction_store.get_first_write_set_version()
+                Some(min_readable_version) => Ok(Some(min_readable_version)),
+                None => self.transaction_store.get_first_write_set_version(),
             }
         })
     }
@@ -945,12 +1107,18 @@ impl DbReader for AptosDB {
                 return Ok(TransactionOutputListWithProof::new_empty());
             }
 
-            error_if_version_is_pruned(
-                &self.pruner,
-                PrunerIndex::LedgerPrunerIndex,
-                "Transaction",
-                start_version,
-            )?;
+            for (store_name, data_type) in [
+                (TRANSACTION_STORE_PRUNER_NAME, "Transaction"),
+                (WRITE_SET_PRUNER_NAME, "Write set"),
+                (EVENT_STORE_PRUNER_NAME, "Event"),
+            ] {
+                error_if_ledger_store_version_is_pruned(
+                    &self.pruner,
+                    store_name,
+                    data_type,
+                    start_version,
+                )?;
+            }
 
             let limit = std::cmp::min(limit, ledger_version - start_version + 1);
 
@@ -998,9 +1166,9 @@ impl DbReader for AptosDB {
         end_version: Version,
     ) -> Result<Vec<WriteSet>> {
         gauged_api("get_write_sets", || {
-            error_if_version_is_pruned(
+            error_if_ledger_store_version_is_pruned(
                 &self.pruner,
-                PrunerIndex::LedgerPrunerIndex,
+                WRITE_SET_PRUNER_NAME,
                 "Write set",
                 begin_version,
             )?;
@@ -1022,6 +1190,39 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_event_by_type_index_start_version(&self) -> Result<Option<Version>> {
+        gauged_api("get_event_by_type_index_start_version", || {
+            self.event_store.event_by_type_index_start_version()
+        })
+    }
+
+    fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        gauged_api("get_events_by_type", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+            error_if_ledger_store_version_is_pruned(
+                &self.pruner,
+                EVENT_STORE_PRUNER_NAME,
+                "Event",
+                start_version,
+            )?;
+
+            self.event_store
+                .lookup_events_by_type(type_tag, start_version, limit, ledger_version)?
+                .into_iter()
+                .map(|(ver, idx)| {
+                    let event = self.event_store.get_event_by_version_and_index(ver, idx)?;
+                    Ok(EventWithVersion::new(ver, event))
+                })
+                .collect()
+        })
+    }
+
     /// Gets ledger info at specified version and ensures it's an epoch ending.
     fn get_epoch_ending_ledger_info(&self, version: u64) -> Result<LedgerInfoWithSignatures> {
         gauged_api("get_epoch_ending_ledger_info", || {
@@ -1148,6 +1349,69 @@ impl DbReader for AptosDB {
         })
     }
 
+    /// Returns the block containing the transaction at `version`.
+    fn get_block_info_by_version(
+        &self,
+        version: Version,
+        ledger_version: Version,
+    ) -> Result<CommittedBlock> {
+        gauged_api("get_block_info_by_version", || {
+            ensure!(
+                version <= ledger_version,
+                "Version {} is greater than ledger version {}.",
+                version,
+                ledger_version,
+            );
+            match self.transaction_store.get_block_metadata(version)? {
+                Some((first_version, metadata)) => {
+                    let seq_num = self
+                        .event_store
+                        .get_events_by_version(first_version)?
+                        .into_iter()
+                        .find(|event| *event.key() == new_block_event_key())
+                        .map(|event| event.sequence_number())
+                        .ok_or_else(|| {
+                            format_err!("NewBlockEvent not found at version {}.", first_version)
+                        })?;
+                    self.get_committed_block(
+                        first_version,
+                        Some(metadata),
+                        seq_num + 1,
+                        ledger_version,
+                    )
+                }
+                // versions preceding the first block metadata transaction belong to genesis
+                None => self.get_committed_block(0, None, 0, ledger_version),
+            }
+        })
+    }
+
+    /// Returns the block at `height`, the genesis transaction being the block at height 0.
+    fn get_block_info_by_height(
+        &self,
+        height: u64,
+        ledger_version: Version,
+    ) -> Result<CommittedBlock> {
+        gauged_api("get_block_info_by_height", || {
+            if height == 0 {
+                return self.get_committed_block(0, None, 0, ledger_version);
+            }
+            let (_seq, first_version, _idx) = *self
+                .event_store
+                .lookup_events_by_key(&new_block_event_key(), height - 1, 1, ledger_version)?
+                .first()
+                .ok_or_else(|| AptosDbError::NotFound(format!("Block at height {}", height)))?;
+            let metadata = match self.transaction_store.get_transaction(first_version)? {
+                Transaction::BlockMetadata(metadata) => metadata,
+                _ => bail!(
+                    "DB corrupt: transaction at version {} is not a block metadata transaction.",
+                    first_version
+                ),
+            };
+            self.get_committed_block(first_version, Some(metadata), height, ledger_version)
+        })
+    }
+
     fn get_last_version_before_timestamp(
         &self,
         timestamp: u64,
@@ -1520,6 +1784,7 @@ impl GetRestoreHandler for Arc<AptosDB> {
             Arc::clone(&self.transaction_store),
             Arc::clone(&self.state_store),
             Arc::clone(&self.event_store),
+            self.pruner.as_ref().map(Pruner::get_progress_handle),
         )
     }
 }
@@ -1,6 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::pruner::PrunerProgress;
 use aptos_logger::{error, info};
 use aptos_types::transaction::Version;
 use schemadb::SchemaBatch;
@@ -53,6 +54,10 @@ pub trait DBPruner {
     /// Sets the target version for the pruner
     fn set_target_version(&self, target_version: Version);
 
+    /// Moves the target version forward according to the prune window of the pruner, given the
+    /// latest version of the DB.
+    fn update_target_version(&self, latest_version: Version);
+
     /// Returns the target version for the DB pruner
     fn target_version(&self) -> Version;
 
@@ -73,4 +78,13 @@ pub trait DBPruner {
     fn is_pruning_pending(&self) -> bool {
         self.target_version() > self.min_readable_version()
     }
+
+    /// Returns the progress of the pruner, or of each of its sub-pruners if it has any.
+    fn progress(&self) -> Vec<PrunerProgress> {
+        vec![PrunerProgress {
+            name: self.name().to_string(),
+            min_readable_version: self.min_readable_version(),
+            target_version: self.target_version(),
+        }]
+    }
 }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{pruner::*, AptosDB, ChangeSet, EventStore};
+use aptos_config::config::LedgerSubPrunerWindows;
 use aptos_proptest_helpers::Index;
 use aptos_temppath::TempPath;
 use aptos_types::{
@@ -62,6 +63,7 @@ fn verify_event_store_pruner(events: Vec<Vec<ContractEvent>>) {
             state_store_prune_window: Some(0),
             ledger_prune_window: Some(0),
             pruning_batch_size: 1,
+            ledger_sub_pruner_windows: LedgerSubPrunerWindows::default(),
         },
     );
 
@@ -76,10 +78,7 @@ fn verify_event_store_pruner(events: Vec<Vec<ContractEvent>>) {
     // start pruning events batches of size 2 and verify transactions have been pruned from DB
     for i in (0..=num_versions).step_by(2) {
         pruner
-            .wake_and_wait(
-                i as u64, /* latest_version */
-                PrunerIndex::LedgerPrunerIndex as usize,
-            )
+            .wake_and_wait(i as u64 /* latest_version */, LEDGER_PRUNER_INDEX)
             .unwrap();
         // ensure that all events up to i * 2 has been pruned
         for j in 0..i {
@@ -109,6 +108,7 @@ fn verify_event_store_pruner_disabled(events: Vec<Vec<ContractEvent>>) {
             state_store_prune_window: Some(0),
             ledger_prune_window: None,
             pruning_batch_size: 1,
+            ledger_sub_pruner_windows: LedgerSubPrunerWindows::default(),
         },
     );
 
@@ -122,9 +122,7 @@ fn verify_event_store_pruner_disabled(events: Vec<Vec<ContractEvent>>) {
 
     // Verify no pruning has happened.
     for _i in (0..=num_versions).step_by(2) {
-        pruner
-            .ensure_disabled(PrunerIndex::LedgerPrunerIndex as usize)
-            .unwrap();
+        pruner.ensure_disabled(LEDGER_PRUNER_INDEX).unwrap();
         // ensure that all events up to i * 2 are valid in DB
         for version in 0..num_versions {
             verify_events_in_store(&events, version as u64, event_store);
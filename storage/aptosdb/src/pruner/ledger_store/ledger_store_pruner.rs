@@ -10,25 +10,88 @@ use crate::{
         transaction_store::{
             transaction_store_pruner::TransactionStorePruner, write_set_pruner::WriteSetPruner,
         },
+        PrunerProgress,
+    },
+    schema::{
+        event::EventSchema, ledger_counters::LedgerCountersSchema, write_set::WriteSetSchema,
     },
     transaction::TransactionSchema,
     EventStore, LedgerStore, TransactionStore,
 };
+use aptos_config::config::{LedgerSubPrunerWindows, PruneWindow};
+use aptos_infallible::duration_since_epoch;
+use aptos_logger::debug;
 use aptos_types::transaction::{AtomicVersion, Version};
-use schemadb::{ReadOptions, SchemaBatch, DB};
-use std::sync::{atomic::Ordering, Arc};
+use schemadb::{schema::Schema, ReadOptions, SchemaBatch, DB};
+use std::{
+    cmp::min,
+    sync::{atomic::Ordering, Arc},
+};
 
 pub const LEDGER_PRUNER_NAME: &str = "ledger pruner";
+pub const TRANSACTION_STORE_PRUNER_NAME: &str = "transaction_store";
+pub const WRITE_SET_PRUNER_NAME: &str = "write_set";
+pub const LEDGER_COUNTER_PRUNER_NAME: &str = "ledger_counter";
+pub const EVENT_STORE_PRUNER_NAME: &str = "event_store";
+
+/// A store pruned by the ledger pruner. Every store has its own prune window and keeps track of
+/// its own progress, so that e.g. events can be kept longer than write sets.
+struct LedgerSubPruner {
+    name: &'static str,
+    pruner: Arc<dyn DBSubPruner + Send + Sync>,
+    /// None if the store is not pruned.
+    prune_window: Option<PruneWindow>,
+    /// Returns the first version remaining in the store.
+    get_first_version: fn(&DB) -> anyhow::Result<Option<Version>>,
+    target_version: AtomicVersion,
+    min_readable_version: AtomicVersion,
+}
+
+impl LedgerSubPruner {
+    fn new(
+        name: &'static str,
+        pruner: Arc<dyn DBSubPruner + Send + Sync>,
+        prune_window: Option<PruneWindow>,
+        get_first_version: fn(&DB) -> anyhow::Result<Option<Version>>,
+    ) -> Self {
+        Self {
+            name,
+            pruner,
+            prune_window,
+            get_first_version,
+            target_version: AtomicVersion::new(0),
+            min_readable_version: AtomicVersion::new(0),
+        }
+    }
+
+    fn min_readable_version(&self) -> Version {
+        self.min_readable_version.load(Ordering::Relaxed)
+    }
+
+    fn target_version(&self) -> Version {
+        self.target_version.load(Ordering::Relaxed)
+    }
+
+    fn is_pruning_pending(&self) -> bool {
+        self.target_version() > self.min_readable_version()
+    }
+
+    fn record_progress(&self, min_readable_version: Version) {
+        self.min_readable_version
+            .store(min_readable_version, Ordering::Relaxed);
+        PRUNER_LEAST_READABLE_VERSION
+            .with_label_values(&[self.name])
+            .set(min_readable_version as i64);
+    }
+}
 
 pub struct LedgerPruner {
     db: Arc<DB>,
-    /// Keeps track of the target version that the pruner needs to achieve.
-    target_version: AtomicVersion,
+    /// Used to resolve the time-based prune windows into versions.
+    event_store: Arc<EventStore>,
+    /// The least readable version among all the stores.
     min_readable_version: AtomicVersion,
-    transaction_store_pruner: Arc<dyn DBSubPruner + Send + Sync>,
-    event_store_pruner: Arc<dyn DBSubPruner + Send + Sync>,
-    write_set_pruner: Arc<dyn DBSubPruner + Send + Sync>,
-    ledger_counter_pruner: Arc<dyn DBSubPruner + Send + Sync>,
+    sub_pruners: Vec<LedgerSubPruner>,
 }
 
 impl DBPruner for LedgerPruner {
@@ -40,45 +103,84 @@ impl DBPruner for LedgerPruner {
         if !self.is_pruning_pending() {
             return Ok(self.min_readable_version());
         }
-        let min_readable_version = self.min_readable_version();
-        // Current target version might be less than the target version to ensure we don't prune
-        // more than max_version in one go.
-        let current_target_version = self.get_currrent_batch_target(max_versions);
-
-        self.transaction_store_pruner.prune(
-            db_batch,
-            min_readable_version,
-            current_target_version,
-        )?;
-        self.write_set_pruner
-            .prune(db_batch, min_readable_version, current_target_version)?;
-        self.ledger_counter_pruner
-            .prune(db_batch, min_readable_version, current_target_version)?;
-
-        self.event_store_pruner
-            .prune(db_batch, min_readable_version, current_target_version)?;
+        for sub_pruner in self.sub_pruners.iter() {
+            if !sub_pruner.is_pruning_pending() {
+                continue;
+            }
+            let min_readable_version = sub_pruner.min_readable_version();
+            // Current target version might be less than the target version to ensure we don't
+            // prune more than max_version in one go.
+            let current_target_version = min(
+                min_readable_version + max_versions,
+                sub_pruner.target_version(),
+            );
+            sub_pruner
+                .pruner
+                .prune(db_batch, min_readable_version, current_target_version)?;
+            sub_pruner.record_progress(current_target_version);
+        }
 
-        self.record_progress(current_target_version);
-        Ok(current_target_version)
+        let min_readable_version = self.sub_pruners_min_readable_version();
+        self.record_progress(min_readable_version);
+        Ok(min_readable_version)
     }
 
     fn initialize_min_readable_version(&self) -> anyhow::Result<Version> {
-        let mut iter = self.db.iter::<TransactionSchema>(ReadOptions::default())?;
-        iter.seek_to_first();
-        let version = iter.next().transpose()?.map_or(0, |(version, _)| version);
-        Ok(version)
+        // The stores are pruned independently, so each of them resumes from its own first
+        // remaining entry.
+        for sub_pruner in self.sub_pruners.iter() {
+            let min_readable_version = (sub_pruner.get_first_version)(&self.db)?;
+            sub_pruner.record_progress(min_readable_version.unwrap_or(0));
+        }
+        Ok(self.sub_pruners_min_readable_version())
     }
 
     fn min_readable_version(&self) -> Version {
         self.min_readable_version.load(Ordering::Relaxed)
     }
 
+    /// Sets the same target version for all the stores, regardless of their prune windows.
     fn set_target_version(&self, target_version: Version) {
-        self.target_version.store(target_version, Ordering::Relaxed)
+        for sub_pruner in self.sub_pruners.iter() {
+            sub_pruner
+                .target_version
+                .store(target_version, Ordering::Relaxed);
+        }
+    }
+
+    fn update_target_version(&self, latest_version: Version) {
+        for sub_pruner in self.sub_pruners.iter() {
+            let target_version = match sub_pruner.prune_window {
+                None => continue,
+                Some(PruneWindow::Versions(window)) => latest_version.saturating_sub(window),
+                Some(PruneWindow::Secs(window)) => {
+                    match self.get_first_version_after_secs(window, latest_version) {
+                        Ok(version) => version,
+                        Err(e) => {
+                            // E.g. the chain is younger than the window.
+                            debug!(
+                                error = ?e,
+                                "{} can't resolve a prune window of {} seconds.",
+                                sub_pruner.name,
+                                window,
+                            );
+                            continue;
+                        }
+                    }
+                }
+            };
+            sub_pruner
+                .target_version
+                .fetch_max(target_version, Ordering::Relaxed);
+        }
     }
 
     fn target_version(&self) -> Version {
-        self.target_version.load(Ordering::Relaxed)
+        self.sub_pruners
+            .iter()
+            .map(LedgerSubPruner::target_version)
+            .max()
+            .unwrap_or(0)
     }
 
     fn record_progress(&self, min_readable_version: Version) {
@@ -88,6 +190,23 @@ impl DBPruner for LedgerPruner {
             .with_label_values(&["ledger_pruner"])
             .set(min_readable_version as i64);
     }
+
+    fn is_pruning_pending(&self) -> bool {
+        self.sub_pruners
+            .iter()
+            .any(LedgerSubPruner::is_pruning_pending)
+    }
+
+    fn progress(&self) -> Vec<PrunerProgress> {
+        self.sub_pruners
+            .iter()
+            .map(|sub_pruner| PrunerProgress {
+                name: sub_pruner.name.to_string(),
+                min_readable_version: sub_pruner.min_readable_version(),
+                target_version: sub_pruner.target_version(),
+            })
+            .collect()
+    }
 }
 
 impl LedgerPruner {
@@ -96,19 +215,74 @@ impl LedgerPruner {
         transaction_store: Arc<TransactionStore>,
         event_store: Arc<EventStore>,
         ledger_store: Arc<LedgerStore>,
+        prune_windows: LedgerSubPrunerWindows,
     ) -> Self {
         let pruner = LedgerPruner {
             db,
-            target_version: AtomicVersion::new(0),
+            event_store: Arc::clone(&event_store),
             min_readable_version: AtomicVersion::new(0),
-            ledger_counter_pruner: Arc::new(LedgerCounterPruner::new(ledger_store)),
-            transaction_store_pruner: Arc::new(TransactionStorePruner::new(
-                transaction_store.clone(),
-            )),
-            event_store_pruner: Arc::new(EventStorePruner::new(event_store)),
-            write_set_pruner: Arc::new(WriteSetPruner::new(transaction_store)),
+            sub_pruners: vec![
+                LedgerSubPruner::new(
+                    TRANSACTION_STORE_PRUNER_NAME,
+                    Arc::new(TransactionStorePruner::new(transaction_store.clone())),
+                    prune_windows.transaction_store,
+                    get_first_version::<TransactionSchema>,
+                ),
+                LedgerSubPruner::new(
+                    WRITE_SET_PRUNER_NAME,
+                    Arc::new(WriteSetPruner::new(transaction_store)),
+                    prune_windows.write_set,
+                    get_first_version::<WriteSetSchema>,
+                ),
+                LedgerSubPruner::new(
+                    LEDGER_COUNTER_PRUNER_NAME,
+                    Arc::new(LedgerCounterPruner::new(ledger_store)),
+                    prune_windows.ledger_counter,
+                    get_first_version::<LedgerCountersSchema>,
+                ),
+                LedgerSubPruner::new(
+                    EVENT_STORE_PRUNER_NAME,
+                    Arc::new(EventStorePruner::new(event_store)),
+                    prune_windows.event_store,
+                    get_first_event_version,
+                ),
+            ],
         };
         pruner.initialize();
         pruner
     }
+
+    fn sub_pruners_min_readable_version(&self) -> Version {
+        self.sub_pruners
+            .iter()
+            .map(LedgerSubPruner::min_readable_version)
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Returns the first version committed in the last `secs` seconds, by the block timestamps.
+    fn get_first_version_after_secs(
+        &self,
+        secs: u64,
+        latest_version: Version,
+    ) -> anyhow::Result<Version> {
+        let now_usecs = duration_since_epoch().as_micros() as u64;
+        let timestamp = now_usecs.saturating_sub(secs.saturating_mul(1_000_000));
+        Ok(self
+            .event_store
+            .get_last_version_before_timestamp(timestamp, latest_version)?
+            + 1)
+    }
+}
+
+fn get_first_version<S: Schema<Key = Version>>(db: &DB) -> anyhow::Result<Option<Version>> {
+    let mut iter = db.iter::<S>(ReadOptions::default())?;
+    iter.seek_to_first();
+    Ok(iter.next().transpose()?.map(|(version, _)| version))
+}
+
+fn get_first_event_version(db: &DB) -> anyhow::Result<Option<Version>> {
+    let mut iter = db.iter::<EventSchema>(ReadOptions::default())?;
+    iter.seek_to_first();
+    Ok(iter.next().transpose()?.map(|((version, _), _)| version))
 }
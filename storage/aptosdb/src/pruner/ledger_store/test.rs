@@ -0,0 +1,111 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    pruner::{db_pruner::DBPruner, *},
+    AptosDB, ChangeSet,
+};
+use aptos_config::config::{LedgerSubPrunerWindows, PruneWindow};
+use aptos_proptest_helpers::Index;
+use aptos_temppath::TempPath;
+use aptos_types::{
+    contract_event::ContractEvent,
+    proptest_types::{AccountInfoUniverse, ContractEventGen},
+    write_set::WriteSet,
+};
+use proptest::{collection::vec, prelude::*};
+use schemadb::SchemaBatch;
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_ledger_sub_pruner_windows(
+        mut universe in any_with::<AccountInfoUniverse>(3),
+        gen_batches in vec(vec((any::<Index>(), any::<ContractEventGen>()), 0..=2), 10),
+        write_sets in vec(any::<WriteSet>(), 10),
+    ) {
+        let event_batches = gen_batches
+            .into_iter()
+            .map(|gens| {
+                gens.into_iter()
+                    .map(|(index, gen)| gen.materialize(*index, &mut universe))
+                    .collect()
+            })
+            .collect();
+
+        verify_ledger_sub_pruner_windows(event_batches, write_sets);
+    }
+}
+
+fn verify_ledger_sub_pruner_windows(events: Vec<Vec<ContractEvent>>, write_sets: Vec<WriteSet>) {
+    let tmp_dir = TempPath::new();
+    let aptos_db = AptosDB::new_for_test(&tmp_dir);
+    let event_store = &aptos_db.event_store;
+    let transaction_store = &aptos_db.transaction_store;
+    let num_versions = events.len() as Version;
+
+    let mut cs = ChangeSet::new();
+    for (version, (events_for_version, write_set)) in events.iter().zip(&write_sets).enumerate() {
+        event_store
+            .put_events(version as Version, events_for_version, &mut cs)
+            .unwrap();
+        transaction_store
+            .put_write_set(version as Version, write_set, &mut cs)
+            .unwrap();
+    }
+    aptos_db.ledger_db.write_schemas(cs.batch).unwrap();
+
+    // Events keep the last 4 versions, write sets are kept for a day, which can't be resolved as
+    // there are no blocks, and the other stores are not pruned.
+    let db_pruners = utils::create_db_pruners(
+        Arc::clone(&aptos_db.ledger_db),
+        Arc::clone(&aptos_db.state_merkle_db),
+        StoragePrunerConfig {
+            state_store_prune_window: None,
+            ledger_prune_window: None,
+            pruning_batch_size: 1,
+            ledger_sub_pruner_windows: LedgerSubPrunerWindows {
+                event_store: Some(PruneWindow::Versions(4)),
+                write_set: Some(PruneWindow::Secs(24 * 60 * 60)),
+                ..LedgerSubPrunerWindows::default()
+            },
+        },
+    );
+    assert!(db_pruners[PrunerIndex::StateStorePrunerIndex as usize].is_none());
+    let ledger_pruner = db_pruners[LEDGER_PRUNER_INDEX].as_ref().unwrap().lock();
+
+    ledger_pruner.update_target_version(num_versions);
+    while ledger_pruner.is_pruning_pending() {
+        let mut db_batch = SchemaBatch::new();
+        ledger_pruner.prune(&mut db_batch, 3).unwrap();
+        aptos_db.ledger_db.write_schemas(db_batch).unwrap();
+    }
+
+    for version in 0..num_versions {
+        assert_eq!(
+            event_store.get_events_by_version(version).unwrap(),
+            if version < num_versions - 4 {
+                vec![]
+            } else {
+                events[version as usize].clone()
+            }
+        );
+        assert_eq!(
+            transaction_store.get_write_set(version).unwrap(),
+            write_sets[version as usize]
+        );
+    }
+    // The ledger is readable from the least pruned store.
+    assert_eq!(ledger_pruner.min_readable_version(), 0);
+    let progress = ledger_pruner.progress();
+    let event_store_progress = progress
+        .iter()
+        .find(|progress| progress.name == "event_store")
+        .unwrap();
+    assert_eq!(event_store_progress.min_readable_version, num_versions - 4);
+    assert!(progress
+        .iter()
+        .filter(|progress| progress.name != "event_store")
+        .all(|progress| progress.min_readable_version == 0 && progress.target_version == 0));
+}
@@ -18,9 +18,9 @@ use crate::metrics::{PRUNER_BATCH_SIZE, PRUNER_WINDOW};
 use aptos_config::config::StoragePrunerConfig;
 use aptos_infallible::Mutex;
 
-use crate::pruner::PrunerIndex::LedgerPrunerIndex;
 use aptos_types::transaction::Version;
 use schemadb::DB;
+use serde::{Deserialize, Serialize};
 use std::{
     sync::{
         mpsc::{channel, Sender},
@@ -30,6 +30,10 @@ use std::{
 };
 use worker::{Command, Worker};
 
+pub(crate) use ledger_store::ledger_store_pruner::{
+    EVENT_STORE_PRUNER_NAME, TRANSACTION_STORE_PRUNER_NAME, WRITE_SET_PRUNER_NAME,
+};
+
 /// The `Pruner` is meant to be part of a `AptosDB` instance and runs in the background to prune old
 /// data.
 ///
@@ -53,6 +57,8 @@ pub(crate) struct Pruner {
     /// as this is accessed both by the Pruner thread and the worker thread.
     #[allow(dead_code)]
     min_readable_versions: Arc<Mutex<Vec<Option<Version>>>>,
+    /// The progress of each pruner and sub-pruner as last recorded by the worker thread.
+    progress: Arc<Mutex<Vec<PrunerProgress>>>,
     /// We send a batch of version to the underlying pruners for performance reason. This tracks the
     /// last version we sent to the pruners.
     last_version_sent_to_pruners: Arc<Mutex<Version>>,
@@ -62,12 +68,25 @@ pub(crate) struct Pruner {
     latest_version: Arc<Mutex<Version>>,
 }
 
+/// The progress of a pruner, or of a sub-pruner of the ledger pruner.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PrunerProgress {
+    pub name: String,
+    /// All versions before it are pruned.
+    pub min_readable_version: Version,
+    /// The version the pruner is pruning up to.
+    pub target_version: Version,
+}
+
 pub enum PrunerIndex {
-    #[allow(dead_code)]
     StateStorePrunerIndex,
-    LedgerPrunerIndex,
 }
 
+/// (For tests only.) The position of the ledger pruner in the pruner lists, after the state store
+/// pruner. The stores pruned by it are looked up by the name of their sub-pruner instead.
+#[cfg(test)]
+pub(crate) const LEDGER_PRUNER_INDEX: usize = 1;
+
 impl Pruner {
     /// Creates a worker thread that waits on a channel for pruning commands.
     pub fn new(
@@ -79,8 +98,13 @@ impl Pruner {
 
         let min_readable_version = Arc::new(Mutex::new(vec![
             storage_pruner_config.state_store_prune_window.map(|_| 0),
-            storage_pruner_config.ledger_prune_window.map(|_| 0),
+            if storage_pruner_config.is_ledger_pruner_enabled() {
+                Some(0)
+            } else {
+                None
+            },
         ]));
+        let progress = Arc::new(Mutex::new(Vec::new()));
 
         let worker_progress_clone = Arc::clone(&min_readable_version);
 
@@ -99,6 +123,7 @@ impl Pruner {
             state_merkle_rocksdb,
             command_receiver,
             min_readable_version,
+            Arc::clone(&progress),
             storage_pruner_config,
         );
         let worker_thread = std::thread::Builder::new()
@@ -112,6 +137,7 @@ impl Pruner {
             worker_thread: Some(worker_thread),
             command_sender: Mutex::new(command_sender),
             min_readable_versions: worker_progress_clone,
+            progress,
             last_version_sent_to_pruners: Arc::new(Mutex::new(0)),
             pruning_batch_size: storage_pruner_config.pruning_batch_size,
             latest_version: Arc::new(Mutex::new(0)),
@@ -133,9 +159,23 @@ impl Pruner {
         self.min_readable_versions.lock()[pruner_index as usize]
     }
 
-    pub fn get_min_readable_ledger_version(&self) -> Option<Version> {
-        self.get_min_readable_version_by_pruner_index(LedgerPrunerIndex)
+    /// Returns the least readable version of a store pruned by the ledger pruner, identified by
+    /// the name of its sub-pruner, e.g. `EVENT_STORE_PRUNER_NAME`. The stores have their own
+    /// prune windows, so e.g. the events can be readable further back than the write sets.
+    pub fn get_min_readable_ledger_store_version(&self, store_name: &str) -> Option<Version> {
+        self.progress
+            .lock()
+            .iter()
+            .find(|progress| progress.name == store_name)
+            .map(|progress| progress.min_readable_version)
     }
+
+    /// Returns a handle on the progress of the pruners, which is kept up to date by the worker
+    /// thread.
+    pub fn get_progress_handle(&self) -> Arc<Mutex<Vec<PrunerProgress>>> {
+        Arc::clone(&self.progress)
+    }
+
     /// Sends pruning command to the worker thread when necessary.
     pub fn maybe_wake_pruner(&self, latest_version: Version) {
         *self.latest_version.lock() = latest_version;
@@ -150,14 +190,7 @@ impl Pruner {
     fn wake_pruner(&self, latest_version: Version) {
         self.command_sender
             .lock()
-            .send(Command::Prune {
-                target_db_versions: vec![
-                    self.state_store_prune_window
-                        .map(|x| latest_version.saturating_sub(x)),
-                    self.ledger_prune_window
-                        .map(|x| latest_version.saturating_sub(x)),
-                ],
-            })
+            .send(Command::Prune { latest_version })
             .expect("Receiver should not destruct prematurely.");
     }
 
@@ -222,6 +255,12 @@ impl Pruner {
     pub fn testonly_update_min_version(&mut self, version: &[Option<Version>]) {
         self.min_readable_versions = Arc::new(Mutex::new(version.to_vec()));
     }
+
+    /// (For tests only.) Updates the progress of the pruners and sub-pruners.
+    #[cfg(test)]
+    pub fn testonly_update_progress(&mut self, progress: Vec<PrunerProgress>) {
+        self.progress = Arc::new(Mutex::new(progress));
+    }
 }
 
 impl Drop for Pruner {
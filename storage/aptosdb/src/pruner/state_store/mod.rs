@@ -25,6 +25,8 @@ pub struct StateStorePruner {
     db: Arc<DB>,
     index_min_nonpurged_version: AtomicVersion,
     index_purged_at: Mutex<Instant>,
+    /// The number of latest versions to keep.
+    prune_window: Version,
     /// Keeps track of the target version that the pruner needs to achieve.
     target_version: AtomicVersion,
     min_readable_version: AtomicVersion,
@@ -94,6 +96,13 @@ impl DBPruner for StateStorePruner {
         self.target_version.store(target_version, Ordering::Relaxed);
     }
 
+    fn update_target_version(&self, latest_version: Version) {
+        self.target_version.fetch_max(
+            latest_version.saturating_sub(self.prune_window),
+            Ordering::Relaxed,
+        );
+    }
+
     fn target_version(&self) -> Version {
         self.target_version.load(Ordering::Relaxed)
     }
@@ -112,11 +121,13 @@ impl StateStorePruner {
         db: Arc<DB>,
         index_min_nonpurged_version: Version,
         index_purged_at: Instant,
+        prune_window: Version,
     ) -> Self {
         let pruner = StateStorePruner {
             db,
             index_min_nonpurged_version: AtomicVersion::new(index_min_nonpurged_version),
             index_purged_at: Mutex::new(index_purged_at),
+            prune_window,
             target_version: AtomicVersion::new(0),
             min_readable_version: AtomicVersion::new(0),
         };
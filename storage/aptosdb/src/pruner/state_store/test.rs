@@ -3,6 +3,7 @@
 
 use std::collections::HashMap;
 
+use aptos_config::config::LedgerSubPrunerWindows;
 use aptos_crypto::HashValue;
 use aptos_temppath::TempPath;
 use aptos_types::state_store::{state_key::StateKey, state_value::StateValue};
@@ -72,6 +73,7 @@ fn test_state_store_pruner() {
             state_store_prune_window: Some(0),
             ledger_prune_window: Some(0),
             pruning_batch_size: prune_batch_size,
+            ledger_sub_pruner_windows: LedgerSubPrunerWindows::default(),
         },
     );
 
@@ -158,6 +160,7 @@ fn test_state_store_pruner_disabled() {
             state_store_prune_window: None,
             ledger_prune_window: Some(0),
             pruning_batch_size: prune_batch_size,
+            ledger_sub_pruner_windows: LedgerSubPrunerWindows::default(),
         },
     );
 
@@ -248,22 +251,20 @@ fn test_worker_quit_eagerly() {
             Arc::clone(&db),
             Arc::clone(&aptos_db.state_merkle_db),
             command_receiver,
-            Arc::new(Mutex::new(vec![Some(0), Some(0)])), /* progress */
+            Arc::new(Mutex::new(vec![Some(0), Some(0)])), /* min_readable_versions */
+            Arc::new(Mutex::new(Vec::new())),             /* progress */
             StoragePrunerConfig {
                 state_store_prune_window: Some(1),
                 ledger_prune_window: Some(1),
                 pruning_batch_size: 100,
+                ledger_sub_pruner_windows: LedgerSubPrunerWindows::default(),
             },
         );
         command_sender
-            .send(Command::Prune {
-                target_db_versions: vec![Some(1), Some(0)],
-            })
+            .send(Command::Prune { latest_version: 2 })
             .unwrap();
         command_sender
-            .send(Command::Prune {
-                target_db_versions: vec![Some(2), Some(0)],
-            })
+            .send(Command::Prune { latest_version: 3 })
             .unwrap();
         command_sender.send(Command::Quit).unwrap();
         // Worker quits immediately although `Command::Quit` is not the first command sent.
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{pruner::*, AptosDB, ChangeSet, LedgerStore, TransactionStore};
+use aptos_config::config::LedgerSubPrunerWindows;
 use aptos_temppath::TempPath;
 use proptest::proptest;
 
@@ -54,6 +55,7 @@ fn verify_write_set_pruner(write_sets: Vec<WriteSet>) {
             state_store_prune_window: Some(0),
             ledger_prune_window: Some(0),
             pruning_batch_size: 1,
+            ledger_sub_pruner_windows: LedgerSubPrunerWindows::default(),
         },
     );
 
@@ -68,10 +70,7 @@ fn verify_write_set_pruner(write_sets: Vec<WriteSet>) {
     // start pruning write sets in batches of size 2 and verify transactions have been pruned from DB
     for i in (0..=num_write_sets).step_by(2) {
         pruner
-            .wake_and_wait(
-                i as u64, /* latest_version */
-                PrunerIndex::LedgerPrunerIndex as usize,
-            )
+            .wake_and_wait(i as u64 /* latest_version */, LEDGER_PRUNER_INDEX)
             .unwrap();
         // ensure that all transaction up to i * 2 has been pruned
         for j in 0..i {
@@ -103,6 +102,7 @@ fn verify_txn_store_pruner(
             state_store_prune_window: Some(0),
             ledger_prune_window: Some(0),
             pruning_batch_size: 1,
+            ledger_sub_pruner_windows: LedgerSubPrunerWindows::default(),
         },
     );
 
@@ -118,10 +118,7 @@ fn verify_txn_store_pruner(
     // start pruning transactions batches of size step_size and verify transactions have been pruned from DB
     for i in (0..=num_transaction).step_by(step_size) {
         pruner
-            .wake_and_wait(
-                i as u64, /* latest_version */
-                PrunerIndex::LedgerPrunerIndex as usize,
-            )
+            .wake_and_wait(i as u64 /* latest_version */, LEDGER_PRUNER_INDEX)
             .unwrap();
         // ensure that all transaction up to i * 2 has been pruned
         assert_eq!(
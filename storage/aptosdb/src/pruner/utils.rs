@@ -22,21 +22,26 @@ pub fn create_db_pruners(
     storage_pruner_config: StoragePrunerConfig,
 ) -> Vec<Option<Mutex<Arc<dyn DBPruner + Send + Sync>>>> {
     vec![
-        if storage_pruner_config.state_store_prune_window.is_some() {
+        if let Some(prune_window) = storage_pruner_config.state_store_prune_window {
             Some(Mutex::new(Arc::new(StateStorePruner::new(
                 Arc::clone(&state_merkle_db),
                 0,
                 Instant::now(),
+                prune_window,
             ))))
         } else {
             None
         },
-        if storage_pruner_config.ledger_prune_window.is_some() {
+        if storage_pruner_config.is_ledger_pruner_enabled() {
             Some(Mutex::new(Arc::new(LedgerPruner::new(
                 Arc::clone(&ledger_db),
                 Arc::new(TransactionStore::new(Arc::clone(&ledger_db))),
-                Arc::new(EventStore::new(Arc::clone(&ledger_db))),
+                Arc::new(EventStore::new(
+                    Arc::clone(&ledger_db),
+                    false, /* enable_event_by_type_index */
+                )),
                 Arc::new(LedgerStore::new(Arc::clone(&ledger_db))),
+                storage_pruner_config.effective_ledger_sub_pruner_windows(),
             ))))
         } else {
             None
@@ -3,10 +3,9 @@
 use aptos_types::transaction::Version;
 use schemadb::{SchemaBatch, DB};
 
-use crate::pruner::{db_pruner::DBPruner, utils};
+use crate::pruner::{db_pruner::DBPruner, utils, PrunerProgress};
 use aptos_config::config::StoragePrunerConfig;
 use aptos_infallible::Mutex;
-use itertools::zip_eq;
 use std::sync::{mpsc::Receiver, Arc};
 
 /// Maintains all the DBPruners and periodically calls the db_pruner's prune method to prune the DB.
@@ -14,14 +13,16 @@ use std::sync::{mpsc::Receiver, Arc};
 pub struct Worker {
     ledger_db: Arc<DB>,
     command_receiver: Receiver<Command>,
-    /// Keeps tracks of all the DB pruners. The order of the pruners are defined in PrunerIndex.
-    /// If a pruner is not enabled, its value will be None.
+    /// Keeps tracks of all the DB pruners: the state store pruner, at PrunerIndex, then the ledger
+    /// pruner. If a pruner is not enabled, its value will be None.
     db_pruners: Vec<Option<Mutex<Arc<dyn DBPruner + Send + Sync>>>>,
     /// Keeps a record of the pruning progress. If this equals to version `V`, we know versions
     /// smaller than `V` are no longer readable.
     /// This being an atomic value is to communicate the info with the Pruner thread (for tests).
     /// If the pruner is disabled, its value will be None.
     min_readable_versions: Arc<Mutex<Vec<Option<Version>>>>,
+    /// Keeps a record of the progress of each pruner and sub-pruner, for reporting.
+    progress: Arc<Mutex<Vec<PrunerProgress>>>,
     /// Indicates if there's NOT any pending work to do currently, to hint
     /// `Self::receive_commands()` to `recv()` blocking-ly.
     blocking_recv: bool,
@@ -34,18 +35,22 @@ impl Worker {
         state_merkle_db: Arc<DB>,
         command_receiver: Receiver<Command>,
         min_readable_versions: Arc<Mutex<Vec<Option<Version>>>>,
+        progress: Arc<Mutex<Vec<PrunerProgress>>>,
         storage_pruner_config: StoragePrunerConfig,
     ) -> Self {
         let db_pruners =
             utils::create_db_pruners(ledger_db.clone(), state_merkle_db, storage_pruner_config);
-        Self {
+        let mut worker = Self {
             ledger_db: Arc::clone(&ledger_db),
             db_pruners,
             command_receiver,
             min_readable_versions,
+            progress,
             blocking_recv: true,
             max_version_to_prune_per_batch: storage_pruner_config.pruning_batch_size as u64,
-        }
+        };
+        worker.record_progress();
+        worker
     }
 
     pub(crate) fn work(mut self) {
@@ -82,11 +87,18 @@ impl Worker {
     }
 
     fn record_progress(&mut self) {
-        let mut updated_min_readable_versions: Vec<Option<Version>> = Vec::new();
-        for pruner in self.db_pruners.iter().flatten() {
-            updated_min_readable_versions.push(Some(pruner.lock().min_readable_version()))
-        }
+        let updated_min_readable_versions: Vec<Option<Version>> = self
+            .db_pruners
+            .iter()
+            .map(|pruner| pruner.as_ref().map(|p| p.lock().min_readable_version()))
+            .collect();
         *self.min_readable_versions.lock() = updated_min_readable_versions;
+        *self.progress.lock() = self
+            .db_pruners
+            .iter()
+            .flatten()
+            .flat_map(|pruner| pruner.lock().progress())
+            .collect();
     }
 
     /// Tries to receive all pending commands, blocking waits for the next command if no work needs
@@ -114,20 +126,14 @@ impl Worker {
             match command {
                 // On `Command::Quit` inform the outer loop to quit by returning `false`.
                 Command::Quit => return false,
-                Command::Prune { target_db_versions } => {
-                    for (new_target_version_option, pruner_option) in
-                        zip_eq(&target_db_versions, &self.db_pruners)
-                    {
-                        if let Some(pruner) = pruner_option {
-                            assert!(new_target_version_option.is_some());
-                            if new_target_version_option.unwrap() > pruner.lock().target_version() {
-                                // Switch to non-blocking to allow some work to be done after the
-                                // channel has drained.
-                                self.blocking_recv = false;
-                            }
-                            pruner
-                                .lock()
-                                .set_target_version(new_target_version_option.unwrap());
+                Command::Prune { latest_version } => {
+                    for pruner in self.db_pruners.iter().flatten() {
+                        let pruner = pruner.lock();
+                        pruner.update_target_version(latest_version);
+                        if pruner.is_pruning_pending() {
+                            // Switch to non-blocking to allow some work to be done after the
+                            // channel has drained.
+                            self.blocking_recv = false;
                         }
                     }
                 }
@@ -139,9 +145,8 @@ impl Worker {
 pub enum Command {
     Quit,
     Prune {
-        /// The first element represents the target DB version for state store pruner while the
-        /// second element is for ledger pruner. If a pruner is not enabled, the corresponding
-        /// value is None.
-        target_db_versions: Vec<Option<Version>>,
+        /// The latest version of the DB, each pruner derives its target version from it according
+        /// to its prune window.
+        latest_version: Version,
     },
 }
@@ -145,6 +145,34 @@ impl StateStore {
         key_prefix: &StateKeyPrefix,
         desired_version: Version,
     ) -> Result<HashMap<StateKey, StateValue>> {
+        let (values, next_key) = self.get_value_chunk_by_key_prefix(
+            key_prefix,
+            None,
+            MAX_VALUES_TO_FETCH_FOR_KEY_PREFIX,
+            desired_version,
+        )?;
+        // We don't allow fetching arbitrarily large number of values to be fetched as this can
+        // potentially slowdown the DB.
+        if next_key.is_some() {
+            return Err(anyhow!(
+                "Too many values requested for key_prefix {:?} - maximum allowed {:?}",
+                key_prefix,
+                MAX_VALUES_TO_FETCH_FOR_KEY_PREFIX
+            ));
+        }
+        Ok(values.into_iter().collect())
+    }
+
+    /// Returns up to `limit` values with the key prefix at `desired_version`, ordered by key and
+    /// starting from `first_key` (inclusive) if given, together with the key of the next value
+    /// with the prefix, if any.
+    pub fn get_value_chunk_by_key_prefix(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        first_key: Option<&StateKey>,
+        limit: usize,
+        desired_version: Version,
+    ) -> Result<(Vec<(StateKey, StateValue)>, Option<StateKey>)> {
         let mut read_opts = ReadOptions::default();
         // Without this, iterators are not guaranteed a total order of all keys, but only keys for the same prefix.
         // For example,
@@ -156,9 +184,12 @@ impl StateStore {
         // keys starting with `aptos/abc`.
         read_opts.set_total_order_seek(true);
         let mut iter = self.ledger_db.iter::<StateValueSchema>(read_opts)?;
-        let mut result = HashMap::new();
+        let mut result = vec![];
         let mut prev_key = None;
-        iter.seek(&(key_prefix))?;
+        match first_key {
+            Some(state_key) => iter.seek(&(state_key.clone(), desired_version))?,
+            None => iter.seek(&(key_prefix))?,
+        }
         while let Some(((state_key, version), state_value)) = iter.next().transpose()? {
             // In case the previous seek() ends on the same key with version 0.
             if Some(&state_key) == prev_key.as_ref() {
@@ -176,21 +207,15 @@ impl StateStore {
                 continue;
             }
 
-            result.insert(state_key.clone(), state_value);
-            // We don't allow fetching arbitrarily large number of values to be fetched as this can
-            // potentially slowdown the DB.
-            if result.len() > MAX_VALUES_TO_FETCH_FOR_KEY_PREFIX {
-                return Err(anyhow!(
-                    "Too many values requested for key_prefix {:?} - maximum allowed {:?}",
-                    key_prefix,
-                    MAX_VALUES_TO_FETCH_FOR_KEY_PREFIX
-                ));
+            if result.len() == limit {
+                return Ok((result, Some(state_key)));
             }
+            result.push((state_key.clone(), state_value));
             prev_key = Some(state_key.clone());
             // Seek to the next key - this can be done by seeking to the current key with version 0
             iter.seek(&(state_key, 0))?;
         }
-        Ok(result)
+        Ok((result, None))
     }
 
     fn expect_value_by_version(
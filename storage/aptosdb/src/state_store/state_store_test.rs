@@ -239,6 +239,79 @@ fn test_get_values_by_key_prefix() {
     assert_eq!(*key_value_map.get(&key5).unwrap(), value5_v2);
 }
 
+#[test]
+fn test_get_value_chunk_by_key_prefix() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.state_store;
+    let address = AccountAddress::new([12u8; AccountAddress::LENGTH]);
+    let other_address = AccountAddress::new([22u8; AccountAddress::LENGTH]);
+
+    let key1 = StateKey::AccessPath(AccessPath::new(address, b"state_key1".to_vec()));
+    let key2 = StateKey::AccessPath(AccessPath::new(address, b"state_key2".to_vec()));
+    let key3 = StateKey::AccessPath(AccessPath::new(address, b"state_key3".to_vec()));
+    let other_key = StateKey::AccessPath(AccessPath::new(other_address, b"state_key".to_vec()));
+
+    let value1 = StateValue::from(String::from("value1").into_bytes());
+    let value2_v0 = StateValue::from(String::from("value2_v0").into_bytes());
+    let value2_v1 = StateValue::from(String::from("value2_v1").into_bytes());
+    let value3 = StateValue::from(String::from("value3").into_bytes());
+    let other_value = StateValue::from(String::from("other_value").into_bytes());
+
+    let account_key_prefix = StateKeyPrefix::new(StateKeyTag::AccessPath, address.to_vec());
+
+    put_value_set(
+        store,
+        vec![
+            (key1.clone(), value1.clone()),
+            (key2.clone(), value2_v0.clone()),
+            (other_key, other_value),
+        ],
+        0,
+        None,
+    );
+    put_value_set(
+        store,
+        vec![
+            (key2.clone(), value2_v1.clone()),
+            (key3.clone(), value3.clone()),
+        ],
+        1,
+        Some(0),
+    );
+
+    // first chunk, the next chunk starts at key2
+    let (values, next_key) = store
+        .get_value_chunk_by_key_prefix(&account_key_prefix, None, 1, 1)
+        .unwrap();
+    assert_eq!(values, vec![(key1.clone(), value1.clone())]);
+    assert_eq!(next_key, Some(key2.clone()));
+
+    // last chunk, values of other accounts are not included
+    let (values, next_key) = store
+        .get_value_chunk_by_key_prefix(&account_key_prefix, Some(&key2), 2, 1)
+        .unwrap();
+    assert_eq!(
+        values,
+        vec![(key2.clone(), value2_v1), (key3.clone(), value3)]
+    );
+    assert_eq!(next_key, None);
+
+    // key3 does not exist at version 0, the chunk ends after key2
+    let (values, next_key) = store
+        .get_value_chunk_by_key_prefix(&account_key_prefix, Some(&key2), 1, 0)
+        .unwrap();
+    assert_eq!(values, vec![(key2, value2_v0)]);
+    assert_eq!(next_key, None);
+
+    // the cursor key does not exist at version 0, the chunk starts at the key following it
+    let (values, next_key) = store
+        .get_value_chunk_by_key_prefix(&account_key_prefix, Some(&key3), 10, 0)
+        .unwrap();
+    assert!(values.is_empty());
+    assert_eq!(next_key, None);
+}
+
 #[test]
 fn test_retired_records() {
     let key1 = StateKey::Raw(String::from("test_key1").into_bytes());
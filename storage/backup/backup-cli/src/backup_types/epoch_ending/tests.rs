@@ -48,6 +48,7 @@ fn end_to_end() {
     let rt = start_backup_service(
         SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port),
         src_db,
+        None, /* checkpoint_dir */
     );
     let client = Arc::new(BackupServiceClient::new(format!(
         "http://localhost:{}",
@@ -80,6 +81,7 @@ fn end_to_end() {
                 db_dir: Some(tgt_db_dir.path().to_path_buf()),
                 dry_run: false,
                 target_version: Some(target_version),
+                target_timestamp: None,
                 trusted_waypoints: TrustedWaypointOpt::default(),
                 rocksdb_opt: RocksdbOpt::default(),
                 concurernt_downloads: ConcurrentDownloadsOpt::default(),
@@ -211,6 +213,7 @@ async fn test_trusted_waypoints_impl(
             db_dir: None,
             dry_run: true,
             target_version: None,
+            target_timestamp: None,
             trusted_waypoints: TrustedWaypointOpt::default(),
             rocksdb_opt: RocksdbOpt::default(),
             concurernt_downloads: ConcurrentDownloadsOpt::default(),
@@ -229,6 +232,7 @@ async fn test_trusted_waypoints_impl(
             db_dir: None,
             dry_run: true,
             target_version: None,
+            target_timestamp: None,
             trusted_waypoints: TrustedWaypointOpt {
                 trust_waypoint: trusted_waypoints,
             },
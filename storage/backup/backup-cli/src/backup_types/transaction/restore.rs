@@ -168,6 +168,40 @@ impl TransactionRestoreController {
 
 impl TransactionRestoreController {}
 
+/// Scans the transaction backups from `start_version` for the first block committed later than
+/// `timestamp_usecs`, and returns the version right before it. Returns None if all blocks in the
+/// backups are committed no later than that.
+pub async fn get_last_version_before_timestamp(
+    storage: &Arc<dyn BackupStorage>,
+    manifest_handles: &[FileHandle],
+    start_version: Version,
+    timestamp_usecs: u64,
+    epoch_history: Option<&Arc<EpochHistory>>,
+) -> Result<Option<Version>> {
+    for manifest_handle in manifest_handles {
+        let manifest: TransactionBackup = storage.load_json_file(manifest_handle).await?;
+        manifest.verify()?;
+        for chunk in manifest.chunks {
+            if chunk.last_version < start_version {
+                continue;
+            }
+            let first_version = chunk.first_version;
+            // Load the chunk fully so the block timestamps are verified.
+            let loaded_chunk = LoadedChunk::load(chunk, storage, epoch_history).await?;
+            for (version, txn) in (first_version..).zip(loaded_chunk.txns.iter()) {
+                if let Transaction::BlockMetadata(block_metadata) = txn {
+                    if version >= start_version
+                        && block_metadata.timestamp_usecs() > timestamp_usecs
+                    {
+                        return Ok(Some(version.saturating_sub(1)));
+                    }
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
 /// Takes a series of transaction backup manifests, preheat in parallel, then execute in order.
 pub struct TransactionRestoreBatchController {
     global_opt: GlobalRestoreOptions,
@@ -4,12 +4,16 @@
 use crate::{
     backup_types::transaction::{
         backup::{TransactionBackupController, TransactionBackupOpt},
-        restore::{TransactionRestoreController, TransactionRestoreOpt},
+        restore::{
+            get_last_version_before_timestamp, TransactionRestoreController, TransactionRestoreOpt,
+        },
     },
     storage::{local_fs::LocalFs, BackupStorage},
     utils::{
         backup_service_client::BackupServiceClient,
-        test_utils::{start_local_backup_service, tmp_db_with_random_content},
+        test_utils::{
+            start_local_backup_service, tmp_db_with_block_timestamps, tmp_db_with_random_content,
+        },
         ConcurrentDownloadsOpt, GlobalBackupOpt, GlobalRestoreOpt, RocksdbOpt, TrustedWaypointOpt,
     },
 };
@@ -80,6 +84,7 @@ fn end_to_end() {
                 dry_run: false,
                 db_dir: Some(tgt_db_dir.path().to_path_buf()),
                 target_version: Some(target_version),
+                target_timestamp: None,
                 trusted_waypoints: TrustedWaypointOpt::default(),
                 rocksdb_opt: RocksdbOpt::default(),
                 concurernt_downloads: ConcurrentDownloadsOpt::default(),
@@ -138,3 +143,65 @@ fn end_to_end() {
 
     rt.shutdown_timeout(Duration::from_secs(1));
 }
+
+#[test]
+fn last_version_before_timestamp() {
+    // Blocks start at versions 1, 3, 5 and 7.
+    let (_src_db_dir, src_db) =
+        tmp_db_with_block_timestamps(&[1_000_000, 2_000_000, 5_000_000, 6_000_000]);
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+    let (rt, port) = start_local_backup_service(src_db);
+    let client = Arc::new(BackupServiceClient::new(format!(
+        "http://localhost:{}",
+        port
+    )));
+    let manifest_handle = rt
+        .block_on(
+            TransactionBackupController::new(
+                TransactionBackupOpt {
+                    start_version: 0,
+                    num_transactions: 9,
+                },
+                GlobalBackupOpt {
+                    max_chunk_size: 2048,
+                },
+                client,
+                Arc::clone(&store),
+            )
+            .run(),
+        )
+        .unwrap();
+
+    for (start_version, timestamp_usecs, expected) in [
+        (0, 0, Some(0)),
+        (0, 999_999, Some(0)),
+        (0, 1_000_000, Some(2)),
+        (0, 4_999_999, Some(4)),
+        (0, 5_000_000, Some(6)),
+        (0, 6_000_000, None),
+        (0, u64::MAX, None),
+        // The blocks before the start version are not looked at.
+        (4, 0, Some(4)),
+        (6, 1_000_000, Some(6)),
+    ] {
+        assert_eq!(
+            rt.block_on(get_last_version_before_timestamp(
+                &store,
+                &[manifest_handle.clone()],
+                start_version,
+                timestamp_usecs,
+                None, /* epoch_history */
+            ))
+            .unwrap(),
+            expected,
+            "start_version: {}, timestamp_usecs: {}",
+            start_version,
+            timestamp_usecs,
+        );
+    }
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use aptos_logger::{prelude::*, Level, Logger};
 use aptos_secure_push_metrics::MetricsPusher;
 use backup_cli::{
@@ -68,6 +68,11 @@ async fn main_impl() -> Result<()> {
 
     let opt = Opt::from_args();
     let global_opt: GlobalRestoreOptions = opt.global.clone().try_into()?;
+    ensure!(
+        global_opt.target_timestamp_usecs.is_none()
+            || matches!(opt.restore_type, RestoreType::Auto { .. }),
+        "--target-timestamp is only supported by the auto restore.",
+    );
 
     match opt.restore_type {
         RestoreType::EpochEnding { opt, storage } => {
@@ -10,7 +10,8 @@ use crate::{
     metadata,
     metadata::cache::MetadataCacheOpt,
     metrics::backup::{
-        EPOCH_ENDING_EPOCH, HEARTBEAT_TS, STATE_SNAPSHOT_VERSION, TRANSACTION_VERSION,
+        EPOCH_ENDING_EPOCH, HEARTBEAT_TS, STATE_SNAPSHOT_VERSION, TRANSACTION_LAG_SECS,
+        TRANSACTION_LAG_VERSIONS, TRANSACTION_VERSION,
     },
     storage::BackupStorage,
     utils::{
@@ -19,6 +20,7 @@ use crate::{
     },
 };
 use anyhow::{anyhow, ensure, Result};
+use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_types::transaction::Version;
 use aptosdb::backup::backup_handler::DbState;
@@ -27,7 +29,7 @@ use std::{fmt::Debug, sync::Arc};
 use structopt::StructOpt;
 use tokio::{
     sync::watch,
-    time::{interval, Duration},
+    time::{interval, Duration, Instant},
 };
 use tokio_stream::wrappers::IntervalStream;
 
@@ -46,6 +48,15 @@ pub struct BackupCoordinatorOpt {
     // slower than expected.
     #[structopt(long, default_value = "100000")]
     pub transaction_batch_size: usize,
+    // Without this, transactions are backed up in full batches only, so the backup can be behind
+    // the chain by a whole batch. With this set, the coordinator tails the node and backs up the
+    // committed transactions once they have been pending for this long, even if the batch is not
+    // full, which bounds the recovery point to about this many seconds behind the chain.
+    // Each partial batch is a backup of its own, with its own metadata file in the backup
+    // storage, and the remote metadata files are not compacted, so a short interval makes the
+    // storage accumulate many small files.
+    #[structopt(long)]
+    pub continuous_backup_interval_secs: Option<u64>,
     #[structopt(flatten)]
     pub concurernt_downloads: ConcurrentDownloadsOpt,
 }
@@ -63,6 +74,10 @@ impl BackupCoordinatorOpt {
              that's not yet in a transaction backup, resulting in replaying all transactions \
              at restore time."
         );
+        ensure!(
+            self.continuous_backup_interval_secs != Some(0),
+            "Continuous backup interval must be greater than 0."
+        );
         Ok(())
     }
 }
@@ -74,7 +89,10 @@ pub struct BackupCoordinator {
     metadata_cache_opt: MetadataCacheOpt,
     state_snapshot_interval: usize,
     transaction_batch_size: usize,
+    continuous_backup_interval: Option<Duration>,
     concurrent_downloads: usize,
+    /// Since when the node has committed transactions that are not yet in the backup.
+    transactions_pending_since: Mutex<Option<Instant>>,
 }
 
 impl BackupCoordinator {
@@ -92,7 +110,11 @@ impl BackupCoordinator {
             metadata_cache_opt: opt.metadata_cache_opt,
             state_snapshot_interval: opt.state_snapshot_interval,
             transaction_batch_size: opt.transaction_batch_size,
+            continuous_backup_interval: opt
+                .continuous_backup_interval_secs
+                .map(Duration::from_secs),
             concurrent_downloads: opt.concurernt_downloads.get(),
+            transactions_pending_since: Mutex::new(None),
         }
     }
     pub async fn run(&self) -> Result<()> {
@@ -256,19 +278,30 @@ impl BackupCoordinator {
         mut last_transaction_version_in_backup: Option<Version>,
         db_state: DbState,
     ) -> Result<Option<u64>> {
+        // The transactions pending in `db_state` were committed no later than this.
+        let observed_at = Instant::now();
         loop {
             if let Some(version) = last_transaction_version_in_backup {
                 TRANSACTION_VERSION.set(version as i64);
             }
-            let (first, last) = get_batch_range(
+            let lag = self.update_transaction_lag(
                 last_transaction_version_in_backup,
-                self.transaction_batch_size,
+                db_state,
+                observed_at,
             );
-
-            if db_state.committed_version < last {
+            let backup_partial_batch = self
+                .continuous_backup_interval
+                .map_or(false, |interval| lag >= interval);
+            let (first, last) = match get_next_transaction_batch(
+                last_transaction_version_in_backup,
+                self.transaction_batch_size,
+                db_state,
+                backup_partial_batch,
+            ) {
+                Some(range) => range,
                 // wait for the next db_state update
-                return Ok(last_transaction_version_in_backup);
-            }
+                None => return Ok(last_transaction_version_in_backup),
+            };
 
             TransactionBackupController::new(
                 TransactionBackupOpt {
@@ -283,9 +316,35 @@ impl BackupCoordinator {
             .await?;
 
             last_transaction_version_in_backup = Some(last);
+            // Start over with the transactions still pending.
+            *self.transactions_pending_since.lock() = None;
         }
     }
 
+    /// Reports how far the transaction backup is behind the node, returns the lag in time.
+    fn update_transaction_lag(
+        &self,
+        last_transaction_version_in_backup: Option<Version>,
+        db_state: DbState,
+        observed_at: Instant,
+    ) -> Duration {
+        let num_pending = match last_transaction_version_in_backup {
+            Some(version) => db_state.committed_version.saturating_sub(version),
+            None => db_state.committed_version + 1,
+        };
+        TRANSACTION_LAG_VERSIONS.set(num_pending as i64);
+
+        let mut pending_since = self.transactions_pending_since.lock();
+        let lag = if num_pending == 0 {
+            *pending_since = None;
+            Duration::ZERO
+        } else {
+            pending_since.get_or_insert(observed_at).elapsed()
+        };
+        TRANSACTION_LAG_SECS.set(lag.as_secs() as i64);
+        lag
+    }
+
     fn backup_work_stream<'a, S, W, Fut>(
         &'a self,
         initial_state: S,
@@ -341,6 +400,25 @@ fn get_batch_range(last_in_backup: Option<u64>, batch_size: usize) -> (u64, u64)
     })
 }
 
+/// Returns the range of the next transaction backup, or None if it's not ready. With
+/// `backup_partial_batch`, the committed transactions are backed up without waiting for the batch
+/// to fill up.
+fn get_next_transaction_batch(
+    last_in_backup: Option<u64>,
+    batch_size: usize,
+    db_state: DbState,
+    backup_partial_batch: bool,
+) -> Option<(u64, u64)> {
+    let (first, last) = get_batch_range(last_in_backup, batch_size);
+    if db_state.committed_version >= last {
+        Some((first, last))
+    } else if backup_partial_batch && db_state.committed_version >= first {
+        Some((first, db_state.committed_version))
+    } else {
+        None
+    }
+}
+
 fn get_next_snapshot(last_in_backup: Option<u64>, db_state: DbState, interval: usize) -> u64 {
     // We don't try to guarantee snapshots are taken at each applicable interval: when the backup
     // progress can't keep up with the ledger growth, we favor timeliness over completeness.
@@ -359,7 +437,9 @@ fn get_next_snapshot(last_in_backup: Option<u64>, db_state: DbState, interval: u
 
 #[cfg(test)]
 mod tests {
-    use crate::coordinators::backup::{get_batch_range, get_next_snapshot};
+    use crate::coordinators::backup::{
+        get_batch_range, get_next_snapshot, get_next_transaction_batch,
+    };
     use aptosdb::backup::backup_handler::DbState;
 
     #[test]
@@ -371,6 +451,41 @@ mod tests {
         assert_eq!(get_batch_range(Some(200), 100), (201, 300));
     }
 
+    #[test]
+    fn test_get_next_transaction_batch() {
+        let _state = |v| DbState {
+            epoch: 0,
+            committed_version: v,
+            synced_version: v,
+        };
+
+        assert_eq!(
+            get_next_transaction_batch(None, 100, _state(0), false),
+            Some((0, 0))
+        );
+        assert_eq!(
+            get_next_transaction_batch(Some(0), 100, _state(50), false),
+            None
+        );
+        assert_eq!(
+            get_next_transaction_batch(Some(0), 100, _state(50), true),
+            Some((1, 50))
+        );
+        assert_eq!(
+            get_next_transaction_batch(Some(50), 100, _state(50), true),
+            None
+        );
+        // Back to the batch boundaries after a partial batch.
+        assert_eq!(
+            get_next_transaction_batch(Some(50), 100, _state(150), false),
+            Some((51, 100))
+        );
+        assert_eq!(
+            get_next_transaction_batch(Some(100), 100, _state(150), true),
+            Some((101, 150))
+        );
+    }
+
     #[test]
     fn test_get_next_snapshot() {
         let _state = |v| DbState {
@@ -91,6 +91,7 @@ impl ReplayVerifyCoordinator {
 
         let global_opt = GlobalRestoreOptions {
             target_version: self.end_version,
+            target_timestamp_usecs: None,
             trusted_waypoints: Arc::new(self.trusted_waypoints_opt.verify()?),
             run_mode: Arc::new(RestoreRunMode::Restore {
                 restore_handler: self.restore_handler,
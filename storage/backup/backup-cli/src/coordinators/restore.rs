@@ -3,12 +3,14 @@
 
 use crate::{
     backup_types::{
-        epoch_ending::restore::EpochHistoryRestoreController,
+        epoch_ending::restore::{EpochHistory, EpochHistoryRestoreController},
         state_snapshot::restore::{StateSnapshotRestoreController, StateSnapshotRestoreOpt},
-        transaction::restore::TransactionRestoreBatchController,
+        transaction::restore::{
+            get_last_version_before_timestamp, TransactionRestoreBatchController,
+        },
     },
     metadata,
-    metadata::{cache::MetadataCacheOpt, TransactionBackupMeta},
+    metadata::{cache::MetadataCacheOpt, view::MetadataView, TransactionBackupMeta},
     metrics::restore::{
         COORDINATOR_FAIL_TS, COORDINATOR_START_TS, COORDINATOR_SUCC_TS, COORDINATOR_TARGET_VERSION,
     },
@@ -17,7 +19,7 @@ use crate::{
 };
 use anyhow::{bail, Result};
 use aptos_logger::prelude::*;
-use aptos_types::transaction::Version;
+use aptos_types::{transaction::Version, waypoint::Waypoint};
 use std::sync::Arc;
 use structopt::StructOpt;
 
@@ -86,7 +88,7 @@ impl RestoreCoordinator {
         ret
     }
 
-    async fn run_impl(self) -> Result<()> {
+    async fn run_impl(mut self) -> Result<()> {
         let metadata_view = metadata::cache::sync_and_load(
             &self.metadata_cache_opt,
             Arc::clone(&self.storage),
@@ -94,6 +96,15 @@ impl RestoreCoordinator {
         )
         .await?;
 
+        let mut verified_epoch_history = None;
+        if let Some(timestamp_usecs) = self.global_opt.target_timestamp_usecs {
+            let (target_version, epoch_history) = self
+                .resolve_target_timestamp(&metadata_view, timestamp_usecs)
+                .await?;
+            self.global_opt.target_version = target_version;
+            verified_epoch_history = epoch_history;
+        }
+
         let mut transactions =
             metadata_view.select_transaction_backups(0, self.target_version())?;
         let actual_target_version = self.get_actual_target_version(&transactions)?;
@@ -152,13 +163,19 @@ impl RestoreCoordinator {
         let epoch_history = if self.skip_epoch_endings {
             None
         } else {
+            // The epoch endings verified when resolving the target timestamp are trusted, so that
+            // their signatures are not verified again while restoring them.
+            let global_opt = match &verified_epoch_history {
+                Some(history) => trust_epoch_history(&self.global_opt, history)?,
+                None => self.global_opt.clone(),
+            };
             Some(Arc::new(
                 EpochHistoryRestoreController::new(
                     epoch_endings
                         .into_iter()
                         .map(|backup| backup.manifest)
                         .collect(),
-                    self.global_opt.clone(),
+                    global_opt,
                     self.storage.clone(),
                 )
                 .run()
@@ -200,6 +217,81 @@ impl RestoreCoordinator {
         self.global_opt.target_version
     }
 
+    /// Finds the last version committed no later than `timestamp_usecs`. The epoch ending ledger
+    /// infos narrow down the transactions in which to look for the block timestamps, and are
+    /// returned once verified.
+    async fn resolve_target_timestamp(
+        &self,
+        metadata_view: &MetadataView,
+        timestamp_usecs: u64,
+    ) -> Result<(Version, Option<Arc<EpochHistory>>)> {
+        let epoch_history = if self.skip_epoch_endings {
+            None
+        } else {
+            // Only verify the epoch endings here, they are restored later, up to the resolved
+            // version.
+            let verify_opt = GlobalRestoreOptions {
+                run_mode: Arc::new(RestoreRunMode::Verify),
+                ..self.global_opt.clone()
+            };
+            Some(Arc::new(
+                EpochHistoryRestoreController::new(
+                    metadata_view
+                        .select_epoch_ending_backups(Version::MAX)?
+                        .into_iter()
+                        .map(|backup| backup.manifest)
+                        .collect(),
+                    verify_opt,
+                    Arc::clone(&self.storage),
+                )
+                .run()
+                .await?,
+            ))
+        };
+        // Transactions up to the last epoch ending no later than the timestamp are all committed
+        // before it.
+        let start_version = epoch_history.as_ref().map_or(0, |history| {
+            history
+                .epoch_endings
+                .iter()
+                .take_while(|li| li.timestamp_usecs() <= timestamp_usecs)
+                .last()
+                .map_or(0, |li| li.version() + 1)
+        });
+        let manifest_handles = metadata_view
+            .select_transaction_backups(start_version, Version::MAX)?
+            .into_iter()
+            .map(|backup| backup.manifest)
+            .collect::<Vec<_>>();
+
+        let target_version = match get_last_version_before_timestamp(
+            &self.storage,
+            &manifest_handles,
+            start_version,
+            timestamp_usecs,
+            epoch_history.as_ref(),
+        )
+        .await?
+        {
+            Some(version) => {
+                info!(
+                    "Target timestamp {} resolved to version {}.",
+                    timestamp_usecs, version
+                );
+                version
+            }
+            None => {
+                warn!(
+                    "All transactions in the backups are committed before the target timestamp \
+                    {}, will restore as much as possible.",
+                    timestamp_usecs
+                );
+                Version::MAX
+            }
+        };
+        Ok((target_version, epoch_history))
+    }
+
     fn get_actual_target_version(
         &self,
         transaction_backups: &[TransactionBackupMeta],
@@ -219,3 +311,149 @@ impl RestoreCoordinator {
         }
     }
 }
+
+/// Adds the waypoints of the epoch endings in `epoch_history`, which are verified already, to the
+/// trusted waypoints.
+fn trust_epoch_history(
+    global_opt: &GlobalRestoreOptions,
+    epoch_history: &EpochHistory,
+) -> Result<GlobalRestoreOptions> {
+    let mut trusted_waypoints = global_opt.trusted_waypoints.as_ref().clone();
+    for li in &epoch_history.epoch_endings {
+        let waypoint = Waypoint::new_epoch_boundary(li)?;
+        trusted_waypoints
+            .entry(waypoint.version())
+            .or_insert(waypoint);
+    }
+    Ok(GlobalRestoreOptions {
+        trusted_waypoints: Arc::new(trusted_waypoints),
+        ..global_opt.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backup_types::{
+            epoch_ending::backup::{EpochEndingBackupController, EpochEndingBackupOpt},
+            state_snapshot::backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+            transaction::backup::{TransactionBackupController, TransactionBackupOpt},
+        },
+        storage::local_fs::LocalFs,
+        utils::{
+            backup_service_client::BackupServiceClient,
+            test_utils::{start_local_backup_service, tmp_db_with_block_timestamps},
+            ConcurrentDownloadsOpt, GlobalBackupOpt, GlobalRestoreOpt, RocksdbOpt,
+            TrustedWaypointOpt,
+        },
+    };
+    use aptos_temppath::TempPath;
+    use aptosdb::AptosDB;
+    use std::convert::TryInto;
+    use storage_interface::DbReader;
+    use tokio::time::Duration;
+
+    #[test]
+    fn test_restore_to_target_timestamp() {
+        // Blocks start at versions 1, 3, 5 and 7, all in epoch 1.
+        let (_src_db_dir, src_db) =
+            tmp_db_with_block_timestamps(&[1_000_000, 2_000_000, 5_000_000, 6_000_000]);
+        let tgt_db_dir = TempPath::new();
+        tgt_db_dir.create_as_dir().unwrap();
+        let backup_dir = TempPath::new();
+        backup_dir.create_as_dir().unwrap();
+        let metadata_cache_dir = TempPath::new();
+        let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+        let (rt, port) = start_local_backup_service(Arc::clone(&src_db));
+        let client = Arc::new(BackupServiceClient::new(format!(
+            "http://localhost:{}",
+            port
+        )));
+        let global_backup_opt = GlobalBackupOpt {
+            max_chunk_size: 2048,
+        };
+        rt.block_on(
+            EpochEndingBackupController::new(
+                EpochEndingBackupOpt {
+                    start_epoch: 0,
+                    end_epoch: 1,
+                },
+                global_backup_opt.clone(),
+                Arc::clone(&client),
+                Arc::clone(&store),
+            )
+            .run(),
+        )
+        .unwrap();
+        rt.block_on(
+            StateSnapshotBackupController::new(
+                StateSnapshotBackupOpt { version: 0 },
+                global_backup_opt.clone(),
+                Arc::clone(&client),
+                Arc::clone(&store),
+            )
+            .run(),
+        )
+        .unwrap();
+        rt.block_on(
+            TransactionBackupController::new(
+                TransactionBackupOpt {
+                    start_version: 0,
+                    num_transactions: 9,
+                },
+                global_backup_opt,
+                client,
+                Arc::clone(&store),
+            )
+            .run(),
+        )
+        .unwrap();
+
+        // 4 seconds is between the blocks at versions 3 and 5.
+        let global_restore_opt: GlobalRestoreOptions = GlobalRestoreOpt {
+            dry_run: false,
+            db_dir: Some(tgt_db_dir.path().to_path_buf()),
+            target_version: None,
+            target_timestamp: Some(4),
+            trusted_waypoints: TrustedWaypointOpt::default(),
+            rocksdb_opt: RocksdbOpt::default(),
+            concurernt_downloads: ConcurrentDownloadsOpt::default(),
+        }
+        .try_into()
+        .unwrap();
+        rt.block_on(
+            RestoreCoordinator::new(
+                RestoreCoordinatorOpt {
+                    metadata_cache_opt: MetadataCacheOpt::new(Some(
+                        metadata_cache_dir.path().to_path_buf(),
+                    )),
+                    replay_all: false,
+                    ledger_history_start_version: 0,
+                    skip_epoch_endings: false,
+                },
+                global_restore_opt,
+                store,
+            )
+            .run(),
+        )
+        .unwrap();
+
+        let tgt_db = AptosDB::new_for_test(&tgt_db_dir);
+        assert_eq!(
+            tgt_db
+                .get_latest_transaction_info_option()
+                .unwrap()
+                .unwrap()
+                .0,
+            4
+        );
+        assert_eq!(
+            tgt_db.get_transactions(0, 5, 4, true).unwrap(),
+            src_db.get_transactions(0, 5, 4, true).unwrap(),
+        );
+
+        rt.shutdown_timeout(Duration::from_secs(1));
+    }
+}
@@ -14,14 +14,14 @@ use async_trait::async_trait;
 use futures::stream::poll_fn;
 use once_cell::sync::Lazy;
 use std::{
-    collections::{HashMap, HashSet},
-    path::PathBuf,
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
     sync::Arc,
     time::Instant,
 };
 use structopt::StructOpt;
 use tokio::{
-    fs::{create_dir_all, read_dir, remove_file, OpenOptions},
+    fs::{create_dir_all, read_dir, read_to_string, remove_file, rename, write, OpenOptions},
     io::{AsyncRead, AsyncReadExt},
 };
 use tokio_stream::StreamExt;
@@ -43,19 +43,39 @@ pub struct MetadataCacheOpt {
     dir: Option<PathBuf>,
 }
 
+/// Metadata entries of the synced metadata files, by file handle hash.
+type MetadataIndex = BTreeMap<String, Vec<Metadata>>;
+
 impl MetadataCacheOpt {
     // in cache we save things other than the cached files.
     const SUB_DIR: &'static str = "cache";
+    const INDEX_FILE: &'static str = "index.json";
 
-    fn cache_dir(&self) -> PathBuf {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self { dir }
+    }
+
+    fn base_dir(&self) -> PathBuf {
         self.dir
             .clone()
             .unwrap_or_else(|| TEMP_METADATA_CACHE_DIR.path().to_path_buf())
-            .join(Self::SUB_DIR)
+    }
+
+    fn cache_dir(&self) -> PathBuf {
+        self.base_dir().join(Self::SUB_DIR)
+    }
+
+    fn index_file(&self) -> PathBuf {
+        self.base_dir().join(Self::INDEX_FILE)
     }
 }
 
 /// Sync local cache folder with remote storage, and load all metadata entries from the cache.
+///
+/// Downloaded metadata files are folded into a single index file and removed from the cache
+/// folder, so that a backup producing many small metadata files (like a continuous backup does)
+/// doesn't make loading the cache slower and slower. The index is local only: the remote storage
+/// still has a metadata file per backup, all of which are listed on every sync.
 pub async fn sync_and_load(
     opt: &MetadataCacheOpt,
     storage: Arc<dyn BackupStorage>,
@@ -84,6 +104,8 @@ pub async fn sync_and_load(
                 .map_err(|s| anyhow!("into_string() failed for file name {:?}", s))
         })
         .collect::<Result<HashSet<_>>>()?;
+    let index_file = opt.index_file();
+    let mut index = load_index(&index_file).await;
 
     // List remote metadata files.
     let remote_file_handles = storage.list_metadata_files().await?;
@@ -96,8 +118,14 @@ pub async fn sync_and_load(
     NUM_META_FILES.set(remote_hashes.len() as i64);
 
     // Sync local cache with remote metadata files.
+    let num_indexed = index.len();
+    index.retain(|h, _| remote_hashes.contains(h));
+    let mut index_updated = index.len() != num_indexed;
     let stale_local_hashes = local_hashes.difference(&remote_hashes);
-    let new_remote_hashes = remote_hashes.difference(&local_hashes).collect::<Vec<_>>();
+    let new_remote_hashes = remote_hashes
+        .iter()
+        .filter(|h| !local_hashes.contains(*h) && !index.contains_key(*h))
+        .collect::<Vec<_>>();
     let up_to_date_local_hashes = local_hashes.intersection(&remote_hashes);
 
     for h in stale_local_hashes {
@@ -145,22 +173,31 @@ pub async fn sync_and_load(
         .collect::<Result<Vec<_>>>()
         .await?;
 
-    // Load metadata from synced cache files.
-    let mut metadata_vec = Vec::new();
+    // Fold synced cache files into the index.
+    let mut indexed_files = Vec::new();
     for h in new_remote_hashes.into_iter().chain(up_to_date_local_hashes) {
         let cached_file = cache_dir.join(&*h);
-        metadata_vec.extend(
-            OpenOptions::new()
-                .read(true)
-                .open(&cached_file)
-                .await
-                .err_notes(&cached_file)?
-                .load_metadata_lines()
-                .await
-                .err_notes(&cached_file)?
-                .into_iter(),
-        )
+        let metadata = OpenOptions::new()
+            .read(true)
+            .open(&cached_file)
+            .await
+            .err_notes(&cached_file)?
+            .load_metadata_lines()
+            .await
+            .err_notes(&cached_file)?;
+        index.insert(h.clone(), metadata);
+        indexed_files.push(cached_file);
+        index_updated = true;
+    }
+    if index_updated {
+        save_index(&index_file, &index).await?;
+        for file in indexed_files {
+            remove_file(&file).await.err_notes(&file)?;
+        }
     }
+
+    // Load metadata from the index.
+    let metadata_vec = index.into_values().flatten().collect::<Vec<_>>();
     info!(
         "Metadata cache loaded in {:.2} seconds.",
         timer.elapsed().as_secs_f64()
@@ -168,6 +205,32 @@ pub async fn sync_and_load(
     Ok(metadata_vec.into())
 }
 
+/// Loads the index, starting over with an empty one if it's missing or can't be read.
+async fn load_index(index_file: &Path) -> MetadataIndex {
+    let index: Result<MetadataIndex> = match read_to_string(index_file).await {
+        Ok(content) => serde_json::from_str(&content).map_err(anyhow::Error::from),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(MetadataIndex::new()),
+        Err(e) => Err(e.into()),
+    };
+    index.unwrap_or_else(|e| {
+        warn!(
+            "Failed to load metadata cache index {:?}: {}. Rebuilding it.",
+            index_file, e
+        );
+        MetadataIndex::new()
+    })
+}
+
+async fn save_index(index_file: &Path, index: &MetadataIndex) -> Result<()> {
+    // write to tmp file then rename, so a failure never leaves a partial index behind
+    let tmp_file = index_file.with_extension("tmp");
+    write(&tmp_file, serde_json::to_vec(index)?)
+        .await
+        .err_notes(&tmp_file)?;
+    rename(&tmp_file, index_file).await.err_notes(index_file)?;
+    Ok(())
+}
+
 trait FileHandleHash {
     fn file_handle_hash(&self) -> String;
 }
@@ -200,3 +263,54 @@ impl<R: AsyncRead + Send + Unpin> LoadMetadataLines for R {
             .collect::<Result<_, serde_json::error::Error>>()?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::local_fs::LocalFs;
+
+    async fn save_transaction_backup_meta(
+        storage: &Arc<dyn BackupStorage>,
+        first_version: u64,
+        last_version: u64,
+    ) {
+        let metadata =
+            Metadata::new_transaction_backup(first_version, last_version, "manifest".to_string());
+        storage
+            .save_metadata_line(&metadata.name(), &metadata.to_text_line().unwrap())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sync_and_load_with_index() {
+        let backup_dir = TempPath::new();
+        backup_dir.create_as_dir().unwrap();
+        let storage: Arc<dyn BackupStorage> =
+            Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+        let cache_dir = TempPath::new();
+        let opt = MetadataCacheOpt {
+            dir: Some(cache_dir.path().to_path_buf()),
+        };
+
+        save_transaction_backup_meta(&storage, 0, 0).await;
+        save_transaction_backup_meta(&storage, 1, 10).await;
+        let view = sync_and_load(&opt, Arc::clone(&storage), 2).await.unwrap();
+        assert_eq!(
+            view.get_storage_state().latest_transaction_version,
+            Some(10)
+        );
+        // The downloaded files are folded into the index.
+        assert_eq!(load_index(&opt.index_file()).await.len(), 2);
+        assert_eq!(std::fs::read_dir(opt.cache_dir()).unwrap().count(), 0);
+
+        save_transaction_backup_meta(&storage, 11, 12).await;
+        let view = sync_and_load(&opt, Arc::clone(&storage), 2).await.unwrap();
+        assert_eq!(
+            view.get_storage_state().latest_transaction_version,
+            Some(12)
+        );
+        assert_eq!(load_index(&opt.index_file()).await.len(), 3);
+        assert_eq!(view.select_transaction_backups(0, 12).unwrap().len(), 3);
+    }
+}
@@ -35,3 +35,20 @@ pub static TRANSACTION_VERSION: Lazy<IntGauge> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static TRANSACTION_LAG_VERSIONS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_db_backup_coordinator_transaction_lag_versions",
+        "Number of transactions committed by the node but not yet backed up."
+    )
+    .unwrap()
+});
+
+pub static TRANSACTION_LAG_SECS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_db_backup_coordinator_transaction_lag_s",
+        "Seconds since the coordinator first saw transactions pending backup. Counted from when \
+         the coordinator observed the pending versions, not from their commit time."
+    )
+    .unwrap()
+});
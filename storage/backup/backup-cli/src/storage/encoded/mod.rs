@@ -0,0 +1,363 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests;
+
+use crate::storage::{
+    BackupHandle, BackupHandleRef, BackupStorage, FileHandle, FileHandleRef, ShellSafeName,
+    TextLine,
+};
+use aes_gcm::{
+    aead::{Aead, NewAead, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use anyhow::{anyhow, ensure, Result};
+use aptos_config::config::SecureBackend;
+use aptos_infallible::Mutex;
+use aptos_secure_storage::{KVStorage, Storage};
+use async_trait::async_trait;
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    convert::TryInto,
+    io::Cursor,
+    path::PathBuf,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use structopt::StructOpt;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite};
+
+/// Leading bytes of an encoded file, a file without them is read as is.
+const MAGIC: &[u8; 8] = b"APTBKENC";
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const ZSTD_LEVEL: i32 = 3;
+
+#[derive(Clone, StructOpt)]
+pub struct EncodingOpt {
+    #[structopt(long, help = "Compress the backup files with zstd.")]
+    pub compress: bool,
+
+    #[structopt(
+        long,
+        parse(from_os_str),
+        help = "Yaml config of the secure storage holding the encryption keys, in the same format \
+        as a secure backend in the node config. Required to write or read encrypted backups."
+    )]
+    pub encryption_key_storage: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        requires = "encryption-key-storage",
+        help = "Encrypt the backup files with AES-256-GCM, using the base64 encoded 32 byte key \
+        of this name in the secure storage."
+    )]
+    pub encryption_key_name: Option<String>,
+}
+
+/// How a file is encoded, written in front of the file content. Decoding only depends on this,
+/// so backups can be read without knowing the options they were written with.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct FileHeader {
+    compression: Option<Compression>,
+    encryption: Option<Encryption>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+enum Compression {
+    Zstd,
+}
+
+/// AES-256-GCM, authenticating the header as well.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Encryption {
+    key_name: String,
+    nonce: Vec<u8>,
+}
+
+/// A storage layer compressing and encrypting the files written to the underlying storage, and
+/// decoding the encoded files read from it. Metadata lines are not encoded, they are not
+/// sensitive and the metadata cache needs to read them without any key.
+pub struct EncodedStorage {
+    inner: Box<dyn BackupStorage>,
+    compress: bool,
+    encryption_key_name: Option<String>,
+    key_storage: Option<Mutex<Storage>>,
+    /// Keys loaded from the key storage, by name.
+    keys: Mutex<HashMap<String, [u8; KEY_LENGTH]>>,
+}
+
+impl EncodedStorage {
+    pub fn new(
+        inner: Box<dyn BackupStorage>,
+        compress: bool,
+        encryption_key_name: Option<String>,
+        key_storage: Option<Storage>,
+    ) -> Result<Self> {
+        let storage = Self {
+            inner,
+            compress,
+            encryption_key_name,
+            key_storage: key_storage.map(Mutex::new),
+            keys: Mutex::new(HashMap::new()),
+        };
+        // Fail early if the encryption key is not available.
+        if let Some(key_name) = &storage.encryption_key_name {
+            storage.get_key(key_name)?;
+        }
+        Ok(storage)
+    }
+
+    pub fn new_with_opt(inner: Box<dyn BackupStorage>, opt: EncodingOpt) -> Result<Self> {
+        let key_storage = opt
+            .encryption_key_storage
+            .map(|path| -> Result<Storage> {
+                let backend: SecureBackend = serde_yaml::from_str(&std::fs::read_to_string(path)?)?;
+                Ok(Storage::from(&backend))
+            })
+            .transpose()?;
+        Self::new(inner, opt.compress, opt.encryption_key_name, key_storage)
+    }
+
+    fn get_key(&self, key_name: &str) -> Result<[u8; KEY_LENGTH]> {
+        let mut keys = self.keys.lock();
+        if let Some(key) = keys.get(key_name) {
+            return Ok(*key);
+        }
+        let key_storage = self.key_storage.as_ref().ok_or_else(|| {
+            anyhow!(
+                "Key storage not configured, can't load encryption key {}.",
+                key_name
+            )
+        })?;
+        let encoded = key_storage.lock().get::<String>(key_name)?.value;
+        let key: [u8; KEY_LENGTH] = base64::decode(encoded)?.try_into().map_err(|_| {
+            anyhow!(
+                "Encryption key {} should be {} bytes long.",
+                key_name,
+                KEY_LENGTH
+            )
+        })?;
+        keys.insert(key_name.to_string(), key);
+        Ok(key)
+    }
+
+    /// Returns the encoder for the files to write, or None if they are written as is.
+    fn encoder(&self) -> Result<Option<Encoder>> {
+        if !self.compress && self.encryption_key_name.is_none() {
+            return Ok(None);
+        }
+        let encryption_key = match &self.encryption_key_name {
+            Some(key_name) => Some((key_name.clone(), self.get_key(key_name)?)),
+            None => None,
+        };
+        Ok(Some(Encoder {
+            compress: self.compress,
+            encryption_key,
+        }))
+    }
+
+    fn decode(&self, bytes: Vec<u8>) -> Result<Vec<u8>> {
+        if !bytes.starts_with(MAGIC) {
+            return Ok(bytes);
+        }
+        let bytes = &bytes[MAGIC.len()..];
+        ensure!(bytes.len() >= 4, "Truncated file header.");
+        let header_len = u32::from_le_bytes(bytes[..4].try_into()?) as usize;
+        let bytes = &bytes[4..];
+        ensure!(bytes.len() >= header_len, "Truncated file header.");
+        let (header_bytes, payload) = bytes.split_at(header_len);
+        let header: FileHeader = bcs::from_bytes(header_bytes)?;
+
+        let mut content = payload.to_vec();
+        if let Some(encryption) = &header.encryption {
+            ensure!(
+                encryption.nonce.len() == NONCE_LENGTH,
+                "Invalid nonce length {}.",
+                encryption.nonce.len(),
+            );
+            content = Aes256Gcm::new(Key::from_slice(&self.get_key(&encryption.key_name)?))
+                .decrypt(
+                    Nonce::from_slice(&encryption.nonce),
+                    Payload {
+                        msg: &content,
+                        aad: header_bytes,
+                    },
+                )
+                .map_err(|_| {
+                    anyhow!(
+                        "Failed to decrypt with key {}, wrong key or corrupted file.",
+                        encryption.key_name
+                    )
+                })?;
+        }
+        if let Some(Compression::Zstd) = header.compression {
+            content = zstd::stream::decode_all(content.as_slice())?;
+        }
+        Ok(content)
+    }
+}
+
+struct Encoder {
+    compress: bool,
+    /// Key name and key.
+    encryption_key: Option<(String, [u8; KEY_LENGTH])>,
+}
+
+impl Encoder {
+    fn encode(&self, content: Vec<u8>) -> Result<Vec<u8>> {
+        let encryption = self.encryption_key.as_ref().map(|(key_name, _key)| {
+            let mut nonce = vec![0; NONCE_LENGTH];
+            OsRng.fill_bytes(&mut nonce);
+            Encryption {
+                key_name: key_name.clone(),
+                nonce,
+            }
+        });
+        let header = FileHeader {
+            compression: self.compress.then(|| Compression::Zstd),
+            encryption,
+        };
+        let header_bytes = bcs::to_bytes(&header)?;
+
+        let mut payload = content;
+        if header.compression.is_some() {
+            payload = zstd::bulk::compress(&payload, ZSTD_LEVEL)?;
+        }
+        if let (Some((_key_name, key)), Some(encryption)) =
+            (&self.encryption_key, &header.encryption)
+        {
+            payload = Aes256Gcm::new(Key::from_slice(key))
+                .encrypt(
+                    Nonce::from_slice(&encryption.nonce),
+                    Payload {
+                        msg: &payload,
+                        aad: &header_bytes,
+                    },
+                )
+                .map_err(|_| anyhow!("Failed to encrypt."))?;
+        }
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&(header_bytes.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&header_bytes);
+        bytes.extend_from_slice(&payload);
+        Ok(bytes)
+    }
+}
+
+enum WriterState {
+    Buffering(Vec<u8>),
+    /// Encoded bytes and how many of them are written to the underlying file.
+    Writing(Vec<u8>, usize),
+    Done,
+}
+
+/// Buffers the whole file and writes it encoded to the underlying file on shutdown.
+struct EncodingWriter {
+    inner: Box<dyn AsyncWrite + Send + Unpin>,
+    encoder: Encoder,
+    state: WriterState,
+}
+
+impl AsyncWrite for EncodingWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        match &mut self.get_mut().state {
+            WriterState::Buffering(buffer) => {
+                buffer.extend_from_slice(buf);
+                Poll::Ready(Ok(buf.len()))
+            }
+            _ => Poll::Ready(Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "Writing to a file being shut down.",
+            ))),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        // Nothing is written to the underlying file before shutdown.
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            match &mut this.state {
+                WriterState::Buffering(buffer) => {
+                    let encoded = this
+                        .encoder
+                        .encode(std::mem::take(buffer))
+                        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+                    this.state = WriterState::Writing(encoded, 0);
+                }
+                WriterState::Writing(encoded, written) => {
+                    while *written < encoded.len() {
+                        let n = futures::ready!(
+                            Pin::new(&mut this.inner).poll_write(cx, &encoded[*written..])
+                        )?;
+                        if n == 0 {
+                            return Poll::Ready(Err(std::io::ErrorKind::WriteZero.into()));
+                        }
+                        *written += n;
+                    }
+                    this.state = WriterState::Done;
+                }
+                WriterState::Done => return Pin::new(&mut this.inner).poll_shutdown(cx),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl BackupStorage for EncodedStorage {
+    async fn create_backup(&self, name: &ShellSafeName) -> Result<BackupHandle> {
+        self.inner.create_backup(name).await
+    }
+
+    async fn create_for_write(
+        &self,
+        backup_handle: &BackupHandleRef,
+        name: &ShellSafeName,
+    ) -> Result<(FileHandle, Box<dyn AsyncWrite + Send + Unpin>)> {
+        let encoder = self.encoder()?;
+        let (file_handle, file) = self.inner.create_for_write(backup_handle, name).await?;
+        let file: Box<dyn AsyncWrite + Send + Unpin> = match encoder {
+            // The file is encoded as a whole, which is fine since chunks are of bounded size.
+            Some(encoder) => Box::new(EncodingWriter {
+                inner: file,
+                encoder,
+                state: WriterState::Buffering(Vec::new()),
+            }),
+            None => file,
+        };
+        Ok((file_handle, file))
+    }
+
+    async fn open_for_read(
+        &self,
+        file_handle: &FileHandleRef,
+    ) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+        let mut bytes = Vec::new();
+        self.inner
+            .open_for_read(file_handle)
+            .await?
+            .read_to_end(&mut bytes)
+            .await?;
+        Ok(Box::new(Cursor::new(self.decode(bytes)?)))
+    }
+
+    async fn save_metadata_line(&self, name: &ShellSafeName, content: &TextLine) -> Result<()> {
+        self.inner.save_metadata_line(name, content).await
+    }
+
+    async fn list_metadata_files(&self) -> Result<Vec<FileHandle>> {
+        self.inner.list_metadata_files().await
+    }
+}
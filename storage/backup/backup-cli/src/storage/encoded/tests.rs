@@ -0,0 +1,131 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::storage::{
+    local_fs::LocalFs,
+    test_util::{
+        arb_backups, arb_metadata_files, test_save_and_list_metadata_files_impl,
+        test_write_and_read_impl,
+    },
+};
+use aptos_secure_storage::InMemoryStorage;
+use aptos_temppath::TempPath;
+use proptest::prelude::*;
+use std::path::Path;
+use tokio::{io::AsyncWriteExt, runtime::Runtime};
+
+const KEY_NAME: &str = "backup_key";
+
+fn key_storage(key: &[u8]) -> Storage {
+    let mut storage = Storage::from(InMemoryStorage::new());
+    storage.set(KEY_NAME, base64::encode(key)).unwrap();
+    storage
+}
+
+fn encoded_storage(dir: &Path, compress: bool, key: Option<&[u8]>) -> EncodedStorage {
+    EncodedStorage::new(
+        Box::new(LocalFs::new(dir.to_path_buf())),
+        compress,
+        key.map(|_| KEY_NAME.to_string()),
+        key.map(key_storage),
+    )
+    .unwrap()
+}
+
+async fn write_file(store: &dyn BackupStorage, content: &[u8]) -> FileHandle {
+    let backup_handle = store
+        .create_backup(&"backup".parse().unwrap())
+        .await
+        .unwrap();
+    let (file_handle, mut file) = store
+        .create_for_write(&backup_handle, &"file".parse().unwrap())
+        .await
+        .unwrap();
+    file.write_all(content).await.unwrap();
+    file.shutdown().await.unwrap();
+    file_handle
+}
+
+async fn read_file(store: &dyn BackupStorage, file_handle: &FileHandleRef) -> Result<Vec<u8>> {
+    let mut content = Vec::new();
+    store
+        .open_for_read(file_handle)
+        .await?
+        .read_to_end(&mut content)
+        .await?;
+    Ok(content)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_write_and_read(
+        backups in arb_backups(),
+        compress in any::<bool>(),
+        key in any::<Option<[u8; KEY_LENGTH]>>(),
+    ) {
+        let tmpdir = TempPath::new();
+        tmpdir.create_as_dir().unwrap();
+        let store = encoded_storage(tmpdir.path(), compress, key.as_ref().map(|k| &k[..]));
+
+        let rt = Runtime::new().unwrap();
+        rt.block_on(test_write_and_read_impl(Box::new(store), backups));
+    }
+
+    #[test]
+    fn test_save_list_metadata_files(
+        input in arb_metadata_files(),
+    ) {
+        let tmpdir = TempPath::new();
+        tmpdir.create_as_dir().unwrap();
+        let store = encoded_storage(tmpdir.path(), true, Some(&[1; KEY_LENGTH]));
+
+        let rt = Runtime::new().unwrap();
+        rt.block_on(test_save_and_list_metadata_files_impl(Box::new(store), input));
+    }
+}
+
+#[tokio::test]
+async fn test_encoded_on_disk() {
+    let tmpdir = TempPath::new();
+    tmpdir.create_as_dir().unwrap();
+    let content = vec![7u8; 10000];
+    let store = encoded_storage(tmpdir.path(), true, Some(&[1; KEY_LENGTH]));
+    let file_handle = write_file(&store, &content).await;
+
+    // Compressed and encrypted on disk.
+    let plain_store = LocalFs::new(tmpdir.path().to_path_buf());
+    let on_disk = read_file(&plain_store, &file_handle).await.unwrap();
+    assert!(on_disk.starts_with(MAGIC));
+    assert!(on_disk.len() < content.len());
+
+    // Decoded without specifying the encoding, but only with the right key.
+    let wrong_key_store = encoded_storage(tmpdir.path(), false, Some(&[2; KEY_LENGTH]));
+    assert!(read_file(&wrong_key_store, &file_handle).await.is_err());
+    let no_key_store = encoded_storage(tmpdir.path(), false, None);
+    assert!(read_file(&no_key_store, &file_handle).await.is_err());
+    let reader_store = EncodedStorage::new(
+        Box::new(LocalFs::new(tmpdir.path().to_path_buf())),
+        false,
+        None,
+        Some(key_storage(&[1; KEY_LENGTH])),
+    )
+    .unwrap();
+    assert_eq!(
+        read_file(&reader_store, &file_handle).await.unwrap(),
+        content
+    );
+}
+
+#[tokio::test]
+async fn test_read_plain_files() {
+    let tmpdir = TempPath::new();
+    tmpdir.create_as_dir().unwrap();
+    let content = b"written without encoding".to_vec();
+    let file_handle = write_file(&LocalFs::new(tmpdir.path().to_path_buf()), &content).await;
+
+    let store = encoded_storage(tmpdir.path(), true, Some(&[1; KEY_LENGTH]));
+    assert_eq!(read_file(&store, &file_handle).await.unwrap(), content);
+}
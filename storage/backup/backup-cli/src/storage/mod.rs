@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod command_adapter;
+pub mod encoded;
 pub mod local_fs;
 
 #[cfg(test)]
@@ -11,6 +12,7 @@ mod tests;
 
 use crate::storage::{
     command_adapter::{CommandAdapter, CommandAdapterOpt},
+    encoded::{EncodedStorage, EncodingOpt},
     local_fs::{LocalFs, LocalFsOpt},
 };
 use anyhow::{ensure, Result};
@@ -172,16 +174,33 @@ pub trait BackupStorage: Send + Sync {
 #[derive(StructOpt)]
 pub enum StorageOpt {
     #[structopt(about = "Select the LocalFs backup store.")]
-    LocalFs(LocalFsOpt),
+    LocalFs {
+        #[structopt(flatten)]
+        opt: LocalFsOpt,
+        #[structopt(flatten)]
+        encoding: EncodingOpt,
+    },
     #[structopt(about = "Select the CommandAdapter backup store.")]
-    CommandAdapter(CommandAdapterOpt),
+    CommandAdapter {
+        #[structopt(flatten)]
+        opt: CommandAdapterOpt,
+        #[structopt(flatten)]
+        encoding: EncodingOpt,
+    },
 }
 
 impl StorageOpt {
     pub async fn init_storage(self) -> Result<Arc<dyn BackupStorage>> {
-        Ok(match self {
-            StorageOpt::LocalFs(opt) => Arc::new(LocalFs::new_with_opt(opt)),
-            StorageOpt::CommandAdapter(opt) => Arc::new(CommandAdapter::new_with_opt(opt).await?),
-        })
+        let (storage, encoding): (Box<dyn BackupStorage>, _) = match self {
+            StorageOpt::LocalFs { opt, encoding } => {
+                (Box::new(LocalFs::new_with_opt(opt)), encoding)
+            }
+            StorageOpt::CommandAdapter { opt, encoding } => {
+                (Box::new(CommandAdapter::new_with_opt(opt).await?), encoding)
+            }
+        };
+        // Encoded files are always decoded when read, files are only encoded when writing if
+        // asked to.
+        Ok(Arc::new(EncodedStorage::new_with_opt(storage, encoding)?))
     }
 }
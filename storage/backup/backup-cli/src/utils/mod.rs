@@ -90,6 +90,14 @@ pub struct GlobalRestoreOpt {
     )]
     pub target_version: Option<Version>,
 
+    #[structopt(
+        long,
+        conflicts_with = "target-version",
+        help = "Recover up to the last transaction committed at or before this time, in seconds \
+        since the Unix epoch, according to the block timestamps. Only supported by the auto restore."
+    )]
+    pub target_timestamp: Option<u64>,
+
     #[structopt(flatten)]
     pub trusted_waypoints: TrustedWaypointOpt,
 
@@ -155,6 +163,8 @@ impl RestoreRunMode {
 #[derive(Clone)]
 pub struct GlobalRestoreOptions {
     pub target_version: Version,
+    /// Resolved into the target version by the restore coordinator.
+    pub target_timestamp_usecs: Option<u64>,
     pub trusted_waypoints: Arc<HashMap<Version, Waypoint>>,
     pub run_mode: Arc<RestoreRunMode>,
     pub concurrent_downloads: usize,
@@ -172,6 +182,7 @@ impl TryFrom<GlobalRestoreOpt> for GlobalRestoreOptions {
                 false,                       /* read_only */
                 NO_OP_STORAGE_PRUNER_CONFIG, /* pruner config */
                 opt.rocksdb_opt.into(),
+                false, /* enable_event_by_type_index */
             )?)
             .get_restore_handler();
             RestoreRunMode::Restore { restore_handler }
@@ -180,6 +191,9 @@ impl TryFrom<GlobalRestoreOpt> for GlobalRestoreOptions {
         };
         Ok(Self {
             target_version,
+            target_timestamp_usecs: opt
+                .target_timestamp
+                .map(|secs| secs.saturating_mul(1_000_000)),
             trusted_waypoints: Arc::new(opt.trusted_waypoints.verify()?),
             run_mode: Arc::new(run_mode),
             concurrent_downloads,
@@ -4,9 +4,18 @@
 use aptos_config::utils::get_available_port;
 use aptos_proptest_helpers::ValueGenerator;
 use aptos_temppath::TempPath;
-use aptos_types::{ledger_info::LedgerInfoWithSignatures, transaction::TransactionToCommit};
+use aptos_types::{
+    block_metadata::BlockMetadata,
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{Transaction, TransactionToCommit, WriteSetPayload},
+    validator_signer::ValidatorSigner,
+};
 use aptosdb::{test_helper::arb_blocks_to_commit, AptosDB};
 use backup_service::start_backup_service;
+use executor_test_helpers::{
+    gen_block_id, gen_ledger_info_with_sigs, integration_test_impl::create_db_and_executor,
+};
+use executor_types::BlockExecutorTrait;
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
     sync::Arc,
@@ -43,8 +52,50 @@ pub fn tmp_db_with_random_content() -> (
     (tmpdir, db, blocks)
 }
 
+/// Creates a DB with a block committed at each of `timestamps_usecs`. A block is a block prologue
+/// followed by a state checkpoint, so the `i`-th block starts at version `2 * i + 1`.
+pub fn tmp_db_with_block_timestamps(timestamps_usecs: &[u64]) -> (TempPath, Arc<AptosDB>) {
+    let (genesis, validators) = vm_genesis::test_genesis_change_set_and_validators(Some(1));
+    let genesis_txn = Transaction::GenesisTransaction(WriteSetPayload::Direct(genesis));
+    let tmpdir = TempPath::new();
+    tmpdir.create_as_dir().unwrap();
+    let (db, _db_rw, executor, _waypoint) = create_db_and_executor(tmpdir.path(), &genesis_txn);
+    let signer = ValidatorSigner::new(validators[0].data.address, validators[0].key.clone());
+
+    let mut parent_block_id = executor.committed_block_id();
+    for (i, timestamp_usecs) in timestamps_usecs.iter().enumerate() {
+        let block_id = gen_block_id(i as u8 + 1);
+        let block = vec![
+            Transaction::BlockMetadata(BlockMetadata::new(
+                block_id,
+                1,            /* epoch */
+                i as u64 + 1, /* round */
+                vec![false],  /* previous_block_votes */
+                signer.author(),
+                vec![], /* failed_proposer_indices */
+                *timestamp_usecs,
+            )),
+            Transaction::StateCheckpoint,
+        ];
+        let output = executor
+            .execute_block((block_id, block), parent_block_id)
+            .unwrap();
+        let ledger_info_with_sigs = gen_ledger_info_with_sigs(1, &output, block_id, vec![&signer]);
+        executor
+            .commit_blocks(vec![block_id], ledger_info_with_sigs)
+            .unwrap();
+        parent_block_id = block_id;
+    }
+
+    (tmpdir, db)
+}
+
 pub fn start_local_backup_service(db: Arc<AptosDB>) -> (Runtime, u16) {
     let port = get_available_port();
-    let rt = start_backup_service(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port), db);
+    let rt = start_backup_service(
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port),
+        db,
+        None, /* checkpoint_dir */
+    );
     (rt, port)
 }
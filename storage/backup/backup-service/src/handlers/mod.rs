@@ -7,10 +7,20 @@ use crate::handlers::utils::{
     handle_rejection, reply_with_async_channel_writer, reply_with_bcs_bytes,
     send_size_prefixed_bcs_bytes, unwrap_or_500, LATENCY_HISTOGRAM,
 };
+use anyhow::format_err;
 use aptos_crypto::hash::HashValue;
 use aptos_types::transaction::Version;
-use aptosdb::backup::backup_handler::BackupHandler;
-use warp::{filters::BoxedFilter, reply::Reply, Filter};
+use aptosdb::{backup::backup_handler::BackupHandler, AptosDB};
+use serde::Serialize;
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+use warp::{filters::BoxedFilter, http::StatusCode, reply::Reply, Filter};
 
 static DB_STATE: &str = "db_state";
 static STATE_RANGE_PROOF: &str = "state_range_proof";
@@ -19,8 +29,72 @@ static STATE_ROOT_PROOF: &str = "state_root_proof";
 static EPOCH_ENDING_LEDGER_INFOS: &str = "epoch_ending_ledger_infos";
 static TRANSACTIONS: &str = "transactions";
 static TRANSACTION_RANGE_PROOF: &str = "transaction_range_proof";
+static PRUNER_PROGRESS: &str = "pruner_progress";
+static CHECKPOINT: &str = "checkpoint";
+
+/// Reply of the checkpoint endpoint.
+#[derive(Serialize)]
+struct CheckpointInfo {
+    path: String,
+    /// The version of the last transaction in the checkpoint.
+    version: Option<Version>,
+}
+
+pub(crate) fn get_routes(
+    backup_handler: BackupHandler,
+    db: Arc<AptosDB>,
+    checkpoint_dir: Option<PathBuf>,
+) -> BoxedFilter<(impl Reply,)> {
+    // POST checkpoint
+    // Creates a checkpoint of the DB, named after the current time, under `checkpoint_dir`.
+    // Only one checkpoint is created at a time, concurrent requests are rejected.
+    let checkpoint_in_progress = Arc::new(AtomicBool::new(false));
+    let checkpoint = warp::path::end()
+        .map(move || -> anyhow::Result<Box<dyn Reply>> {
+            let checkpoint_dir = checkpoint_dir
+                .as_ref()
+                .ok_or_else(|| format_err!("Checkpoint directory not configured."))?;
+            if checkpoint_in_progress.swap(true, Ordering::SeqCst) {
+                return Ok(Box::new(warp::reply::with_status(
+                    "Checkpoint already in progress.",
+                    StatusCode::CONFLICT,
+                )));
+            }
+            let result = create_checkpoint(&db, checkpoint_dir);
+            checkpoint_in_progress.store(false, Ordering::SeqCst);
+            result
+        })
+        .map(unwrap_or_500)
+        .recover(handle_rejection);
+
+    // Serve the routes reading the DB for GET only, and the ones writing to disk for POST only.
+    warp::get()
+        .and(get_read_routes(backup_handler))
+        .or(warp::post().and(warp::path(CHECKPOINT).and(checkpoint)))
+        .with(warp::log::custom(|info| {
+            let endpoint = info.path().split('/').nth(1).unwrap_or("-");
+            LATENCY_HISTOGRAM
+                .with_label_values(&[endpoint, info.status().as_str()])
+                .observe(info.elapsed().as_secs_f64())
+        }))
+        .boxed()
+}
+
+fn create_checkpoint(db: &AptosDB, checkpoint_dir: &Path) -> anyhow::Result<Box<dyn Reply>> {
+    let path = checkpoint_dir.join(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)?
+            .as_secs()
+            .to_string(),
+    );
+    let version = db.create_checkpoint(&path)?;
+    Ok(Box::new(warp::reply::json(&CheckpointInfo {
+        path: path.display().to_string(),
+        version,
+    })))
+}
 
-pub(crate) fn get_routes(backup_handler: BackupHandler) -> BoxedFilter<(impl Reply,)> {
+fn get_read_routes(backup_handler: BackupHandler) -> BoxedFilter<(impl Reply,)> {
     // GET db_state
     let bh = backup_handler.clone();
     let db_state = warp::path::end()
@@ -95,6 +169,13 @@ pub(crate) fn get_routes(backup_handler: BackupHandler) -> BoxedFilter<(impl Rep
         })
         .recover(handle_rejection);
 
+    // GET pruner_progress
+    // Unlike the other endpoints, replies in JSON as it's meant to be read by operators.
+    let bh = backup_handler.clone();
+    let pruner_progress = warp::path::end()
+        .map(move || warp::reply::json(&bh.get_pruner_progress()))
+        .recover(handle_rejection);
+
     // GET transaction_range_proof/<first_version>/<last_version>
     let bh = backup_handler;
     let transaction_range_proof = warp::path!(Version / Version)
@@ -115,16 +196,8 @@ pub(crate) fn get_routes(backup_handler: BackupHandler) -> BoxedFilter<(impl Rep
         .or(warp::path(STATE_ROOT_PROOF).and(state_root_proof))
         .or(warp::path(EPOCH_ENDING_LEDGER_INFOS).and(epoch_ending_ledger_infos))
         .or(warp::path(TRANSACTIONS).and(transactions))
-        .or(warp::path(TRANSACTION_RANGE_PROOF).and(transaction_range_proof));
+        .or(warp::path(TRANSACTION_RANGE_PROOF).and(transaction_range_proof))
+        .or(warp::path(PRUNER_PROGRESS).and(pruner_progress));
 
-    // Serve all routes for GET only.
-    warp::get()
-        .and(routes)
-        .with(warp::log::custom(|info| {
-            let endpoint = info.path().split('/').nth(1).unwrap_or("-");
-            LATENCY_HISTOGRAM
-                .with_label_values(&[endpoint, info.status().as_str()])
-                .observe(info.elapsed().as_secs_f64())
-        }))
-        .boxed()
+    routes.boxed()
 }
@@ -6,12 +6,18 @@ mod handlers;
 use crate::handlers::get_routes;
 use aptos_logger::prelude::*;
 use aptosdb::AptosDB;
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 use tokio::runtime::{Builder, Runtime};
 
-pub fn start_backup_service(address: SocketAddr, db: Arc<AptosDB>) -> Runtime {
+/// Starts the backup service, which can also create checkpoints of the DB under `checkpoint_dir`
+/// if given.
+pub fn start_backup_service(
+    address: SocketAddr,
+    db: Arc<AptosDB>,
+    checkpoint_dir: Option<PathBuf>,
+) -> Runtime {
     let backup_handler = db.get_backup_handler();
-    let routes = get_routes(backup_handler);
+    let routes = get_routes(backup_handler, db, checkpoint_dir);
 
     let runtime = Builder::new_multi_thread()
         .thread_name("backup")
@@ -52,7 +58,11 @@ mod tests {
         let tmpdir = TempPath::new();
         let db = Arc::new(AptosDB::new_for_test(&tmpdir));
         let port = get_available_port();
-        let _rt = start_backup_service(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port), db);
+        let _rt = start_backup_service(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port),
+            db,
+            None, /* checkpoint_dir */
+        );
 
         // Endpoint doesn't exist.
         let resp = get(&format!("http://127.0.0.1:{}/", port)).unwrap();
@@ -93,5 +103,44 @@ mod tests {
         assert_eq!(resp.status(), 200);
         assert_eq!(resp.content_length(), None);
         assert!(resp.bytes().is_err());
+
+        // Pruning is disabled, so there is no progress to report.
+        let resp = get(&format!("http://127.0.0.1:{}/pruner_progress", port,)).unwrap();
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.text().unwrap(), "[]");
+
+        // Checkpoints are disabled, and only served for POST.
+        let resp = get(&format!("http://127.0.0.1:{}/checkpoint", port,)).unwrap();
+        assert_eq!(resp.status(), 405);
+        let resp = reqwest::blocking::Client::new()
+            .post(&format!("http://127.0.0.1:{}/checkpoint", port,))
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), 500);
+    }
+
+    #[test]
+    fn create_checkpoint() {
+        let tmpdir = TempPath::new();
+        let db = Arc::new(AptosDB::new_for_test(&tmpdir));
+        let checkpoint_dir = TempPath::new();
+        let port = get_available_port();
+        let _rt = start_backup_service(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port),
+            db,
+            Some(checkpoint_dir.path().to_path_buf()),
+        );
+
+        let resp = reqwest::blocking::Client::new()
+            .post(&format!("http://127.0.0.1:{}/checkpoint", port,))
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+        // The DB is empty.
+        let reply: serde_json::Value = resp.json().unwrap();
+        assert!(reply["version"].is_null());
+        let path = reply["path"].as_str().unwrap();
+        assert!(std::path::Path::new(path).starts_with(checkpoint_dir.path()));
+        AptosDB::new_for_test(path);
     }
 }
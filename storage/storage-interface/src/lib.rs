@@ -10,6 +10,7 @@ use aptos_types::{
     access_path::AccessPath,
     account_address::AccountAddress,
     account_config::aptos_root_address,
+    block_metadata::BlockMetadata,
     contract_event::EventWithVersion,
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
@@ -34,6 +35,7 @@ use aptos_types::{
     },
     write_set::WriteSet,
 };
+use move_deps::move_core_types::language_storage::TypeTag;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
 use thiserror::Error;
@@ -165,6 +167,17 @@ impl TreeState {
     }
 }
 
+/// A committed block, i.e. the block metadata transaction and the transactions following it up to
+/// the next block metadata transaction. The genesis transaction is the block at height 0.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CommittedBlock {
+    pub height: u64,
+    pub first_version: Version,
+    pub last_version: Version,
+    /// The block metadata transaction at `first_version`, `None` for the genesis block.
+    pub metadata: Option<BlockMetadata>,
+}
+
 pub trait StateSnapshotReceiver<K, V>: Send {
     fn add_chunk(&mut self, chunk: Vec<(K, V)>, proof: SparseMerkleRangeProof) -> Result<()>;
 
@@ -311,6 +324,25 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the first version whose events are indexed by type, `None` if the event by type
+    /// index is not enabled.
+    fn get_event_by_type_index_start_version(&self) -> Result<Option<Version>> {
+        unimplemented!()
+    }
+
+    /// Returns up to `limit` events of the Move type `type_tag`, starting at `start_version`, in
+    /// order of the transaction versions and of the events within a transaction. Only available
+    /// if the event by type index is enabled.
+    fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        unimplemented!()
+    }
+
     /// See [AptosDB::get_block_timestamp].
     ///
     /// [AptosDB::get_block_timestamp]:
@@ -319,6 +351,30 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// See [AptosDB::get_block_info_by_version].
+    ///
+    /// [AptosDB::get_block_info_by_version]:
+    /// ../aptosdb/struct.AptosDB.html#method.get_block_info_by_version
+    fn get_block_info_by_version(
+        &self,
+        version: Version,
+        ledger_version: Version,
+    ) -> Result<CommittedBlock> {
+        unimplemented!()
+    }
+
+    /// See [AptosDB::get_block_info_by_height].
+    ///
+    /// [AptosDB::get_block_info_by_height]:
+    /// ../aptosdb/struct.AptosDB.html#method.get_block_info_by_height
+    fn get_block_info_by_height(
+        &self,
+        height: u64,
+        ledger_version: Version,
+    ) -> Result<CommittedBlock> {
+        unimplemented!()
+    }
+
     /// Gets the version of the last transaction committed before timestamp,
     /// a committed block at or after the required timestamp must exist (otherwise it's possible
     /// the next block committed as a timestamp smaller than the one in the request).
@@ -349,6 +405,24 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns up to `limit` key, value pairs for a particular state key prefix at the desired
+    /// version, ordered by key and starting from `first_key` (inclusive) if given. Also returns
+    /// the key to start the next chunk from, if there are more values with the prefix.
+    ///
+    /// See [AptosDB::get_state_value_chunk_by_key_prefix].
+    ///
+    /// [AptosDB::get_state_value_chunk_by_key_prefix]:
+    /// ../aptosdb/struct.AptosDB.html#method.get_state_value_chunk_by_key_prefix
+    fn get_state_value_chunk_by_key_prefix(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        first_key: Option<&StateKey>,
+        limit: usize,
+        version: Version,
+    ) -> Result<(Vec<(StateKey, StateValue)>, Option<StateKey>)> {
+        unimplemented!()
+    }
+
     /// Returns the latest ledger info, if any.
     fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
         unimplemented!()
@@ -2,12 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::aptos_cli::setup_cli_test;
-use aptos::{account::create::DEFAULT_FUNDED_COINS, test::CliTestFramework};
+use aptos::{
+    account::create::DEFAULT_FUNDED_COINS, common::types::CliConfig, test::CliTestFramework,
+};
 use aptos_config::{config::ApiConfig, utils::get_available_port};
+use aptos_crypto::{SigningKey, ValidCryptoMaterialStringExt};
 use aptos_rosetta::{
     client::RosettaClient,
-    types::{AccountBalanceRequest, BlockRequest, Currency},
+    types::{
+        AccountBalanceRequest, AccountIdentifier, Amount, BlockRequest, ConstructionCombineRequest,
+        ConstructionDeriveRequest, ConstructionHashRequest, ConstructionMetadataRequest,
+        ConstructionParseRequest, ConstructionPayloadsRequest, ConstructionPreprocessRequest,
+        ConstructionSubmitRequest, Currency, CurveType, MempoolRequest, MempoolTransactionRequest,
+        Operation, OperationIdentifier, PublicKey, SearchTransactionsRequest, Signature,
+        SignatureType,
+    },
 };
+use aptos_types::transaction::RawTransaction;
 use forge::{LocalSwarm, Node};
 use std::{future::Future, str::FromStr, time::Duration};
 use tokio::task::JoinHandle;
@@ -32,6 +43,8 @@ pub async fn setup_test(
         tls_cert_path: None,
         tls_key_path: None,
         content_length_limit: None,
+        max_view_function_gas: None,
+        max_unpaginated_account_state_values: None,
     };
 
     // Start the server
@@ -173,6 +186,248 @@ async fn test_block() {
     assert!(newer_block.timestamp > latest_block.timestamp);
 }
 
+#[tokio::test]
+#[ignore]
+async fn test_construction_transfer() {
+    let (swarm, cli, _faucet, rosetta_client) = setup_test(1, 2).await;
+    let chain_id = swarm.chain_id();
+    let sender = CliTestFramework::account_id(0);
+    let receiver = CliTestFramework::account_id(1);
+    let profile = CliConfig::load_profile("0").unwrap().unwrap();
+    let private_key = profile.private_key.unwrap();
+    let public_key = PublicKey {
+        hex_bytes: profile.public_key.unwrap().to_encoded_string().unwrap(),
+        curve_type: CurveType::Edwards25519,
+    };
+
+    // The public key derives the sender's address
+    let derive_request = ConstructionDeriveRequest {
+        network_identifier: chain_id.into(),
+        public_key: public_key.clone(),
+    };
+    let response = try_until_ok(|| rosetta_client.construction_derive(&derive_request))
+        .await
+        .unwrap();
+    assert_eq!(
+        Some(AccountIdentifier::from(sender)),
+        response.account_identifier
+    );
+
+    let amount = 100;
+    let operations = vec![
+        transfer_operation(0, sender.into(), format!("-{}", amount)),
+        transfer_operation(1, receiver.into(), amount.to_string()),
+    ];
+    let response = rosetta_client
+        .construction_preprocess(&ConstructionPreprocessRequest {
+            network_identifier: chain_id.into(),
+            operations: operations.clone(),
+            max_fee: None,
+            suggested_fee_multiplier: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        Some(vec![AccountIdentifier::from(sender)]),
+        response.required_public_keys
+    );
+
+    let response = rosetta_client
+        .construction_metadata(&ConstructionMetadataRequest {
+            network_identifier: chain_id.into(),
+            options: response.options.unwrap(),
+            public_keys: vec![public_key.clone()],
+        })
+        .await
+        .unwrap();
+    let payloads = rosetta_client
+        .construction_payloads(&ConstructionPayloadsRequest {
+            network_identifier: chain_id.into(),
+            operations: operations.clone(),
+            metadata: Some(response.metadata),
+            public_keys: Some(vec![public_key.clone()]),
+        })
+        .await
+        .unwrap();
+
+    // The unsigned transaction parses back into the same operations
+    let parsed = rosetta_client
+        .construction_parse(&ConstructionParseRequest {
+            network_identifier: chain_id.into(),
+            signed: false,
+            transaction: payloads.unsigned_transaction.clone(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(operations, strip_related_operations(parsed.operations));
+
+    // Sign outside of the server, it never sees private keys
+    let raw_txn: RawTransaction =
+        bcs::from_bytes(&hex::decode(&payloads.unsigned_transaction).unwrap()).unwrap();
+    let signing_payload = payloads.payloads.first().unwrap().clone();
+    assert_eq!(
+        hex::encode(raw_txn.signing_message()),
+        signing_payload.hex_bytes
+    );
+    let signature = private_key.sign(&raw_txn);
+    let combined = rosetta_client
+        .construction_combine(&ConstructionCombineRequest {
+            network_identifier: chain_id.into(),
+            unsigned_transaction: payloads.unsigned_transaction,
+            signatures: vec![Signature {
+                signing_payload,
+                public_key,
+                signature_type: SignatureType::Ed25519,
+                hex_bytes: signature.to_encoded_string().unwrap(),
+            }],
+        })
+        .await
+        .unwrap();
+
+    let parsed = rosetta_client
+        .construction_parse(&ConstructionParseRequest {
+            network_identifier: chain_id.into(),
+            signed: true,
+            transaction: combined.signed_transaction.clone(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(operations, strip_related_operations(parsed.operations));
+    assert_eq!(
+        Some(vec![AccountIdentifier::from(sender)]),
+        parsed.account_identifier_signers
+    );
+
+    let hash = rosetta_client
+        .construction_hash(&ConstructionHashRequest {
+            network_identifier: chain_id.into(),
+            signed_transaction: combined.signed_transaction.clone(),
+        })
+        .await
+        .unwrap()
+        .transaction_identifier;
+    let submitted = rosetta_client
+        .construction_submit(&ConstructionSubmitRequest {
+            network_identifier: chain_id.into(),
+            signed_transaction: combined.signed_transaction,
+        })
+        .await
+        .unwrap()
+        .transaction_identifier;
+    assert_eq!(hash, submitted);
+
+    cli.wait_for_balance(1, DEFAULT_FUNDED_COINS + amount)
+        .await
+        .unwrap();
+}
+
+fn transfer_operation(index: u64, account: AccountIdentifier, value: String) -> Operation {
+    Operation {
+        operation_identifier: OperationIdentifier {
+            index,
+            network_index: None,
+        },
+        related_operations: None,
+        operation_type: "transfer".to_string(),
+        status: None,
+        account: Some(account),
+        amount: Some(Amount {
+            value,
+            currency: Currency::test_coin(),
+        }),
+        metadata: None,
+    }
+}
+
+fn strip_related_operations(operations: Vec<Operation>) -> Vec<Operation> {
+    operations
+        .into_iter()
+        .map(|operation| Operation {
+            related_operations: None,
+            ..operation
+        })
+        .collect()
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_search_transactions() {
+    let (swarm, cli, _faucet, rosetta_client) = setup_test(1, 2).await;
+    let chain_id = swarm.chain_id();
+    let sender = CliTestFramework::account_id(0);
+    let receiver = CliTestFramework::account_id(1);
+
+    let amount = 100;
+    cli.transfer_coins(0, 1, amount).await.unwrap();
+
+    let request = SearchTransactionsRequest {
+        network_identifier: chain_id.into(),
+        operator: None,
+        max_block: None,
+        offset: None,
+        limit: None,
+        transaction_identifier: None,
+        account_identifier: Some(sender.into()),
+        success: None,
+    };
+    let response = try_until_ok(|| rosetta_client.search_transactions(&request))
+        .await
+        .unwrap();
+    assert_eq!(1, response.total_count);
+    assert_eq!(None, response.next_offset);
+    assert_eq!(1, response.transactions.len());
+    let transfer = response.transactions.first().unwrap().clone();
+    let operations = &transfer.transaction.operations;
+    assert_eq!(2, operations.len());
+    assert_eq!(Some(AccountIdentifier::from(sender)), operations[0].account);
+    assert_eq!(
+        format!("-{}", amount),
+        operations[0].amount.as_ref().unwrap().value
+    );
+    assert_eq!(
+        Some(AccountIdentifier::from(receiver)),
+        operations[1].account
+    );
+    assert_eq!(
+        amount.to_string(),
+        operations[1].amount.as_ref().unwrap().value
+    );
+    assert!(operations
+        .iter()
+        .all(|operation| operation.status.as_deref() == Some("success")));
+
+    // The same transaction is found by hash
+    let request = SearchTransactionsRequest {
+        transaction_identifier: Some(transfer.transaction.transaction_identifier.clone()),
+        account_identifier: None,
+        ..request
+    };
+    let response = rosetta_client.search_transactions(&request).await.unwrap();
+    assert_eq!(vec![transfer.clone()], response.transactions);
+
+    // But not by hash with conditions it doesn't match
+    let request = SearchTransactionsRequest {
+        max_block: Some(transfer.block_identifier.index - 1),
+        ..request
+    };
+    let response = rosetta_client.search_transactions(&request).await.unwrap();
+    assert!(response.transactions.is_empty());
+
+    // Committed transactions are not in the mempool anymore
+    let request = MempoolTransactionRequest {
+        network_identifier: chain_id.into(),
+        transaction_identifier: transfer.transaction.transaction_identifier.clone(),
+    };
+    assert!(rosetta_client.mempool_transaction(&request).await.is_err());
+    let request = MempoolRequest {
+        network_identifier: chain_id.into(),
+    };
+    let response = rosetta_client.mempool(&request).await.unwrap();
+    assert!(!response
+        .transaction_identifiers
+        .contains(&transfer.transaction.transaction_identifier));
+}
+
 /// Try for 2 seconds to get a response.  This handles the fact that it's starting async
 async fn try_until_ok<F, Fut, T>(function: F) -> anyhow::Result<T>
 where
@@ -12,6 +12,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum OnChainConsensusConfig {
     V1(ConsensusConfigV1),
+    V2(ConsensusConfigV2),
 }
 
 /// The public interface that exposes all values with safe fallback.
@@ -20,6 +21,7 @@ impl OnChainConsensusConfig {
     pub fn leader_reputation_exclude_round(&self) -> u64 {
         match &self {
             OnChainConsensusConfig::V1(config) => config.exclude_round,
+            OnChainConsensusConfig::V2(config) => config.exclude_round,
         }
     }
 
@@ -27,6 +29,7 @@ impl OnChainConsensusConfig {
     pub fn decoupled_execution(&self) -> bool {
         match &self {
             OnChainConsensusConfig::V1(config) => config.decoupled_execution,
+            OnChainConsensusConfig::V2(config) => config.decoupled_execution,
         }
     }
 
@@ -39,6 +42,7 @@ impl OnChainConsensusConfig {
         }
         match &self {
             OnChainConsensusConfig::V1(config) => config.back_pressure_limit,
+            OnChainConsensusConfig::V2(config) => config.back_pressure_limit,
         }
     }
 
@@ -47,6 +51,7 @@ impl OnChainConsensusConfig {
     pub fn max_failed_authors_to_store(&self) -> usize {
         match &self {
             OnChainConsensusConfig::V1(config) => config.max_failed_authors_to_store,
+            OnChainConsensusConfig::V2(config) => config.max_failed_authors_to_store,
         }
     }
 
@@ -54,6 +59,16 @@ impl OnChainConsensusConfig {
     pub fn proposer_election_type(&self) -> &ProposerElectionType {
         match &self {
             OnChainConsensusConfig::V1(config) => &config.proposer_election_type,
+            OnChainConsensusConfig::V2(config) => &config.proposer_election_type,
+        }
+    }
+
+    /// The gas budget of a block, the transactions after the one with which the block reaches
+    /// it are retried in a later block.
+    pub fn block_gas_limit(&self) -> Option<u64> {
+        match &self {
+            OnChainConsensusConfig::V1(_config) => None,
+            OnChainConsensusConfig::V2(config) => config.block_gas_limit,
         }
     }
 }
@@ -116,6 +131,32 @@ impl Default for ConsensusConfigV1 {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ConsensusConfigV2 {
+    pub decoupled_execution: bool,
+    pub back_pressure_limit: u64,
+    pub exclude_round: u64,
+    pub proposer_election_type: ProposerElectionType,
+    pub max_failed_authors_to_store: usize,
+    /// Executing the block stops after the transaction with which its gas used reaches the limit,
+    /// the same on all the validators as it's part of the on-chain config.
+    pub block_gas_limit: Option<u64>,
+}
+
+impl Default for ConsensusConfigV2 {
+    fn default() -> Self {
+        let v1 = ConsensusConfigV1::default();
+        Self {
+            decoupled_execution: v1.decoupled_execution,
+            back_pressure_limit: v1.back_pressure_limit,
+            exclude_round: v1.exclude_round,
+            proposer_election_type: v1.proposer_election_type,
+            max_failed_authors_to_store: v1.max_failed_authors_to_store,
+            block_gas_limit: None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")] // cannot use tag = "type" as nested enums cannot work, and bcs doesn't support it
 pub enum ProposerElectionType {
@@ -141,6 +182,9 @@ pub enum LeaderReputationType {
     // Proposer election based on whether nodes succeeded or failed
     // their proposer election rounds, and whether they voted.
     ProposerAndVoter(ProposerAndVoterConfig),
+    // Proposer election based on the voting power of the nodes, scaled by
+    // their failed proposer rounds and their vote participation.
+    StakeWeighted(StakeWeightedConfig),
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -169,6 +213,27 @@ pub struct ProposerAndVoterConfig {
     pub voter_window_num_validators_multiplier: usize,
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StakeWeightedConfig {
+    // Reputation from the proposer rounds and the votes, the active_weight
+    // is scaled by the share of the voter window the validator voted in
+    pub reputation: ProposerAndVoterConfig,
+    // How the voting power of the validators scales their reputation
+    pub stake_weighting: StakeWeighting,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StakeWeighting {
+    // Ignore the voting power
+    Uniform,
+    // Proportional to the voting power
+    Linear,
+    // Proportional to the square root of the voting power, limiting
+    // the share of the rounds led by the largest validators
+    SquareRoot,
+}
+
 #[cfg(test)]
 mod test {
     use std::sync::Arc;
@@ -211,6 +276,52 @@ mod test {
         ));
     }
 
+    #[test]
+    fn test_config_block_gas_limit() {
+        assert_eq!(OnChainConsensusConfig::default().block_gas_limit(), None);
+        let config = OnChainConsensusConfig::V2(ConsensusConfigV2 {
+            block_gas_limit: Some(1_000_000),
+            ..ConsensusConfigV2::default()
+        });
+
+        let s = bcs::to_bytes(&config).unwrap();
+        let result = bcs::from_bytes::<OnChainConsensusConfig>(&s).unwrap();
+        assert_eq!(result.block_gas_limit(), Some(1_000_000));
+        assert_eq!(
+            result.proposer_election_type(),
+            &ConsensusConfigV1::default().proposer_election_type
+        );
+    }
+
+    #[test]
+    fn test_config_serialization_stake_weighted() {
+        let proposer_election_type = ProposerElectionType::LeaderReputation(
+            LeaderReputationType::StakeWeighted(StakeWeightedConfig {
+                reputation: ProposerAndVoterConfig {
+                    active_weight: 1000,
+                    inactive_weight: 10,
+                    failed_weight: 1,
+                    failure_threshold_percent: 10,
+                    proposer_window_num_validators_multiplier: 10,
+                    voter_window_num_validators_multiplier: 1,
+                },
+                stake_weighting: StakeWeighting::SquareRoot,
+            }),
+        );
+        let config = OnChainConsensusConfig::V1(ConsensusConfigV1 {
+            proposer_election_type: proposer_election_type.clone(),
+            ..ConsensusConfigV1::default()
+        });
+
+        let s = serde_yaml::to_string(&config).unwrap();
+        let result = serde_yaml::from_str::<OnChainConsensusConfig>(&s).unwrap();
+        assert_eq!(result.proposer_election_type(), &proposer_election_type);
+
+        let s = bcs::to_bytes(&config).unwrap();
+        let result = bcs::from_bytes::<OnChainConsensusConfig>(&s).unwrap();
+        assert_eq!(result.proposer_election_type(), &proposer_election_type);
+    }
+
     #[test]
     fn test_config_onchain_payload() {
         let consensus_config = OnChainConsensusConfig::V1(ConsensusConfigV1 {
@@ -29,7 +29,8 @@ pub use self::{
         Version, APTOS_MAX_KNOWN_VERSION, APTOS_VERSION_2, APTOS_VERSION_3, APTOS_VERSION_4,
     },
     consensus_config::{
-        ConsensusConfigV1, LeaderReputationType, OnChainConsensusConfig, ProposerElectionType,
+        ConsensusConfigV1, ConsensusConfigV2, LeaderReputationType, OnChainConsensusConfig,
+        ProposerAndVoterConfig, ProposerElectionType, StakeWeightedConfig, StakeWeighting,
     },
     registered_currencies::RegisteredCurrencies,
     validator_set::ValidatorSet,
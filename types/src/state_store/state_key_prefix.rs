@@ -17,6 +17,11 @@ impl StateKeyPrefix {
         Self { tag, bytes }
     }
 
+    /// Prefix of the state keys of all the items of a table.
+    pub fn table(handle: u128) -> Self {
+        Self::new(StateKeyTag::TableItem, handle.to_be_bytes().to_vec())
+    }
+
     /// Serializes to bytes for physical storage.
     pub fn encode(&self) -> anyhow::Result<Vec<u8>> {
         let mut out = vec![self.tag.clone() as u8];
@@ -69,4 +74,13 @@ mod tests {
         assert!(!account1_key_prefx.is_prefix(&key2).unwrap());
         assert!(!account2_key_prefx.is_prefix(&key1).unwrap());
     }
+
+    #[test]
+    fn test_table_state_key_prefix() {
+        let key1 = StateKey::table_item(1, b"key".to_vec());
+        let key2 = StateKey::table_item(2, b"key".to_vec());
+
+        assert!(StateKeyPrefix::table(1).is_prefix(&key1).unwrap());
+        assert!(!StateKeyPrefix::table(1).is_prefix(&key2).unwrap());
+    }
 }